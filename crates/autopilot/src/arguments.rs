@@ -1,8 +1,10 @@
 use primitive_types::H160;
 use shared::{
-    arguments::display_option, bad_token::token_owner_finder, http_client, price_estimation,
+    arguments::{display_list, display_option, display_secret_option, Driver},
+    bad_token::token_owner_finder,
+    http_client, price_estimation,
 };
-use std::{net::SocketAddr, num::NonZeroUsize, time::Duration};
+use std::{net::SocketAddr, num::NonZeroUsize, path::PathBuf, time::Duration};
 use url::Url;
 
 #[derive(clap::Parser)]
@@ -139,6 +141,53 @@ pub struct Arguments {
     /// How many quotes the limit order quoter updates in parallel.
     #[clap(long, env, default_value = "5")]
     pub limit_order_quoter_parallelism: usize,
+
+    /// Drivers that compete in the solver competition run by the autopilot in the following
+    /// format: `<NAME>|<URL>,<NAME>|<URL>`. If empty, the autopilot doesn't run competitions.
+    #[clap(long, env, use_value_delimiter = true)]
+    pub drivers: Vec<Driver>,
+
+    /// How long drivers have to respond to a solve request. Expects a value in seconds.
+    #[clap(
+        long,
+        env,
+        default_value = "15",
+        value_parser = shared::arguments::duration_from_seconds,
+    )]
+    pub solve_deadline: Duration,
+
+    /// The list of tokens the settlement contract is willing to hold, so that drivers can
+    /// internalize interactions buying them. Sent to the drivers as trusted tokens.
+    #[clap(long, env, default_value = "https://files.cow.fi/token_list.json")]
+    pub trusted_token_list: String,
+
+    /// Time interval after which the trusted token list needs to be updated.
+    #[clap(
+        long,
+        env,
+        default_value = "3600",
+        value_parser = shared::arguments::duration_from_seconds,
+    )]
+    pub trusted_token_list_update_interval: Duration,
+
+    /// Bearer token sent to the drivers in the `Authorization` header.
+    #[clap(long, env)]
+    pub driver_bearer_token: Option<String>,
+
+    /// Secret key used to sign the driver requests with HMAC-SHA256. The signed message is the
+    /// Unix timestamp sent in the `X-Timestamp` header, a `.` and the request body. The hex
+    /// encoded signature is sent in the `X-Signature` header.
+    #[clap(long, env)]
+    pub driver_hmac_key: Option<String>,
+
+    /// Path to a PEM encoded TLS client certificate chain used to authenticate to the drivers.
+    /// Must be specified together with `--driver-tls-key`.
+    #[clap(long, env, requires = "driver_tls_key")]
+    pub driver_tls_certificate: Option<PathBuf>,
+
+    /// Path to the PEM encoded PKCS #8 private key of the TLS client certificate.
+    #[clap(long, env, requires = "driver_tls_certificate")]
+    pub driver_tls_key: Option<PathBuf>,
 }
 
 impl std::fmt::Display for Arguments {
@@ -196,6 +245,22 @@ impl std::fmt::Display for Arguments {
             "limit_order_quoter_parallelism: {:?}",
             self.limit_order_quoter_parallelism
         )?;
+        display_list(f, "drivers", &self.drivers)?;
+        writeln!(f, "solve_deadline: {:?}", self.solve_deadline)?;
+        writeln!(f, "trusted_token_list: {}", self.trusted_token_list)?;
+        writeln!(
+            f,
+            "trusted_token_list_update_interval: {:?}",
+            self.trusted_token_list_update_interval
+        )?;
+        display_secret_option(f, "driver_bearer_token", &self.driver_bearer_token)?;
+        display_secret_option(f, "driver_hmac_key", &self.driver_hmac_key)?;
+        writeln!(
+            f,
+            "driver_tls_certificate: {:?}",
+            self.driver_tls_certificate
+        )?;
+        writeln!(f, "driver_tls_key: {:?}", self.driver_tls_key)?;
         Ok(())
     }
 }
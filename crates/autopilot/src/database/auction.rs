@@ -1,8 +1,14 @@
 use super::Postgres;
 use anyhow::{Context, Result};
-use database::{auction::AuctionId, quotes::QuoteKind};
+use database::{
+    auction::AuctionId, byte_array::ByteArray, order_auction_states::OrderAuctionState,
+    quotes::QuoteKind,
+};
 use futures::{StreamExt, TryStreamExt};
-use model::{auction::Auction, order::Order};
+use model::{
+    auction::{Auction, AuctionWithId},
+    order::{Order, OrderUid},
+};
 
 pub struct SolvableOrders {
     pub orders: Vec<Order>,
@@ -94,6 +100,33 @@ impl Postgres {
         })
     }
 
+    /// Stores the orders whose auction state changed with the specified auction. The state is
+    /// `None` if the order is part of the auction and otherwise the reason for why it got
+    /// filtered out.
+    pub async fn store_order_auction_states(
+        &self,
+        auction_id: AuctionId,
+        states: &[(OrderUid, Option<&str>)],
+    ) -> Result<()> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["store_order_auction_states"])
+            .start_timer();
+
+        let states = states
+            .iter()
+            .map(|(uid, filter_reason)| OrderAuctionState {
+                order_uid: ByteArray(uid.0),
+                auction_id,
+                filter_reason: filter_reason.map(str::to_owned),
+            })
+            .collect::<Vec<_>>();
+        let mut ex = self.0.begin().await?;
+        database::order_auction_states::insert(&mut ex, &states).await?;
+        ex.commit().await?;
+        Ok(())
+    }
+
     pub async fn replace_current_auction(&self, auction: &Auction) -> Result<AuctionId> {
         let _timer = super::Metrics::get()
             .database_queries
@@ -107,4 +140,19 @@ impl Postgres {
         ex.commit().await?;
         Ok(id)
     }
+
+    pub async fn most_recent_auction(&self) -> Result<Option<AuctionWithId>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["load_most_recent_auction"])
+            .start_timer();
+
+        let mut ex = self.0.acquire().await?;
+        let (id, json) = match database::auction::load_most_recent(&mut ex).await? {
+            Some(inner) => inner,
+            None => return Ok(None),
+        };
+        let auction: Auction = serde_json::from_value(json)?;
+        Ok(Some(AuctionWithId { id, auction }))
+    }
 }
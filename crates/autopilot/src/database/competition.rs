@@ -0,0 +1,48 @@
+use anyhow::Context;
+use database::byte_array::ByteArray;
+use model::solver_competition::Request;
+use number_conversions::u256_to_big_decimal;
+
+impl super::Postgres {
+    /// Stores the result of a solver competition together with the transaction that is expected
+    /// to settle it and how the winning solution executed the orders.
+    pub async fn save_competition(&self, competition: &Request) -> anyhow::Result<()> {
+        let json = &serde_json::to_value(&competition.competition)?;
+
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["save_competition"])
+            .start_timer();
+
+        let mut ex = self.0.begin().await.context("begin")?;
+
+        database::solver_competition::save(&mut ex, competition.auction, json)
+            .await
+            .context("solver_competition::save")?;
+
+        let transaction = &competition.transaction;
+        database::auction_transaction::upsert_auction_transaction(
+            &mut ex,
+            competition.auction,
+            &ByteArray(transaction.account.0),
+            transaction.nonce.try_into().context("convert nonce")?,
+        )
+        .await
+        .context("upsert_auction_transaction")?;
+
+        for (order, execution) in &competition.executions {
+            let surplus_fee = execution.surplus_fee.as_ref().map(u256_to_big_decimal);
+            database::order_execution::save(
+                &mut ex,
+                &ByteArray(order.0),
+                competition.auction,
+                execution.reward,
+                surplus_fee.as_ref(),
+            )
+            .await
+            .context("order_execution::save")?;
+        }
+
+        ex.commit().await.context("commit")
+    }
+}
@@ -8,7 +8,7 @@ use database::{
     ethflow_orders::EthOrderPlacement,
     events::EventIndex,
     onchain_broadcasted_orders::OnchainOrderPlacement,
-    orders::{Interaction, Order},
+    orders::{ExecutionTime, Interaction, Order},
     PgTransaction,
 };
 use ethcontract::Event as EthContractEvent;
@@ -97,9 +97,13 @@ impl OnchainOrderParsing<EthFlowData, EthFlowDataForDb> for EthFlowOnchainOrderP
         database::ethflow_orders::insert_or_overwrite_orders(ex, eth_order_placements.as_slice())
             .await
             .context("append_ethflow_orders failed during appending eth order placement data")?;
-        database::orders::insert_or_overwrite_pre_interactions(ex, pre_interactions_data.as_slice())
-            .await
-            .context("append_ethflow_orders failed during appending pre_interactions")
+        database::orders::insert_or_overwrite_interactions(
+            ex,
+            pre_interactions_data.as_slice(),
+            ExecutionTime::Pre,
+        )
+        .await
+        .context("append_ethflow_orders failed during appending pre_interactions")
     }
 
     fn customized_event_data_for_event_index(
@@ -0,0 +1,153 @@
+//! Client for the driver API as defined in `crates/driver/openapi.yml`.
+
+use crate::driver_model::{execute, solve};
+use anyhow::{ensure, Context, Result};
+use hmac::Mac;
+use reqwest::{header, Client, ClientBuilder};
+use serde::{de::DeserializeOwned, Serialize};
+use url::Url;
+
+/// The header containing the hex encoded HMAC-SHA256 signature of the request timestamp and body.
+const SIGNATURE_HEADER: &str = "X-Signature";
+
+/// The header containing the time at which the request was signed, in seconds since the Unix
+/// epoch. The drivers reject requests with stale timestamps.
+const TIMESTAMP_HEADER: &str = "X-Timestamp";
+
+pub struct Driver {
+    pub name: String,
+    pub url: Url,
+    client: Client,
+    hmac_key: Option<Vec<u8>>,
+}
+
+/// How the autopilot authenticates to the drivers.
+#[derive(Clone, Default)]
+pub struct Auth {
+    /// Static token sent in the `Authorization: Bearer` header.
+    pub bearer_token: Option<String>,
+    /// Secret key used to sign the request bodies.
+    pub hmac_key: Option<Vec<u8>>,
+    /// PEM encoded TLS client certificate chain and PKCS #8 private key.
+    pub tls_identity: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+impl Auth {
+    /// Configures the HTTP client used for the drivers to send the bearer token and the TLS
+    /// client certificate.
+    pub fn configure(&self, mut builder: ClientBuilder) -> Result<ClientBuilder> {
+        if let Some(token) = &self.bearer_token {
+            let mut value = header::HeaderValue::from_str(&format!("Bearer {token}"))
+                .context("invalid bearer token")?;
+            value.set_sensitive(true);
+            builder = builder.default_headers(header::HeaderMap::from_iter([(
+                header::AUTHORIZATION,
+                value,
+            )]));
+        }
+        if let Some((certificate, key)) = &self.tls_identity {
+            builder = builder.identity(
+                reqwest::Identity::from_pkcs8_pem(certificate, key)
+                    .context("invalid TLS client certificate")?,
+            );
+        }
+        Ok(builder)
+    }
+}
+
+impl Driver {
+    /// Creates a driver client. The HTTP client should already be configured with
+    /// [`Auth::configure`], the request bodies are signed with the HMAC key of the `auth`.
+    pub fn new(name: String, url: Url, client: Client, auth: &Auth) -> Self {
+        Self {
+            name,
+            url,
+            client,
+            hmac_key: auth.hmac_key.clone(),
+        }
+    }
+
+    pub async fn solve(&self, request: &solve::Request) -> Result<solve::Response> {
+        self.request("solve", request).await
+    }
+
+    pub async fn execute(&self, request: &execute::Request) -> Result<execute::Response> {
+        self.request("execute", request).await
+    }
+
+    async fn request<Response>(&self, path: &str, request: &impl Serialize) -> Result<Response>
+    where
+        Response: DeserializeOwned,
+    {
+        let url = endpoint(&self.url, path);
+        let body = serde_json::to_vec(request).context("serialize")?;
+        tracing::trace!(%url, body = %String::from_utf8_lossy(&body), "driver request");
+        let mut request = self
+            .client
+            .post(url)
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(key) = &self.hmac_key {
+            let timestamp = model::time::now_in_epoch_seconds() as u64;
+            request = request
+                .header(TIMESTAMP_HEADER, timestamp)
+                .header(SIGNATURE_HEADER, sign(key, timestamp, &body));
+        }
+        let response = request.body(body).send().await.context("send")?;
+        let status = response.status();
+        let text = response.text().await.context("body")?;
+        tracing::trace!(%status, %text, "driver response");
+        ensure!(status.is_success(), "bad status {status}: {text}");
+        serde_json::from_str(&text).with_context(|| format!("bad response {text}"))
+    }
+}
+
+/// Signs the request timestamp and body with HMAC-SHA256. The signed message is the decimal
+/// timestamp, a `.` and the body. The signature is hex encoded.
+fn sign(key: &[u8], timestamp: u64, body: &[u8]) -> String {
+    let mut mac =
+        hmac::Hmac::<sha2::Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Drivers serve the API of each of their solvers under a separate path so the endpoints need to
+/// be appended to the configured url instead of replacing its last path segment.
+fn endpoint(url: &Url, path: &str) -> Url {
+    let mut url = url.clone();
+    url.path_segments_mut()
+        .expect("driver url can be a base")
+        .pop_if_empty()
+        .push(path);
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_endpoint_to_url() {
+        for url in ["http://driver/solver", "http://driver/solver/"] {
+            assert_eq!(
+                endpoint(&url.parse().unwrap(), "solve").as_str(),
+                "http://driver/solver/solve"
+            );
+        }
+        assert_eq!(
+            endpoint(&"http://driver".parse().unwrap(), "execute").as_str(),
+            "http://driver/execute"
+        );
+    }
+
+    #[test]
+    fn signs_request_timestamp_and_body() {
+        // HMAC-SHA256 of "1700000000.what do ya want for nothing?" with the key of RFC 4231,
+        // test case 2.
+        assert_eq!(
+            sign(b"Jefe", 1700000000, b"what do ya want for nothing?"),
+            "1cdd0650c8be1cb0974b1788d458b1e781206cfef59b85faafc582d2e182c57e"
+        );
+    }
+}
@@ -40,52 +40,131 @@ pub mod quote {
 }
 
 pub mod solve {
-    use bigdecimal::num_bigint::BigUint;
     use chrono::{DateTime, Utc};
-    use primitive_types::H160;
+    use model::{
+        app_id::AppId,
+        bytes_hex,
+        order::{BuyTokenDestination, OrderKind, OrderUid, SellTokenSource},
+        signature::SigningScheme,
+        u256_decimal::{self, DecimalU256},
+    };
+    use primitive_types::{H160, U256};
     use serde::{Deserialize, Serialize};
     use serde_with::{serde_as, DisplayFromStr};
     use std::collections::BTreeMap;
 
+    #[serde_as]
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
     pub struct Request {
-        pub auction: Auction,
+        #[serde_as(as = "Option<DisplayFromStr>")]
+        pub id: Option<i64>,
+        pub tokens: BTreeMap<H160, Token>,
+        pub orders: Vec<Order>,
+        #[serde(with = "u256_decimal")]
+        pub effective_gas_price: U256,
         pub deadline: DateTime<Utc>,
     }
 
     #[serde_as]
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
-    pub struct Auction {
-        pub id: u64,
-        pub block: u64,
-        pub orders: Vec<Order>,
-        #[serde_as(as = "BTreeMap<_, DisplayFromStr>")]
-        pub prices: BTreeMap<H160, BigUint>,
+    #[serde(rename_all = "camelCase")]
+    pub struct Token {
+        pub decimals: Option<u8>,
+        pub symbol: Option<String>,
+        /// The price of the token denominated in native token, used for computing the score of
+        /// solutions.
+        #[serde_as(as = "Option<DecimalU256>")]
+        pub reference_price: Option<U256>,
+        /// The balance of the token held by the settlement contract.
+        #[serde(with = "u256_decimal")]
+        pub available_balance: U256,
+        pub trusted: bool,
     }
 
+    #[serde_as]
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
     pub struct Order {
-        // TODO: what fields? Needs to be documented in openapi too.
+        pub uid: OrderUid,
+        pub sell_token: H160,
+        pub buy_token: H160,
+        #[serde(with = "u256_decimal")]
+        pub sell_amount: U256,
+        #[serde(with = "u256_decimal")]
+        pub buy_amount: U256,
+        /// The fee used for computing the objective value of a solution.
+        #[serde(with = "u256_decimal")]
+        pub solver_fee: U256,
+        /// The fee signed by the user.
+        #[serde(with = "u256_decimal")]
+        pub user_fee: U256,
+        pub valid_to: u32,
+        pub kind: OrderKind,
+        pub receiver: Option<H160>,
+        pub owner: H160,
+        pub partially_fillable: bool,
+        /// The already executed amount of a partially fillable order in the token of its kind.
+        #[serde(with = "u256_decimal")]
+        pub executed: U256,
+        pub pre_interactions: Vec<Interaction>,
+        pub post_interactions: Vec<Interaction>,
+        pub sell_token_balance: SellTokenSource,
+        pub buy_token_balance: BuyTokenDestination,
+        pub class: Class,
+        #[serde_as(as = "Option<DecimalU256>")]
+        pub surplus_fee: Option<U256>,
+        pub app_data: AppId,
+        pub reward: f64,
+        pub signing_scheme: SigningScheme,
+        #[serde(with = "bytes_hex")]
+        pub signature: Vec<u8>,
+    }
+
+    #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Interaction {
+        pub target: H160,
+        #[serde(with = "u256_decimal")]
+        pub value: U256,
+        #[serde(with = "bytes_hex")]
+        pub call_data: Vec<u8>,
+    }
+
+    #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum Class {
+        #[default]
+        Market,
+        Limit,
+        Liquidity,
     }
 
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
     pub struct Response {
-        pub objective: f64,
-        pub signature: String,
+        /// Identifies the solution when asking the driver to execute it.
+        #[serde(with = "bytes_hex")]
+        pub id: Vec<u8>,
+        pub score: f64,
     }
 }
 
 pub mod execute {
-    use model::{bytes_hex, order::OrderUid, u256_decimal};
+    use model::{
+        bytes_hex,
+        order::OrderUid,
+        u256_decimal::{self, DecimalU256},
+    };
     use primitive_types::{H160, U256};
     use serde::{Deserialize, Serialize};
-    use serde_with::{serde_as, DisplayFromStr};
+    use serde_with::serde_as;
     use std::collections::BTreeMap;
 
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct Request {
-        pub auction_id: u64,
+        #[serde(with = "bytes_hex")]
+        pub solution_id: Vec<u8>,
     }
 
     #[serde_as]
@@ -94,7 +173,7 @@ pub mod execute {
     pub struct Response {
         pub account: H160,
         pub nonce: u64,
-        #[serde_as(as = "BTreeMap<_, DisplayFromStr>")]
+        #[serde_as(as = "BTreeMap<_, DecimalU256>")]
         pub clearing_prices: BTreeMap<H160, U256>,
         pub trades: Vec<Trade>,
         pub internalized_interactions: Vec<InternalizedInteraction>,
@@ -116,9 +195,9 @@ pub mod execute {
     pub struct InternalizedInteraction {
         #[serde(with = "bytes_hex")]
         pub calldata: Vec<u8>,
-        #[serde_as(as = "BTreeMap<_, DisplayFromStr>")]
+        #[serde_as(as = "BTreeMap<_, DecimalU256>")]
         pub inputs: BTreeMap<H160, U256>,
-        #[serde_as(as = "BTreeMap<_, DisplayFromStr>")]
+        #[serde_as(as = "BTreeMap<_, DecimalU256>")]
         pub outputs: BTreeMap<H160, U256>,
     }
 }
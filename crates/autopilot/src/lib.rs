@@ -3,8 +3,10 @@ pub mod auction_transaction;
 pub mod database;
 pub mod event_updater;
 pub mod risk_adjusted_rewards;
+pub mod run_loop;
 pub mod solvable_orders;
 
+pub mod driver_api;
 pub mod driver_model;
 pub mod limit_orders;
 
@@ -18,8 +20,10 @@ use crate::{
         },
         Postgres,
     },
+    driver_api::{self, Driver},
     event_updater::{EventUpdater, GPv2SettlementContract},
     limit_orders::{LimitOrderMetrics, LimitOrderQuoter},
+    run_loop::RunLoop,
     solvable_orders::SolvableOrdersCache,
 };
 use contracts::{
@@ -57,6 +61,7 @@ use shared::{
         BaselineSource, PoolAggregator,
     },
     token_info::{CachedTokenInfoFetcher, TokenInfoFetcher},
+    token_list::{AutoUpdatingTokenList, TokenListConfiguration},
     zeroex_api::DefaultZeroExApi,
 };
 use std::{collections::HashSet, sync::Arc, time::Duration};
@@ -457,7 +462,7 @@ pub async fn main(args: arguments::Arguments) -> ! {
     let quoter = Arc::new(OrderQuoter::new(
         price_estimator,
         native_price_estimator.clone(),
-        gas_price_estimator,
+        gas_price_estimator.clone(),
         fee_subsidy,
         Arc::new(db.clone()),
         chrono::Duration::from_std(args.order_quoting.eip1271_onchain_quote_validity_seconds)
@@ -554,9 +559,9 @@ pub async fn main(args: arguments::Arguments) -> ! {
     let serve_metrics = shared::metrics::serve_metrics(Arc::new(liveness), args.metrics_address);
 
     let auction_transaction_updater = crate::auction_transaction::AuctionTransactionUpdater {
-        web3,
+        web3: web3.clone(),
         db: db.clone(),
-        current_block: current_block_stream,
+        current_block: current_block_stream.clone(),
     };
     tokio::task::spawn(
         auction_transaction_updater
@@ -564,6 +569,59 @@ pub async fn main(args: arguments::Arguments) -> ! {
             .instrument(tracing::info_span!("AuctionTransactionUpdater")),
     );
 
+    if !args.drivers.is_empty() {
+        let auth = driver_api::Auth {
+            bearer_token: args.driver_bearer_token.clone(),
+            hmac_key: args.driver_hmac_key.clone().map(String::into_bytes),
+            tls_identity: args
+                .driver_tls_certificate
+                .as_ref()
+                .zip(args.driver_tls_key.as_ref())
+                .map(|(certificate, key)| {
+                    (
+                        std::fs::read(certificate).expect("failed to read TLS client certificate"),
+                        std::fs::read(key).expect("failed to read TLS client key"),
+                    )
+                }),
+        };
+        let client = http_factory
+            .configure(|builder| auth.configure(builder).expect("invalid driver credentials"));
+        let trusted_tokens = AutoUpdatingTokenList::from_configuration(TokenListConfiguration {
+            url: args.trusted_token_list.clone(),
+            update_interval: args.trusted_token_list_update_interval,
+            chain_id,
+            client: http_factory.create(),
+        })
+        .await;
+        let run_loop = RunLoop {
+            database: db.clone(),
+            drivers: args
+                .drivers
+                .iter()
+                .map(|driver| {
+                    Driver::new(
+                        driver.name.clone(),
+                        driver.url.clone(),
+                        client.clone(),
+                        &auth,
+                    )
+                })
+                .collect(),
+            current_block: current_block_stream,
+            solve_deadline: args.solve_deadline,
+            web3,
+            settlement: settlement_contract.address(),
+            gas_price: gas_price_estimator,
+            token_info: token_info_fetcher,
+            trusted_tokens,
+        };
+        tokio::task::spawn(
+            run_loop
+                .run_forever()
+                .instrument(tracing::info_span!("RunLoop")),
+        );
+    }
+
     if args.enable_limit_orders {
         let domain_separator = DomainSeparator::new(chain_id, settlement_contract.address());
         let limit_order_age = chrono::Duration::from_std(args.max_surplus_fee_age).unwrap();
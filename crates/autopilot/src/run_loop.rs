@@ -0,0 +1,429 @@
+//! Drives solver competitions through the driver API.
+//!
+//! Every new auction is sent to all configured drivers. The driver proposing the solution with
+//! the best score wins and gets asked to execute it. The competition is stored in the
+//! database so that the api can serve it and settlements can be linked to their auction.
+
+use crate::{
+    database::Postgres,
+    driver_api::Driver,
+    driver_model::{execute, solve},
+};
+use anyhow::Context;
+use chrono::Utc;
+use contracts::ERC20;
+use gas_estimation::GasPriceEstimating;
+use itertools::Itertools;
+use model::{
+    auction::{Auction, AuctionId, AuctionWithId},
+    order::{Order, OrderClass, OrderKind},
+    solver_competition::{
+        self, CompetitionAuction, Execution, Objective, SolverCompetitionDB, SolverSettlement,
+    },
+};
+use number_conversions::big_uint_to_u256;
+use primitive_types::{H160, U256};
+use shared::{
+    current_block::CurrentBlockStream,
+    ethrpc::{Web3, Web3CallBatch, MAX_BATCH_SIZE},
+    token_info::TokenInfoFetching,
+    token_list::AutoUpdatingTokenList,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::Duration,
+};
+use tracing::Instrument;
+
+/// How often the database is checked for a new auction.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct RunLoop {
+    pub database: Postgres,
+    pub drivers: Vec<Driver>,
+    pub current_block: CurrentBlockStream,
+    /// How long drivers have to respond to a solve request.
+    pub solve_deadline: Duration,
+    pub web3: Web3,
+    pub settlement: H160,
+    pub gas_price: Arc<dyn GasPriceEstimating>,
+    pub token_info: Arc<dyn TokenInfoFetching>,
+    /// Tokens the settlement contract is willing to hold.
+    pub trusted_tokens: AutoUpdatingTokenList,
+}
+
+impl RunLoop {
+    pub async fn run_forever(self) -> ! {
+        let mut last_auction_id = None;
+        loop {
+            match self.database.most_recent_auction().await {
+                Ok(Some(auction)) if Some(auction.id) != last_auction_id => {
+                    let id = auction.id;
+                    last_auction_id = Some(id);
+                    self.single_run(auction)
+                        .instrument(tracing::info_span!("auction", id))
+                        .await;
+                }
+                Ok(_) => (),
+                Err(err) => tracing::error!(?err, "failed to load current auction"),
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    async fn single_run(&self, AuctionWithId { id, auction }: AuctionWithId) {
+        if auction.orders.is_empty() {
+            tracing::debug!("skipping empty auction");
+            return;
+        }
+
+        let deadline = Utc::now() + chrono::Duration::from_std(self.solve_deadline).unwrap();
+        let request = match self.solve_request(id, &auction, deadline).await {
+            Ok(request) => request,
+            Err(err) => {
+                tracing::warn!(?err, "failed to create solve request");
+                return;
+            }
+        };
+        let solutions = self.solve(&request).await;
+        let ranking = rank(&solutions);
+        let (winner, _) = match ranking.last() {
+            Some(winner) => *winner,
+            None => {
+                tracing::info!("no solutions for auction");
+                return;
+            }
+        };
+
+        let driver = &self.drivers[winner];
+        let solution_id = solutions
+            .iter()
+            .find(|(i, _)| *i == winner)
+            .map(|(_, response)| response.id.clone())
+            .expect("winner was ranked");
+        tracing::info!(driver = %driver.name, "executing winning solution");
+        let execution = match driver.execute(&execute::Request { solution_id }).await {
+            Ok(execution) => execution,
+            Err(err) => {
+                tracing::warn!(driver = %driver.name, ?err, "failed to execute solution");
+                return;
+            }
+        };
+
+        let competition = self.competition(id, &auction, &ranking, execution);
+        if let Err(err) = self.database.save_competition(&competition).await {
+            tracing::error!(?err, "failed to save competition");
+        }
+    }
+
+    async fn solve_request(
+        &self,
+        id: AuctionId,
+        auction: &Auction,
+        deadline: chrono::DateTime<Utc>,
+    ) -> anyhow::Result<solve::Request> {
+        let gas_price = self
+            .gas_price
+            .estimate()
+            .await
+            .context("gas price")?
+            .effective_gas_price();
+        Ok(solve::Request {
+            id: Some(id),
+            tokens: self.tokens(auction).await,
+            orders: auction
+                .orders
+                .iter()
+                .map(|order| {
+                    let reward = auction.rewards.get(&order.metadata.uid).copied();
+                    solve_order(order, reward.unwrap_or(0.))
+                })
+                .collect(),
+            effective_gas_price: U256::from_f64_lossy(gas_price),
+            deadline,
+        })
+    }
+
+    /// Collects the information drivers need about the tokens traded in the auction.
+    async fn tokens(&self, auction: &Auction) -> BTreeMap<H160, solve::Token> {
+        let addresses: Vec<_> = auction
+            .orders
+            .iter()
+            .flat_map(|order| [order.data.sell_token, order.data.buy_token])
+            .chain(auction.prices.keys().copied())
+            .unique()
+            .collect();
+        let infos = self.token_info.get_token_infos(&addresses).await;
+        let mut batch = Web3CallBatch::new(self.web3.transport().clone());
+        let balances: Vec<_> = addresses
+            .iter()
+            .map(|token| {
+                ERC20::at(&self.web3, *token)
+                    .balance_of(self.settlement)
+                    .batch_call(&mut batch)
+            })
+            .collect();
+        batch.execute_all(MAX_BATCH_SIZE).await;
+        let balances = futures::future::join_all(balances).await;
+        let trusted = self.trusted_tokens.addresses();
+
+        addresses
+            .into_iter()
+            .zip(balances)
+            .map(|(address, balance)| {
+                let info = infos.get(&address).cloned().unwrap_or_default();
+                let available_balance = balance.unwrap_or_else(|err| {
+                    tracing::debug!(
+                        ?address,
+                        ?err,
+                        "failed to fetch settlement contract balance"
+                    );
+                    U256::zero()
+                });
+                let token = solve::Token {
+                    decimals: info.decimals,
+                    symbol: info.symbol,
+                    reference_price: auction.prices.get(&address).copied(),
+                    available_balance,
+                    trusted: trusted.contains(&address),
+                };
+                (address, token)
+            })
+            .collect()
+    }
+
+    /// Returns the responses of all drivers that answered in time by driver index.
+    async fn solve(&self, request: &solve::Request) -> Vec<(usize, solve::Response)> {
+        let futures = self
+            .drivers
+            .iter()
+            .enumerate()
+            .map(|(i, driver)| async move {
+                let result = tokio::time::timeout(self.solve_deadline, driver.solve(request))
+                    .await
+                    .context("timeout")
+                    .and_then(|result| result);
+                match result {
+                    Ok(response) => Some((i, response)),
+                    Err(err) => {
+                        tracing::warn!(driver = %driver.name, ?err, "solve error");
+                        None
+                    }
+                }
+            });
+        futures::future::join_all(futures)
+            .await
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    fn competition(
+        &self,
+        id: AuctionId,
+        auction: &Auction,
+        ranking: &[(usize, f64)],
+        execution: execute::Response,
+    ) -> solver_competition::Request {
+        let winner = ranking.last().map(|(i, _)| *i);
+        let solutions = ranking
+            .iter()
+            .map(|(i, score)| {
+                let mut solution = SolverSettlement {
+                    solver: self.drivers[*i].name.clone(),
+                    objective: Objective {
+                        total: *score,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                };
+                // Only the winner reveals the details of its solution.
+                if Some(*i) == winner {
+                    solution.clearing_prices = execution.clearing_prices.clone();
+                    solution.orders = execution
+                        .trades
+                        .iter()
+                        .map(|trade| solver_competition::Order {
+                            id: trade.uid,
+                            executed_amount: trade.executed_amount,
+                        })
+                        .collect();
+                    solution.call_data = execution.calldata.clone();
+                }
+                solution
+            })
+            .collect();
+
+        let orders: HashMap<_, _> = auction
+            .orders
+            .iter()
+            .map(|order| (order.metadata.uid, order))
+            .collect();
+        let executions = execution
+            .trades
+            .iter()
+            .map(|trade| {
+                let surplus_fee = match orders.get(&trade.uid).map(|order| &order.metadata.class) {
+                    Some(OrderClass::Limit(limit)) => limit.surplus_fee,
+                    _ => None,
+                };
+                let execution = Execution {
+                    reward: auction.rewards.get(&trade.uid).copied().unwrap_or(0.),
+                    surplus_fee,
+                };
+                (trade.uid, execution)
+            })
+            .collect();
+
+        solver_competition::Request {
+            auction: id,
+            transaction: solver_competition::Transaction {
+                account: execution.account,
+                nonce: execution.nonce,
+            },
+            competition: SolverCompetitionDB {
+                // Drivers don't report the gas price and liquidity they solved with.
+                gas_price: 0.,
+                auction_start_block: auction.block,
+                liquidity_collected_block: auction.block,
+                competition_simulation_block: self.current_block.borrow().number,
+                auction: CompetitionAuction {
+                    orders: auction
+                        .orders
+                        .iter()
+                        .map(|order| order.metadata.uid)
+                        .collect(),
+                    prices: auction.prices.clone(),
+                },
+                solutions,
+            },
+            executions,
+        }
+    }
+}
+
+fn solve_order(order: &Order, reward: f64) -> solve::Order {
+    let interactions = |interactions: &[model::interaction::InteractionData]| {
+        interactions
+            .iter()
+            .map(|interaction| solve::Interaction {
+                target: interaction.target,
+                value: interaction.value,
+                call_data: interaction.call_data.clone(),
+            })
+            .collect()
+    };
+    let executed = match (order.data.partially_fillable, order.data.kind) {
+        (false, _) => U256::zero(),
+        (true, OrderKind::Sell) => order.metadata.executed_sell_amount_before_fees,
+        (true, OrderKind::Buy) => {
+            big_uint_to_u256(&order.metadata.executed_buy_amount).unwrap_or(U256::MAX)
+        }
+    };
+    let (class, surplus_fee) = match &order.metadata.class {
+        OrderClass::Market => (solve::Class::Market, None),
+        OrderClass::Liquidity => (solve::Class::Liquidity, None),
+        OrderClass::Limit(limit) => (solve::Class::Limit, limit.surplus_fee),
+    };
+    solve::Order {
+        uid: order.metadata.uid,
+        sell_token: order.data.sell_token,
+        buy_token: order.data.buy_token,
+        sell_amount: order.data.sell_amount,
+        buy_amount: order.data.buy_amount,
+        solver_fee: order.metadata.full_fee_amount,
+        user_fee: order.data.fee_amount,
+        valid_to: order.data.valid_to,
+        kind: order.data.kind,
+        receiver: order.data.receiver,
+        owner: order.metadata.owner,
+        partially_fillable: order.data.partially_fillable,
+        executed,
+        pre_interactions: interactions(&order.interactions.pre),
+        post_interactions: interactions(&order.interactions.post),
+        sell_token_balance: order.data.sell_token_balance,
+        buy_token_balance: order.data.buy_token_balance,
+        class,
+        surplus_fee,
+        app_data: order.data.app_data,
+        reward,
+        signing_scheme: order.signature.scheme(),
+        signature: order.signature.to_bytes(),
+    }
+}
+
+/// Orders the proposed solutions by ascending score so that the winner comes last. Responses
+/// with an invalid score are dropped.
+fn rank(solutions: &[(usize, solve::Response)]) -> Vec<(usize, f64)> {
+    let mut ranking: Vec<_> = solutions
+        .iter()
+        .map(|(i, response)| (*i, response.score))
+        .filter(|(_, score)| score.is_finite())
+        .collect();
+    ranking.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+    ranking
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use model::order::{LimitOrderClass, OrderData, OrderMetadata, OrderUid};
+
+    #[test]
+    fn ranks_solutions_by_score() {
+        let response = |score| solve::Response {
+            id: Default::default(),
+            score,
+        };
+        let solutions = [
+            (0, response(2.)),
+            (1, response(f64::INFINITY)),
+            (2, response(3.)),
+            (3, response(f64::NAN)),
+            (4, response(-1.)),
+        ];
+        assert_eq!(rank(&solutions), vec![(4, -1.), (0, 2.), (2, 3.)]);
+        assert!(rank(&[]).is_empty());
+    }
+
+    #[test]
+    fn converts_orders() {
+        let order = Order {
+            metadata: OrderMetadata {
+                uid: OrderUid([1; 56]),
+                full_fee_amount: 5.into(),
+                executed_sell_amount_before_fees: 7.into(),
+                class: OrderClass::Limit(LimitOrderClass {
+                    surplus_fee: Some(3.into()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            data: OrderData {
+                kind: OrderKind::Sell,
+                partially_fillable: true,
+                fee_amount: 1.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let converted = solve_order(&order, 0.5);
+        assert_eq!(converted.uid, OrderUid([1; 56]));
+        assert_eq!(converted.solver_fee, 5.into());
+        assert_eq!(converted.user_fee, 1.into());
+        assert_eq!(converted.executed, 7.into());
+        assert!(matches!(converted.class, solve::Class::Limit));
+        assert_eq!(converted.surplus_fee, Some(3.into()));
+        assert_eq!(converted.reward, 0.5);
+
+        let order = Order {
+            data: OrderData {
+                partially_fillable: false,
+                ..order.data
+            },
+            ..order
+        };
+        assert_eq!(solve_order(&order, 0.).executed, U256::zero());
+    }
+}
@@ -4,7 +4,7 @@ use bigdecimal::BigDecimal;
 use chrono::Utc;
 use itertools::Itertools;
 use model::{
-    auction::Auction,
+    auction::{Auction, AuctionId},
     order::{Order, OrderClass, OrderUid},
     signature::Signature,
     time::now_in_epoch_seconds,
@@ -74,6 +74,8 @@ type Balances = HashMap<Query, U256>;
 struct Inner {
     orders: SolvableOrders,
     balances: Balances,
+    /// The auction states of orders as they were last stored in the database.
+    order_states: OrderStates,
 }
 
 #[derive(Clone, Debug)]
@@ -115,6 +117,7 @@ impl SolvableOrdersCache {
                     block: 0,
                 },
                 balances: Default::default(),
+                order_states: Default::default(),
             }),
             native_price_estimator,
             signature_validator,
@@ -192,8 +195,12 @@ impl SolvableOrdersCache {
 
         let mut orders = solvable_orders(orders, &new_balances, self.ethflow_contract_address);
         for order in &mut orders {
-            let query = Query::from_order(order);
-            order.metadata.available_balance = new_balances.get(&query).copied();
+            // Partially fillable orders may already have been assigned only a part of the
+            // balance.
+            if order.metadata.available_balance.is_none() {
+                let query = Query::from_order(order);
+                order.metadata.available_balance = new_balances.get(&query).copied();
+            }
         }
         counter.checkpoint("insufficient_balance", &orders);
 
@@ -237,7 +244,10 @@ impl SolvableOrdersCache {
             prices,
             rewards,
         };
-        let _id = self.database.replace_current_auction(&auction).await?;
+        let id = self.database.replace_current_auction(&auction).await?;
+
+        let order_states = counter.record(&auction.orders);
+        let order_states = self.store_order_state_changes(id, order_states).await;
 
         *self.cache.lock().unwrap() = Inner {
             orders: SolvableOrders {
@@ -247,9 +257,9 @@ impl SolvableOrdersCache {
                 block,
             },
             balances: new_balances,
+            order_states,
         };
 
-        counter.record(&auction.orders);
         tracing::debug!(
             "updated auction with {} solvable orders",
             auction.orders.len(),
@@ -261,6 +271,53 @@ impl SolvableOrdersCache {
     pub fn last_update_time(&self) -> Instant {
         self.cache.lock().unwrap().orders.update_time
     }
+
+    /// Stores which orders changed their auction state with the given auction. Returns the order
+    /// states that are now stored in the database.
+    async fn store_order_state_changes(
+        &self,
+        auction_id: AuctionId,
+        order_states: OrderStates,
+    ) -> OrderStates {
+        let previous = std::mem::take(&mut self.cache.lock().unwrap().order_states);
+        let changes = order_state_changes(&previous, &order_states);
+        match self
+            .database
+            .store_order_auction_states(auction_id, &changes)
+            .await
+        {
+            Ok(()) => order_states,
+            Err(err) => {
+                // Keep the old states so that the changes get stored with the next auction.
+                tracing::warn!(?err, "failed to store order auction states");
+                previous
+            }
+        }
+    }
+}
+
+/// The auction state of orders. `None` if the order was part of the auction and the reason it was
+/// filtered out otherwise.
+type OrderStates = HashMap<OrderUid, Option<Reason>>;
+
+/// The filter reason for orders that are no longer returned by the database as candidates for the
+/// auction. For example because they got filled, cancelled or expired.
+const NOT_SOLVABLE: Reason = "not_solvable";
+
+/// Computes the orders whose auction state is different from the previous auction.
+fn order_state_changes(
+    previous: &OrderStates,
+    current: &OrderStates,
+) -> Vec<(OrderUid, Option<Reason>)> {
+    let changed = current
+        .iter()
+        .filter(|(uid, state)| previous.get(uid) != Some(state))
+        .map(|(uid, state)| (*uid, *state));
+    let removed = previous
+        .keys()
+        .filter(|uid| !current.contains_key(uid))
+        .map(|uid| (*uid, Some(NOT_SOLVABLE)));
+    changed.chain(removed).collect()
 }
 
 /// Filters all orders whose owners are in the set of "banned" users.
@@ -363,11 +420,6 @@ fn solvable_orders(
                 result.push(order);
                 continue;
             }
-            // TODO: This is overly pessimistic for partially filled orders where the needed balance
-            // is lower. For partially fillable orders that cannot be fully filled because of the
-            // balance we could also give them as much balance as possible instead of skipping. For
-            // that we first need a way to communicate this to the solver. We could repurpose
-            // availableBalance for this.
             let needed_balance = match max_transfer_out_amount(&order) {
                 // Should only ever happen if a partially fillable order has been filled completely
                 Ok(balance) if balance.is_zero() => continue,
@@ -389,6 +441,14 @@ fn solvable_orders(
             if let Some(balance) = remaining_balance.checked_sub(needed_balance) {
                 remaining_balance = balance;
                 result.push(order);
+            } else if order.data.partially_fillable && !remaining_balance.is_zero() {
+                // Partially fillable orders that cannot be fully filled because of the balance
+                // get as much of the balance as is left. This is communicated to the solvers
+                // through the order's available balance so they can scale the order down.
+                let mut order = order;
+                order.metadata.available_balance = Some(remaining_balance);
+                remaining_balance = U256::zero();
+                result.push(order);
             }
         }
     }
@@ -576,6 +636,8 @@ struct OrderFilterCounter {
     orders: HashMap<OrderUid, OrderClass>,
     /// Running tally for counts of filtered orders.
     counts: HashMap<Reason, usize>,
+    /// Why individual orders were filtered.
+    reasons: HashMap<OrderUid, Reason>,
 }
 
 type Reason = &'static str;
@@ -603,6 +665,7 @@ impl OrderFilterCounter {
                 .map(|order| (order.metadata.uid, order.metadata.class.clone()))
                 .collect(),
             counts: HashMap::new(),
+            reasons: HashMap::new(),
         }
     }
 
@@ -619,12 +682,14 @@ impl OrderFilterCounter {
         *self.counts.entry(reason).or_default() += filtered_orders.len();
         for order in filtered_orders {
             self.orders.remove(&order).unwrap();
+            self.reasons.insert(order, reason);
             tracing::debug!(%order, %reason, "filtered order")
         }
     }
 
-    /// Records the filter counter to metrics.
-    fn record(mut self, orders: &[Order]) {
+    /// Records the filter counter to metrics and returns the auction state of all candidate
+    /// orders.
+    fn record(mut self, orders: &[Order]) -> OrderStates {
         if self.orders.len() != orders.len() {
             self.checkpoint("other", orders);
         }
@@ -646,6 +711,16 @@ impl OrderFilterCounter {
                 .with_label_values(&[reason])
                 .set(count as _);
         }
+
+        self.orders
+            .into_keys()
+            .map(|uid| (uid, None))
+            .chain(
+                self.reasons
+                    .into_iter()
+                    .map(|(uid, reason)| (uid, Some(reason))),
+            )
+            .collect()
     }
 }
 
@@ -705,6 +780,58 @@ mod tests {
         assert_eq!(orders_, orders[1..]);
     }
 
+    #[tokio::test]
+    async fn partially_fillable_orders_get_remaining_balance() {
+        let orders = vec![
+            Order {
+                data: OrderData {
+                    sell_amount: 3.into(),
+                    fee_amount: 3.into(),
+                    ..Default::default()
+                },
+                metadata: OrderMetadata {
+                    creation_date: DateTime::from_utc(NaiveDateTime::from_timestamp(2, 0), Utc),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Order {
+                data: OrderData {
+                    sell_amount: 2.into(),
+                    fee_amount: 2.into(),
+                    partially_fillable: true,
+                    ..Default::default()
+                },
+                metadata: OrderMetadata {
+                    creation_date: DateTime::from_utc(NaiveDateTime::from_timestamp(1, 0), Utc),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Order {
+                data: OrderData {
+                    sell_amount: 1.into(),
+                    partially_fillable: true,
+                    ..Default::default()
+                },
+                metadata: OrderMetadata {
+                    creation_date: DateTime::from_utc(NaiveDateTime::from_timestamp(0, 0), Utc),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ];
+
+        let balances = hashmap! {Query::from_order(&orders[0]) => U256::from(8)};
+        let orders_ = solvable_orders(orders.clone(), &balances, None);
+        // The first order takes 6 of the balance, the partially fillable second order gets the
+        // remaining 2 and there is nothing left for the third order.
+        assert_eq!(orders_.len(), 2);
+        assert_eq!(orders_[0], orders[0]);
+        assert_eq!(orders_[1].data, orders[1].data);
+        assert_eq!(orders_[1].metadata.available_balance, Some(2.into()));
+    }
+
     #[tokio::test]
     async fn do_not_filters_insufficient_balances_for_ethflow_orders() {
         let ethflow_address = H160([3u8; 20]);
@@ -1202,4 +1329,58 @@ mod tests {
             valid_orders,
         );
     }
+
+    #[test]
+    fn order_filter_counter_records_order_states() {
+        let metrics = Metrics::instance(global_metrics::get_metric_storage_registry()).unwrap();
+        let order = |byte: u8| Order {
+            metadata: OrderMetadata {
+                uid: OrderUid([byte; 56]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let orders = vec![order(1), order(2), order(3)];
+
+        let mut counter = OrderFilterCounter::new(metrics, &orders);
+        counter.checkpoint("banned_user", &orders[1..]);
+        counter.checkpoint("insufficient_balance", &orders[2..]);
+        let states = counter.record(&orders[2..]);
+
+        assert_eq!(
+            states,
+            hashmap! {
+                OrderUid([1; 56]) => Some("banned_user"),
+                OrderUid([2; 56]) => Some("insufficient_balance"),
+                OrderUid([3; 56]) => None,
+            }
+        );
+    }
+
+    #[test]
+    fn computes_order_state_changes() {
+        let uid = |byte: u8| OrderUid([byte; 56]);
+        let previous = hashmap! {
+            uid(1) => None,
+            uid(2) => Some("insufficient_balance"),
+            uid(3) => None,
+        };
+        let current = hashmap! {
+            uid(1) => None,
+            uid(2) => None,
+            uid(4) => Some("missing_price"),
+        };
+
+        let changes = order_state_changes(&previous, &current)
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+        assert_eq!(
+            changes,
+            hashmap! {
+                uid(2) => None,
+                uid(3) => Some(NOT_SOLVABLE),
+                uid(4) => Some("missing_price"),
+            }
+        );
+    }
 }
@@ -0,0 +1,58 @@
+use crate::AppId;
+use sqlx::PgConnection;
+
+/// Stores the full app data for the hash. Returns whether it was newly inserted, `false` if app
+/// data for the hash already existed.
+pub async fn insert(
+    ex: &mut PgConnection,
+    contract_app_data: &AppId,
+    full_app_data: &[u8],
+) -> Result<bool, sqlx::Error> {
+    const QUERY: &str = r#"
+INSERT INTO app_data (contract_app_data, full_app_data)
+VALUES ($1, $2)
+ON CONFLICT DO NOTHING
+    ;"#;
+    let result = sqlx::query(QUERY)
+        .bind(contract_app_data)
+        .bind(full_app_data)
+        .execute(ex)
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+pub async fn fetch(
+    ex: &mut PgConnection,
+    contract_app_data: &AppId,
+) -> Result<Option<Vec<u8>>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT full_app_data
+FROM app_data
+WHERE contract_app_data = $1
+    ;"#;
+    sqlx::query_scalar(QUERY)
+        .bind(contract_app_data)
+        .fetch_optional(ex)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::byte_array::ByteArray;
+    use sqlx::Connection;
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_roundtrip() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let hash = ByteArray([1; 32]);
+        assert_eq!(fetch(&mut db, &hash).await.unwrap(), None);
+        assert!(insert(&mut db, &hash, b"{}").await.unwrap());
+        assert!(!insert(&mut db, &hash, b"{}").await.unwrap());
+        assert_eq!(fetch(&mut db, &hash).await.unwrap(), Some(b"{}".to_vec()));
+    }
+}
@@ -1,6 +1,7 @@
 // https://github.com/rust-lang/rust-clippy/issues/9782
 #![allow(clippy::needless_borrow)]
 
+pub mod app_data;
 pub mod auction;
 pub mod auction_transaction;
 pub mod byte_array;
@@ -8,6 +9,8 @@ pub mod ethflow_orders;
 pub mod events;
 pub mod onchain_broadcasted_orders;
 pub mod onchain_invalidations;
+pub mod order_auction_states;
+pub mod order_events;
 pub mod order_execution;
 pub mod orders;
 pub mod quotes;
@@ -52,6 +55,8 @@ pub const ALL_TABLES: &[&str] = &[
     "interactions",
     "auction_transaction",
     "ethflow_refunds",
+    "order_auction_states",
+    "app_data",
 ];
 
 /// Delete all data in the database. Only used by tests.
@@ -0,0 +1,92 @@
+use crate::{auction::AuctionId, OrderUid};
+use sqlx::PgConnection;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct OrderAuctionState {
+    pub order_uid: OrderUid,
+    /// The first auction in which the order had this state.
+    pub auction_id: AuctionId,
+    /// Why the order was not part of the auction. `None` if it was.
+    pub filter_reason: Option<String>,
+}
+
+pub async fn insert(
+    ex: &mut PgConnection,
+    states: &[OrderAuctionState],
+) -> Result<(), sqlx::Error> {
+    for state in states {
+        insert_one(ex, state).await?;
+    }
+    Ok(())
+}
+
+async fn insert_one(ex: &mut PgConnection, state: &OrderAuctionState) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+INSERT INTO order_auction_states (order_uid, auction_id, filter_reason)
+VALUES ($1, $2, $3)
+ON CONFLICT DO NOTHING
+    ;"#;
+    sqlx::query(QUERY)
+        .bind(state.order_uid)
+        .bind(state.auction_id)
+        .bind(state.filter_reason.as_deref())
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
+/// All state changes of an order ordered by auction.
+pub async fn load(
+    ex: &mut PgConnection,
+    order_uid: &OrderUid,
+) -> Result<Vec<OrderAuctionState>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT order_uid, auction_id, filter_reason
+FROM order_auction_states
+WHERE order_uid = $1
+ORDER BY auction_id
+    ;"#;
+    sqlx::query_as(QUERY).bind(order_uid).fetch_all(ex).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::byte_array::ByteArray;
+    use sqlx::Connection;
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_roundtrip() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let state =
+            |byte: u8, auction_id: AuctionId, filter_reason: Option<&str>| OrderAuctionState {
+                order_uid: ByteArray([byte; 56]),
+                auction_id,
+                filter_reason: filter_reason.map(str::to_owned),
+            };
+        insert(
+            &mut db,
+            &[
+                state(1, 2, Some("insufficient_balance")),
+                state(1, 1, None),
+                state(2, 1, None),
+            ],
+        )
+        .await
+        .unwrap();
+        // Duplicates are ignored.
+        insert(&mut db, &[state(1, 1, Some("banned_user"))])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            load(&mut db, &ByteArray([1; 56])).await.unwrap(),
+            vec![state(1, 1, None), state(1, 2, Some("insufficient_balance"))]
+        );
+        assert!(load(&mut db, &ByteArray([3; 56])).await.unwrap().is_empty());
+    }
+}
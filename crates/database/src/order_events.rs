@@ -0,0 +1,422 @@
+//! Queries for changes in the lifecycle of orders.
+//!
+//! There is no dedicated table for order events. Instead they are derived from the data that order
+//! placement and the event indexing already store. Queries are cursor based so that callers can
+//! repeatedly ask for everything that happened since their last query.
+
+use crate::{Address, OrderUid};
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+
+/// An order event that happened at a point in time.
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct TimestampedOrderEvent {
+    pub order_uid: OrderUid,
+    pub owner: Address,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// An order event that was emitted onchain.
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct OnchainOrderEvent {
+    pub order_uid: OrderUid,
+    pub owner: Address,
+    pub block_number: i64,
+}
+
+/// An order event without any associated cursor.
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct OrderEvent {
+    pub order_uid: OrderUid,
+    pub owner: Address,
+}
+
+// The user that placed an order. For onchain orders this is the sender of the placement
+// transaction instead of the contract owning the order.
+const OWNER: &str = "COALESCE((SELECT onchain_o.sender FROM onchain_placed_orders onchain_o WHERE \
+                     onchain_o.uid = o.uid), o.owner) AS owner";
+
+/// Orders that were created through the API after the specified timestamp. Onchain orders are
+/// timestamped with their block which can be older than orders stored before them, so they are
+/// returned by [`placed_onchain_after_block`] instead.
+pub async fn created_after(
+    ex: &mut PgConnection,
+    after: DateTime<Utc>,
+) -> Result<Vec<TimestampedOrderEvent>, sqlx::Error> {
+    const QUERY: &str = const_format::concatcp!(
+        "SELECT o.uid AS order_uid, ",
+        OWNER,
+        ", o.creation_timestamp AS timestamp",
+        " FROM orders o",
+        " WHERE o.creation_timestamp > $1",
+        " AND NOT EXISTS (SELECT 1 FROM onchain_placed_orders onchain_o WHERE onchain_o.uid = \
+         o.uid)",
+        " ORDER BY o.creation_timestamp",
+    );
+    sqlx::query_as(QUERY).bind(after).fetch_all(ex).await
+}
+
+/// Orders that were cancelled through the API after the specified timestamp.
+pub async fn cancelled_after(
+    ex: &mut PgConnection,
+    after: DateTime<Utc>,
+) -> Result<Vec<TimestampedOrderEvent>, sqlx::Error> {
+    const QUERY: &str = const_format::concatcp!(
+        "SELECT o.uid AS order_uid, ",
+        OWNER,
+        ", o.cancellation_timestamp AS timestamp",
+        " FROM orders o",
+        " WHERE o.cancellation_timestamp > $1",
+        " ORDER BY o.cancellation_timestamp",
+    );
+    sqlx::query_as(QUERY).bind(after).fetch_all(ex).await
+}
+
+/// Orders that were placed onchain in a block after the specified one.
+pub async fn placed_onchain_after_block(
+    ex: &mut PgConnection,
+    block_number: i64,
+) -> Result<Vec<OnchainOrderEvent>, sqlx::Error> {
+    const QUERY: &str = const_format::concatcp!(
+        "SELECT o.uid AS order_uid, ",
+        OWNER,
+        ", p.block_number",
+        " FROM onchain_placed_orders p",
+        " JOIN orders o ON o.uid = p.uid",
+        " WHERE p.block_number > $1 AND NOT p.is_reorged",
+        " ORDER BY p.block_number, p.log_index",
+    );
+    sqlx::query_as(QUERY).bind(block_number).fetch_all(ex).await
+}
+
+/// Orders that got a confirmed presignature in a block after the specified one.
+pub async fn presigned_after_block(
+    ex: &mut PgConnection,
+    block_number: i64,
+) -> Result<Vec<OnchainOrderEvent>, sqlx::Error> {
+    const QUERY: &str = const_format::concatcp!(
+        "SELECT o.uid AS order_uid, ",
+        OWNER,
+        ", p.block_number",
+        " FROM presignature_events p",
+        " JOIN orders o ON o.uid = p.order_uid",
+        " WHERE p.block_number > $1 AND p.signed",
+        " ORDER BY p.block_number, p.log_index",
+    );
+    sqlx::query_as(QUERY).bind(block_number).fetch_all(ex).await
+}
+
+/// Orders that traded in a block after the specified one.
+pub async fn traded_after_block(
+    ex: &mut PgConnection,
+    block_number: i64,
+) -> Result<Vec<OnchainOrderEvent>, sqlx::Error> {
+    const QUERY: &str = const_format::concatcp!(
+        "SELECT o.uid AS order_uid, ",
+        OWNER,
+        ", t.block_number",
+        " FROM trades t",
+        " JOIN orders o ON o.uid = t.order_uid",
+        " WHERE t.block_number > $1",
+        " ORDER BY t.block_number, t.log_index",
+    );
+    sqlx::query_as(QUERY).bind(block_number).fetch_all(ex).await
+}
+
+/// Orders that were invalidated onchain in a block after the specified one. This includes
+/// invalidations through the settlement contract and through the onchain order contracts.
+pub async fn invalidated_after_block(
+    ex: &mut PgConnection,
+    block_number: i64,
+) -> Result<Vec<OnchainOrderEvent>, sqlx::Error> {
+    #[rustfmt::skip]
+    const QUERY: &str = const_format::concatcp!(
+"SELECT * FROM (",
+    "SELECT o.uid AS order_uid, ", OWNER, ", i.block_number, i.log_index",
+    " FROM invalidations i",
+    " JOIN orders o ON o.uid = i.order_uid",
+    " WHERE i.block_number > $1",
+" UNION ALL ",
+    "SELECT o.uid AS order_uid, ", OWNER, ", i.block_number, i.log_index",
+    " FROM onchain_order_invalidations i",
+    " JOIN orders o ON o.uid = i.uid",
+    " WHERE i.block_number > $1",
+") AS invalidated",
+" ORDER BY block_number, log_index",
+    );
+    sqlx::query_as(QUERY).bind(block_number).fetch_all(ex).await
+}
+
+/// Orders whose validity ended in the time range `(after, until]` (in unix seconds) without being
+/// fulfilled or cancelled.
+pub async fn expired_between(
+    ex: &mut PgConnection,
+    after: i64,
+    until: i64,
+) -> Result<Vec<OrderEvent>, sqlx::Error> {
+    // For ethflow orders the user valid to is stored separately because the valid to of the
+    // order itself is always `u32::MAX`.
+    #[rustfmt::skip]
+    const QUERY: &str = const_format::concatcp!(
+"SELECT o.uid AS order_uid, ", OWNER,
+" FROM orders o",
+" LEFT OUTER JOIN ethflow_orders eth_o ON eth_o.uid = o.uid",
+" WHERE COALESCE(eth_o.valid_to, o.valid_to) > $1",
+" AND COALESCE(eth_o.valid_to, o.valid_to) <= $2",
+r#"
+AND o.cancellation_timestamp IS NULL
+AND NOT EXISTS (SELECT 1 FROM invalidations i WHERE i.order_uid = o.uid)
+AND NOT EXISTS (SELECT 1 FROM onchain_order_invalidations i WHERE i.uid = o.uid)
+AND CASE o.kind
+    WHEN 'sell' THEN (
+        SELECT COALESCE(SUM(t.sell_amount - t.fee_amount), 0) FROM trades t
+        WHERE t.order_uid = o.uid
+    ) < o.sell_amount
+    WHEN 'buy' THEN (
+        SELECT COALESCE(SUM(t.buy_amount), 0) FROM trades t
+        WHERE t.order_uid = o.uid
+    ) < o.buy_amount
+END
+ORDER BY COALESCE(eth_o.valid_to, o.valid_to)
+"#
+    );
+    sqlx::query_as(QUERY)
+        .bind(after)
+        .bind(until)
+        .fetch_all(ex)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        byte_array::ByteArray,
+        events::{Event, EventIndex, Invalidation, PreSignature, Trade},
+        onchain_broadcasted_orders::{insert_onchain_order, OnchainOrderPlacement},
+        orders::{cancel_order, insert_order, Order, OrderKind},
+    };
+    use chrono::NaiveDateTime;
+    use sqlx::Connection;
+
+    fn timestamp(seconds: i64) -> DateTime<Utc> {
+        DateTime::from_utc(NaiveDateTime::from_timestamp(seconds, 0), Utc)
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_created_and_cancelled_orders() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let order = |byte: u8, creation: i64| Order {
+            uid: ByteArray([byte; 56]),
+            owner: ByteArray([byte; 20]),
+            creation_timestamp: timestamp(creation),
+            ..Default::default()
+        };
+        insert_order(&mut db, &order(1, 1)).await.unwrap();
+        insert_order(&mut db, &order(2, 2)).await.unwrap();
+        insert_order(&mut db, &order(3, 3)).await.unwrap();
+
+        let created = created_after(&mut db, timestamp(1)).await.unwrap();
+        assert_eq!(
+            created,
+            vec![
+                TimestampedOrderEvent {
+                    order_uid: ByteArray([2; 56]),
+                    owner: ByteArray([2; 20]),
+                    timestamp: timestamp(2),
+                },
+                TimestampedOrderEvent {
+                    order_uid: ByteArray([3; 56]),
+                    owner: ByteArray([3; 20]),
+                    timestamp: timestamp(3),
+                },
+            ]
+        );
+
+        // Onchain orders are returned by block instead and report the user placing the order as
+        // the owner.
+        insert_onchain_order(
+            &mut db,
+            &EventIndex {
+                block_number: 1,
+                log_index: 0,
+            },
+            &OnchainOrderPlacement {
+                order_uid: ByteArray([3; 56]),
+                sender: ByteArray([4; 20]),
+                placement_error: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert!(created_after(&mut db, timestamp(2))
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            placed_onchain_after_block(&mut db, 0).await.unwrap(),
+            vec![OnchainOrderEvent {
+                order_uid: ByteArray([3; 56]),
+                owner: ByteArray([4; 20]),
+                block_number: 1,
+            }]
+        );
+        assert!(placed_onchain_after_block(&mut db, 1)
+            .await
+            .unwrap()
+            .is_empty());
+
+        assert!(cancelled_after(&mut db, timestamp(0))
+            .await
+            .unwrap()
+            .is_empty());
+        cancel_order(&mut db, &ByteArray([1; 56]), timestamp(5))
+            .await
+            .unwrap();
+        let cancelled = cancelled_after(&mut db, timestamp(4)).await.unwrap();
+        assert_eq!(
+            cancelled,
+            vec![TimestampedOrderEvent {
+                order_uid: ByteArray([1; 56]),
+                owner: ByteArray([1; 20]),
+                timestamp: timestamp(5),
+            }]
+        );
+        assert!(cancelled_after(&mut db, timestamp(5))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_onchain_order_events() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let order = Order::default();
+        insert_order(&mut db, &order).await.unwrap();
+
+        let index = |block_number: i64| EventIndex {
+            block_number,
+            log_index: 0,
+        };
+        crate::events::append(
+            &mut db,
+            &[
+                (
+                    index(1),
+                    Event::PreSignature(PreSignature {
+                        owner: order.owner,
+                        order_uid: order.uid,
+                        signed: true,
+                    }),
+                ),
+                (
+                    index(2),
+                    Event::PreSignature(PreSignature {
+                        owner: order.owner,
+                        order_uid: order.uid,
+                        signed: false,
+                    }),
+                ),
+                (
+                    index(3),
+                    Event::Trade(Trade {
+                        order_uid: order.uid,
+                        ..Default::default()
+                    }),
+                ),
+                (
+                    index(4),
+                    Event::Invalidation(Invalidation {
+                        order_uid: order.uid,
+                    }),
+                ),
+            ],
+        )
+        .await
+        .unwrap();
+
+        let event = |block_number: i64| OnchainOrderEvent {
+            order_uid: order.uid,
+            owner: order.owner,
+            block_number,
+        };
+        assert_eq!(
+            presigned_after_block(&mut db, 0).await.unwrap(),
+            vec![event(1)]
+        );
+        assert!(presigned_after_block(&mut db, 1).await.unwrap().is_empty());
+        assert_eq!(
+            traded_after_block(&mut db, 2).await.unwrap(),
+            vec![event(3)]
+        );
+        assert!(traded_after_block(&mut db, 3).await.unwrap().is_empty());
+        assert_eq!(
+            invalidated_after_block(&mut db, 3).await.unwrap(),
+            vec![event(4)]
+        );
+        assert!(invalidated_after_block(&mut db, 4)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_expired_orders() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let order = |byte: u8, valid_to: i64| Order {
+            uid: ByteArray([byte; 56]),
+            kind: OrderKind::Sell,
+            sell_amount: 10.into(),
+            buy_amount: 10.into(),
+            valid_to,
+            ..Default::default()
+        };
+        insert_order(&mut db, &order(1, 10)).await.unwrap();
+        insert_order(&mut db, &order(2, 20)).await.unwrap();
+        insert_order(&mut db, &order(3, 20)).await.unwrap();
+        insert_order(&mut db, &order(4, 20)).await.unwrap();
+
+        // Fulfilled and cancelled orders don't expire.
+        crate::events::append(
+            &mut db,
+            &[(
+                EventIndex::default(),
+                Event::Trade(Trade {
+                    order_uid: ByteArray([3; 56]),
+                    sell_amount_including_fee: 10.into(),
+                    buy_amount: 10.into(),
+                    fee_amount: 0.into(),
+                }),
+            )],
+        )
+        .await
+        .unwrap();
+        cancel_order(&mut db, &ByteArray([4; 56]), timestamp(0))
+            .await
+            .unwrap();
+
+        async fn expired(ex: &mut PgConnection, after: i64, until: i64) -> Vec<OrderUid> {
+            expired_between(ex, after, until)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|event| event.order_uid)
+                .collect()
+        }
+        assert_eq!(expired(&mut db, 0, 10).await, vec![ByteArray([1; 56])]);
+        assert_eq!(expired(&mut db, 10, 30).await, vec![ByteArray([2; 56])]);
+        assert!(expired(&mut db, 20, 30).await.is_empty());
+    }
+}
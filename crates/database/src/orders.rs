@@ -67,7 +67,19 @@ pub enum BuyTokenDestination {
     Internal,
 }
 
-/// one row in the pre_interaction table
+/// When an interaction of an order is executed relative to the trades of a settlement.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, sqlx::Type)]
+#[sqlx(type_name = "ExecutionTime")]
+#[sqlx(rename_all = "lowercase")]
+pub enum ExecutionTime {
+    /// Interaction executed before the trades, e.g. setting allowances or wrapping ETH
+    #[default]
+    Pre,
+    /// Interaction executed after the trades, e.g. hooks like bridging or staking the proceeds
+    Post,
+}
+
+/// one row in the interactions table
 #[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
 pub struct Interaction {
     pub target: Address,
@@ -103,20 +115,23 @@ pub struct Order {
     pub surplus_fee_timestamp: Option<DateTime<Utc>>,
 }
 
-pub async fn insert_or_overwrite_pre_interactions(
+pub async fn insert_or_overwrite_interactions(
     ex: &mut PgConnection,
-    uid_and_pre_interaction: &[(OrderUid, Interaction)],
+    uid_and_interaction: &[(OrderUid, Interaction)],
+    execution: ExecutionTime,
 ) -> Result<(), sqlx::Error> {
-    for (index, (order_uid, pre_interaction)) in uid_and_pre_interaction.iter().enumerate() {
-        insert_or_overwrite_pre_interaction(ex, index as i64, pre_interaction, order_uid).await?;
+    for (index, (order_uid, interaction)) in uid_and_interaction.iter().enumerate() {
+        insert_or_overwrite_interaction(ex, index as i64, interaction, execution, order_uid)
+            .await?;
     }
     Ok(())
 }
 
-pub async fn insert_or_overwrite_pre_interaction(
+pub async fn insert_or_overwrite_interaction(
     ex: &mut PgConnection,
     index: i64,
-    pre_interaction: &Interaction,
+    interaction: &Interaction,
+    execution: ExecutionTime,
     order_uid: &OrderUid,
 ) -> Result<(), sqlx::Error> {
     const QUERY: &str = r#"
@@ -125,34 +140,41 @@ INSERT INTO interactions (
     index,
     target,
     value,
-    data
+    data,
+    execution
 )
-VALUES ($1, $2, $3, $4, $5)
-ON CONFLICT (order_uid, index) DO UPDATE
+VALUES ($1, $2, $3, $4, $5, $6)
+ON CONFLICT (order_uid, execution, index) DO UPDATE
 SET target = $3,
 value = $4, data = $5
     "#;
     sqlx::query(QUERY)
         .bind(&order_uid)
         .bind(&index)
-        .bind(&pre_interaction.target)
-        .bind(&pre_interaction.value)
-        .bind(&pre_interaction.data)
+        .bind(&interaction.target)
+        .bind(&interaction.value)
+        .bind(&interaction.data)
+        .bind(execution)
         .execute(ex)
         .await?;
     Ok(())
 }
 
-pub async fn read_order_pre_interactions(
+pub async fn read_order_interactions(
     ex: &mut PgConnection,
     id: &OrderUid,
+    execution: ExecutionTime,
 ) -> Result<Vec<Interaction>, sqlx::Error> {
     const QUERY: &str = r#"
 SELECT * FROM interactions
-WHERE order_uid = $1
+WHERE order_uid = $1 AND execution = $2
 ORDER BY index
     "#;
-    sqlx::query_as(QUERY).bind(id).fetch_all(ex).await
+    sqlx::query_as(QUERY)
+        .bind(id)
+        .bind(execution)
+        .fetch_all(ex)
+        .await
 }
 
 pub async fn insert_orders_and_ignore_conflicts(
@@ -395,6 +417,7 @@ pub struct FullOrder {
     pub buy_token_balance: BuyTokenDestination,
     pub presignature_pending: bool,
     pub pre_interactions: Vec<(Address, BigDecimal, Vec<u8>)>,
+    pub post_interactions: Vec<(Address, BigDecimal, Vec<u8>)>,
     pub ethflow_data: Option<(Option<TransactionHash>, i64)>,
     pub onchain_user: Option<Address>,
     pub onchain_placement_error: Option<OnchainOrderPlacementError>,
@@ -435,10 +458,10 @@ impl FullOrder {
 // to get a better idea of what indexes postgres *could* use even if it decides that with the
 // current amount of data this wouldn't be better.
 //
-// The pre_interactions are read as arrays of their fields: target, value, data. This is done
-// as sqlx does not support reading arrays of more complicated types than just one field.
-// The pre_interaction's data of target, value and data are composed to an array of
-// interactions later.
+// The pre and post interactions are read as arrays of their fields: target, value, data. This is
+// done as sqlx does not support reading arrays of more complicated types than just one field.
+// The interaction's data of target, value and data are composed to an array of interactions
+// later.
 const ORDERS_SELECT: &str = r#"
 o.uid, o.owner, o.creation_timestamp, o.sell_token, o.buy_token, o.sell_amount, o.buy_amount,
 o.valid_to, o.app_data, o.fee_amount, o.full_fee_amount, o.kind, o.partially_fillable, o.signature,
@@ -458,7 +481,8 @@ o.class, o.surplus_fee, o.surplus_fee_timestamp,
     ORDER BY p.block_number DESC, p.log_index DESC
     LIMIT 1
 ), true)) AS presignature_pending,
-array(Select (p.target, p.value, p.data) from interactions p where p.order_uid = o.uid order by p.index) as pre_interactions,
+array(Select (p.target, p.value, p.data) from interactions p where p.order_uid = o.uid and p.execution = 'pre' order by p.index) as pre_interactions,
+array(Select (p.target, p.value, p.data) from interactions p where p.order_uid = o.uid and p.execution = 'post' order by p.index) as post_interactions,
 (SELECT (tx_hash, eth_o.valid_to) from ethflow_orders eth_o
     left join ethflow_refunds on ethflow_refunds.order_uid=eth_o.uid
     where eth_o.uid = o.uid limit 1) as ethflow_data,
@@ -570,7 +594,9 @@ const OPEN_ORDERS: &str = const_format::concatcp!(
 r#") AS unfiltered
 WHERE
     CASE kind
-        WHEN 'sell' THEN sum_sell < sell_amount
+        -- The executed sell amount of trades includes fees, but the settlement contract only
+        -- counts the sell amount before fees towards the filled amount of an order.
+        WHEN 'sell' THEN sum_sell - sum_fee < sell_amount
         WHEN 'buy' THEN sum_buy < buy_amount
     END AND
     (NOT invalidated) AND
@@ -869,12 +895,24 @@ mod tests {
             value: BigDecimal::new(10.into(), 1),
             data: vec![0u8, 1u8],
         };
-        insert_or_overwrite_pre_interaction(&mut db, 0, &pre_interaction_1, &order.uid)
-            .await
-            .unwrap();
-        insert_or_overwrite_pre_interaction(&mut db, 1, &pre_interaction_2, &order.uid)
-            .await
-            .unwrap();
+        insert_or_overwrite_interaction(
+            &mut db,
+            0,
+            &pre_interaction_1,
+            ExecutionTime::Pre,
+            &order.uid,
+        )
+        .await
+        .unwrap();
+        insert_or_overwrite_interaction(
+            &mut db,
+            1,
+            &pre_interaction_2,
+            ExecutionTime::Pre,
+            &order.uid,
+        )
+        .await
+        .unwrap();
         let order_ = single_full_order(&mut db, &order.uid)
             .await
             .unwrap()
@@ -905,7 +943,7 @@ mod tests {
                 .map(|v| v.2)
                 .collect::<Vec<Vec<u8>>>()
         );
-        let pre_interactions = read_order_pre_interactions(&mut db, &order.uid)
+        let pre_interactions = read_order_interactions(&mut db, &order.uid, ExecutionTime::Pre)
             .await
             .unwrap();
         assert_eq!(*pre_interactions.get(0).unwrap(), pre_interaction_1);
@@ -916,16 +954,92 @@ mod tests {
             value: BigDecimal::new(100.into(), 1),
             data: vec![0u8, 2u8],
         };
-        insert_or_overwrite_pre_interaction(&mut db, 0, &pre_interaction_overwrite, &order.uid)
-            .await
-            .unwrap();
-        let pre_interactions = read_order_pre_interactions(&mut db, &order.uid)
+        insert_or_overwrite_interaction(
+            &mut db,
+            0,
+            &pre_interaction_overwrite,
+            ExecutionTime::Pre,
+            &order.uid,
+        )
+        .await
+        .unwrap();
+        let pre_interactions = read_order_interactions(&mut db, &order.uid, ExecutionTime::Pre)
             .await
             .unwrap();
         assert_eq!(*pre_interactions.get(0).unwrap(), pre_interaction_overwrite);
         assert_eq!(*pre_interactions.get(1).unwrap(), pre_interaction_2);
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_order_roundtrip_post_interactions() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let order = Order::default();
+        insert_order(&mut db, &order).await.unwrap();
+        let pre_interaction = Interaction {
+            target: ByteArray([1; 20]),
+            value: BigDecimal::new(10.into(), 1),
+            data: vec![0u8, 1u8],
+        };
+        let post_interaction = Interaction {
+            target: ByteArray([2; 20]),
+            value: BigDecimal::default(),
+            data: vec![2u8],
+        };
+        // Pre and post interactions have their own indices.
+        insert_or_overwrite_interaction(
+            &mut db,
+            0,
+            &pre_interaction,
+            ExecutionTime::Pre,
+            &order.uid,
+        )
+        .await
+        .unwrap();
+        insert_or_overwrite_interaction(
+            &mut db,
+            0,
+            &post_interaction,
+            ExecutionTime::Post,
+            &order.uid,
+        )
+        .await
+        .unwrap();
+
+        let order_ = single_full_order(&mut db, &order.uid)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            order_.pre_interactions,
+            vec![(
+                pre_interaction.target,
+                pre_interaction.value.clone(),
+                pre_interaction.data.clone()
+            )]
+        );
+        assert_eq!(
+            order_.post_interactions,
+            vec![(
+                post_interaction.target,
+                post_interaction.value.clone(),
+                post_interaction.data.clone()
+            )]
+        );
+
+        let pre_interactions = read_order_interactions(&mut db, &order.uid, ExecutionTime::Pre)
+            .await
+            .unwrap();
+        assert_eq!(pre_interactions, vec![pre_interaction]);
+        let post_interactions = read_order_interactions(&mut db, &order.uid, ExecutionTime::Post)
+            .await
+            .unwrap();
+        assert_eq!(post_interactions, vec![post_interaction]);
+    }
+
     #[tokio::test]
     #[ignore]
     async fn postgres_insert_same_order_twice_fails() {
@@ -1339,6 +1453,73 @@ mod tests {
         assert!(get_order(&mut db, 2).await.is_none());
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_partially_filled_sell_order_with_fees_is_solvable() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let order = Order {
+            kind: OrderKind::Sell,
+            sell_amount: 10.into(),
+            buy_amount: 100.into(),
+            fee_amount: 2.into(),
+            valid_to: 3,
+            partially_fillable: true,
+            ..Default::default()
+        };
+        insert_order(&mut db, &order).await.unwrap();
+
+        async fn get_order(ex: &mut PgConnection) -> Option<FullOrder> {
+            solvable_orders(ex, 0, Utc::now())
+                .next()
+                .await
+                .transpose()
+                .unwrap()
+        }
+
+        // 9 of the 10 sell tokens are executed plus 2 in fees, so the executed sell amount
+        // including fees exceeds the order's sell amount but the order is not filled yet.
+        crate::events::append(
+            &mut db,
+            &[(
+                EventIndex {
+                    block_number: 0,
+                    log_index: 0,
+                },
+                Event::Trade(Trade {
+                    order_uid: order.uid,
+                    sell_amount_including_fee: 11.into(),
+                    fee_amount: 2.into(),
+                    ..Default::default()
+                }),
+            )],
+        )
+        .await
+        .unwrap();
+        assert!(get_order(&mut db).await.is_some());
+
+        // The remaining sell amount gets executed.
+        crate::events::append(
+            &mut db,
+            &[(
+                EventIndex {
+                    block_number: 1,
+                    log_index: 0,
+                },
+                Event::Trade(Trade {
+                    order_uid: order.uid,
+                    sell_amount_including_fee: 1.into(),
+                    ..Default::default()
+                }),
+            )],
+        )
+        .await
+        .unwrap();
+        assert!(get_order(&mut db).await.is_none());
+    }
+
     type Data = ([u8; 56], Address, DateTime<Utc>);
     async fn user_orders(
         ex: &mut PgConnection,
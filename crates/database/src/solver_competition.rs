@@ -1,6 +1,8 @@
-use crate::{auction::AuctionId, TransactionHash};
+use crate::{auction::AuctionId, OrderUid, TransactionHash};
 use sqlx::{types::JsonValue, PgConnection};
 
+/// Stores the competition of an auction. Both the autopilot and the orderbook (on behalf of the
+/// solver) can report the competition, so an existing entry is overwritten by the latest one.
 pub async fn save(
     ex: &mut PgConnection,
     id: AuctionId,
@@ -9,6 +11,8 @@ pub async fn save(
     const QUERY: &str = r#"
 INSERT INTO solver_competitions (id, json)
 VALUES ($1, $2)
+ON CONFLICT (id) DO UPDATE
+SET json = EXCLUDED.json
     ;"#;
     sqlx::query(QUERY).bind(id).bind(data).execute(ex).await?;
     Ok(())
@@ -55,6 +59,37 @@ WHERE s.tx_hash = $1
     sqlx::query_as(QUERY).bind(tx_hash).fetch_optional(ex).await
 }
 
+#[derive(sqlx::FromRow)]
+pub struct LoadByOrder {
+    pub json: JsonValue,
+    pub id: AuctionId,
+}
+
+/// Solver competitions starting with the specified auction in which at least one solution
+/// executed the order.
+pub async fn load_by_order(
+    ex: &mut PgConnection,
+    order_uid: &OrderUid,
+    min_auction_id: AuctionId,
+) -> Result<Vec<LoadByOrder>, sqlx::Error> {
+    // Order uids are serialized as 0x prefixed hex strings in the json.
+    const QUERY: &str = r#"
+SELECT json, id
+FROM solver_competitions
+WHERE
+    id >= $2 AND
+    json->'solutions' @> jsonb_build_array(jsonb_build_object(
+        'orders', jsonb_build_array(jsonb_build_object('id', '0x' || encode($1, 'hex')))
+    ))
+ORDER BY id
+    ;"#;
+    sqlx::query_as(QUERY)
+        .bind(order_uid)
+        .bind(min_auction_id)
+        .fetch_all(ex)
+        .await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -79,6 +114,11 @@ mod tests {
         assert!(value_.tx_hash.is_none());
 
         assert!(load_by_id(&mut db, 1).await.unwrap().is_none());
+
+        let value = JsonValue::Bool(false);
+        save(&mut db, 0, &value).await.unwrap();
+        let value_ = load_by_id(&mut db, 0).await.unwrap().unwrap();
+        assert_eq!(value, value_.json);
     }
 
     #[tokio::test]
@@ -138,4 +178,40 @@ mod tests {
         let value_by_id = load_by_id(&mut db, id).await.unwrap().unwrap();
         assert_eq!(hash, value_by_id.tx_hash.unwrap());
     }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_by_order() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let competition = |order: &str| -> JsonValue {
+            format!(
+                r#"{{"solutions": [
+                    {{"solver": "a", "orders": []}},
+                    {{"solver": "b", "orders": [{{"id": "{order}", "executedAmount": "1"}}]}}
+                ]}}"#
+            )
+            .parse()
+            .unwrap()
+        };
+        let uid = ByteArray([0x11; 56]);
+        let uid_json = format!("0x{}", "11".repeat(56));
+        save(&mut db, 1, &competition(&uid_json)).await.unwrap();
+        save(&mut db, 2, &competition("0x22")).await.unwrap();
+        save(&mut db, 3, &competition(&uid_json)).await.unwrap();
+
+        let ids = |competitions: Vec<LoadByOrder>| {
+            competitions
+                .into_iter()
+                .map(|competition| competition.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ids(load_by_order(&mut db, &uid, 0).await.unwrap()),
+            vec![1, 3]
+        );
+        assert_eq!(ids(load_by_order(&mut db, &uid, 2).await.unwrap()), vec![3]);
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{Address, OrderUid, TransactionHash};
+use crate::{auction::AuctionId, events::EventIndex, Address, OrderUid, TransactionHash};
 use bigdecimal::BigDecimal;
 use futures::stream::BoxStream;
 use sqlx::PgConnection;
@@ -17,10 +17,26 @@ pub struct TradesQueryRow {
     pub tx_hash: Option<TransactionHash>,
 }
 
+/// Restricts which trades get returned. `None` fields are unfiltered.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TradesFilter<'a> {
+    pub owner: Option<&'a Address>,
+    pub order_uid: Option<&'a OrderUid>,
+    pub sell_token: Option<&'a Address>,
+    pub buy_token: Option<&'a Address>,
+    /// Inclusive.
+    pub from_block: Option<i64>,
+    /// Inclusive.
+    pub to_block: Option<i64>,
+    /// Only trades strictly after this event are returned. Used as the pagination cursor.
+    pub after: Option<EventIndex>,
+    pub limit: Option<i64>,
+}
+
+/// Trades ordered by their event index.
 pub fn trades<'a>(
     ex: &'a mut PgConnection,
-    owner_filter: Option<&'a Address>,
-    order_uid_filter: Option<&'a OrderUid>,
+    filter: &TradesFilter<'a>,
 ) -> BoxStream<'a, Result<TradesQueryRow, sqlx::Error>> {
     const COMMON_QUERY: &str = r#"
 SELECT
@@ -44,24 +60,75 @@ LEFT OUTER JOIN LATERAL (
 ) AS settlement ON true
 JOIN orders o
 ON o.uid = t.order_uid"#;
+    const COMMON_FILTERS: &str = const_format::concatcp!(
+        " AND ($2 IS NULL OR o.uid = $2)",
+        " AND ($3 IS NULL OR o.sell_token = $3)",
+        " AND ($4 IS NULL OR o.buy_token = $4)",
+        " AND ($5 IS NULL OR t.block_number >= $5)",
+        " AND ($6 IS NULL OR t.block_number <= $6)",
+        " AND ($7 IS NULL OR (t.block_number, t.log_index) > ($7, $8))",
+    );
     const QUERY: &str = const_format::concatcp!(
+        "SELECT * FROM (",
         COMMON_QUERY,
         " WHERE ($1 IS NULL OR o.owner = $1)",
-        " AND ($2 IS NULL OR o.uid = $2)",
-        "UNION",
+        COMMON_FILTERS,
+        " UNION ",
         COMMON_QUERY,
         " LEFT OUTER JOIN onchain_placed_orders onchain_o",
         " ON onchain_o.uid = t.order_uid",
         " WHERE onchain_o.sender = $1",
-        " AND ($2 IS NULL OR o.uid = $2)",
+        COMMON_FILTERS,
+        ") AS trades",
+        " ORDER BY block_number, log_index",
+        " LIMIT $9",
     );
 
     sqlx::query_as(QUERY)
-        .bind(owner_filter)
-        .bind(order_uid_filter)
+        .bind(filter.owner)
+        .bind(filter.order_uid)
+        .bind(filter.sell_token)
+        .bind(filter.buy_token)
+        .bind(filter.from_block)
+        .bind(filter.to_block)
+        .bind(filter.after.map(|index| index.block_number))
+        .bind(filter.after.map(|index| index.log_index))
+        .bind(filter.limit)
         .fetch(ex)
 }
 
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct OrderTradeRow {
+    pub block_number: i64,
+    pub log_index: i64,
+    pub tx_hash: Option<TransactionHash>,
+    /// The auction that was settled by the trade's transaction.
+    pub auction_id: Option<AuctionId>,
+}
+
+/// All trades of an order together with their settlement.
+pub async fn order_trades(
+    ex: &mut PgConnection,
+    order_uid: &OrderUid,
+) -> Result<Vec<OrderTradeRow>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT t.block_number, t.log_index, settlement.tx_hash, at.auction_id
+FROM trades t
+LEFT OUTER JOIN LATERAL (
+    SELECT tx_hash, tx_from, tx_nonce FROM settlements s
+    WHERE s.block_number = t.block_number
+    AND   s.log_index > t.log_index
+    ORDER BY s.log_index ASC
+    LIMIT 1
+) AS settlement ON true
+LEFT OUTER JOIN auction_transaction at
+ON (at.tx_from, at.tx_nonce) = (settlement.tx_from, settlement.tx_nonce)
+WHERE t.order_uid = $1
+ORDER BY t.block_number, t.log_index
+    ;"#;
+    sqlx::query_as(QUERY).bind(order_uid).fetch_all(ex).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,10 +202,12 @@ mod tests {
         order_uid_filter: Option<&OrderUid>,
         expected: &[TradesQueryRow],
     ) {
-        let filtered = trades(db, owner_filter, order_uid_filter)
-            .try_collect::<Vec<_>>()
-            .await
-            .unwrap();
+        let filter = TradesFilter {
+            owner: owner_filter,
+            order_uid: order_uid_filter,
+            ..Default::default()
+        };
+        let filtered = trades(db, &filter).try_collect::<Vec<_>>().await.unwrap();
         assert_eq!(filtered, expected);
     }
 
@@ -166,7 +235,7 @@ mod tests {
         };
         let trade_b =
             add_order_and_trade(&mut db, owners[0], order_ids[1], event_index_b, None).await;
-        assert_trades(&mut db, None, None, &[trade_b, trade_a]).await;
+        assert_trades(&mut db, None, None, &[trade_a, trade_b]).await;
     }
 
     #[tokio::test]
@@ -207,7 +276,12 @@ mod tests {
         }
 
         let now = std::time::Instant::now();
-        trades(&mut db, Some(&ByteArray([2u8; 20])), None)
+        let owner = ByteArray([2u8; 20]);
+        let filter = TradesFilter {
+            owner: Some(&owner),
+            ..Default::default()
+        };
+        trades(&mut db, &filter)
             .try_collect::<Vec<_>>()
             .await
             .unwrap();
@@ -314,6 +388,92 @@ mod tests {
     }
 
     // Testing Trades with settlements
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_trades_with_token_block_filters_and_pagination() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let (owners, order_ids) = generate_owners_and_order_ids(1, 3).await;
+        let tokens = [ByteArray([1; 20]), ByteArray([2; 20])];
+        let mut expected = Vec::new();
+        for (i, (sell_token, buy_token)) in [(0, 1), (1, 0), (0, 1)].into_iter().enumerate() {
+            let order = Order {
+                uid: order_ids[i],
+                owner: owners[0],
+                sell_token: tokens[sell_token],
+                buy_token: tokens[buy_token],
+                ..Default::default()
+            };
+            crate::orders::insert_order(&mut db, &order).await.unwrap();
+            let event_index = EventIndex {
+                block_number: i as i64,
+                log_index: 0,
+            };
+            let trade = add_trade(&mut db, owners[0], order_ids[i], event_index, None).await;
+            expected.push(TradesQueryRow {
+                sell_token: tokens[sell_token],
+                buy_token: tokens[buy_token],
+                ..trade
+            });
+        }
+
+        async fn query(ex: &mut PgConnection, filter: TradesFilter<'_>) -> Vec<TradesQueryRow> {
+            trades(ex, &filter).try_collect().await.unwrap()
+        }
+
+        assert_eq!(
+            query(
+                &mut db,
+                TradesFilter {
+                    sell_token: Some(&tokens[0]),
+                    ..Default::default()
+                }
+            )
+            .await,
+            [expected[0].clone(), expected[2].clone()]
+        );
+        assert_eq!(
+            query(
+                &mut db,
+                TradesFilter {
+                    buy_token: Some(&tokens[0]),
+                    ..Default::default()
+                }
+            )
+            .await,
+            [expected[1].clone()]
+        );
+        assert_eq!(
+            query(
+                &mut db,
+                TradesFilter {
+                    from_block: Some(1),
+                    to_block: Some(1),
+                    ..Default::default()
+                }
+            )
+            .await,
+            [expected[1].clone()]
+        );
+
+        // Paginate through all trades one at a time.
+        let mut page = TradesFilter {
+            owner: Some(&owners[0]),
+            limit: Some(1),
+            ..Default::default()
+        };
+        for trade in &expected {
+            assert_eq!(query(&mut db, page).await, [trade.clone()]);
+            page.after = Some(EventIndex {
+                block_number: trade.block_number,
+                log_index: trade.log_index,
+            });
+        }
+        assert!(query(&mut db, page).await.is_empty());
+    }
+
     async fn add_settlement(
         ex: &mut PgTransaction<'_>,
         event_index: EventIndex,
@@ -491,4 +651,62 @@ mod tests {
         .await;
         assert_trades(&mut db, None, None, &[trade_a, trade_b]).await;
     }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_order_trades_with_auction() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let (owners, order_ids) = generate_owners_and_order_ids(1, 1).await;
+        let settlement_index = EventIndex {
+            block_number: 0,
+            log_index: 1,
+        };
+        let settlement = add_settlement(
+            &mut db,
+            settlement_index,
+            Default::default(),
+            ByteArray([1; 32]),
+        )
+        .await;
+        add_order_and_trade(
+            &mut db,
+            owners[0],
+            order_ids[0],
+            EventIndex {
+                block_number: 0,
+                log_index: 0,
+            },
+            Some(settlement.transaction_hash),
+        )
+        .await;
+
+        let expected = OrderTradeRow {
+            block_number: 0,
+            log_index: 0,
+            tx_hash: Some(settlement.transaction_hash),
+            auction_id: None,
+        };
+        assert_eq!(
+            order_trades(&mut db, &order_ids[0]).await.unwrap(),
+            vec![expected.clone()]
+        );
+
+        let tx_from = ByteArray([2; 20]);
+        crate::auction_transaction::insert_settlement_tx_info(&mut db, 0, 1, &tx_from, 3)
+            .await
+            .unwrap();
+        crate::auction_transaction::upsert_auction_transaction(&mut db, 4, &tx_from, 3)
+            .await
+            .unwrap();
+        assert_eq!(
+            order_trades(&mut db, &order_ids[0]).await.unwrap(),
+            vec![OrderTradeRow {
+                auction_id: Some(4),
+                ..expected
+            }]
+        );
+    }
 }
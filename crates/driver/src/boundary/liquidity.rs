@@ -0,0 +1,713 @@
+use {
+    crate::{
+        domain::{eth, liquidity},
+        infra::{self, blockchain::Ethereum},
+    },
+    anyhow::{Context, Result},
+    contracts::GPv2Settlement,
+    model::TokenPair,
+    num::{rational::Ratio, ToPrimitive},
+    shared::{
+        baseline_solver::{BaseTokens, BaselineSolvable},
+        current_block::{self, BlockRetrieving},
+        ethrpc::Web3,
+        http_client::HttpClientFactory,
+        maintenance::{Maintaining, ServiceMaintenance},
+        recent_block_cache::{Block, CacheConfig},
+        sources::{
+            self,
+            balancer_v2::{
+                pool_fetching::{
+                    AmplificationParameter,
+                    BalancerContracts,
+                    BalancerFactoryKind,
+                    BalancerPoolFetcher,
+                    BalancerPoolFetching,
+                    StablePool,
+                    TokenState,
+                    WeightedPool,
+                    WeightedTokenState,
+                },
+                pools::common::compute_scaling_rate,
+                swap::fixed_point::Bfp,
+            },
+            uniswap_v2::{
+                pool_cache::PoolCache,
+                pool_fetching::{Pool, PoolFetching as _},
+            },
+            uniswap_v3::{
+                graph_api::Token,
+                pool_fetching::{
+                    PoolFetching as _,
+                    PoolInfo,
+                    PoolState,
+                    PoolStats,
+                    UniswapV3PoolFetcher,
+                },
+            },
+            BaselineSource,
+        },
+        token_info::{CachedTokenInfoFetcher, TokenInfoFetcher},
+    },
+    solver::{
+        interactions::allowances::{AllowanceManaging, Allowances},
+        liquidity::{
+            self as legacy,
+            ConcentratedLiquidity,
+            ConstantProductOrder,
+            StablePoolOrder,
+            WeightedProductOrder,
+        },
+    },
+    std::{
+        collections::{HashMap, HashSet},
+        sync::{Arc, Mutex},
+        time::Duration,
+    },
+};
+
+/// How often the node is polled for new blocks. The pool caches are updated
+/// on every new block.
+const BLOCK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The number of Uniswap V3 pools which are fetched when the cache is
+/// initialized.
+const MAX_UNISWAP_V3_POOLS_TO_INITIALIZE: u64 = 100;
+
+pub struct Fetcher {
+    base_tokens: BaseTokens,
+    /// The pool caches of the Uniswap V2-like sources, together with the
+    /// address of the router of each source.
+    uniswap_v2: Vec<(eth::H160, Arc<PoolCache>)>,
+    /// The Balancer V2 pool fetcher together with the vault address.
+    balancer_v2: Option<(eth::H160, Arc<BalancerPoolFetcher>)>,
+    /// The Uniswap V3 pool fetcher together with the swap router address.
+    uniswap_v3: Option<(eth::H160, Arc<UniswapV3PoolFetcher>)>,
+}
+
+impl std::fmt::Debug for Fetcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Fetcher")
+            .field("base_tokens", &self.base_tokens.tokens())
+            .field("uniswap_v2", &self.uniswap_v2.len())
+            .field("balancer_v2", &self.balancer_v2.is_some())
+            .field("uniswap_v3", &self.uniswap_v3.is_some())
+            .finish()
+    }
+}
+
+impl Fetcher {
+    pub async fn new(config: &infra::liquidity::Config, eth: &Ethereum) -> Result<Self> {
+        let http = HttpClientFactory::default();
+        let web3 = eth.contracts().settlement().raw_instance().web3();
+        let chain_id = eth.chain_id().0.as_u64();
+        let native_token = eth.contracts().weth().address();
+        let base_tokens = BaseTokens::new(
+            native_token,
+            &config
+                .base_tokens
+                .iter()
+                .copied()
+                .map(Into::into)
+                .collect::<Vec<_>>(),
+        );
+        let sources = match &config.sources {
+            Some(sources) => sources.iter().copied().map(to_baseline_source).collect(),
+            None => sources::defaults_for_chain(chain_id)?,
+        };
+
+        let block_retriever: Arc<dyn BlockRetrieving> = Arc::new(web3.clone());
+        let blocks =
+            current_block::current_block_stream(block_retriever.clone(), BLOCK_POLL_INTERVAL)
+                .await?;
+        let cache_config = CacheConfig::default();
+        let mut maintainers: Vec<Arc<dyn Maintaining>> = Vec::new();
+
+        let mut uniswap_v2 = Vec::new();
+        for (source, (_, fetcher)) in sources::uniswap_like_liquidity_sources(&web3, &sources)
+            .await
+            .context("uniswap like liquidity sources")?
+        {
+            let router = uniswap_like_router(&web3, source).await?;
+            let cache = Arc::new(PoolCache::new(cache_config, fetcher, blocks.clone())?);
+            maintainers.push(cache.clone());
+            uniswap_v2.push((router, cache));
+        }
+
+        let balancer_v2 = if sources.contains(&BaselineSource::BalancerV2) {
+            let contracts =
+                BalancerContracts::new(&web3, BalancerFactoryKind::for_chain(chain_id)).await?;
+            let fetcher = Arc::new(
+                BalancerPoolFetcher::new(
+                    chain_id,
+                    block_retriever.clone(),
+                    Arc::new(CachedTokenInfoFetcher::new(Box::new(TokenInfoFetcher {
+                        web3: web3.clone(),
+                    }))),
+                    cache_config,
+                    blocks.clone(),
+                    http.create(),
+                    web3.clone(),
+                    &contracts,
+                    Default::default(),
+                )
+                .await
+                .context("balancer pool fetcher")?,
+            );
+            maintainers.push(fetcher.clone());
+            Some((contracts.vault.address(), fetcher))
+        } else {
+            None
+        };
+
+        let uniswap_v3 = if sources.contains(&BaselineSource::UniswapV3) {
+            let fetcher = Arc::new(
+                UniswapV3PoolFetcher::new(
+                    chain_id,
+                    web3.clone(),
+                    http.create(),
+                    block_retriever,
+                    MAX_UNISWAP_V3_POOLS_TO_INITIALIZE,
+                )
+                .await
+                .context("uniswap v3 pool fetcher")?,
+            );
+            maintainers.push(fetcher.clone());
+            let router = contracts::UniswapV3SwapRouter::deployed(&web3)
+                .await
+                .context("uniswap v3 router")?;
+            Some((router.address(), fetcher))
+        } else {
+            None
+        };
+
+        tokio::task::spawn(
+            ServiceMaintenance::new(maintainers).run_maintenance_on_new_block(blocks),
+        );
+
+        Ok(Self {
+            base_tokens,
+            uniswap_v2,
+            balancer_v2,
+            uniswap_v3,
+        })
+    }
+
+    /// Fetch the liquidity for the given token pairs, including the pools
+    /// which connect the tokens through the base tokens.
+    pub async fn fetch(
+        &self,
+        pairs: &HashSet<liquidity::TokenPair>,
+    ) -> Result<Vec<liquidity::Liquidity>> {
+        let pairs = self
+            .base_tokens
+            .relevant_pairs(pairs.iter().filter_map(|pair| {
+                let (a, b) = pair.get();
+                TokenPair::new(a.into(), b.into())
+            }));
+
+        let mut kinds = Vec::new();
+        for (router, cache) in &self.uniswap_v2 {
+            let pools = cache.fetch(pairs.clone(), Block::Recent).await?;
+            kinds.extend(pools.iter().map(|pool| uniswap_v2(*router, pool)));
+        }
+        if let Some((vault, fetcher)) = &self.balancer_v2 {
+            let pools = fetcher.fetch(pairs.clone(), Block::Recent).await?;
+            for pool in &pools.weighted_pools {
+                match balancer_v2_weighted(*vault, pool) {
+                    Ok(kind) => kinds.push(kind),
+                    Err(err) => {
+                        tracing::warn!(?err, pool = ?pool.common.address, "invalid pool state")
+                    }
+                }
+            }
+            for pool in &pools.stable_pools {
+                match balancer_v2_stable(*vault, pool) {
+                    Ok(kind) => kinds.push(kind),
+                    Err(err) => {
+                        tracing::warn!(?err, pool = ?pool.common.address, "invalid pool state")
+                    }
+                }
+            }
+        }
+        if let Some((router, fetcher)) = &self.uniswap_v3 {
+            let pools = fetcher.fetch(&pairs, Block::Recent).await?;
+            for pool in &pools {
+                match uniswap_v3(*router, pool) {
+                    Ok(kind) => kinds.push(kind),
+                    Err(err) => tracing::debug!(?err, pool = ?pool.address, "invalid pool state"),
+                }
+            }
+        }
+
+        Ok(kinds
+            .into_iter()
+            .enumerate()
+            .map(|(id, (address, gas, kind))| liquidity::Liquidity {
+                id: id.into(),
+                address: address.into(),
+                gas: gas.into(),
+                kind,
+            })
+            .collect())
+    }
+}
+
+/// Converts the liquidity of an auction into the liquidity of the legacy
+/// solver crate, which is needed to encode the interactions of solutions with
+/// the settlement handlers of the legacy code. Liquidity which can't be
+/// represented is skipped, so encoding a solution which uses it fails.
+///
+/// Like the legacy liquidity sources, the settlement handlers only approve a
+/// router or the vault if the current allowance of the settlement contract is
+/// too small for the swap.
+pub async fn to_boundary_liquidity(
+    liquidity: &[liquidity::Liquidity],
+    decimals: &HashMap<eth::TokenAddress, u8>,
+    settlement: &GPv2Settlement,
+    allowances: &dyn AllowanceManaging,
+) -> Result<Vec<legacy::Liquidity>> {
+    let handlers = Handlers::new(liquidity, settlement, allowances).await?;
+    Ok(liquidity
+        .iter()
+        .filter_map(
+            |liquidity| match to_boundary_kind(liquidity, decimals, &handlers) {
+                Ok(converted) => Some(converted),
+                Err(err) => {
+                    tracing::warn!(
+                        ?err,
+                        address = ?liquidity.address,
+                        "failed to convert liquidity to legacy liquidity"
+                    );
+                    None
+                }
+            },
+        )
+        .collect())
+}
+
+/// The settlement handlers of the liquidity. Pools with the same router or
+/// vault share a handler, so that they also share the cached allowances of the
+/// settlement contract and approve each token at most once.
+struct Handlers {
+    settlement: GPv2Settlement,
+    uniswap_v2: HashMap<eth::H160, Arc<legacy::uniswap_v2::Inner>>,
+    uniswap_v3: HashMap<eth::H160, Arc<legacy::uniswap_v3::Inner>>,
+    balancer_v2: HashMap<eth::H160, (contracts::BalancerV2Vault, Arc<Allowances>)>,
+}
+
+impl Handlers {
+    async fn new(
+        liquidity: &[liquidity::Liquidity],
+        settlement: &GPv2Settlement,
+        allowances: &dyn AllowanceManaging,
+    ) -> Result<Self> {
+        let mut uniswap_v2 = HashMap::<eth::H160, HashSet<eth::H160>>::new();
+        let mut uniswap_v3 = HashMap::<eth::H160, HashSet<eth::H160>>::new();
+        let mut balancer_v2 = HashMap::<eth::H160, HashSet<eth::H160>>::new();
+        for liquidity in liquidity {
+            match &liquidity.kind {
+                liquidity::Kind::UniswapV2(pool) => {
+                    uniswap_v2.entry(pool.router.into()).or_default().extend(
+                        pool.reserves
+                            .iter()
+                            .map(|reserve| eth::H160::from(reserve.token)),
+                    )
+                }
+                liquidity::Kind::UniswapV3(pool) => uniswap_v3
+                    .entry(pool.router.into())
+                    .or_default()
+                    .extend(pool.tokens.iter().copied().map(eth::H160::from)),
+                liquidity::Kind::BalancerV2Weighted(pool) => {
+                    balancer_v2.entry(pool.vault.into()).or_default().extend(
+                        pool.reserves
+                            .iter()
+                            .map(|reserve| eth::H160::from(reserve.asset.token)),
+                    )
+                }
+                liquidity::Kind::BalancerV2Stable(pool) => {
+                    balancer_v2.entry(pool.vault.into()).or_default().extend(
+                        pool.reserves
+                            .iter()
+                            .map(|reserve| eth::H160::from(reserve.asset.token)),
+                    )
+                }
+            }
+        }
+
+        let web3 = settlement.raw_instance().web3();
+        let mut handlers = Self {
+            settlement: settlement.clone(),
+            uniswap_v2: Default::default(),
+            uniswap_v3: Default::default(),
+            balancer_v2: Default::default(),
+        };
+        for (router, tokens) in uniswap_v2 {
+            let allowances = allowances
+                .get_allowances(tokens, router)
+                .await
+                .context("uniswap v2 allowances")?;
+            handlers.uniswap_v2.insert(
+                router,
+                Arc::new(legacy::uniswap_v2::Inner::new(
+                    contracts::IUniswapLikeRouter::at(&web3, router),
+                    settlement.clone(),
+                    Mutex::new(allowances),
+                )),
+            );
+        }
+        for (router, tokens) in uniswap_v3 {
+            let allowances = allowances
+                .get_allowances(tokens, router)
+                .await
+                .context("uniswap v3 allowances")?;
+            handlers.uniswap_v3.insert(
+                router,
+                Arc::new(legacy::uniswap_v3::Inner::new(
+                    contracts::UniswapV3SwapRouter::at(&web3, router),
+                    settlement.clone(),
+                    Mutex::new(allowances),
+                )),
+            );
+        }
+        for (vault, tokens) in balancer_v2 {
+            let allowances = allowances
+                .get_allowances(tokens, vault)
+                .await
+                .context("balancer v2 allowances")?;
+            handlers.balancer_v2.insert(
+                vault,
+                (
+                    contracts::BalancerV2Vault::at(&web3, vault),
+                    Arc::new(allowances),
+                ),
+            );
+        }
+        Ok(handlers)
+    }
+
+    fn uniswap_v2(&self, router: eth::H160) -> Result<Arc<legacy::uniswap_v2::Inner>> {
+        self.uniswap_v2
+            .get(&router)
+            .cloned()
+            .context("missing uniswap v2 settlement handler")
+    }
+
+    fn uniswap_v3(&self, router: eth::H160) -> Result<Arc<legacy::uniswap_v3::Inner>> {
+        self.uniswap_v3
+            .get(&router)
+            .cloned()
+            .context("missing uniswap v3 settlement handler")
+    }
+
+    fn balancer_v2(
+        &self,
+        vault: eth::ContractAddress,
+        pool_id: eth::H256,
+    ) -> Result<legacy::balancer_v2::SettlementHandler> {
+        let vault: eth::H160 = vault.into();
+        let (vault, allowances) = self
+            .balancer_v2
+            .get(&vault)
+            .context("missing balancer v2 settlement handler")?;
+        Ok(legacy::balancer_v2::SettlementHandler::new(
+            pool_id,
+            self.settlement.clone(),
+            vault.clone(),
+            allowances.clone(),
+        ))
+    }
+}
+
+fn to_boundary_kind(
+    liquidity: &liquidity::Liquidity,
+    decimals: &HashMap<eth::TokenAddress, u8>,
+    handlers: &Handlers,
+) -> Result<legacy::Liquidity> {
+    let address = liquidity.address.into();
+    Ok(match &liquidity.kind {
+        liquidity::Kind::UniswapV2(pool) => {
+            let [reserve0, reserve1] = &pool.reserves;
+            legacy::Liquidity::ConstantProduct(ConstantProductOrder {
+                address,
+                tokens: TokenPair::new(reserve0.token.into(), reserve1.token.into())
+                    .context("pool tokens are the same")?,
+                reserves: (
+                    reserve0
+                        .amount
+                        .try_into()
+                        .ok()
+                        .context("reserve overflows u128")?,
+                    reserve1
+                        .amount
+                        .try_into()
+                        .ok()
+                        .context("reserve overflows u128")?,
+                ),
+                fee: decimal_to_ratio(&pool.fee)?,
+                settlement_handling: handlers.uniswap_v2(pool.router.into())?,
+            })
+        }
+        liquidity::Kind::BalancerV2Weighted(pool) => {
+            legacy::Liquidity::BalancerWeighted(WeightedProductOrder {
+                address,
+                reserves: pool
+                    .reserves
+                    .iter()
+                    .map(|reserve| {
+                        Ok((
+                            reserve.asset.token.into(),
+                            WeightedTokenState {
+                                common: TokenState {
+                                    balance: reserve.asset.amount,
+                                    scaling_exponent: scaling_exponent(reserve.scaling_factor)?,
+                                },
+                                weight: decimal_to_bfp(&reserve.weight)?,
+                            },
+                        ))
+                    })
+                    .collect::<Result<_>>()?,
+                fee: decimal_to_bfp(&pool.fee)?,
+                settlement_handling: Arc::new(handlers.balancer_v2(pool.vault, pool.id)?),
+            })
+        }
+        liquidity::Kind::BalancerV2Stable(pool) => {
+            legacy::Liquidity::BalancerStable(StablePoolOrder {
+                address,
+                reserves: pool
+                    .reserves
+                    .iter()
+                    .map(|reserve| {
+                        Ok((
+                            reserve.asset.token.into(),
+                            TokenState {
+                                balance: reserve.asset.amount,
+                                scaling_exponent: scaling_exponent(reserve.scaling_factor)?,
+                            },
+                        ))
+                    })
+                    .collect::<Result<_>>()?,
+                fee: decimal_to_bfp(&pool.fee)?.into(),
+                amplification_parameter: AmplificationParameter::new(
+                    number_conversions::big_int_to_u256(pool.amplification_parameter.numer())?,
+                    number_conversions::big_int_to_u256(pool.amplification_parameter.denom())?,
+                )?,
+                settlement_handling: Arc::new(handlers.balancer_v2(pool.vault, pool.id)?),
+            })
+        }
+        liquidity::Kind::UniswapV3(pool) => {
+            let fee = decimal_to_ratio(&pool.fee)?;
+            let tokens = pool
+                .tokens
+                .iter()
+                .map(|token| {
+                    Ok(Token {
+                        id: (*token).into(),
+                        decimals: *decimals
+                            .get(token)
+                            .with_context(|| format!("unknown decimals of token {token:?}"))?,
+                    })
+                })
+                .collect::<Result<_>>()?;
+            legacy::Liquidity::Concentrated(ConcentratedLiquidity {
+                tokens: TokenPair::new(pool.tokens[0].into(), pool.tokens[1].into())
+                    .context("pool tokens are the same")?,
+                pool: PoolInfo {
+                    address,
+                    tokens,
+                    state: PoolState {
+                        sqrt_price: pool.sqrt_price,
+                        liquidity: pool.liquidity.into(),
+                        tick: pool.tick.into(),
+                        liquidity_net: pool
+                            .liquidity_net
+                            .iter()
+                            .map(|(&tick, &liquidity)| (tick.into(), liquidity.into()))
+                            .collect(),
+                        fee,
+                    },
+                    gas_stats: PoolStats {
+                        mean_gas: liquidity.gas.into(),
+                    },
+                },
+                settlement_handling: Arc::new(legacy::uniswap_v3::UniswapV3SettlementHandler::new(
+                    handlers.uniswap_v3(pool.router.into())?,
+                    fee,
+                )?),
+            })
+        }
+    })
+}
+
+fn to_baseline_source(source: infra::liquidity::Source) -> BaselineSource {
+    match source {
+        infra::liquidity::Source::UniswapV2 => BaselineSource::UniswapV2,
+        infra::liquidity::Source::SushiSwap => BaselineSource::SushiSwap,
+        infra::liquidity::Source::Honeyswap => BaselineSource::Honeyswap,
+        infra::liquidity::Source::Baoswap => BaselineSource::Baoswap,
+        infra::liquidity::Source::Swapr => BaselineSource::Swapr,
+        infra::liquidity::Source::BalancerV2 => BaselineSource::BalancerV2,
+        infra::liquidity::Source::UniswapV3 => BaselineSource::UniswapV3,
+    }
+}
+
+async fn uniswap_like_router(web3: &Web3, source: BaselineSource) -> Result<eth::H160> {
+    let router = match source {
+        BaselineSource::UniswapV2 => contracts::UniswapV2Router02::deployed(web3)
+            .await?
+            .address(),
+        BaselineSource::SushiSwap => contracts::SushiSwapRouter::deployed(web3).await?.address(),
+        BaselineSource::Honeyswap => contracts::HoneyswapRouter::deployed(web3).await?.address(),
+        BaselineSource::Baoswap => contracts::BaoswapRouter::deployed(web3).await?.address(),
+        BaselineSource::Swapr => contracts::SwaprRouter::deployed(web3).await?.address(),
+        _ => anyhow::bail!("{source:?} is not a uniswap like liquidity source"),
+    };
+    Ok(router)
+}
+
+fn uniswap_v2(router: eth::H160, pool: &Pool) -> (eth::H160, eth::U256, liquidity::Kind) {
+    let (token0, token1) = pool.tokens.get();
+    let kind = liquidity::Kind::UniswapV2(liquidity::uniswap_v2::Pool {
+        router: router.into(),
+        reserves: [
+            eth::Asset {
+                token: token0.into(),
+                amount: pool.reserves.0.into(),
+            },
+            eth::Asset {
+                token: token1.into(),
+                amount: pool.reserves.1.into(),
+            },
+        ],
+        fee: ratio_to_decimal(pool.fee.numer(), pool.fee.denom()),
+    });
+    (pool.address, pool.gas_cost().into(), kind)
+}
+
+fn balancer_v2_weighted(
+    vault: eth::H160,
+    pool: &WeightedPool,
+) -> Result<(eth::H160, eth::U256, liquidity::Kind)> {
+    let kind = liquidity::Kind::BalancerV2Weighted(liquidity::balancer_v2::WeightedPool {
+        vault: vault.into(),
+        id: pool.common.id,
+        reserves: pool
+            .reserves
+            .iter()
+            .map(|(&token, state)| {
+                Ok(liquidity::balancer_v2::WeightedReserve {
+                    asset: eth::Asset {
+                        token: token.into(),
+                        amount: state.common.balance,
+                    },
+                    weight: bfp_to_decimal(state.weight),
+                    scaling_factor: compute_scaling_rate(state.common.scaling_exponent)?,
+                })
+            })
+            .collect::<Result<_>>()?,
+        fee: bfp_to_decimal(pool.common.swap_fee),
+    });
+    Ok((pool.common.address, pool.gas_cost().into(), kind))
+}
+
+fn balancer_v2_stable(
+    vault: eth::H160,
+    pool: &StablePool,
+) -> Result<(eth::H160, eth::U256, liquidity::Kind)> {
+    let kind = liquidity::Kind::BalancerV2Stable(liquidity::balancer_v2::StablePool {
+        vault: vault.into(),
+        id: pool.common.id,
+        reserves: pool
+            .reserves
+            .iter()
+            .map(|(&token, state)| {
+                Ok(liquidity::balancer_v2::StableReserve {
+                    asset: eth::Asset {
+                        token: token.into(),
+                        amount: state.balance,
+                    },
+                    scaling_factor: compute_scaling_rate(state.scaling_exponent)?,
+                })
+            })
+            .collect::<Result<_>>()?,
+        amplification_parameter: pool.amplification_parameter.as_big_rational(),
+        fee: bfp_to_decimal(pool.common.swap_fee),
+    });
+    Ok((pool.common.address, pool.gas_cost().into(), kind))
+}
+
+fn uniswap_v3(
+    router: eth::H160,
+    pool: &PoolInfo,
+) -> Result<(eth::H160, eth::U256, liquidity::Kind)> {
+    let kind = liquidity::Kind::UniswapV3(liquidity::uniswap_v3::Pool {
+        router: router.into(),
+        tokens: match pool.tokens.as_slice() {
+            [token0, token1] => [token0.id.into(), token1.id.into()],
+            _ => anyhow::bail!("pool doesn't have exactly two tokens"),
+        },
+        sqrt_price: pool.state.sqrt_price,
+        liquidity: pool
+            .state
+            .liquidity
+            .try_into()
+            .ok()
+            .context("liquidity overflows u128")?,
+        tick: pool.state.tick.to_i32().context("tick overflows i32")?,
+        liquidity_net: pool
+            .state
+            .liquidity_net
+            .iter()
+            .map(|(tick, liquidity)| {
+                Ok((
+                    tick.to_i32().context("tick overflows i32")?,
+                    liquidity
+                        .to_i128()
+                        .context("net liquidity overflows i128")?,
+                ))
+            })
+            .collect::<Result<_>>()?,
+        fee: ratio_to_decimal(pool.state.fee.numer(), pool.state.fee.denom()),
+    });
+    Ok((pool.address, pool.gas_stats.mean_gas, kind))
+}
+
+fn ratio_to_decimal(numer: &u32, denom: &u32) -> bigdecimal::BigDecimal {
+    bigdecimal::BigDecimal::from(*numer) / bigdecimal::BigDecimal::from(*denom)
+}
+
+fn decimal_to_ratio(value: &bigdecimal::BigDecimal) -> Result<Ratio<u32>> {
+    let (numer, exp) = value.as_bigint_and_exponent();
+    let numer = numer.to_u32().context("fee numerator overflows u32")?;
+    let denom = u32::try_from(exp)
+        .ok()
+        .and_then(|exp| 10_u32.checked_pow(exp))
+        .context("fee denominator overflows u32")?;
+    Ok(Ratio::new(numer, denom))
+}
+
+/// Balancer fixed point numbers have 18 decimals.
+fn decimal_to_bfp(value: &bigdecimal::BigDecimal) -> Result<Bfp> {
+    let wei = value.clone() * bigdecimal::BigDecimal::new(1.into(), -18);
+    Ok(Bfp::from_wei(
+        number_conversions::big_decimal_to_u256(&wei).context("invalid fixed point number")?,
+    ))
+}
+
+/// The inverse of [`compute_scaling_rate`].
+fn scaling_exponent(scaling_factor: eth::U256) -> Result<u8> {
+    (0..=18_u8)
+        .find(|decimals| eth::U256::exp10((*decimals).into()) == scaling_factor)
+        .map(|decimals| 18 - decimals)
+        .context("scaling factor is not a power of ten")
+}
+
+/// Balancer fixed point numbers have 18 decimals.
+fn bfp_to_decimal(value: Bfp) -> bigdecimal::BigDecimal {
+    let mut bytes = [0; 32];
+    value.as_uint256().to_big_endian(&mut bytes);
+    bigdecimal::BigDecimal::new(
+        bigdecimal::num_bigint::BigInt::from_bytes_be(bigdecimal::num_bigint::Sign::Plus, &bytes),
+        18,
+    )
+}
@@ -22,6 +22,8 @@
 //! By Eric Evans, Domain-Driven Design: Tackling Complexity in the Heart of
 //! Software (2014)
 
+pub mod liquidity;
+pub mod order;
 pub mod settlement;
 
-pub use {anyhow::Error, contracts, settlement::Settlement};
+pub use {anyhow::Error, contracts, settlement::Settlement, shared::price_estimation::gas};
@@ -0,0 +1,81 @@
+use {
+    crate::domain::{competition::order, eth},
+    anyhow::Result,
+    model::{
+        app_id::AppId,
+        order::{BuyTokenDestination, OrderData, OrderKind, SellTokenSource},
+        signature::{hashed_eip712_message, Signature, SigningScheme},
+        DomainSeparator,
+    },
+};
+
+/// The UID of a JIT order, which is derived from the signed order data and the
+/// signer.
+pub fn jit_uid(jit: &order::Jit, domain: &eth::DomainSeparator) -> order::Uid {
+    to_boundary_jit_order_data(jit)
+        .uid(&DomainSeparator(domain.0), &jit.signature.signer.into())
+        .0
+        .into()
+}
+
+/// The EIP-712 hash of a JIT order. This is the hash signed by ECDSA signatures
+/// and the hash passed to the `isValidSignature` method of EIP-1271 signers.
+pub fn jit_signing_hash(jit: &order::Jit, domain: &eth::DomainSeparator) -> [u8; 32] {
+    hashed_eip712_message(
+        &DomainSeparator(domain.0),
+        &to_boundary_jit_order_data(jit).hash_struct(),
+    )
+}
+
+/// Recover the signer of a JIT order. Returns [`None`] for signing schemes
+/// which are verified onchain, i.e. EIP-1271 signatures and presignatures.
+pub fn jit_recover_signer(
+    jit: &order::Jit,
+    domain: &eth::DomainSeparator,
+) -> Result<Option<eth::Address>> {
+    let signature = to_boundary_signature(&jit.signature)?;
+    let signer = signature.recover(
+        &DomainSeparator(domain.0),
+        &to_boundary_jit_order_data(jit).hash_struct(),
+    )?;
+    Ok(signer.map(Into::into))
+}
+
+pub(super) fn to_boundary_jit_order_data(jit: &order::Jit) -> OrderData {
+    OrderData {
+        sell_token: jit.sell.token.into(),
+        buy_token: jit.buy.token.into(),
+        receiver: Some(jit.receiver.into()),
+        sell_amount: jit.sell.amount,
+        buy_amount: jit.buy.amount,
+        valid_to: jit.valid_to.into(),
+        app_data: AppId(jit.app_data.into()),
+        fee_amount: jit.fee.into(),
+        kind: match jit.side {
+            order::Side::Buy => OrderKind::Buy,
+            order::Side::Sell => OrderKind::Sell,
+        },
+        partially_fillable: jit.partially_fillable,
+        sell_token_balance: match jit.sell_token_balance {
+            order::SellTokenBalance::Erc20 => SellTokenSource::Erc20,
+            order::SellTokenBalance::Internal => SellTokenSource::Internal,
+            order::SellTokenBalance::External => SellTokenSource::External,
+        },
+        buy_token_balance: match jit.buy_token_balance {
+            order::BuyTokenBalance::Erc20 => BuyTokenDestination::Erc20,
+            order::BuyTokenBalance::Internal => BuyTokenDestination::Internal,
+        },
+    }
+}
+
+pub(super) fn to_boundary_signature(signature: &order::Signature) -> Result<Signature> {
+    Signature::from_bytes(
+        match signature.scheme {
+            order::signature::Scheme::Eip712 => SigningScheme::Eip712,
+            order::signature::Scheme::EthSign => SigningScheme::EthSign,
+            order::signature::Scheme::Eip1271 => SigningScheme::Eip1271,
+            order::signature::Scheme::PreSign => SigningScheme::PreSign,
+        },
+        &signature.data,
+    )
+}
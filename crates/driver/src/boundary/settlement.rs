@@ -3,7 +3,7 @@ use {
         domain::{competition, competition::order, eth},
         Ethereum,
     },
-    anyhow::Result,
+    anyhow::{anyhow, Context, Result},
     async_trait::async_trait,
     model::{
         app_id::AppId,
@@ -39,26 +39,34 @@ use {
     solver::{
         driver::solver_settlements::RatedSettlement,
         interactions::allowances::{AllowanceManaging, Allowances, Approval, ApprovalRequest},
-        liquidity::{order_converter::OrderConverter, slippage::SlippageCalculator},
+        liquidity::{
+            order_converter::OrderConverter,
+            slippage::{SlippageCalculator, SlippageContext},
+            AmmOrderExecution,
+            Liquidity,
+        },
         settlement::external_prices::ExternalPrices,
         settlement_simulation::settle_method_builder,
         solver::http_solver::settlement::{convert_settlement, SettlementContext},
     },
-    std::{collections::HashSet, sync::Arc},
+    std::{
+        collections::{HashMap, HashSet},
+        sync::Arc,
+    },
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Settlement {
     settlement: solver::settlement::Settlement,
     contract: contracts::GPv2Settlement,
     solver: eth::Address,
+    internalized_liquidity: Vec<eth::Interaction>,
 }
 
 impl Settlement {
     pub async fn encode(
         eth: &Ethereum,
         solution: &competition::Solution,
-        // TODO I think it's possible to remove this parameter, do this in a follow-up
         auction: &competition::Auction,
     ) -> Result<Self> {
         let native_token = eth.contracts().weth();
@@ -73,39 +81,73 @@ impl Settlement {
             eth.chain_id(),
             settlement_contract.clone().address().into(),
         );
-        let limit_orders = auction
-            .orders
+        // The settlement context only contains the orders fulfilled by the
+        // solution, in the same order as their trades. JIT orders are passed
+        // to the legacy code as foreign liquidity orders instead.
+        let limit_orders = solution
+            .trades
             .iter()
-            .filter_map(|order| {
-                let boundary_order = to_boundary_order(order);
-                order_converter.normalize_limit_order(boundary_order).ok()
+            .filter_map(|trade| match trade {
+                competition::solution::Trade::Fulfillment(fulfillment) => Some(&fulfillment.order),
+                competition::solution::Trade::Jit(_) => None,
             })
-            .collect();
+            .map(|order| order_converter.normalize_limit_order(to_boundary_order(order)))
+            .collect::<Result<_>>()?;
+        let liquidity = super::liquidity::to_boundary_liquidity(
+            &auction.liquidity,
+            &auction
+                .tokens
+                .iter()
+                .filter_map(|token| Some((token.address, token.decimals?)))
+                .collect(),
+            &settlement_contract,
+            &solver::interactions::allowances::AllowanceManager::new(
+                settlement_contract.raw_instance().web3(),
+                settlement_contract.address(),
+            ),
+        )
+        .await?;
+        let slippage_calculator = SlippageCalculator {
+            relative: to_big_decimal(solution.solver.slippage().relative.clone()),
+            absolute: solution.solver.slippage().absolute.map(Into::into),
+        };
+        let external_prices = ExternalPrices::try_from_auction_prices(
+            native_token.address(),
+            auction
+                .tokens
+                .iter()
+                .filter_map(|token| {
+                    token
+                        .price
+                        .map(|price| (token.address.into(), price.into()))
+                })
+                .collect(),
+        )?;
+        let slippage = slippage_calculator.context(&external_prices);
+        let internalized_liquidity = encode_internalized_liquidity(
+            solution
+                .interactions
+                .iter()
+                .filter_map(|interaction| match interaction {
+                    competition::solution::Interaction::Liquidity(interaction)
+                        if interaction.internalize =>
+                    {
+                        Some(interaction)
+                    }
+                    _ => None,
+                }),
+            &liquidity,
+            &slippage,
+        )?;
         let settlement = convert_settlement(
             to_boundary_solution(solution, eth).await?,
             &SettlementContext {
                 orders: limit_orders,
-                // TODO: #899
-                liquidity: Default::default(),
+                liquidity,
             },
             Arc::new(AllowanceManager),
             Arc::new(order_converter),
-            SlippageCalculator {
-                relative: to_big_decimal(solution.solver.slippage().relative.clone()),
-                absolute: solution.solver.slippage().absolute.map(Into::into),
-            }
-            .context(&ExternalPrices::try_from_auction_prices(
-                native_token.address(),
-                auction
-                    .tokens
-                    .iter()
-                    .filter_map(|token| {
-                        token
-                            .price
-                            .map(|price| (token.address.into(), price.into()))
-                    })
-                    .collect(),
-            )?),
+            slippage,
             &DomainSeparator(domain.0),
         )
         .await?;
@@ -113,12 +155,14 @@ impl Settlement {
             settlement,
             contract: settlement_contract,
             solver: solution.solver.address(),
+            internalized_liquidity,
         })
     }
 
-    pub fn tx(self) -> eth::Tx {
+    pub fn tx(&self) -> eth::Tx {
         let encoded_settlement = self
             .settlement
+            .clone()
             .encode(InternalizationStrategy::SkipInternalizableInteraction);
         let builder = settle_method_builder(
             &self.contract,
@@ -136,7 +180,7 @@ impl Settlement {
     }
 
     pub async fn score(
-        self,
+        &self,
         eth: &Ethereum,
         auction: &competition::Auction,
         gas: eth::Gas,
@@ -158,7 +202,7 @@ impl Settlement {
         let unscaled_subsidized_fee = self.settlement.total_unscaled_subsidized_fees(&prices);
         Ok(RatedSettlement {
             id: 0,
-            settlement: self.settlement,
+            settlement: self.settlement.clone(),
             surplus,
             unscaled_subsidized_fee,
             scaled_unsubsidized_fee: scaled_solver_fees,
@@ -168,6 +212,82 @@ impl Settlement {
         .objective_value()
         .into())
     }
+
+    /// The clearing prices of the tokens traded by user orders.
+    pub fn clearing_prices(&self) -> HashMap<eth::TokenAddress, eth::U256> {
+        self.settlement
+            .clearing_prices()
+            .iter()
+            .map(|(&token, &price)| (token.into(), price))
+            .collect()
+    }
+
+    /// The executed amounts of the settled user orders.
+    pub fn trades(&self) -> impl Iterator<Item = (order::Uid, eth::U256)> + '_ {
+        self.settlement
+            .user_trades()
+            .map(|trade| (trade.order.metadata.uid.0.into(), trade.executed_amount))
+    }
+
+    /// The encoded swaps of the internalized liquidity interactions, in the
+    /// order in which they appear in the solution.
+    pub fn internalized_liquidity(&self) -> &[eth::Interaction] {
+        &self.internalized_liquidity
+    }
+}
+
+/// Encodes the internalized liquidity interactions on their own, since they
+/// are left out of the settlement transaction but still need to be reported.
+///
+/// The legacy settlement handlers approve the router or vault if needed before
+/// swapping through it. Approvals don't move any tokens, so only the swap is
+/// returned for every interaction.
+fn encode_internalized_liquidity<'a>(
+    interactions: impl Iterator<Item = &'a competition::solution::interaction::Liquidity>,
+    liquidity: &[Liquidity],
+    slippage: &SlippageContext,
+) -> Result<Vec<eth::Interaction>> {
+    interactions
+        .map(|interaction| {
+            let address = eth::H160::from(interaction.liquidity.address);
+            let liquidity = liquidity
+                .iter()
+                .find(|liquidity| liquidity.address() == Some(address))
+                .ok_or_else(|| anyhow!("invalid AMM {address:?}"))?;
+            let execution = slippage.apply_to_amm_execution(AmmOrderExecution {
+                input_max: (interaction.input.token.into(), interaction.input.amount),
+                output: (interaction.output.token.into(), interaction.output.amount),
+                internalizable: true,
+            })?;
+            let mut settlement = solver::settlement::Settlement::new(Default::default());
+            match liquidity {
+                Liquidity::ConstantProduct(liquidity) => {
+                    settlement.with_liquidity(liquidity, execution)
+                }
+                Liquidity::BalancerWeighted(liquidity) => {
+                    settlement.with_liquidity(liquidity, execution)
+                }
+                Liquidity::BalancerStable(liquidity) => {
+                    settlement.with_liquidity(liquidity, execution)
+                }
+                Liquidity::Concentrated(liquidity) => {
+                    settlement.with_liquidity(liquidity, execution)
+                }
+                Liquidity::LimitOrder(_) => Err(anyhow!("limit orders are not AMMs")),
+            }?;
+            let [_, mut interactions, _] = settlement
+                .encode(InternalizationStrategy::EncodeAllInteractions)
+                .interactions;
+            let (target, value, call_data) = interactions
+                .pop()
+                .context("liquidity without interactions")?;
+            Ok(eth::Interaction {
+                target: target.into(),
+                value: value.into(),
+                call_data: call_data.0,
+            })
+        })
+        .collect()
 }
 
 fn to_boundary_order(order: &competition::Order) -> Order {
@@ -227,13 +347,14 @@ fn to_boundary_order(order: &competition::Order) -> Order {
         signature: Default::default(),
         interactions: Interactions {
             pre: order
-                .interactions
+                .pre_interactions
                 .iter()
-                .map(|interaction| model::interaction::InteractionData {
-                    target: interaction.target.into(),
-                    value: interaction.value.into(),
-                    call_data: interaction.call_data.clone(),
-                })
+                .map(to_interaction_data)
+                .collect(),
+            post: order
+                .post_interactions
+                .iter()
+                .map(to_interaction_data)
                 .collect(),
         },
     }
@@ -248,8 +369,17 @@ async fn to_boundary_solution(
             .trades
             .iter()
             .enumerate()
-            .filter_map(|(index, trade)| match trade {
-                competition::solution::Trade::Fulfillment(fulfillment) => Some((
+            .filter_map(|(position, trade)| match trade {
+                competition::solution::Trade::Fulfillment(fulfillment) => {
+                    Some((position, fulfillment))
+                }
+                competition::solution::Trade::Jit(_) => None,
+            })
+            // The orders are keyed by their index in the settlement context,
+            // which only contains the fulfilled orders. See [`Settlement::encode`].
+            .enumerate()
+            .map(|(index, (position, fulfillment))| {
+                (
                     index,
                     ExecutedOrderModel {
                         exec_sell_amount: match fulfillment.order.side {
@@ -267,57 +397,27 @@ async fn to_boundary_solution(
                         exec_plan: Some(ExecutionPlan {
                             coordinates: ExecutionPlanCoordinatesModel {
                                 sequence: 0,
-                                position: index.try_into().unwrap(),
+                                position: position.try_into().unwrap(),
                             },
                             internal: false,
                         }),
                     },
-                )),
-                competition::solution::Trade::Jit(_) => None,
+                )
             })
             .collect(),
         foreign_liquidity_orders: solution
             .trades
             .iter()
             .filter_map(|trade| match trade {
-                competition::solution::Trade::Jit(jit) => Some(ExecutedLiquidityOrderModel {
+                competition::solution::Trade::Jit(jit) => Some(jit),
+                competition::solution::Trade::Fulfillment(_) => None,
+            })
+            .map(|jit| {
+                Ok(ExecutedLiquidityOrderModel {
                     order: NativeLiquidityOrder {
                         from: jit.order.signature.signer.into(),
-                        data: OrderData {
-                            sell_token: jit.order.sell.token.into(),
-                            buy_token: jit.order.buy.token.into(),
-                            receiver: Some(jit.order.receiver.into()),
-                            sell_amount: jit.order.sell.amount,
-                            buy_amount: jit.order.buy.amount,
-                            valid_to: jit.order.valid_to.into(),
-                            app_data: AppId(jit.order.app_data.into()),
-                            fee_amount: jit.order.fee.into(),
-                            kind: match jit.order.side {
-                                competition::order::Side::Buy => OrderKind::Buy,
-                                competition::order::Side::Sell => OrderKind::Sell,
-                            },
-                            partially_fillable: jit.order.partially_fillable,
-                            sell_token_balance: match jit.order.sell_token_balance {
-                                competition::order::SellTokenBalance::Erc20 => {
-                                    SellTokenSource::Erc20
-                                }
-                                competition::order::SellTokenBalance::Internal => {
-                                    SellTokenSource::Internal
-                                }
-                                competition::order::SellTokenBalance::External => {
-                                    SellTokenSource::External
-                                }
-                            },
-                            buy_token_balance: match jit.order.buy_token_balance {
-                                competition::order::BuyTokenBalance::Erc20 => {
-                                    BuyTokenDestination::Erc20
-                                }
-                                competition::order::BuyTokenBalance::Internal => {
-                                    BuyTokenDestination::Internal
-                                }
-                            },
-                        },
-                        signature: Default::default(),
+                        data: super::order::to_boundary_jit_order_data(&jit.order),
+                        signature: super::order::to_boundary_signature(&jit.order.signature)?,
                     },
                     exec_sell_amount: match jit.order.side {
                         order::Side::Sell => jit.executed.into(),
@@ -327,10 +427,9 @@ async fn to_boundary_solution(
                         order::Side::Buy => jit.executed.into(),
                         order::Side::Sell => Default::default(),
                     },
-                }),
-                competition::solution::Trade::Fulfillment(_) => None,
+                })
             })
-            .collect(),
+            .collect::<Result<_>>()?,
         amms: solution
             .interactions
             .iter()
@@ -409,6 +508,14 @@ fn to_token_amount(asset: &eth::Asset) -> TokenAmount {
     }
 }
 
+fn to_interaction_data(interaction: &eth::Interaction) -> model::interaction::InteractionData {
+    model::interaction::InteractionData {
+        target: interaction.target.into(),
+        value: interaction.value.into(),
+        call_data: interaction.call_data.clone(),
+    }
+}
+
 fn to_big_decimal(value: bigdecimal::BigDecimal) -> num::BigRational {
     let (x, exp) = value.into_bigint_and_exponent();
     let numerator_bytes = x.to_bytes_le();
@@ -440,3 +547,177 @@ impl AllowanceManaging for AllowanceManager {
             .collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::domain::liquidity,
+        contracts::{GPv2Settlement, WETH9},
+        maplit::hashmap,
+    };
+
+    /// Reports the same allowance of the settlement contract for every token.
+    struct FixedAllowance(eth::U256);
+
+    #[async_trait]
+    impl AllowanceManaging for FixedAllowance {
+        async fn get_allowances(
+            &self,
+            tokens: HashSet<eth::H160>,
+            spender: eth::H160,
+        ) -> Result<Allowances> {
+            Ok(Allowances::new(
+                spender,
+                tokens.into_iter().map(|token| (token, self.0)).collect(),
+            ))
+        }
+
+        async fn get_approvals(&self, _: &[ApprovalRequest]) -> Result<Vec<Approval>> {
+            unimplemented!()
+        }
+    }
+
+    /// A Uniswap V2 pool trading token A for token B through the router.
+    fn pool() -> liquidity::Liquidity {
+        liquidity::Liquidity {
+            id: 0.into(),
+            address: eth::H160([0x03; 20]).into(),
+            gas: eth::U256::from(100_000).into(),
+            kind: liquidity::Kind::UniswapV2(liquidity::uniswap_v2::Pool {
+                router: eth::H160([0x02; 20]).into(),
+                reserves: [
+                    eth::Asset {
+                        token: eth::H160([0x04; 20]).into(),
+                        amount: eth::U256::exp10(20),
+                    },
+                    eth::Asset {
+                        token: eth::H160([0x05; 20]).into(),
+                        amount: eth::U256::exp10(20),
+                    },
+                ],
+                fee: "0.003".parse().unwrap(),
+            }),
+        }
+    }
+
+    async fn boundary_liquidity(allowance: eth::U256) -> Vec<Liquidity> {
+        super::super::liquidity::to_boundary_liquidity(
+            &[pool()],
+            &Default::default(),
+            &shared::dummy_contract!(GPv2Settlement, [0x01; 20]),
+            &FixedAllowance(allowance),
+        )
+        .await
+        .unwrap()
+    }
+
+    /// Encodes a swap through a Uniswap V2 pool and returns the targets of the
+    /// interactions.
+    async fn encode_liquidity_interactions(allowance: eth::U256) -> Vec<eth::H160> {
+        let pool = eth::H160([0x03; 20]);
+        let token_a = eth::H160([0x04; 20]);
+        let token_b = eth::H160([0x05; 20]);
+
+        let liquidity = boundary_liquidity(allowance).await;
+        let settlement = convert_settlement(
+            SettledBatchAuctionModel {
+                amms: hashmap! {
+                    pool => UpdatedAmmModel {
+                        execution: vec![ExecutedAmmModel {
+                            sell_token: token_b,
+                            buy_token: token_a,
+                            exec_sell_amount: 99.into(),
+                            exec_buy_amount: 100.into(),
+                            exec_plan: Default::default(),
+                        }],
+                        cost: None,
+                    },
+                },
+                ..Default::default()
+            },
+            &SettlementContext {
+                orders: Default::default(),
+                liquidity,
+            },
+            Arc::new(AllowanceManager),
+            Arc::new(OrderConverter {
+                native_token: shared::dummy_contract!(WETH9, [0x06; 20]),
+                fee_objective_scaling_factor: 1.,
+                min_order_age: Default::default(),
+            }),
+            SlippageContext::default(),
+            &Default::default(),
+        )
+        .await
+        .unwrap()
+        .encode(InternalizationStrategy::SkipInternalizableInteraction);
+
+        settlement.interactions[1]
+            .iter()
+            .map(|(target, ..)| *target)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn encodes_liquidity_interactions() {
+        let router = eth::H160([0x02; 20]);
+        let token_a = eth::H160([0x04; 20]);
+
+        // The legacy settlement handler approves the router if the allowance
+        // is too small and swaps through it.
+        assert_eq!(
+            encode_liquidity_interactions(0.into()).await,
+            vec![token_a, router]
+        );
+        assert_eq!(
+            encode_liquidity_interactions(eth::U256::max_value()).await,
+            vec![router]
+        );
+    }
+
+    #[tokio::test]
+    async fn encodes_internalized_liquidity_swaps() {
+        let router = eth::H160([0x02; 20]);
+        let token_a = eth::H160([0x04; 20]);
+        let token_b = eth::H160([0x05; 20]);
+
+        // The approval of the router isn't reported, only the swap.
+        let interaction = competition::solution::interaction::Liquidity {
+            liquidity: pool(),
+            input: eth::Asset {
+                token: token_a.into(),
+                amount: 100.into(),
+            },
+            output: eth::Asset {
+                token: token_b.into(),
+                amount: 99.into(),
+            },
+            internalize: true,
+        };
+        let encoded = encode_internalized_liquidity(
+            std::iter::once(&interaction),
+            &boundary_liquidity(0.into()).await,
+            &SlippageContext::default(),
+        )
+        .unwrap();
+        assert_eq!(encoded.len(), 1);
+        assert_eq!(eth::H160::from(encoded[0].target), router);
+        assert!(!encoded[0].call_data.is_empty());
+
+        // Interactions with unknown liquidity can't be encoded.
+        let unknown = competition::solution::interaction::Liquidity {
+            liquidity: liquidity::Liquidity {
+                address: eth::H160([0x07; 20]).into(),
+                ..pool()
+            },
+            ..interaction
+        };
+        assert!(encode_internalized_liquidity(
+            std::iter::once(&unknown),
+            &boundary_liquidity(0.into()).await,
+            &SlippageContext::default(),
+        )
+        .is_err());
+    }
+}
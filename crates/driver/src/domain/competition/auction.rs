@@ -1,6 +1,6 @@
 use {
     crate::domain::{competition, eth, liquidity},
-    std::{num::ParseIntError, str::FromStr},
+    std::{collections::HashSet, num::ParseIntError, str::FromStr},
     thiserror::Error,
 };
 
@@ -19,6 +19,17 @@ pub struct Auction {
     pub deadline: Deadline,
 }
 
+impl Auction {
+    /// The token pairs traded by the orders in this auction. Liquidity is
+    /// fetched for these pairs.
+    pub fn token_pairs(&self) -> HashSet<liquidity::TokenPair> {
+        self.orders
+            .iter()
+            .filter_map(|order| liquidity::TokenPair::new(order.sell.token, order.buy.token))
+            .collect()
+    }
+}
+
 #[derive(Debug)]
 pub struct Token {
     pub decimals: Option<u8>,
@@ -78,12 +89,19 @@ impl Deadline {
             .map_err(|_| DeadlineExceeded)
     }
 
+    /// The time left until the deadline is reached.
+    pub fn remaining(&self) -> Result<std::time::Duration, DeadlineExceeded> {
+        (self.0 - chrono::Utc::now())
+            .to_std()
+            .map_err(|_| DeadlineExceeded)
+    }
+
     fn time_buffer() -> chrono::Duration {
         chrono::Duration::seconds(1)
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Id(pub u64);
 
 impl From<u64> for Id {
@@ -5,7 +5,7 @@ pub mod solution;
 pub use {
     auction::Auction,
     order::Order,
-    solution::{solve, Score, Solution},
+    solution::{compete, solve, Competition, Score, Solution},
 };
 
 use crate::domain::eth;
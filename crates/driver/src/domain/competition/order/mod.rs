@@ -1,4 +1,4 @@
-use crate::{domain::eth, util};
+use crate::{boundary, domain::eth, util};
 
 pub mod signature;
 
@@ -24,9 +24,13 @@ pub struct Order {
     pub kind: Kind,
     pub app_data: AppData,
     pub partial: Partial,
-    /// The onchain calls necessary to fulfill this order. These are set by the
-    /// user and included in the settlement transaction.
-    pub interactions: Vec<eth::Interaction>,
+    /// The onchain calls to run before sending user funds to the settlement
+    /// contract. These are set by the user and included in the settlement
+    /// transaction.
+    pub pre_interactions: Vec<eth::Interaction>,
+    /// The onchain calls to run after sending tokens from the settlement
+    /// contract to the user.
+    pub post_interactions: Vec<eth::Interaction>,
     pub sell_token_balance: SellTokenBalance,
     pub buy_token_balance: BuyTokenBalance,
     pub signature: Signature,
@@ -235,3 +239,89 @@ pub struct Jit {
     pub buy_token_balance: BuyTokenBalance,
     pub signature: Signature,
 }
+
+impl Jit {
+    /// The UID of this order, derived from the signed order data.
+    pub fn uid(&self, eth: &Ethereum) -> Uid {
+        boundary::order::jit_uid(self, &self.domain_separator(eth))
+    }
+
+    /// Verify that this order was signed by its signer. ECDSA signatures are
+    /// recovered offchain, while EIP-1271 signatures and presignatures are
+    /// checked against the blockchain.
+    pub async fn verify_signature(&self, eth: &Ethereum) -> Result<(), JitError> {
+        let domain = self.domain_separator(eth);
+        let valid = match self.signature.scheme {
+            signature::Scheme::Eip712 | signature::Scheme::EthSign => {
+                boundary::order::jit_recover_signer(self, &domain)
+                    .map_err(|_| JitError::InvalidSignature)?
+                    == Some(self.signature.signer)
+            }
+            signature::Scheme::Eip1271 => {
+                eth.is_valid_signature(
+                    self.signature.signer,
+                    boundary::order::jit_signing_hash(self, &domain),
+                    self.signature.data.clone(),
+                )
+                .await?
+            }
+            signature::Scheme::PreSign => eth.is_presigned(self.uid(eth).into()).await?,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(JitError::InvalidSignature)
+        }
+    }
+
+    /// Verify that the signer holds enough sell tokens and approved them to the
+    /// vault relayer, so that the given sell amount can be transferred into the
+    /// settlement contract.
+    ///
+    /// Only ERC20 balances are checked. Orders selling Balancer vault balances
+    /// are left to the settlement simulation.
+    pub async fn verify_funds(&self, eth: &Ethereum, amount: eth::U256) -> Result<(), JitError> {
+        if !matches!(self.sell_token_balance, SellTokenBalance::Erc20) {
+            return Ok(());
+        }
+        let signer = self.signature.signer;
+        let balance = eth.balance(signer, self.sell.token).await?;
+        if balance < amount {
+            return Err(JitError::InsufficientBalance);
+        }
+        let allowance = eth
+            .allowance(
+                signer,
+                eth::allowance::Spender {
+                    address: eth.vault_relayer().await?.into(),
+                    token: self.sell.token,
+                },
+            )
+            .await?;
+        if allowance.0.amount < amount {
+            return Err(JitError::InsufficientAllowance);
+        }
+        Ok(())
+    }
+
+    fn domain_separator(&self, eth: &Ethereum) -> eth::DomainSeparator {
+        signature::domain_separator(
+            eth.chain_id(),
+            eth.contracts().settlement().address().into(),
+        )
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum JitError {
+    #[error("invalid signature")]
+    InvalidSignature,
+    #[error("invalid executed amount")]
+    InvalidAmount,
+    #[error("insufficient sell token balance")]
+    InsufficientBalance,
+    #[error("insufficient sell token allowance")]
+    InsufficientAllowance,
+    #[error("blockchain error: {0:?}")]
+    Blockchain(#[from] blockchain::Error),
+}
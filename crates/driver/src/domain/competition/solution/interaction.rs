@@ -38,7 +38,7 @@ pub enum Interaction {
 }
 
 /// An arbitrary interaction with any smart contract.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Custom {
     pub target: eth::Address,
     pub value: eth::Ether,
@@ -11,7 +11,7 @@ use {
         Ethereum,
         Simulator,
     },
-    futures::future::try_join_all,
+    futures::future::{join_all, try_join_all},
     itertools::Itertools,
     num::ToPrimitive,
     rand::Rng,
@@ -66,8 +66,27 @@ impl Solution {
         Ok(approvals)
     }
 
+    /// Verify the JIT orders of this solution: each order must carry a valid
+    /// signature of its signer, and the signer must be able to pay for the
+    /// sell tokens which the trade transfers into the settlement contract. The
+    /// signer doesn't have to be the solver, since JIT orders can come from
+    /// market makers which the solver works with.
+    async fn verify_jit_orders(&self, eth: &Ethereum) -> Result<(), Error> {
+        try_join_all(self.jit_trades().map(|trade| async move {
+            trade.order.verify_signature(eth).await?;
+            let amount = trade.sell_amount().ok_or(order::JitError::InvalidAmount)?;
+            trade.order.verify_funds(eth, amount).await
+        }))
+        .await?;
+        Ok(())
+    }
+
     /// Return the trades which fulfill non-liquidity auction orders. These are
     /// the orders placed by end-users.
+    ///
+    /// JIT orders are provided by the solver and are not part of this set.
+    /// Like liquidity orders, they don't contribute surplus or fees to the
+    /// score of the solution.
     fn user_trades(&self) -> impl Iterator<Item = &trade::Fulfillment> {
         self.trades.iter().filter_map(|trade| match trade {
             Trade::Fulfillment(fulfillment) => match fulfillment.order.kind {
@@ -78,6 +97,14 @@ impl Solution {
         })
     }
 
+    /// Return the trades which add JIT orders to this solution.
+    fn jit_trades(&self) -> impl Iterator<Item = &trade::Jit> {
+        self.trades.iter().filter_map(|trade| match trade {
+            Trade::Jit(jit) => Some(jit),
+            Trade::Fulfillment(_) => None,
+        })
+    }
+
     /// Return the allowances in a normalized form, where there is only one
     /// allowance per [`eth::allowance::Spender`], and they're ordered
     /// deterministically.
@@ -113,7 +140,7 @@ impl Solution {
     }
 
     /// Simulate this solution on the blockchain.
-    async fn simulate(
+    pub async fn simulate(
         &self,
         eth: &Ethereum,
         simulator: &Simulator,
@@ -148,10 +175,9 @@ impl Solution {
         // Second, simulate the full access list, passing the partial access
         // list into the simulation. This way the settlement contract does not
         // fail, and hence the full access list estimation also does not fail.
-        let settlement = simulator.access_list(settlement).await?;
-
-        // Finally, get the gas for the settlement with the full access list.
-        simulator.gas(settlement).await.map_err(Into::into)
+        // The gas is that of the settlement with the full access list.
+        let (_, gas) = simulator.access_list_and_gas(settlement).await?;
+        Ok(gas)
     }
 
     async fn partial_access_list(
@@ -190,31 +216,102 @@ impl From<num::BigRational> for Score {
     }
 }
 
-/// Solve an auction and return the [`Score`] of the solution.
+/// Solve an auction and return the [`Score`] of the solution together with
+/// the [`Settlement`] which executes it.
 pub async fn solve(
     solver: Solver,
     eth: &Ethereum,
     simulator: &Simulator,
     auction: &competition::Auction,
-) -> Result<Score, Error> {
+) -> Result<(Score, Settlement), Error> {
     let solution = solver.solve(auction).await?;
+    solution.verify_jit_orders(eth).await?;
     // TODO Keep in mind that the driver needs to make sure that the solution
     // doesn't fail simulation. Currently this is the case, but this needs to stay
     // the same as this code changes.
     let gas = solution.simulate(eth, simulator, auction).await?;
     let settlement = Settlement::encode(eth, auction, &solution).await?;
-    settlement
-        .score(eth, auction, gas)
-        .await
-        .map_err(Into::into)
+    let score = settlement.score(eth, auction, gas).await?;
+    Ok((score, settlement))
+}
+
+/// The outcome of solving an auction with multiple solvers competing against
+/// each other.
+#[derive(Debug)]
+pub struct Competition {
+    /// The highest scoring solution, if any solver found a valid one.
+    pub winner: Option<Scored>,
+    /// Valid solutions which were outscored by the winner.
+    pub losers: Vec<Scored>,
+    /// The solvers whose solutions were rejected, along with the reason.
+    pub rejected: Vec<(Solver, Error)>,
+}
+
+/// A simulated and scored solution.
+#[derive(Debug)]
+pub struct Scored {
+    pub solver: Solver,
+    pub score: Score,
+    pub settlement: Settlement,
+}
+
+/// Solve an auction with all of the solvers in parallel. Each solution is
+/// simulated and scored, and the solution with the highest score wins. The
+/// solutions which aren't scored before the auction deadline are rejected.
+pub async fn compete(
+    solvers: &[Solver],
+    eth: &Ethereum,
+    simulator: &Simulator,
+    auction: &competition::Auction,
+) -> Competition {
+    let results = join_all(solvers.iter().map(|solver| async move {
+        let result = match auction.deadline.remaining() {
+            Ok(remaining) => {
+                tokio::time::timeout(remaining, solve(solver.clone(), eth, simulator, auction))
+                    .await
+                    .unwrap_or(Err(Error::DeadlineExceeded(
+                        competition::auction::DeadlineExceeded,
+                    )))
+            }
+            Err(err) => Err(err.into()),
+        };
+        (solver.clone(), result)
+    }))
+    .await;
+
+    let mut scored = Vec::new();
+    let mut rejected = Vec::new();
+    for (solver, result) in results {
+        match result {
+            Ok((score, settlement)) => scored.push(Scored {
+                solver,
+                score,
+                settlement,
+            }),
+            Err(err) => rejected.push((solver, err)),
+        }
+    }
+    scored.sort_by(|a, b| b.score.cmp(&a.score));
+    let mut scored = scored.into_iter();
+    Competition {
+        winner: scored.next(),
+        losers: scored.collect(),
+        rejected,
+    }
 }
 
 /// A unique solution ID. This ID is encoded as part of the calldata of the
 /// settlement transaction, and it's used by the protocol to match onchain
 /// transactions to corresponding solutions.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Id(pub u32);
 
+impl From<[u8; 4]> for Id {
+    fn from(bytes: [u8; 4]) -> Self {
+        Self(u32::from_be_bytes(bytes))
+    }
+}
+
 impl Id {
     pub fn random() -> Self {
         Self(rand::thread_rng().gen())
@@ -233,6 +330,10 @@ pub enum Error {
     Simulation(#[from] simulator::Error),
     #[error("blockchain error: {0:?}")]
     Blockchain(#[from] blockchain::Error),
+    #[error("invalid JIT order: {0:?}")]
+    Jit(#[from] order::JitError),
     #[error("boundary error: {0:?}")]
     Boundary(#[from] boundary::Error),
+    #[error("the auction deadline was exceeded")]
+    DeadlineExceeded(#[from] competition::auction::DeadlineExceeded),
 }
@@ -1,7 +1,14 @@
-use crate::{
-    boundary,
-    domain::{competition, eth},
-    Ethereum,
+use {
+    super::interaction,
+    crate::{
+        boundary,
+        domain::{
+            competition::{self, order},
+            eth,
+        },
+        Ethereum,
+    },
+    std::collections::HashMap,
 };
 
 /// A transaction calling into our settlement contract on the blockchain.
@@ -11,8 +18,14 @@ use crate::{
 /// enough, it's an intermediate state between a solution and an onchain
 /// settlement. The intention with this type is to represent the settlement
 /// transaction itself, not an intermediate state.
-#[derive(Debug)]
-pub struct Settlement(boundary::Settlement);
+#[derive(Debug, Clone)]
+pub struct Settlement {
+    inner: boundary::Settlement,
+    /// The interactions of the solution which are executed using the liquidity
+    /// of our settlement contract and are therefore not part of the
+    /// transaction.
+    internalized: Vec<interaction::Custom>,
+}
 
 impl Settlement {
     /// Encode a solution into an onchain settlement transaction.
@@ -21,25 +34,66 @@ impl Settlement {
         auction: &competition::Auction,
         solution: &competition::Solution,
     ) -> anyhow::Result<Self> {
-        boundary::Settlement::encode(eth, solution, auction)
-            .await
-            .map(Self)
+        let inner = boundary::Settlement::encode(eth, solution, auction).await?;
+        // Liquidity interactions are only encoded in the boundary, which
+        // returns them in the order in which they appear in the solution.
+        let mut encoded_liquidity = inner.internalized_liquidity().iter();
+        let internalized = solution
+            .interactions
+            .iter()
+            .filter_map(|interaction| match interaction {
+                super::Interaction::Custom(custom) if custom.internalize => Some(custom.clone()),
+                super::Interaction::Liquidity(liquidity) if liquidity.internalize => {
+                    let encoded = encoded_liquidity.next()?;
+                    Some(interaction::Custom {
+                        target: encoded.target,
+                        value: encoded.value,
+                        call_data: encoded.call_data.clone(),
+                        allowances: Default::default(),
+                        inputs: vec![liquidity.input],
+                        outputs: vec![liquidity.output],
+                        internalize: true,
+                    })
+                }
+                _ => None,
+            })
+            .collect();
+        Ok(Self {
+            inner,
+            internalized,
+        })
     }
 
     /// Calculate the score for this settlement. This method is here only
     /// temporarily, in the future the entire scoring formula should operate on
     /// a [`super::Solution`].
     pub(super) async fn score(
-        self,
+        &self,
         eth: &Ethereum,
         auction: &competition::Auction,
         gas: eth::Gas,
     ) -> Result<super::Score, boundary::Error> {
-        self.0.score(eth, auction, gas).await
+        self.inner.score(eth, auction, gas).await
     }
 
     /// The onchain transaction representing this settlement.
-    pub fn tx(self) -> eth::Tx {
-        self.0.tx()
+    pub fn tx(&self) -> eth::Tx {
+        self.inner.tx()
+    }
+
+    /// The uniform clearing prices of the tokens traded by user orders.
+    pub fn clearing_prices(&self) -> HashMap<eth::TokenAddress, eth::U256> {
+        self.inner.clearing_prices()
+    }
+
+    /// The settled user orders and their executed amounts.
+    pub fn trades(&self) -> impl Iterator<Item = (order::Uid, eth::U256)> + '_ {
+        self.inner.trades()
+    }
+
+    /// The interactions which were left out of the transaction because they
+    /// can be executed using the liquidity of our settlement contract.
+    pub fn internalized_interactions(&self) -> &[interaction::Custom] {
+        &self.internalized
     }
 }
@@ -1,4 +1,7 @@
-use crate::domain::competition::{self, order};
+use crate::domain::{
+    competition::{self, order},
+    eth,
+};
 
 /// A trade which executes an order as part of this solution.
 #[derive(Debug)]
@@ -24,3 +27,79 @@ pub struct Jit {
     pub order: order::Jit,
     pub executed: competition::order::TargetAmount,
 }
+
+impl Jit {
+    /// The amount of sell tokens, including the fee, transferred from the
+    /// signer of the JIT order by this trade. Returns [`None`] if the amount
+    /// overflows or the order has a zero limit amount.
+    pub fn sell_amount(&self) -> Option<eth::U256> {
+        let executed = eth::U256::from(self.executed);
+        let (sell, limit) = match self.order.side {
+            order::Side::Sell => (executed, self.order.sell.amount),
+            order::Side::Buy => (
+                executed
+                    .checked_mul(self.order.sell.amount)?
+                    .checked_div(self.order.buy.amount)?,
+                self.order.buy.amount,
+            ),
+        };
+        let fee = eth::U256::from(self.order.fee)
+            .checked_mul(executed)?
+            .checked_div(limit)?;
+        sell.checked_add(fee)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jit(side: order::Side, executed: u64) -> Jit {
+        Jit {
+            order: order::Jit {
+                sell: eth::Asset {
+                    amount: 100.into(),
+                    token: eth::H160([1; 20]).into(),
+                },
+                buy: eth::Asset {
+                    amount: 50.into(),
+                    token: eth::H160([2; 20]).into(),
+                },
+                fee: eth::U256::from(10).into(),
+                receiver: eth::H160([3; 20]).into(),
+                valid_to: u32::MAX.into(),
+                app_data: [0; 32].into(),
+                side,
+                partially_fillable: true,
+                sell_token_balance: order::SellTokenBalance::Erc20,
+                buy_token_balance: order::BuyTokenBalance::Erc20,
+                signature: order::Signature {
+                    scheme: order::signature::Scheme::Eip712,
+                    data: Default::default(),
+                    signer: eth::H160([4; 20]).into(),
+                },
+            },
+            executed: eth::U256::from(executed).into(),
+        }
+    }
+
+    #[test]
+    fn sell_amount_includes_proportional_fee() {
+        // Half of the sell amount is executed, so half of the fee is charged.
+        assert_eq!(jit(order::Side::Sell, 50).sell_amount(), Some(55.into()));
+        // Half of the buy amount is executed, which sells half of the sell
+        // amount and is charged half of the fee.
+        assert_eq!(jit(order::Side::Buy, 25).sell_amount(), Some(55.into()));
+    }
+
+    #[test]
+    fn sell_amount_rejects_invalid_amounts() {
+        let mut zero_limit = jit(order::Side::Buy, 25);
+        zero_limit.order.buy.amount = 0.into();
+        assert_eq!(zero_limit.sell_amount(), None);
+
+        let mut overflow = jit(order::Side::Sell, 0);
+        overflow.executed = eth::U256::max_value().into();
+        assert_eq!(overflow.sell_amount(), None);
+    }
+}
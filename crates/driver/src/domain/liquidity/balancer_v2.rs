@@ -0,0 +1,44 @@
+use crate::domain::eth;
+
+/// A Balancer V2 weighted product pool.
+#[derive(Debug, Clone)]
+pub struct WeightedPool {
+    /// The Balancer V2 vault which holds the reserves of the pool and
+    /// executes swaps against it.
+    pub vault: eth::ContractAddress,
+    pub id: eth::H256,
+    pub reserves: Vec<WeightedReserve>,
+    /// The fraction of the input amount which is taken as a fee.
+    pub fee: bigdecimal::BigDecimal,
+}
+
+#[derive(Debug, Clone)]
+pub struct WeightedReserve {
+    pub asset: eth::Asset,
+    /// The normalized weight of the token in the pool, all weights sum up to
+    /// one.
+    pub weight: bigdecimal::BigDecimal,
+    /// The factor the balance is scaled by to normalize token decimals.
+    pub scaling_factor: eth::U256,
+}
+
+/// A Balancer V2 stable pool.
+#[derive(Debug, Clone)]
+pub struct StablePool {
+    /// The Balancer V2 vault which holds the reserves of the pool and
+    /// executes swaps against it.
+    pub vault: eth::ContractAddress,
+    pub id: eth::H256,
+    pub reserves: Vec<StableReserve>,
+    /// The amplification parameter of the StableSwap invariant.
+    pub amplification_parameter: num::BigRational,
+    /// The fraction of the input amount which is taken as a fee.
+    pub fee: bigdecimal::BigDecimal,
+}
+
+#[derive(Debug, Clone)]
+pub struct StableReserve {
+    pub asset: eth::Asset,
+    /// The factor the balance is scaled by to normalize token decimals.
+    pub scaling_factor: eth::U256,
+}
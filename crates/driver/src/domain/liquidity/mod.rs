@@ -0,0 +1,68 @@
+use crate::domain::eth;
+
+pub mod balancer_v2;
+pub mod uniswap_v2;
+pub mod uniswap_v3;
+
+/// A source of liquidity which can be used by the solver.
+#[derive(Debug, Clone)]
+pub struct Liquidity {
+    pub id: Id,
+    /// Depending on the liquidity provider, this can mean different things.
+    /// Usually it's the address of the liquidity pool.
+    pub address: eth::Address,
+    /// Estimation of gas needed to use this liquidity on-chain.
+    pub gas: eth::Gas,
+    pub kind: Kind,
+}
+
+/// The onchain state of a liquidity source, which solvers use to compute how
+/// much can be traded through it.
+#[derive(Debug, Clone)]
+pub enum Kind {
+    UniswapV2(uniswap_v2::Pool),
+    UniswapV3(uniswap_v3::Pool),
+    BalancerV2Weighted(balancer_v2::WeightedPool),
+    BalancerV2Stable(balancer_v2::StablePool),
+}
+
+/// An unordered pair of tokens which can be traded against each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TokenPair(eth::TokenAddress, eth::TokenAddress);
+
+impl TokenPair {
+    /// Create a token pair, returns `None` if both tokens are the same.
+    pub fn new(a: eth::TokenAddress, b: eth::TokenAddress) -> Option<Self> {
+        match a.cmp(&b) {
+            std::cmp::Ordering::Less => Some(Self(a, b)),
+            std::cmp::Ordering::Greater => Some(Self(b, a)),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// The tokens of the pair in ascending order.
+    pub fn get(&self) -> (eth::TokenAddress, eth::TokenAddress) {
+        (self.0, self.1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Id(pub usize);
+
+impl From<usize> for Id {
+    fn from(value: usize) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Id> for usize {
+    fn from(value: Id) -> Self {
+        value.0
+    }
+}
+
+impl PartialEq<usize> for Id {
+    fn eq(&self, other: &usize) -> bool {
+        self.0 == *other
+    }
+}
@@ -0,0 +1,13 @@
+use crate::domain::eth;
+
+/// A Uniswap V2-like constant product pool, used by Uniswap V2 itself and its
+/// forks like SushiSwap.
+#[derive(Debug, Clone)]
+pub struct Pool {
+    /// The router contract through which swaps against the pool are executed.
+    pub router: eth::ContractAddress,
+    /// The reserves of the two tokens of the pool.
+    pub reserves: [eth::Asset; 2],
+    /// The fraction of the input amount which is taken as a fee.
+    pub fee: bigdecimal::BigDecimal,
+}
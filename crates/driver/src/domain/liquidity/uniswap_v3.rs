@@ -0,0 +1,19 @@
+use {crate::domain::eth, std::collections::BTreeMap};
+
+/// A Uniswap V3 concentrated liquidity pool.
+#[derive(Debug, Clone)]
+pub struct Pool {
+    /// The router contract through which swaps against the pool are executed.
+    pub router: eth::ContractAddress,
+    pub tokens: [eth::TokenAddress; 2],
+    /// The square root of the current price as a Q64.96 fixed point number.
+    pub sqrt_price: eth::U256,
+    /// The liquidity which is currently in range.
+    pub liquidity: u128,
+    /// The current tick of the pool.
+    pub tick: i32,
+    /// The change in liquidity when crossing each of the initialized ticks.
+    pub liquidity_net: BTreeMap<i32, i128>,
+    /// The fraction of the input amount which is taken as a fee.
+    pub fee: bigdecimal::BigDecimal,
+}
@@ -0,0 +1,292 @@
+use {
+    crate::{
+        boundary,
+        domain::{
+            competition::{self, order, solution},
+            eth,
+        },
+        infra::{blockchain, liquidity},
+        Ethereum,
+        Solver,
+    },
+    std::collections::HashMap,
+    thiserror::Error,
+};
+
+/// A single order which should be priced. Quotes are used by the protocol to
+/// estimate the price of an order before the user places it.
+#[derive(Debug, Clone)]
+pub struct Order {
+    pub sell: eth::TokenAddress,
+    pub buy: eth::TokenAddress,
+    pub side: order::Side,
+    /// The amount of the sell token for sell orders, or of the buy token for
+    /// buy orders.
+    pub amount: order::TargetAmount,
+}
+
+/// The gas needed to call the settlement contract, excluding the trades and
+/// the interactions.
+const SETTLEMENT_GAS: u64 = boundary::gas::INITIALIZATION_COST + boundary::gas::SETTLEMENT;
+
+/// The gas needed to execute a trade: computing the trade execution and
+/// transferring the tokens into and out of the settlement contract.
+const TRADE_GAS: u64 = boundary::gas::TRADE + 2 * boundary::gas::ERC20_TRANSFER;
+
+/// The amounts at which a solver can fill an [`Order`], and the gas needed to
+/// settle it.
+#[derive(Debug)]
+pub struct Quote {
+    pub sell: eth::U256,
+    pub buy: eth::U256,
+    pub gas: eth::Gas,
+}
+
+impl Order {
+    /// Quote the order by asking the solver to solve an auction containing only
+    /// this order.
+    ///
+    /// The order isn't signed by anyone and nobody holds its sell tokens, so
+    /// the settlement can't be simulated: its interactions would revert without
+    /// the sell tokens in the settlement contract. Like the legacy quoter, the
+    /// gas is estimated from the trades and interactions of the solution
+    /// instead.
+    pub async fn quote(
+        &self,
+        solver: &Solver,
+        eth: &Ethereum,
+        liquidity: &liquidity::Fetcher,
+    ) -> Result<Quote, Error> {
+        let mut auction = self.fake_auction(eth.gas_price().await?);
+        auction.liquidity = liquidity.fetch(&auction).await;
+        let deadline_exceeded = || Error::Solution(competition::auction::DeadlineExceeded.into());
+        let remaining = auction
+            .deadline
+            .remaining()
+            .map_err(|_| deadline_exceeded())?;
+        tokio::time::timeout(remaining, self.solve(solver, &auction))
+            .await
+            .unwrap_or_else(|_| Err(deadline_exceeded()))
+    }
+
+    async fn solve(&self, solver: &Solver, auction: &competition::Auction) -> Result<Quote, Error> {
+        let solution = solver.solve(auction).await.map_err(solution::Error::from)?;
+        let fulfilled = solution
+            .trades
+            .iter()
+            .any(|trade| matches!(trade, solution::Trade::Fulfillment(_)));
+        if !fulfilled {
+            return Err(Unfillable::NoSolution.into());
+        }
+        let (sell, buy) = self.amounts(&solution.prices)?;
+        Ok(Quote {
+            sell,
+            buy,
+            gas: gas(&solution),
+        })
+    }
+
+    /// The sell and buy amounts of the order when it's executed at the
+    /// clearing prices of the solution. The amount which isn't fixed by the
+    /// order is rounded in favor of the user.
+    fn amounts(
+        &self,
+        prices: &HashMap<eth::TokenAddress, competition::Price>,
+    ) -> Result<(eth::U256, eth::U256), Unfillable> {
+        let price = |token| {
+            prices
+                .get(&token)
+                .map(|price| eth::U256::from(*price))
+                .filter(|price| !price.is_zero())
+                .ok_or(Unfillable::InvalidPrices)
+        };
+        let (sell_price, buy_price) = (price(self.sell)?, price(self.buy)?);
+        let amount = eth::U256::from(self.amount);
+        match self.side {
+            order::Side::Sell => {
+                let buy = amount
+                    .checked_mul(sell_price)
+                    .ok_or(Unfillable::InvalidPrices)?
+                    / buy_price;
+                Ok((amount, buy))
+            }
+            order::Side::Buy => {
+                let sell = amount
+                    .checked_mul(buy_price)
+                    .and_then(|value| value.checked_add(sell_price - 1))
+                    .ok_or(Unfillable::InvalidPrices)?
+                    / sell_price;
+                Ok((sell, amount))
+            }
+        }
+    }
+
+    /// An auction containing only this order. The limit amount of the order is
+    /// set so that any price is acceptable, the solver is expected to find the
+    /// best one.
+    fn fake_auction(&self, gas_price: eth::EffectiveGasPrice) -> competition::Auction {
+        let amount = eth::U256::from(self.amount);
+        let (sell, buy) = match self.side {
+            order::Side::Sell => (amount, eth::U256::one()),
+            order::Side::Buy => (eth::U256::from(u128::MAX), amount),
+        };
+        competition::Auction {
+            id: None,
+            tokens: [self.sell, self.buy]
+                .into_iter()
+                .map(|address| competition::auction::Token {
+                    decimals: None,
+                    symbol: None,
+                    address,
+                    price: None,
+                    available_balance: Default::default(),
+                    trusted: false,
+                })
+                .collect(),
+            orders: vec![competition::Order {
+                uid: [0; 56].into(),
+                receiver: None,
+                valid_to: u32::MAX.into(),
+                sell: eth::Asset {
+                    amount: sell,
+                    token: self.sell,
+                },
+                buy: eth::Asset {
+                    amount: buy,
+                    token: self.buy,
+                },
+                side: self.side,
+                fee: order::Fee {
+                    user: eth::U256::zero().into(),
+                    solver: eth::U256::zero().into(),
+                },
+                kind: order::Kind::Market,
+                app_data: [0; 32].into(),
+                partial: order::Partial::No,
+                pre_interactions: Default::default(),
+                post_interactions: Default::default(),
+                sell_token_balance: order::SellTokenBalance::Erc20,
+                buy_token_balance: order::BuyTokenBalance::Erc20,
+                signature: order::Signature {
+                    scheme: order::signature::Scheme::PreSign,
+                    data: Default::default(),
+                    signer: eth::H160::zero().into(),
+                },
+                reward: 0.,
+            }],
+            liquidity: Default::default(),
+            gas_price,
+            deadline: (chrono::Utc::now() + timeout()).into(),
+        }
+    }
+}
+
+/// Estimate the gas needed to settle the solution from its trades and
+/// interactions.
+fn gas(solution: &competition::Solution) -> eth::Gas {
+    let interactions = solution
+        .interactions
+        .iter()
+        .map(|interaction| match interaction {
+            solution::Interaction::Liquidity(interaction) => interaction.liquidity.gas.0,
+            // Solvers don't estimate the gas of their custom interactions, so
+            // they are assumed to cost as much as a median AMM swap.
+            solution::Interaction::Custom(_) => boundary::gas::GAS_PER_UNISWAP.into(),
+        })
+        .fold(eth::U256::zero(), eth::U256::saturating_add);
+    eth::U256::from(SETTLEMENT_GAS)
+        .saturating_add(eth::U256::from(TRADE_GAS).saturating_mul(solution.trades.len().into()))
+        .saturating_add(interactions)
+        .into()
+}
+
+/// The time the solver has to quote an order, including the simulation of
+/// the settlement. Quotes are requested while the user is waiting, so this is
+/// much shorter than the deadline of an auction.
+fn timeout() -> chrono::Duration {
+    chrono::Duration::seconds(5)
+}
+
+/// The reasons why a solver can't fill an order. These aren't errors, the
+/// solver handled the request correctly but couldn't quote the order.
+#[derive(Debug, Error)]
+pub enum Unfillable {
+    #[error("the solver didn't find a solution for the order")]
+    NoSolution,
+    #[error("the clearing prices of the solution are missing or invalid")]
+    InvalidPrices,
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("unfillable order: {0}")]
+    Unfillable(#[from] Unfillable),
+    #[error("blockchain error: {0:?}")]
+    Blockchain(#[from] blockchain::Error),
+    #[error("solution error: {0:?}")]
+    Solution(#[from] solution::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(byte: u8) -> eth::TokenAddress {
+        eth::H160([byte; 20]).into()
+    }
+
+    fn order(side: order::Side, amount: u64) -> Order {
+        Order {
+            sell: token(1),
+            buy: token(2),
+            side,
+            amount: eth::U256::from(amount).into(),
+        }
+    }
+
+    /// Clearing prices at which 1 sell token trades for 2/3 buy tokens.
+    fn prices() -> HashMap<eth::TokenAddress, competition::Price> {
+        [
+            (token(1), eth::U256::from(2).into()),
+            (token(2), eth::U256::from(3).into()),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn rounds_buy_amount_of_sell_orders_down() {
+        let (sell, buy) = order(order::Side::Sell, 10).amounts(&prices()).unwrap();
+        assert_eq!(sell, 10.into());
+        // 10 * 2 / 3 = 6.67
+        assert_eq!(buy, 6.into());
+    }
+
+    #[test]
+    fn rounds_sell_amount_of_buy_orders_up() {
+        let (sell, buy) = order(order::Side::Buy, 10).amounts(&prices()).unwrap();
+        // 10 * 3 / 2 = 15 is exact and isn't rounded.
+        assert_eq!(sell, 15.into());
+        assert_eq!(buy, 10.into());
+
+        let (sell, buy) = order(order::Side::Buy, 11).amounts(&prices()).unwrap();
+        // 11 * 3 / 2 = 16.5
+        assert_eq!(sell, 17.into());
+        assert_eq!(buy, 11.into());
+    }
+
+    #[test]
+    fn rejects_missing_and_zero_prices() {
+        let mut prices = prices();
+        prices.insert(token(2), eth::U256::zero().into());
+        assert!(matches!(
+            order(order::Side::Sell, 10).amounts(&prices),
+            Err(Unfillable::InvalidPrices)
+        ));
+        prices.remove(&token(2));
+        assert!(matches!(
+            order(order::Side::Buy, 10).amounts(&prices),
+            Err(Unfillable::InvalidPrices)
+        ));
+    }
+}
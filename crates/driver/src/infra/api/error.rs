@@ -0,0 +1,142 @@
+use {
+    crate::{
+        domain::{
+            competition::{order, solution},
+            quote,
+        },
+        infra::{metrics::Metrics, simulator, solver},
+    },
+    axum::http::StatusCode,
+    serde::Serialize,
+};
+
+/// The kinds of errors returned by the driver API. Every kind maps to its own
+/// HTTP status code.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Kind {
+    /// The auction sent to the driver is malformed.
+    InvalidAuction,
+    /// The auction deadline was exceeded before a solution was scored.
+    DeadlineExceeded,
+    /// The solver failed to respond with a valid solution.
+    SolverFailed,
+    /// The solution of the solver failed to simulate.
+    SimulationFailed,
+    /// The driver failed to communicate with the blockchain.
+    Blockchain,
+    /// The requested solution doesn't exist or was already discarded.
+    SolutionNotFound,
+    /// The request isn't authenticated with the credentials required by the
+    /// driver.
+    Unauthorized,
+    /// The request body exceeds the size limit of the driver API.
+    PayloadTooLarge,
+}
+
+impl Kind {
+    fn status(self) -> StatusCode {
+        match self {
+            Self::InvalidAuction => StatusCode::BAD_REQUEST,
+            Self::DeadlineExceeded => StatusCode::GATEWAY_TIMEOUT,
+            Self::SolverFailed => StatusCode::BAD_GATEWAY,
+            Self::SimulationFailed => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::Blockchain => StatusCode::SERVICE_UNAVAILABLE,
+            Self::SolutionNotFound => StatusCode::NOT_FOUND,
+            Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+        }
+    }
+
+    /// The name of the error kind, used in the response body and as a metric
+    /// label.
+    fn name(self) -> &'static str {
+        match self {
+            Self::InvalidAuction => "InvalidAuction",
+            Self::DeadlineExceeded => "DeadlineExceeded",
+            Self::SolverFailed => "SolverFailed",
+            Self::SimulationFailed => "SimulationFailed",
+            Self::Blockchain => "Blockchain",
+            Self::SolutionNotFound => "SolutionNotFound",
+            Self::Unauthorized => "Unauthorized",
+            Self::PayloadTooLarge => "PayloadTooLarge",
+        }
+    }
+}
+
+/// An error response of the driver API. Errors are returned as JSON bodies
+/// with the status code of their [`Kind`].
+#[derive(Debug)]
+pub(super) struct Error {
+    kind: Kind,
+    description: String,
+}
+
+impl Error {
+    pub fn new(kind: Kind, description: impl Into<String>) -> Self {
+        Self {
+            kind,
+            description: description.into(),
+        }
+    }
+}
+
+impl axum::response::IntoResponse for Error {
+    fn into_response(self) -> axum::response::Response {
+        tracing::warn!(kind = self.kind.name(), description = %self.description, "API error");
+        Metrics::get()
+            .api_errors
+            .with_label_values(&[self.kind.name()])
+            .inc();
+        (
+            self.kind.status(),
+            axum::response::Json(Body {
+                kind: self.kind.name(),
+                description: self.description,
+            }),
+        )
+            .into_response()
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Body {
+    kind: &'static str,
+    description: String,
+}
+
+/// Malformed request bodies are reported like any other invalid auction, so
+/// that they get a JSON error body and are counted in the metrics.
+impl From<axum::extract::rejection::JsonRejection> for Error {
+    fn from(err: axum::extract::rejection::JsonRejection) -> Self {
+        Self::new(Kind::InvalidAuction, err.body_text())
+    }
+}
+
+impl From<solution::Error> for Error {
+    fn from(err: solution::Error) -> Self {
+        let kind = match &err {
+            solution::Error::Solver(solver::Error::DeadlineExceeded(_))
+            | solution::Error::DeadlineExceeded(_) => Kind::DeadlineExceeded,
+            solution::Error::Blockchain(_)
+            | solution::Error::Simulation(simulator::Error::Blockchain(_))
+            | solution::Error::Simulation(simulator::Error::Evm(simulator::evm::Error::Node(_)))
+            | solution::Error::Jit(order::JitError::Blockchain(_)) => Kind::Blockchain,
+            solution::Error::Simulation(_) => Kind::SimulationFailed,
+            solution::Error::Solver(_) | solution::Error::Jit(_) | solution::Error::Boundary(_) => {
+                Kind::SolverFailed
+            }
+        };
+        Self::new(kind, err.to_string())
+    }
+}
+
+impl From<quote::Error> for Error {
+    fn from(err: quote::Error) -> Self {
+        match err {
+            quote::Error::Solution(err) => err.into(),
+            quote::Error::Blockchain(_) => Self::new(Kind::Blockchain, err.to_string()),
+            quote::Error::Unfillable(_) => Self::new(Kind::SolverFailed, err.to_string()),
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use {
+    crate::{
+        domain::{competition::solution, eth},
+        util::serialize,
+    },
+    serde::Serialize,
+    serde_with::serde_as,
+    std::collections::HashMap,
+};
+
+impl Execution {
+    pub fn from_domain(settlement: &solution::Settlement, tx: eth::Tx, nonce: eth::U256) -> Self {
+        Self {
+            account: tx.from.into(),
+            nonce: nonce.as_u64(),
+            clearing_prices: settlement
+                .clearing_prices()
+                .into_iter()
+                .map(|(token, price)| (token.into(), price))
+                .collect(),
+            trades: settlement
+                .trades()
+                .map(|(uid, executed_amount)| Trade {
+                    uid: uid.into(),
+                    executed_amount,
+                })
+                .collect(),
+            internalized_interactions: settlement
+                .internalized_interactions()
+                .iter()
+                .map(|interaction| InternalizedInteraction {
+                    calldata: interaction.call_data.clone(),
+                    inputs: interaction
+                        .inputs
+                        .iter()
+                        .map(|asset| (asset.token.into(), asset.amount))
+                        .collect(),
+                    outputs: interaction
+                        .outputs
+                        .iter()
+                        .map(|asset| (asset.token.into(), asset.amount))
+                        .collect(),
+                })
+                .collect(),
+            calldata: tx.input,
+            // TODO Sign the execution once it's decided what the signature
+            // should cover.
+            signature: Default::default(),
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Execution {
+    account: eth::H160,
+    nonce: u64,
+    #[serde_as(as = "HashMap<_, serialize::U256>")]
+    clearing_prices: HashMap<eth::H160, eth::U256>,
+    trades: Vec<Trade>,
+    internalized_interactions: Vec<InternalizedInteraction>,
+    #[serde_as(as = "serialize::Hex")]
+    calldata: Vec<u8>,
+    signature: String,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Trade {
+    #[serde_as(as = "serialize::Hex")]
+    uid: [u8; 56],
+    #[serde_as(as = "serialize::U256")]
+    executed_amount: eth::U256,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+struct InternalizedInteraction {
+    #[serde_as(as = "serialize::Hex")]
+    calldata: Vec<u8>,
+    #[serde_as(as = "HashMap<_, serialize::U256>")]
+    inputs: HashMap<eth::H160, eth::U256>,
+    #[serde_as(as = "HashMap<_, serialize::U256>")]
+    outputs: HashMap<eth::H160, eth::U256>,
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, autopilot::driver_model::execute, maplit::hashmap};
+
+    #[test]
+    fn serializes_autopilot_response() {
+        let token = eth::H160([1; 20]);
+        let execution = Execution {
+            account: eth::H160([2; 20]),
+            nonce: 3,
+            clearing_prices: hashmap! { token => 1_000_000.into() },
+            trades: vec![Trade {
+                uid: [4; 56],
+                executed_amount: 5.into(),
+            }],
+            internalized_interactions: vec![InternalizedInteraction {
+                calldata: vec![6],
+                inputs: hashmap! { token => 7.into() },
+                outputs: Default::default(),
+            }],
+            calldata: vec![8, 9],
+            signature: Default::default(),
+        };
+        let response: execute::Response =
+            serde_json::from_value(serde_json::to_value(&execution).unwrap()).unwrap();
+        assert_eq!(response.account, eth::H160([2; 20]));
+        assert_eq!(response.nonce, 3);
+        assert_eq!(response.clearing_prices[&token], 1_000_000.into());
+        assert_eq!(response.trades[0].uid.0, [4; 56]);
+        assert_eq!(response.trades[0].executed_amount, 5.into());
+        assert_eq!(
+            response.internalized_interactions[0].inputs[&token],
+            7.into()
+        );
+        assert_eq!(response.calldata, vec![8, 9]);
+    }
+}
@@ -0,0 +1,4 @@
+mod execution;
+mod request;
+
+pub use {execution::Execution, request::Request};
@@ -0,0 +1,10 @@
+use {crate::util::serialize, serde::Deserialize, serde_with::serde_as};
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    /// The ID of a solution previously returned by the solve endpoint.
+    #[serde_as(as = "serialize::Hex")]
+    pub solution_id: [u8; 4],
+}
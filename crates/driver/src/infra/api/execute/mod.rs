@@ -0,0 +1,112 @@
+use {
+    super::error::{self, Error},
+    crate::domain::competition::solution,
+};
+
+mod dto;
+
+pub(super) fn route(router: axum::Router<super::State>) -> axum::Router<super::State> {
+    router.route("/execute", axum::routing::post(execute))
+}
+
+type Request = Result<axum::extract::Json<dto::Request>, axum::extract::rejection::JsonRejection>;
+
+async fn execute(
+    state: axum::extract::State<super::State>,
+    request: Request,
+) -> Result<axum::response::Json<dto::Execution>, Error> {
+    let settlement = find(&state.settlements(), request)?;
+    let tx = settlement.tx();
+    let nonce = state.ethereum().nonce(tx.from).await.map_err(|err| {
+        Error::new(
+            error::Kind::Blockchain,
+            format!("failed to fetch the solver nonce: {err:?}"),
+        )
+    })?;
+    Ok(axum::response::Json(dto::Execution::from_domain(
+        &settlement,
+        tx,
+        nonce,
+    )))
+}
+
+/// Find the settlement of the solution which should be executed.
+fn find<T: Clone>(settlements: &super::Settlements<T>, request: Request) -> Result<T, Error> {
+    let id = solution::Id::from(request?.0.solution_id);
+    settlements
+        .get(id)
+        .cloned()
+        .ok_or_else(|| Error::new(error::Kind::SolutionNotFound, "unknown solution ID"))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        axum::{
+            body::Body,
+            http::{header::CONTENT_TYPE, StatusCode},
+        },
+        std::sync::{Arc, Mutex},
+        tower::ServiceExt,
+    };
+
+    /// Serves the settlement found for the request instead of executing it.
+    fn app(settlements: super::super::Settlements<&'static str>) -> axum::Router {
+        let settlements = Arc::new(Mutex::new(settlements));
+        axum::Router::new().route(
+            "/execute",
+            axum::routing::post(move |request: super::Request| {
+                let result = find(&settlements.lock().unwrap(), request);
+                async move { result }
+            }),
+        )
+    }
+
+    async fn respond(app: axum::Router, body: &'static str) -> (StatusCode, serde_json::Value) {
+        let request = axum::http::Request::post("/execute")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body = serde_json::from_slice(&body).unwrap_or_else(|_| {
+            serde_json::Value::String(String::from_utf8(body.to_vec()).unwrap())
+        });
+        (status, body)
+    }
+
+    #[tokio::test]
+    async fn finds_stored_settlement() {
+        let mut settlements = super::super::Settlements::default();
+        settlements.insert(0.into(), solution::Id(1), "settlement");
+
+        let (status, body) = respond(app(settlements), r#"{"solutionId":"0x00000001"}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "settlement");
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_solution() {
+        let mut settlements = super::super::Settlements::default();
+        settlements.insert(0.into(), solution::Id(1), "settlement");
+
+        let (status, body) = respond(app(settlements), r#"{"solutionId":"0x00000002"}"#).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["kind"], "SolutionNotFound");
+    }
+
+    #[tokio::test]
+    async fn rejects_malformed_request() {
+        let app = app(Default::default());
+
+        let (status, body) = respond(app.clone(), r#"{"solutionId":"0x01"}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["kind"], "InvalidAuction");
+
+        let (status, body) = respond(app, "{").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["kind"], "InvalidAuction");
+    }
+}
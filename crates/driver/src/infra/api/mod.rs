@@ -1,20 +1,42 @@
 use {
-    crate::{solver::Solver, Ethereum, Simulator},
+    crate::{
+        domain::competition::{auction, solution},
+        infra::{auth, liquidity, metrics},
+        solver::Solver,
+        Ethereum,
+        Simulator,
+    },
     futures::Future,
-    std::{net::SocketAddr, sync::Arc},
+    std::{
+        collections::{BTreeMap, HashMap},
+        net::SocketAddr,
+        sync::{Arc, Mutex},
+    },
 };
 
+mod error;
 mod execute;
 mod info;
+mod quote;
 mod solve;
 
 const REQUEST_BODY_LIMIT: usize = 10 * 1024 * 1024;
 
+/// The number of most recent auctions for which the scored settlements are
+/// kept around so that they can be executed.
+const SETTLEMENT_AUCTIONS: usize = 10;
+
 pub struct Api {
     pub solvers: Vec<Solver>,
     pub simulator: Simulator,
     pub eth: Ethereum,
+    pub liquidity: liquidity::Fetcher,
     pub addr: SocketAddr,
+    /// Serve a `/solve` route which solves the auction with all of the solvers
+    /// and returns the best solution.
+    pub aggregate: bool,
+    /// The credentials required from the clients of the API.
+    pub auth: auth::Verifier,
 }
 
 impl Api {
@@ -35,19 +57,50 @@ impl Api {
         let shared = Arc::new(SharedState {
             simulator: self.simulator,
             eth: self.eth,
+            liquidity: self.liquidity,
         });
+        let mut states = Vec::new();
         for solver in self.solvers {
             let name = solver.name().clone();
+            let state = State {
+                solver,
+                settlements: Default::default(),
+                shared: Arc::clone(&shared),
+            };
             let router = axum::Router::new();
             let router = solve::route(router);
+            let router = quote::route(router);
+            let router = execute::route(router);
             let router = info::route(router);
-            let router = router.with_state(State {
-                solver,
-                shared: Arc::clone(&shared),
-            });
+            let router = router.with_state(state.clone());
             app = app.nest(&name.0, router);
+            states.push(state);
+        }
+
+        // Let all of the solvers compete in a single request.
+        if self.aggregate {
+            let router = solve::route_aggregate(axum::Router::new()).with_state(AggregateState {
+                solvers: Arc::new(states),
+                shared,
+            });
+            app = app.merge(router);
+        }
+
+        // Authenticate the API requests if credentials are required. The metrics
+        // are served without authentication.
+        if self.auth.is_enabled() {
+            app = app.layer(axum::middleware::from_fn_with_state(
+                Arc::new(self.auth),
+                authenticate,
+            ));
         }
 
+        // Expose the Prometheus metrics of the driver.
+        app = app.route(
+            "/metrics",
+            axum::routing::get(|| async { metrics::encode() }),
+        );
+
         // Start the server.
         axum::Server::bind(&self.addr)
             .serve(app.into_make_service())
@@ -56,9 +109,46 @@ impl Api {
     }
 }
 
+/// Reject the requests which don't carry the credentials required by the
+/// [`auth::Verifier`].
+async fn authenticate(
+    axum::extract::State(verifier): axum::extract::State<Arc<auth::Verifier>>,
+    request: axum::http::Request<axum::body::Body>,
+    next: axum::middleware::Next<axum::body::Body>,
+) -> Result<axum::response::Response, error::Error> {
+    let unauthorized = |description| error::Error::new(error::Kind::Unauthorized, description);
+    if !verifier.verify_headers(request.headers()) {
+        return Err(unauthorized("invalid bearer token"));
+    }
+    if !verifier.needs_body() {
+        return Ok(next.run(request).await);
+    }
+
+    // Buffer the body to verify its signature, then pass it on to the
+    // handler.
+    let (parts, mut body) = request.into_parts();
+    let mut data = Vec::new();
+    while let Some(chunk) = hyper::body::HttpBody::data(&mut body).await {
+        let chunk = chunk.map_err(|_| unauthorized("failed to read the request body"))?;
+        if data.len() + chunk.len() > REQUEST_BODY_LIMIT {
+            return Err(error::Error::new(
+                error::Kind::PayloadTooLarge,
+                "the request body is too large",
+            ));
+        }
+        data.extend_from_slice(&chunk);
+    }
+    if !verifier.verify_body(&parts.headers, &data) {
+        return Err(unauthorized("invalid or stale request signature"));
+    }
+    let request = axum::http::Request::from_parts(parts, axum::body::Body::from(data));
+    Ok(next.run(request).await)
+}
+
 #[derive(Debug, Clone)]
 struct State {
     solver: Solver,
+    settlements: Arc<Mutex<Settlements>>,
     shared: Arc<SharedState>,
 }
 
@@ -74,6 +164,66 @@ impl State {
     fn ethereum(&self) -> &Ethereum {
         &self.shared.eth
     }
+
+    fn liquidity(&self) -> &liquidity::Fetcher {
+        &self.shared.liquidity
+    }
+
+    fn settlements(&self) -> std::sync::MutexGuard<'_, Settlements> {
+        self.settlements.lock().unwrap()
+    }
+}
+
+/// The state of the routes which fan out to all of the solvers.
+#[derive(Debug, Clone)]
+struct AggregateState {
+    solvers: Arc<Vec<State>>,
+    shared: Arc<SharedState>,
+}
+
+impl AggregateState {
+    fn solvers(&self) -> &[State] {
+        &self.solvers
+    }
+
+    fn simulator(&self) -> &Simulator {
+        &self.shared.simulator
+    }
+
+    fn ethereum(&self) -> &Ethereum {
+        &self.shared.eth
+    }
+
+    fn liquidity(&self) -> &liquidity::Fetcher {
+        &self.shared.liquidity
+    }
+}
+
+/// The settlements scored by a solver, grouped by the auction they solve.
+#[derive(Debug)]
+struct Settlements<T = solution::Settlement>(BTreeMap<auction::Id, HashMap<solution::Id, T>>);
+
+impl<T> Default for Settlements<T> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+impl<T> Settlements<T> {
+    /// Keep a scored settlement so that it can be executed later. Only the
+    /// settlements of the most recent [`SETTLEMENT_AUCTIONS`] auctions are
+    /// kept.
+    fn insert(&mut self, auction: auction::Id, id: solution::Id, settlement: T) {
+        self.0.entry(auction).or_default().insert(id, settlement);
+        while self.0.len() > SETTLEMENT_AUCTIONS {
+            let oldest = *self.0.keys().next().unwrap();
+            self.0.remove(&oldest);
+        }
+    }
+
+    fn get(&self, id: solution::Id) -> Option<&T> {
+        self.0.values().find_map(|settlements| settlements.get(&id))
+    }
 }
 
 /// State which is shared among all multiplexed solvers.
@@ -81,4 +231,158 @@ impl State {
 struct SharedState {
     simulator: Simulator,
     eth: Ethereum,
+    liquidity: liquidity::Fetcher,
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        axum::{
+            body::Body,
+            http::{header::AUTHORIZATION, Request, StatusCode},
+        },
+        tower::ServiceExt,
+    };
+
+    fn app(verifier: auth::Verifier) -> axum::Router {
+        axum::Router::new()
+            .route(
+                "/solve",
+                axum::routing::post(|body: String| async move { body }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::new(verifier),
+                authenticate,
+            ))
+    }
+
+    fn signed(key: &[u8], timestamp: u64, body: &[u8]) -> Request<Body> {
+        Request::post("/solve")
+            .header(auth::TIMESTAMP_HEADER, timestamp)
+            .header(auth::SIGNATURE_HEADER, auth::sign(key, timestamp, body))
+            .body(Body::from(body.to_vec()))
+            .unwrap()
+    }
+
+    async fn respond(app: axum::Router, request: Request<Body>) -> (StatusCode, Vec<u8>) {
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn checks_bearer_token() {
+        let app = app(auth::Verifier {
+            bearer_token: Some("token".to_owned()),
+            hmac_key: None,
+        });
+
+        let missing = Request::post("/solve").body(Body::empty()).unwrap();
+        let (status, _) = respond(app.clone(), missing).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let valid = Request::post("/solve")
+            .header(AUTHORIZATION, "Bearer token")
+            .body(Body::from("auction"))
+            .unwrap();
+        let (status, body) = respond(app, valid).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, b"auction");
+    }
+
+    #[tokio::test]
+    async fn checks_request_signature() {
+        let app = app(auth::Verifier {
+            bearer_token: None,
+            hmac_key: Some(b"key".to_vec()),
+        });
+        let now = auth::timestamp();
+
+        // The verified body is passed on to the handler.
+        let (status, body) = respond(app.clone(), signed(b"key", now, b"auction")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, b"auction");
+
+        let (status, _) = respond(app.clone(), signed(b"other", now, b"auction")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let stale = now - auth::MAX_TIMESTAMP_SKEW.as_secs() - 1;
+        let (status, _) = respond(app.clone(), signed(b"key", stale, b"auction")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let unsigned = Request::post("/solve").body(Body::from("auction")).unwrap();
+        let (status, _) = respond(app, unsigned).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn rejects_oversized_body() {
+        let app = app(auth::Verifier {
+            bearer_token: None,
+            hmac_key: Some(b"key".to_vec()),
+        });
+        let body = vec![0; REQUEST_BODY_LIMIT + 1];
+        let (status, _) = respond(app, signed(b"key", auth::timestamp(), &body)).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn reports_malformed_json() {
+        let app = axum::Router::new().route(
+            "/solve",
+            axum::routing::post(
+                |body: Result<
+                    axum::extract::Json<serde_json::Value>,
+                    axum::extract::rejection::JsonRejection,
+                >| async move {
+                    body.map(|body| body.0.to_string())
+                        .map_err(error::Error::from)
+                },
+            ),
+        );
+        let malformed = Request::post("/solve")
+            .header(axum::http::header::CONTENT_TYPE, "application/json")
+            .body(Body::from("{"))
+            .unwrap();
+        let (status, body) = respond(app, malformed).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["kind"], "InvalidAuction");
+    }
+
+    #[test]
+    fn stores_settlements_of_recent_auctions() {
+        let mut settlements = Settlements::default();
+        settlements.insert(0.into(), solution::Id(1), "first");
+        settlements.insert(0.into(), solution::Id(2), "second");
+        settlements.insert(1.into(), solution::Id(3), "third");
+
+        assert_eq!(settlements.get(solution::Id(1)), Some(&"first"));
+        assert_eq!(settlements.get(solution::Id(2)), Some(&"second"));
+        assert_eq!(settlements.get(solution::Id(3)), Some(&"third"));
+        assert_eq!(settlements.get(solution::Id(4)), None);
+
+        // Solving the same auction again keeps the earlier settlements.
+        settlements.insert(1.into(), solution::Id(4), "fourth");
+        assert_eq!(settlements.get(solution::Id(3)), Some(&"third"));
+        assert_eq!(settlements.get(solution::Id(4)), Some(&"fourth"));
+    }
+
+    #[test]
+    fn expires_settlements_of_old_auctions() {
+        let mut settlements = Settlements::default();
+        for auction in 0..SETTLEMENT_AUCTIONS as u64 {
+            settlements.insert(auction.into(), solution::Id(auction as u32), auction);
+        }
+        assert_eq!(settlements.get(solution::Id(0)), Some(&0));
+
+        // Only the most recent auctions are kept, so the oldest one expires.
+        let newest = SETTLEMENT_AUCTIONS as u64;
+        settlements.insert(newest.into(), solution::Id(newest as u32), newest);
+        assert_eq!(settlements.get(solution::Id(0)), None);
+        assert_eq!(settlements.get(solution::Id(1)), Some(&1));
+        assert_eq!(settlements.get(solution::Id(newest as u32)), Some(&newest));
+    }
 }
@@ -0,0 +1,4 @@
+mod order;
+mod quote;
+
+pub use {order::Order, quote::Quote};
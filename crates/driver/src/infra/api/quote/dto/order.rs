@@ -0,0 +1,40 @@
+use {
+    crate::{
+        domain::{competition, eth, quote},
+        util::serialize,
+    },
+    serde::Deserialize,
+    serde_with::serde_as,
+};
+
+impl Order {
+    pub fn into_domain(self) -> quote::Order {
+        quote::Order {
+            sell: self.sell_token.into(),
+            buy: self.buy_token.into(),
+            side: match self.kind {
+                Kind::Sell => competition::order::Side::Sell,
+                Kind::Buy => competition::order::Side::Buy,
+            },
+            amount: self.amount.into(),
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Order {
+    sell_token: eth::H160,
+    buy_token: eth::H160,
+    kind: Kind,
+    #[serde_as(as = "serialize::U256")]
+    amount: eth::U256,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Kind {
+    Sell,
+    Buy,
+}
@@ -0,0 +1,45 @@
+use {
+    crate::{
+        domain::{eth, quote},
+        util::serialize,
+    },
+    serde::Serialize,
+    serde_with::serde_as,
+};
+
+impl Quote {
+    pub fn from_domain(quote: &quote::Quote) -> Self {
+        Self::Successful {
+            sell_amount: quote.sell,
+            buy_amount: quote.buy,
+            // No settlement can use more gas than fits into a block, so this
+            // only saturates for bogus gas estimates.
+            gas: quote.gas.0.try_into().unwrap_or(u64::MAX),
+        }
+    }
+
+    pub fn unfillable(reason: &quote::Unfillable) -> Self {
+        Self::Unfillable {
+            unfillable_reason: reason.to_string(),
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum Quote {
+    Successful {
+        #[serde(rename = "sellAmount")]
+        #[serde_as(as = "serialize::U256")]
+        sell_amount: eth::U256,
+        #[serde(rename = "buyAmount")]
+        #[serde_as(as = "serialize::U256")]
+        buy_amount: eth::U256,
+        gas: u64,
+    },
+    Unfillable {
+        #[serde(rename = "unfillableReason")]
+        unfillable_reason: String,
+    },
+}
@@ -0,0 +1,25 @@
+use {super::error::Error, crate::domain};
+
+mod dto;
+
+pub(super) fn route(router: axum::Router<super::State>) -> axum::Router<super::State> {
+    router.route("/quote", axum::routing::post(quote))
+}
+
+async fn quote(
+    state: axum::extract::State<super::State>,
+    order: Result<axum::extract::Json<dto::Order>, axum::extract::rejection::JsonRejection>,
+) -> Result<axum::response::Json<dto::Quote>, Error> {
+    let order = order?.0.into_domain();
+    let quote = order
+        .quote(&state.solver(), state.ethereum(), state.liquidity())
+        .await;
+    match quote {
+        Ok(quote) => Ok(axum::response::Json(dto::Quote::from_domain(&quote))),
+        Err(domain::quote::Error::Unfillable(reason)) => {
+            tracing::debug!(%reason, "order is unfillable");
+            Ok(axum::response::Json(dto::Quote::unfillable(&reason)))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
@@ -70,14 +70,15 @@ impl Auction {
                         } else {
                             competition::order::Partial::No
                         },
-                        interactions: order
-                            .interactions
+                        pre_interactions: order
+                            .pre_interactions
                             .into_iter()
-                            .map(|interaction| eth::Interaction {
-                                target: interaction.target.into(),
-                                value: interaction.value.into(),
-                                call_data: interaction.call_data,
-                            })
+                            .map(Into::into)
+                            .collect(),
+                        post_interactions: order
+                            .post_interactions
+                            .into_iter()
+                            .map(Into::into)
                             .collect(),
                         sell_token_balance: match order.sell_token_balance {
                             SellTokenBalance::Erc20 => competition::order::SellTokenBalance::Erc20,
@@ -116,7 +117,7 @@ impl Auction {
                     })
                 })
                 .try_collect()?,
-            // TODO #899
+            // The liquidity is fetched by the driver.
             liquidity: Default::default(),
             gas_price: self.effective_gas_price.into(),
             deadline: self.deadline.into(),
@@ -168,7 +169,9 @@ struct Order {
     /// Always zero if the order is not partially fillable.
     #[serde_as(as = "serialize::U256")]
     executed: eth::U256,
-    interactions: Vec<Interaction>,
+    pre_interactions: Vec<Interaction>,
+    #[serde(default)]
+    post_interactions: Vec<Interaction>,
     sell_token_balance: SellTokenBalance,
     buy_token_balance: BuyTokenBalance,
     class: Class,
@@ -200,6 +203,16 @@ struct Interaction {
     call_data: Vec<u8>,
 }
 
+impl From<Interaction> for eth::Interaction {
+    fn from(interaction: Interaction) -> Self {
+        Self {
+            target: interaction.target.into(),
+            value: interaction.value.into(),
+            call_data: interaction.call_data,
+        }
+    }
+}
+
 #[derive(Debug, Default, Deserialize)]
 #[serde(rename_all = "lowercase")]
 enum SellTokenBalance {
@@ -246,3 +259,83 @@ struct Token {
     available_balance: eth::U256,
     trusted: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        autopilot::driver_model::solve,
+        maplit::btreemap,
+        model::order::{OrderKind, OrderUid},
+    };
+
+    #[test]
+    fn deserializes_autopilot_request() {
+        let sell_token = eth::H160([1; 20]);
+        let buy_token = eth::H160([2; 20]);
+        let request = solve::Request {
+            id: Some(42),
+            tokens: btreemap! {
+                sell_token => solve::Token {
+                    decimals: Some(18),
+                    symbol: Some("COW".to_string()),
+                    reference_price: Some(1_000.into()),
+                    available_balance: 5.into(),
+                    trusted: false,
+                },
+                buy_token => solve::Token::default(),
+            },
+            orders: vec![solve::Order {
+                uid: OrderUid([3; 56]),
+                sell_token,
+                buy_token,
+                sell_amount: 10.into(),
+                buy_amount: 20.into(),
+                solver_fee: 2.into(),
+                user_fee: 1.into(),
+                kind: OrderKind::Buy,
+                partially_fillable: true,
+                executed: 4.into(),
+                pre_interactions: vec![solve::Interaction {
+                    target: eth::H160([4; 20]),
+                    value: 0.into(),
+                    call_data: vec![1, 2, 3],
+                }],
+                class: solve::Class::Limit,
+                surplus_fee: Some(3.into()),
+                signature: vec![5; 65],
+                ..Default::default()
+            }],
+            effective_gas_price: 7.into(),
+            deadline: chrono::Utc::now(),
+        };
+
+        let auction: Auction =
+            serde_json::from_value(serde_json::to_value(&request).unwrap()).unwrap();
+        let auction = auction.into_domain().unwrap();
+
+        assert_eq!(auction.id.unwrap().0, 42);
+        assert_eq!(auction.gas_price.0 .0, 7.into());
+        let token = auction
+            .tokens
+            .iter()
+            .find(|token| token.address.0 == sell_token)
+            .unwrap();
+        assert_eq!(token.symbol.as_deref(), Some("COW"));
+        assert_eq!(eth::U256::from(token.price.unwrap()), 1_000.into());
+        assert_eq!(token.available_balance, 5.into());
+
+        let order = &auction.orders[0];
+        assert_eq!(order.uid, [3; 56]);
+        assert_eq!(order.sell.amount, 10.into());
+        assert_eq!(order.buy.token.0, buy_token);
+        assert!(matches!(order.side, competition::order::Side::Buy));
+        assert!(matches!(order.kind, competition::order::Kind::Limit { .. }));
+        assert!(matches!(
+            order.partial,
+            competition::order::Partial::Yes { .. }
+        ));
+        assert_eq!(order.pre_interactions[0].call_data, vec![1, 2, 3]);
+        assert_eq!(order.signature.data, vec![5; 65]);
+    }
+}
@@ -1,6 +1,7 @@
 use {
     crate::{
         domain::competition::{self, solution},
+        infra::solver,
         util::serialize,
     },
     serde::Serialize,
@@ -16,6 +17,15 @@ impl Solution {
     }
 }
 
+impl Winner {
+    pub fn from_domain(solver: &solver::Name, id: solution::Id, score: competition::Score) -> Self {
+        Self {
+            solver: solver.0.clone(),
+            solution: Solution::from_domain(id, score),
+        }
+    }
+}
+
 #[serde_as]
 #[derive(Debug, Serialize)]
 pub struct Solution {
@@ -23,3 +33,29 @@ pub struct Solution {
     id: [u8; 4],
     score: f64,
 }
+
+/// The winning solution of a competition between all solvers. The solution
+/// can be executed using the `/execute` route of the named solver.
+#[derive(Debug, Serialize)]
+pub struct Winner {
+    solver: String,
+    #[serde(flatten)]
+    solution: Solution,
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, autopilot::driver_model::solve};
+
+    #[test]
+    fn serializes_autopilot_response() {
+        let solution = Solution {
+            id: [0, 0, 0, 42],
+            score: 1.5,
+        };
+        let response: solve::Response =
+            serde_json::from_value(serde_json::to_value(&solution).unwrap()).unwrap();
+        assert_eq!(response.id, vec![0, 0, 0, 42]);
+        assert_eq!(response.score, 1.5);
+    }
+}
@@ -1,4 +1,10 @@
-use crate::domain::competition::{self, solution};
+use {
+    super::error::{self, Error},
+    crate::{
+        domain::competition::{self, solution},
+        infra::metrics::Metrics,
+    },
+};
 
 mod dto;
 
@@ -6,19 +12,106 @@ pub(super) fn route(router: axum::Router<super::State>) -> axum::Router<super::S
     router.route("/solve", axum::routing::post(solve))
 }
 
+pub(super) fn route_aggregate(
+    router: axum::Router<super::AggregateState>,
+) -> axum::Router<super::AggregateState> {
+    router.route("/solve", axum::routing::post(solve_aggregate))
+}
+
 async fn solve(
     state: axum::extract::State<super::State>,
-    auction: axum::extract::Json<dto::Auction>,
-) -> axum::response::Json<dto::Solution> {
-    // TODO Report errors instead of unwrapping
-    let auction = auction.0.into_domain().unwrap();
-    let score = competition::solve(
+    auction: Result<axum::extract::Json<dto::Auction>, axum::extract::rejection::JsonRejection>,
+) -> Result<axum::response::Json<dto::Solution>, Error> {
+    let mut auction = auction?.0.into_domain()?;
+    auction.liquidity = state.liquidity().fetch(&auction).await;
+    let (score, settlement) = competition::solve(
         state.solver(),
         state.ethereum(),
         state.simulator(),
         &auction,
     )
-    .await
-    .unwrap();
-    axum::response::Json(dto::Solution::from_domain(solution::Id::random(), score))
+    .await?;
+    let id = solution::Id::random();
+    if let Some(auction) = auction.id {
+        state.settlements().insert(auction, id, settlement);
+    }
+    Ok(axum::response::Json(dto::Solution::from_domain(id, score)))
+}
+
+/// Solve the auction with all of the solvers and respond with the winning
+/// solution.
+async fn solve_aggregate(
+    state: axum::extract::State<super::AggregateState>,
+    auction: Result<axum::extract::Json<dto::Auction>, axum::extract::rejection::JsonRejection>,
+) -> Result<axum::response::Json<dto::Winner>, Error> {
+    let mut auction = auction?.0.into_domain()?;
+    auction.liquidity = state.liquidity().fetch(&auction).await;
+    let solvers = state
+        .solvers()
+        .iter()
+        .map(super::State::solver)
+        .collect::<Vec<_>>();
+    let competition =
+        competition::compete(&solvers, state.ethereum(), state.simulator(), &auction).await;
+
+    for (solver, err) in &competition.rejected {
+        tracing::warn!(solver = %solver.name().0, ?err, "solution rejected");
+        observe(solver.name(), rejection_reason(err));
+    }
+    for loser in &competition.losers {
+        tracing::debug!(solver = %loser.solver.name().0, score = ?loser.score, "solution outscored");
+        observe(loser.solver.name(), "loser");
+    }
+    let winner = competition.winner.ok_or_else(|| {
+        Error::new(
+            error::Kind::SolverFailed,
+            "none of the solvers found a valid solution",
+        )
+    })?;
+    tracing::debug!(solver = %winner.solver.name().0, score = ?winner.score, "solution won");
+    observe(winner.solver.name(), "winner");
+
+    let id = solution::Id::random();
+    if let Some(auction) = auction.id {
+        // Keep the settlement with the winning solver, so that it can be
+        // executed through the routes of that solver.
+        if let Some(solver) = state
+            .solvers()
+            .iter()
+            .find(|solver| solver.solver.name().0 == winner.solver.name().0)
+        {
+            solver.settlements().insert(auction, id, winner.settlement);
+        }
+    }
+    Ok(axum::response::Json(dto::Winner::from_domain(
+        winner.solver.name(),
+        id,
+        winner.score,
+    )))
+}
+
+fn observe(solver: &crate::infra::solver::Name, result: &str) {
+    Metrics::get()
+        .solutions
+        .with_label_values(&[&solver.0, result])
+        .inc();
+}
+
+/// A short description of why a solution was rejected, used as a metric
+/// label.
+fn rejection_reason(err: &solution::Error) -> &'static str {
+    match err {
+        solution::Error::Solver(_) => "solver",
+        solution::Error::Simulation(_) => "simulation",
+        solution::Error::Blockchain(_) => "blockchain",
+        solution::Error::Jit(_) => "jit",
+        solution::Error::Boundary(_) => "boundary",
+        solution::Error::DeadlineExceeded(_) => "deadline",
+    }
+}
+
+impl From<dto::AuctionError> for Error {
+    fn from(err: dto::AuctionError) -> Self {
+        Self::new(error::Kind::InvalidAuction, err.to_string())
+    }
 }
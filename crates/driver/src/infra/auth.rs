@@ -0,0 +1,254 @@
+//! Authentication of the HTTP requests sent from the autopilot to the driver,
+//! and from the driver to the solvers.
+
+use {
+    hmac::Mac,
+    std::time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The header containing the hex encoded HMAC-SHA256 signature of the request
+/// timestamp and body.
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+
+/// The header containing the time at which the request was signed, in seconds
+/// since the Unix epoch.
+pub const TIMESTAMP_HEADER: &str = "X-Timestamp";
+
+/// How far the signing timestamp of a request may be from the current time.
+/// Older requests are rejected so that captured requests can't be replayed
+/// later on.
+pub const MAX_TIMESTAMP_SKEW: Duration = Duration::from_secs(5 * 60);
+
+type HmacSha256 = hmac::Hmac<sha2::Sha256>;
+
+/// The current time in seconds since the Unix epoch, as sent in the
+/// [`TIMESTAMP_HEADER`] header.
+pub fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time is after the Unix epoch")
+        .as_secs()
+}
+
+/// Sign the request timestamp and body with the secret key shared between the
+/// client and the server. The signed message is the decimal timestamp, a `.`
+/// and the body. The signature is hex encoded.
+pub fn sign(key: &[u8], timestamp: u64, body: &[u8]) -> String {
+    hex::encode(mac(key, timestamp, body).finalize().into_bytes())
+}
+
+/// Verify the hex encoded signature of the request timestamp and body. The
+/// comparison runs in constant time.
+pub fn verify(key: &[u8], timestamp: u64, body: &[u8], signature: &str) -> bool {
+    let signature = match hex::decode(signature) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+    mac(key, timestamp, body).verify_slice(&signature).is_ok()
+}
+
+fn mac(key: &[u8], timestamp: u64, body: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+/// How the driver authenticates to a solver.
+#[derive(Clone)]
+pub enum Auth {
+    /// A static token sent in the `Authorization: Bearer` header.
+    Bearer(String),
+    /// A secret key used to sign the request timestamp and body. The
+    /// timestamp is sent in the [`TIMESTAMP_HEADER`] header and the signature
+    /// in the [`SIGNATURE_HEADER`] header.
+    Hmac(Vec<u8>),
+    /// A TLS client certificate chain and its private key, both PEM encoded.
+    Tls { certificate: Vec<u8>, key: Vec<u8> },
+}
+
+/// Secrets are never printed.
+impl std::fmt::Debug for Auth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bearer(_) => f.write_str("Bearer(SECRET)"),
+            Self::Hmac(_) => f.write_str("Hmac(SECRET)"),
+            Self::Tls { .. } => f.write_str("Tls(SECRET)"),
+        }
+    }
+}
+
+/// The credentials required from the clients of the driver API. Requests are
+/// accepted only if they satisfy all of the configured checks.
+///
+/// TLS client certificates aren't verified by the driver itself. If they are
+/// required, the driver should be run behind a proxy which terminates TLS and
+/// verifies them.
+#[derive(Clone, Default)]
+pub struct Verifier {
+    pub bearer_token: Option<String>,
+    pub hmac_key: Option<Vec<u8>>,
+}
+
+impl Verifier {
+    /// Does this verifier check anything?
+    pub fn is_enabled(&self) -> bool {
+        self.bearer_token.is_some() || self.hmac_key.is_some()
+    }
+
+    /// Does the verifier need the request body to verify a request?
+    pub fn needs_body(&self) -> bool {
+        self.hmac_key.is_some()
+    }
+
+    /// Verify the credentials sent in the request headers.
+    pub fn verify_headers(&self, headers: &axum::http::HeaderMap) -> bool {
+        match &self.bearer_token {
+            Some(token) => headers
+                .get(axum::http::header::AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .map(|value| constant_time_eq(value.as_bytes(), token.as_bytes()))
+                .unwrap_or(false),
+            None => true,
+        }
+    }
+
+    /// Verify the signature of the request body. Requests signed more than
+    /// [`MAX_TIMESTAMP_SKEW`] away from the current time are rejected.
+    pub fn verify_body(&self, headers: &axum::http::HeaderMap, body: &[u8]) -> bool {
+        self.verify_body_at(headers, body, timestamp())
+    }
+
+    fn verify_body_at(&self, headers: &axum::http::HeaderMap, body: &[u8], now: u64) -> bool {
+        let key = match &self.hmac_key {
+            Some(key) => key,
+            None => return true,
+        };
+        let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+        let timestamp = match header(TIMESTAMP_HEADER).and_then(|value| value.parse::<u64>().ok()) {
+            Some(timestamp) => timestamp,
+            None => return false,
+        };
+        if now.abs_diff(timestamp) > MAX_TIMESTAMP_SKEW.as_secs() {
+            return false;
+        }
+        header(SIGNATURE_HEADER)
+            .map(|signature| verify(key, timestamp, body, signature))
+            .unwrap_or(false)
+    }
+}
+
+/// Secrets are never printed.
+impl std::fmt::Debug for Verifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Verifier")
+            .field(
+                "bearer_token",
+                &self.bearer_token.as_ref().map(|_| "SECRET"),
+            )
+            .field("hmac_key", &self.hmac_key.as_ref().map(|_| "SECRET"))
+            .finish()
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, axum::http::HeaderMap};
+
+    fn headers(timestamp: u64, signature: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(TIMESTAMP_HEADER, timestamp.to_string().parse().unwrap());
+        headers.insert(SIGNATURE_HEADER, signature.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn signs_timestamp_and_body() {
+        // The autopilot signs its requests to the driver the same way.
+        assert_eq!(
+            sign(b"Jefe", 1700000000, b"what do ya want for nothing?"),
+            "1cdd0650c8be1cb0974b1788d458b1e781206cfef59b85faafc582d2e182c57e"
+        );
+
+        let signature = sign(b"key", 1000, b"body");
+        assert!(verify(b"key", 1000, b"body", &signature));
+        assert!(!verify(b"key", 1001, b"body", &signature));
+        assert!(!verify(b"key", 1000, b"other", &signature));
+        assert!(!verify(b"other", 1000, b"body", &signature));
+        assert!(!verify(b"key", 1000, b"body", "not hex"));
+    }
+
+    #[test]
+    fn verifies_bearer_token() {
+        let verifier = Verifier {
+            bearer_token: Some("token".to_owned()),
+            hmac_key: None,
+        };
+        assert!(verifier.is_enabled());
+        assert!(!verifier.needs_body());
+
+        let mut headers = HeaderMap::new();
+        assert!(!verifier.verify_headers(&headers));
+        headers.insert(
+            axum::http::header::AUTHORIZATION,
+            "Bearer other".parse().unwrap(),
+        );
+        assert!(!verifier.verify_headers(&headers));
+        headers.insert(
+            axum::http::header::AUTHORIZATION,
+            "Bearer token".parse().unwrap(),
+        );
+        assert!(verifier.verify_headers(&headers));
+    }
+
+    #[test]
+    fn verifies_body_signature() {
+        let verifier = Verifier {
+            bearer_token: None,
+            hmac_key: Some(b"key".to_vec()),
+        };
+        assert!(verifier.is_enabled());
+        assert!(verifier.needs_body());
+        assert!(verifier.verify_headers(&HeaderMap::new()));
+
+        let now = 1_000_000;
+        let skew = MAX_TIMESTAMP_SKEW.as_secs();
+        for timestamp in [now, now - skew, now + skew] {
+            let headers = headers(timestamp, &sign(b"key", timestamp, b"body"));
+            assert!(verifier.verify_body_at(&headers, b"body", now));
+            assert!(!verifier.verify_body_at(&headers, b"other", now));
+        }
+
+        // Stale and future requests are rejected even if they are signed.
+        for timestamp in [now - skew - 1, now + skew + 1] {
+            let headers = headers(timestamp, &sign(b"key", timestamp, b"body"));
+            assert!(!verifier.verify_body_at(&headers, b"body", now));
+        }
+
+        // The timestamp can't be changed without invalidating the signature.
+        let forged = headers(now, &sign(b"key", now - skew - 1, b"body"));
+        assert!(!verifier.verify_body_at(&forged, b"body", now));
+
+        // Both headers are required.
+        let mut missing = headers(now, &sign(b"key", now, b"body"));
+        missing.remove(TIMESTAMP_HEADER);
+        assert!(!verifier.verify_body_at(&missing, b"body", now));
+        let mut missing = headers(now, &sign(b"key", now, b"body"));
+        missing.remove(SIGNATURE_HEADER);
+        assert!(!verifier.verify_body_at(&missing, b"body", now));
+    }
+
+    #[test]
+    fn disabled_verifier_accepts_everything() {
+        let verifier = Verifier::default();
+        assert!(!verifier.is_enabled());
+        assert!(verifier.verify_headers(&HeaderMap::new()));
+        assert!(verifier.verify_body(&HeaderMap::new(), b"body"));
+    }
+}
@@ -1 +1 @@
-pub use crate::boundary::contracts::{GPv2Settlement, ERC20, WETH9};
+pub use crate::boundary::contracts::{ERC1271SignatureValidator, GPv2Settlement, ERC20, WETH9};
@@ -61,12 +61,89 @@ impl Ethereum {
         Ok(eth::Allowance { spender, amount }.into())
     }
 
+    /// Fetch the ERC20 balance of the owner. See the balanceOf method in
+    /// EIP-20.
+    ///
+    /// https://eips.ethereum.org/EIPS/eip-20#methods
+    pub async fn balance(
+        &self,
+        owner: eth::Address,
+        token: eth::TokenAddress,
+    ) -> Result<eth::U256, Error> {
+        contracts::ERC20::at(&self.web3, token.0)
+            .balance_of(owner.0)
+            .call()
+            .await
+            .map_err(Into::into)
+    }
+
+    /// The address of the vault relayer, which is the contract pulling the
+    /// sell tokens of the traders into the settlement contract.
+    pub async fn vault_relayer(&self) -> Result<eth::ContractAddress, Error> {
+        self.contracts()
+            .settlement()
+            .vault_relayer()
+            .call()
+            .await
+            .map(Into::into)
+            .map_err(Into::into)
+    }
+
+    /// Check if the signature is valid for the hash according to the EIP-1271
+    /// smart contract deployed at the signer address.
+    ///
+    /// https://eips.ethereum.org/EIPS/eip-1271
+    pub async fn is_valid_signature(
+        &self,
+        signer: eth::Address,
+        hash: [u8; 32],
+        signature: Vec<u8>,
+    ) -> Result<bool, Error> {
+        let result = contracts::ERC1271SignatureValidator::at(&self.web3, signer.0)
+            .is_valid_signature(ethcontract::Bytes(hash), ethcontract::Bytes(signature))
+            .call()
+            .await?;
+        Ok(result.0 == EIP1271_MAGIC_VALUE)
+    }
+
+    /// Check if the order with the given UID was presigned onchain using the
+    /// setPreSignature method of the settlement contract.
+    pub async fn is_presigned(&self, uid: [u8; 56]) -> Result<bool, Error> {
+        let pre_signature = self
+            .contracts()
+            .settlement()
+            .pre_signature(ethcontract::Bytes(uid.to_vec()))
+            .call()
+            .await?;
+        Ok(pre_signature == eth::U256::from_big_endian(&web3::signing::keccak256(PRE_SIGNED)))
+    }
+
+    /// The current gas price, as reported by the node.
+    pub async fn gas_price(&self) -> Result<eth::EffectiveGasPrice, Error> {
+        self.web3
+            .eth()
+            .gas_price()
+            .await
+            .map(Into::into)
+            .map_err(Into::into)
+    }
+
     /// Check if a smart contract is deployed to the given address.
     pub async fn is_contract(&self, address: eth::Address) -> Result<bool, Error> {
         let code = self.web3.eth().code(address.into(), None).await?;
         Ok(!code.0.is_empty())
     }
 
+    /// The number of transactions sent from the given address, which is the
+    /// nonce of the next transaction it sends.
+    pub async fn nonce(&self, address: eth::Address) -> Result<eth::U256, Error> {
+        self.web3
+            .eth()
+            .transaction_count(address.into(), None)
+            .await
+            .map_err(Into::into)
+    }
+
     pub async fn create_access_list(&self, tx: eth::Tx) -> Result<eth::AccessList, Error> {
         // Seems like the web3 library still doesn't have a convenience method for this,
         // so the call request has to be built manually.
@@ -114,6 +191,13 @@ impl Ethereum {
     }
 }
 
+/// The value returned by isValidSignature for valid EIP-1271 signatures.
+const EIP1271_MAGIC_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
+
+/// The preimage of the value stored by the settlement contract for presigned
+/// orders.
+const PRE_SIGNED: &[u8] = b"GPv2Signing.Scheme.PreSign";
+
 pub struct Contracts<'a>(&'a Ethereum);
 
 impl Contracts<'_> {
@@ -1,4 +1,5 @@
 use {
+    crate::{domain::eth, infra::liquidity},
     std::{net::SocketAddr, path::PathBuf},
     url::Url,
 };
@@ -18,8 +19,36 @@ pub struct Args {
     #[clap(long, env)]
     pub solvers_config: PathBuf,
 
+    /// Serve an additional `/solve` route which sends the auction to all of
+    /// the configured solvers in parallel and responds with the best solution.
+    #[clap(long, env)]
+    pub aggregate_solve: bool,
+
+    /// Bearer token which the clients of the driver API have to send in the
+    /// `Authorization` header. If not specified, the header isn't checked.
+    #[clap(long, env)]
+    pub api_bearer_token: Option<String>,
+
+    /// Secret key used to verify the HMAC-SHA256 signatures of the requests
+    /// sent to the driver API. The signed message is the Unix timestamp from
+    /// the `X-Timestamp` header, a `.` and the request body. The hex encoded
+    /// signature is expected in the `X-Signature` header. Requests signed more
+    /// than 5 minutes away from the current time are rejected. If not
+    /// specified, signatures aren't checked.
+    #[clap(long, env)]
+    pub api_hmac_key: Option<String>,
+
+    /// Simulate settlements in an EVM embedded in the driver instead of on
+    /// the node or on Tenderly. The state is loaded lazily from the node, so
+    /// simulations aren't rate limited, and they produce full call traces.
+    #[clap(long, env)]
+    pub evm_simulation: bool,
+
     #[clap(flatten)]
     pub tenderly: Tenderly,
+
+    #[clap(flatten)]
+    pub liquidity: Liquidity,
 }
 
 /// Arg types have custom `Display` impls instead of relying on `Debug` to avoid
@@ -28,7 +57,41 @@ impl std::fmt::Display for Args {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "ethrpc: SECRET")?;
         write!(f, "bind_addr: {}", self.bind_addr)?;
-        write!(f, "{}", self.tenderly)
+        write!(f, "aggregate_solve: {}", self.aggregate_solve)?;
+        write!(
+            f,
+            "api_bearer_token: {:?}",
+            self.api_bearer_token.as_ref().map(|_| "SECRET")
+        )?;
+        write!(
+            f,
+            "api_hmac_key: {:?}",
+            self.api_hmac_key.as_ref().map(|_| "SECRET")
+        )?;
+        write!(f, "evm_simulation: {}", self.evm_simulation)?;
+        write!(f, "{}", self.tenderly)?;
+        write!(f, "{}", self.liquidity)
+    }
+}
+
+/// Arguments for fetching the liquidity passed to the solvers.
+#[derive(Debug, clap::Parser)]
+pub struct Liquidity {
+    /// The liquidity sources to fetch pools from. Defaults to the sources
+    /// supported on the chain.
+    #[clap(long, env, value_enum, use_value_delimiter = true)]
+    pub liquidity_sources: Option<Vec<liquidity::Source>>,
+
+    /// Tokens which are used as intermediate hops between the traded tokens
+    /// when fetching pools. The native token is always included.
+    #[clap(long, env, use_value_delimiter = true)]
+    pub base_tokens: Vec<eth::H160>,
+}
+
+impl std::fmt::Display for Liquidity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "liquidity_sources: {:?}", self.liquidity_sources)?;
+        write!(f, "base_tokens: {:?}", self.base_tokens)
     }
 }
 
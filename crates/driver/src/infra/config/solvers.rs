@@ -1,8 +1,12 @@
 use {
-    crate::{domain::eth, infra::solver, util::serialize},
+    crate::{
+        domain::eth,
+        infra::{auth, solver},
+        util::serialize,
+    },
     serde::Deserialize,
     serde_with::serde_as,
-    std::path::Path,
+    std::path::{Path, PathBuf},
     tokio::fs,
 };
 
@@ -14,10 +18,9 @@ pub async fn load(path: &Path) -> Vec<solver::Config> {
         .unwrap_or_else(|_| panic!("I/O error while reading {path:?}"));
     let config: Config = toml::de::from_slice(&data)
         .unwrap_or_else(|_| panic!("YAML syntax error while reading {path:?}"));
-    config
-        .solvers
-        .into_iter()
-        .map(|config| solver::Config {
+    let mut solvers = Vec::new();
+    for config in config.solvers {
+        solvers.push(solver::Config {
             endpoint: config.endpoint,
             name: config.name.into(),
             slippage: solver::Slippage {
@@ -25,8 +28,24 @@ pub async fn load(path: &Path) -> Vec<solver::Config> {
                 absolute: config.absolute_slippage.map(Into::into),
             },
             address: config.address.into(),
-        })
-        .collect()
+            auth: match config.auth {
+                Some(AuthConfig::Bearer { token }) => Some(auth::Auth::Bearer(token)),
+                Some(AuthConfig::Hmac { key }) => Some(auth::Auth::Hmac(key.into_bytes())),
+                Some(AuthConfig::Tls { certificate, key }) => Some(auth::Auth::Tls {
+                    certificate: read(&certificate).await,
+                    key: read(&key).await,
+                }),
+                None => None,
+            },
+        });
+    }
+    solvers
+}
+
+async fn read(path: &Path) -> Vec<u8> {
+    fs::read(path)
+        .await
+        .unwrap_or_else(|_| panic!("I/O error while reading {path:?}"))
 }
 
 #[serde_as]
@@ -47,4 +66,20 @@ struct SolverConfig {
     #[serde_as(as = "Option<serialize::U256>")]
     absolute_slippage: Option<eth::U256>,
     address: eth::H160,
+    /// How the driver authenticates to the solver.
+    #[serde(default)]
+    auth: Option<AuthConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", tag = "kind")]
+enum AuthConfig {
+    /// Send a static token in the `Authorization: Bearer` header.
+    Bearer { token: String },
+    /// Sign the request timestamp and body with HMAC-SHA256 using a shared
+    /// secret key.
+    Hmac { key: String },
+    /// Authenticate with a TLS client certificate. Both files must be PEM
+    /// encoded, and the key must be in PKCS #8 format.
+    Tls { certificate: PathBuf, key: PathBuf },
 }
@@ -0,0 +1,61 @@
+use {
+    crate::{
+        boundary,
+        domain::{competition, eth, liquidity},
+        infra::blockchain::Ethereum,
+    },
+    std::sync::Arc,
+};
+
+/// Fetches the state of onchain liquidity sources, so that solvers can route
+/// orders through them.
+#[derive(Debug, Clone)]
+pub struct Fetcher(Arc<boundary::liquidity::Fetcher>);
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// The liquidity sources to fetch from. [`None`] uses the default sources
+    /// of the chain.
+    pub sources: Option<Vec<Source>>,
+    /// Tokens which are commonly used as intermediate hops between the traded
+    /// tokens. The native token is always a base token.
+    pub base_tokens: Vec<eth::TokenAddress>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum)]
+#[clap(rename_all = "verbatim")]
+pub enum Source {
+    UniswapV2,
+    SushiSwap,
+    Honeyswap,
+    Baoswap,
+    Swapr,
+    BalancerV2,
+    UniswapV3,
+}
+
+impl Fetcher {
+    pub async fn new(config: Config, eth: &Ethereum) -> Result<Self, Error> {
+        boundary::liquidity::Fetcher::new(&config, eth)
+            .await
+            .map(|fetcher| Self(Arc::new(fetcher)))
+            .map_err(Into::into)
+    }
+
+    /// Fetch the liquidity which can be used to settle the orders of the
+    /// auction. Failing to fetch liquidity doesn't prevent solving, so errors
+    /// are logged and no liquidity is returned in that case.
+    pub async fn fetch(&self, auction: &competition::Auction) -> Vec<liquidity::Liquidity> {
+        match self.0.fetch(&auction.token_pairs()).await {
+            Ok(liquidity) => liquidity,
+            Err(err) => {
+                tracing::warn!(?err, "failed to fetch liquidity");
+                Default::default()
+            }
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("boundary error: {0:?}")]
+pub struct Error(#[from] boundary::Error);
@@ -0,0 +1,35 @@
+//! Prometheus metrics of the driver, served on the `/metrics` route.
+
+use prometheus::Encoder;
+
+#[derive(prometheus_metric_storage::MetricStorage, Clone, Debug)]
+#[metric(subsystem = "driver")]
+pub struct Metrics {
+    /// The outcome of the solutions competing for an auction, by solver. The
+    /// result is either "winner", "loser" or the reason for rejecting the
+    /// solution.
+    #[metric(labels("solver", "result"))]
+    pub solutions: prometheus::IntCounterVec,
+
+    /// The errors returned by the driver API, by kind.
+    #[metric(labels("kind"))]
+    pub api_errors: prometheus::IntCounterVec,
+}
+
+impl Metrics {
+    pub fn get() -> &'static Self {
+        Self::instance(global_metrics::get_metric_storage_registry()).unwrap()
+    }
+}
+
+/// Encode all registered metrics in the Prometheus text format.
+pub fn encode() -> String {
+    let mut buffer = Vec::new();
+    if let Err(err) = prometheus::TextEncoder::new().encode(
+        &global_metrics::get_metrics_registry().gather(),
+        &mut buffer,
+    ) {
+        tracing::error!(?err, "could not encode metrics");
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
@@ -1,6 +1,9 @@
 mod api;
+pub mod auth;
 pub mod blockchain;
 pub mod config;
+pub mod liquidity;
+pub mod metrics;
 pub mod simulator;
 pub mod solver;
 
@@ -0,0 +1,457 @@
+use {
+    crate::domain::eth,
+    revm::{
+        db::{CacheDB, DatabaseRef},
+        primitives::{
+            AccountInfo,
+            Bytecode,
+            Env,
+            ExecutionResult,
+            ResultAndState,
+            State,
+            TransactTo,
+            B160,
+            B256,
+            U256,
+        },
+        EVMError,
+        EVM,
+    },
+    thiserror::Error,
+    web3::{
+        transports::Http,
+        types::{BlockId, BlockNumber},
+        Web3,
+    },
+};
+
+mod trace;
+
+pub use trace::Call;
+
+/// Simulates transactions in an EVM embedded in the driver. The state is
+/// loaded lazily from the node RPC API, so only the accounts and storage slots
+/// touched by the transaction are fetched.
+#[derive(Debug)]
+pub(super) struct Evm {
+    web3: Web3<Http>,
+    config: Config,
+}
+
+#[derive(Debug)]
+pub struct Config {
+    /// The node RPC API endpoint from which the state is loaded.
+    pub ethrpc: url::Url,
+    pub chain_id: eth::ChainId,
+}
+
+impl Evm {
+    pub(super) fn new(config: Config) -> Self {
+        Self {
+            web3: Web3::new(Http::new(config.ethrpc.as_str()).unwrap()),
+            config,
+        }
+    }
+
+    /// Execute the transaction on top of the state of the latest block.
+    pub(super) async fn simulate(&self, tx: eth::Tx) -> Result<Simulation, Error> {
+        let block = self
+            .web3
+            .eth()
+            .block(BlockId::Number(BlockNumber::Latest))
+            .await?
+            .ok_or(Error::MissingBlock)?;
+        let number = block.number.ok_or(Error::MissingBlock)?;
+
+        let mut env = Env::default();
+        env.cfg.chain_id = to_revm(self.config.chain_id.0);
+        // The solver account doesn't necessarily hold enough ETH to pay for the
+        // gas, and the gas price is irrelevant for the simulation.
+        env.cfg.disable_balance_check = true;
+        env.cfg.disable_base_fee = true;
+        // Execute the transaction as if it was included in the next block.
+        env.block.number = U256::from(number.as_u64() + 1);
+        env.block.timestamp = to_revm(block.timestamp);
+        env.block.coinbase = B160(block.author.0);
+        env.block.difficulty = to_revm(block.difficulty);
+        env.block.prevrandao = block.mix_hash.map(|hash| B256(hash.0));
+        env.block.basefee = to_revm(block.base_fee_per_gas.unwrap_or_default());
+        env.block.gas_limit = to_revm(block.gas_limit);
+        env.tx.caller = B160(tx.from.0 .0);
+        env.tx.transact_to = TransactTo::Call(B160(tx.to.0 .0));
+        env.tx.value = to_revm(tx.value.0);
+        env.tx.data = tx.input.into();
+        env.tx.gas_limit = block.gas_limit.as_u64();
+        env.tx.gas_price = U256::ZERO;
+        env.tx.access_list = web3::types::AccessList::from(tx.access_list)
+            .into_iter()
+            .map(|item| {
+                (
+                    B160(item.address.0),
+                    item.storage_keys
+                        .into_iter()
+                        .map(|key| U256::from_be_bytes(key.0))
+                        .collect(),
+                )
+            })
+            .collect();
+
+        let node = Node {
+            web3: self.web3.clone(),
+            block: BlockNumber::Number(number),
+            runtime: tokio::runtime::Handle::current(),
+        };
+        // The EVM is synchronous and blocks on the node requests when loading
+        // state, so it has to run on a thread where blocking is allowed.
+        tokio::task::spawn_blocking(move || execute(env, node)).await?
+    }
+}
+
+fn execute(env: Env, node: Node) -> Result<Simulation, Error> {
+    // These accounts are warm from the start of the transaction, so their
+    // address entries would only increase the gas cost. Their storage slots are
+    // still cold though, so they are kept in the access list if any were read.
+    let mut warm = vec![env.tx.caller, env.block.coinbase];
+    if let TransactTo::Call(to) = env.tx.transact_to {
+        warm.push(to);
+    }
+
+    let mut evm = EVM::new();
+    evm.env = env;
+    // The state loaded from the node is cached between the executions below, so
+    // it's only fetched once.
+    evm.database(CacheDB::new(node));
+    let (gas, state) = transact(&mut evm)?;
+    let access_list: Vec<_> = state
+        .into_iter()
+        .filter(|(address, account)| {
+            !is_precompile(address) && (!warm.contains(address) || !account.storage.is_empty())
+        })
+        .map(|(address, account)| (address, account.storage.into_keys().collect::<Vec<_>>()))
+        .collect();
+
+    // The access list changes the gas used by the transaction, so it's executed
+    // again with the access list if it contains any accounts or storage slots
+    // which the transaction didn't already have in its access list.
+    let known = evm.env.tx.access_list.clone();
+    let mut merged = known.clone();
+    for (address, keys) in access_list {
+        match merged.iter_mut().find(|(known, _)| *known == address) {
+            Some((_, known)) => known.extend(keys.into_iter().filter(|key| !known.contains(key))),
+            None => merged.push((address, keys)),
+        }
+    }
+    let gas = if merged == known {
+        gas
+    } else {
+        evm.env.tx.access_list = merged.clone();
+        transact(&mut evm)?.0
+    };
+
+    let access_list: web3::types::AccessList = merged
+        .into_iter()
+        .map(|(address, keys)| web3::types::AccessListItem {
+            address: eth::H160(address.0),
+            storage_keys: keys
+                .into_iter()
+                .map(|key| eth::H256(key.to_be_bytes()))
+                .collect(),
+        })
+        .collect();
+    Ok(Simulation {
+        gas,
+        access_list: access_list.into(),
+    })
+}
+
+/// Execute the transaction without committing its state changes, returning the
+/// gas it used and the accounts it touched.
+fn transact(evm: &mut EVM<CacheDB<Node>>) -> Result<(eth::Gas, State), Error> {
+    let mut tracer = trace::Tracer::default();
+    let ResultAndState { result, state } = evm.inspect(&mut tracer).map_err(|err| match err {
+        EVMError::Database(err) => err,
+        err => Error::Evm(format!("{err:?}")),
+    })?;
+    let trace = tracer.finish().ok_or(Error::MissingTrace)?;
+
+    match result {
+        ExecutionResult::Success { gas_used, .. } => {
+            tracing::trace!(?trace, "EVM simulation succeeded");
+            Ok((eth::U256::from(gas_used).into(), state))
+        }
+        ExecutionResult::Revert { output, .. } => Err(Error::Revert {
+            output: output.to_vec(),
+            trace,
+        }),
+        ExecutionResult::Halt { reason, .. } => Err(Error::Halt {
+            reason: format!("{reason:?}"),
+            trace,
+        }),
+    }
+}
+
+/// Precompiles are warm from the start of the transaction, so there is no need
+/// to include them in the access list.
+fn is_precompile(address: &B160) -> bool {
+    address.0[..19].iter().all(|byte| *byte == 0) && address.0[19] <= 9
+}
+
+fn to_revm(value: eth::U256) -> U256 {
+    U256::from_limbs(value.0)
+}
+
+fn from_revm(value: U256) -> eth::U256 {
+    eth::U256(value.into_limbs())
+}
+
+/// Loads the state from the node. All of the state is read at the same block
+/// so that it is consistent.
+struct Node {
+    web3: Web3<Http>,
+    block: BlockNumber,
+    runtime: tokio::runtime::Handle,
+}
+
+impl DatabaseRef for Node {
+    type Error = Error;
+
+    fn basic(&self, address: B160) -> Result<Option<AccountInfo>, Self::Error> {
+        let address = eth::H160(address.0);
+        let eth = self.web3.eth();
+        let (balance, nonce, code) = self.runtime.block_on(futures::future::try_join3(
+            eth.balance(address, Some(self.block)),
+            eth.transaction_count(address, Some(self.block)),
+            eth.code(address, Some(self.block)),
+        ))?;
+        Ok(Some(AccountInfo::new(
+            to_revm(balance),
+            nonce.as_u64(),
+            Bytecode::new_raw(code.0.into()),
+        )))
+    }
+
+    fn code_by_hash(&self, _: B256) -> Result<Bytecode, Self::Error> {
+        // The code is always loaded together with the account in the basic
+        // method above and cached from there on, so it never has to be looked
+        // up by its hash.
+        Ok(Bytecode::new())
+    }
+
+    fn storage(&self, address: B160, index: U256) -> Result<U256, Self::Error> {
+        let value = self.runtime.block_on(self.web3.eth().storage(
+            eth::H160(address.0),
+            from_revm(index),
+            Some(self.block),
+        ))?;
+        Ok(U256::from_be_bytes(value.0))
+    }
+
+    fn block_hash(&self, number: U256) -> Result<B256, Self::Error> {
+        let block = self
+            .runtime
+            .block_on(self.web3.eth().block(BlockId::Number(BlockNumber::Number(
+                from_revm(number).as_u64().into(),
+            ))))?;
+        Ok(B256(
+            block.and_then(|block| block.hash).unwrap_or_default().0,
+        ))
+    }
+}
+
+#[derive(Debug)]
+pub struct Simulation {
+    /// The gas used by the transaction with the simulated access list.
+    pub gas: eth::Gas,
+    pub access_list: eth::AccessList,
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("node error: {0:?}")]
+    Node(#[from] web3::Error),
+    #[error("the node didn't return the latest block")]
+    MissingBlock,
+    #[error("EVM error: {0}")]
+    Evm(String),
+    #[error("the EVM simulation task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+    #[error("the EVM didn't trace the transaction call")]
+    MissingTrace,
+    /// The transaction reverted. The call trace shows which of the nested
+    /// calls caused the revert.
+    #[error("transaction reverted: 0x{}", hex::encode(.output))]
+    Revert { output: Vec<u8>, trace: Call },
+    #[error("transaction halted: {reason}")]
+    Halt { reason: String, trace: Call },
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        web3::types::{TransactionReceipt, TransactionRequest},
+    };
+
+    /// The node the e2e tests run against.
+    const NODE_URL: &str = "http://127.0.0.1:8545";
+
+    async fn setup() -> (Evm, Web3<Http>, Vec<eth::H160>) {
+        let web3 = Web3::new(Http::new(NODE_URL).unwrap());
+        let chain_id = web3.eth().chain_id().await.unwrap();
+        let accounts = web3.eth().accounts().await.unwrap();
+        let evm = Evm::new(Config {
+            ethrpc: NODE_URL.parse().unwrap(),
+            chain_id: chain_id.into(),
+        });
+        (evm, web3, accounts)
+    }
+
+    #[test]
+    fn detects_precompiles() {
+        let address = |last: u8| {
+            let mut address = B160([0; 20]);
+            address.0[19] = last;
+            address
+        };
+        assert!(is_precompile(&address(1)));
+        assert!(is_precompile(&address(9)));
+        assert!(!is_precompile(&address(10)));
+        let mut address = address(1);
+        address.0[0] = 1;
+        assert!(!is_precompile(&address));
+    }
+
+    #[test]
+    fn traces_nested_calls() {
+        let caller = B160([1; 20]);
+        let outer = B160([2; 20]);
+        let inner = B160([3; 20]);
+        let mut db = CacheDB::new(revm::db::EmptyDB::default());
+        // Calls `inner` with all of the remaining gas, ignores whether the call
+        // succeeded and stops.
+        let code = [
+            hex::decode("60006000600060006000").unwrap(),
+            vec![0x73],
+            inner.0.to_vec(),
+            hex::decode("5af15000").unwrap(),
+        ]
+        .concat();
+        db.insert_account_info(
+            outer,
+            AccountInfo::new(U256::ZERO, 0, Bytecode::new_raw(code.into())),
+        );
+        // `PUSH1 0 PUSH1 0 REVERT`
+        db.insert_account_info(
+            inner,
+            AccountInfo::new(
+                U256::ZERO,
+                0,
+                Bytecode::new_raw(hex::decode("60006000fd").unwrap().into()),
+            ),
+        );
+        let mut evm = EVM::new();
+        evm.env.tx.caller = caller;
+        evm.env.tx.transact_to = TransactTo::Call(outer);
+        evm.env.tx.data = vec![1, 2, 3].into();
+        evm.env.tx.gas_limit = 1_000_000;
+        evm.database(db);
+        let mut tracer = trace::Tracer::default();
+
+        let result = evm.inspect(&mut tracer).unwrap().result;
+
+        assert!(result.is_success());
+        let trace = tracer.finish().unwrap();
+        assert_eq!(trace.from, eth::H160(caller.0).into());
+        assert_eq!(trace.to, eth::H160(outer.0).into());
+        assert_eq!(trace.input, vec![1, 2, 3]);
+        assert!(trace.success);
+        assert_eq!(trace.calls.len(), 1);
+        let call = &trace.calls[0];
+        assert_eq!(call.from, eth::H160(outer.0).into());
+        assert_eq!(call.to, eth::H160(inner.0).into());
+        assert!(call.input.is_empty());
+        assert!(!call.success);
+        assert!(call.calls.is_empty());
+        assert!(call.gas_used.0 < trace.gas_used.0);
+    }
+
+    async fn receipt(web3: &Web3<Http>, tx: TransactionRequest) -> TransactionReceipt {
+        let hash = web3.eth().send_transaction(tx).await.unwrap();
+        web3.eth()
+            .transaction_receipt(hash)
+            .await
+            .unwrap()
+            .expect("the e2e node mines transactions instantly")
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn simulates_transfer_against_node() {
+        let (evm, web3, accounts) = setup().await;
+        let tx = eth::Tx {
+            from: accounts[0].into(),
+            to: accounts[1].into(),
+            value: eth::U256::from(1).into(),
+            input: Default::default(),
+            access_list: Default::default(),
+        };
+
+        let simulation = evm.simulate(tx.clone()).await.unwrap();
+        let estimate = web3
+            .eth()
+            .estimate_gas(
+                web3::types::CallRequest {
+                    from: Some(accounts[0]),
+                    to: Some(accounts[1]),
+                    value: Some(1.into()),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(simulation.gas.0, estimate);
+        // The sender and the receiver are warm anyway.
+        assert!(simulation.access_list.is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn traces_reverting_call_against_node() {
+        let (evm, web3, accounts) = setup().await;
+        // Deploys a contract whose code is `PUSH1 0 PUSH1 0 REVERT`.
+        let deployment = receipt(
+            &web3,
+            TransactionRequest {
+                from: accounts[0],
+                data: Some(hex::decode("6460006000fd6000526005601bf3").unwrap().into()),
+                ..Default::default()
+            },
+        )
+        .await;
+        let contract = deployment.contract_address.unwrap();
+
+        let err = evm
+            .simulate(eth::Tx {
+                from: accounts[0].into(),
+                to: contract.into(),
+                value: Default::default(),
+                input: vec![1, 2, 3],
+                access_list: Default::default(),
+            })
+            .await
+            .unwrap_err();
+
+        let (output, trace) = match err {
+            Error::Revert { output, trace } => (output, trace),
+            err => panic!("unexpected error {err:?}"),
+        };
+        assert!(output.is_empty());
+        assert_eq!(trace.from, eth::Address::from(accounts[0]));
+        assert_eq!(trace.to, eth::Address::from(contract));
+        assert_eq!(trace.input, vec![1, 2, 3]);
+        assert!(!trace.success);
+        assert!(trace.calls.is_empty());
+    }
+}
@@ -0,0 +1,84 @@
+use {
+    crate::domain::eth,
+    revm::{
+        interpreter::{return_ok, CallInputs, Gas, InstructionResult},
+        primitives::Bytes,
+        Database,
+        EVMData,
+        Inspector,
+    },
+};
+
+/// A call executed during a simulation, together with the nested calls it made.
+#[derive(Debug, Clone)]
+pub struct Call {
+    pub from: eth::Address,
+    pub to: eth::Address,
+    pub value: eth::Ether,
+    pub input: Vec<u8>,
+    pub output: Vec<u8>,
+    pub gas_used: eth::Gas,
+    /// Whether the call succeeded. Nested calls can fail without reverting
+    /// the calls which made them.
+    pub success: bool,
+    pub calls: Vec<Call>,
+}
+
+/// Builds the call trace of a transaction while it's executed by the EVM.
+#[derive(Debug, Default)]
+pub(super) struct Tracer {
+    /// The calls which are currently executing, from the outermost to the
+    /// innermost.
+    stack: Vec<Call>,
+    root: Option<Call>,
+}
+
+impl Tracer {
+    /// The trace of the transaction call, if it was executed.
+    pub(super) fn finish(self) -> Option<Call> {
+        self.root
+    }
+}
+
+impl<DB: Database> Inspector<DB> for Tracer {
+    fn call(
+        &mut self,
+        _: &mut EVMData<'_, DB>,
+        inputs: &mut CallInputs,
+        _: bool,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.stack.push(Call {
+            from: eth::H160(inputs.context.caller.0).into(),
+            to: eth::H160(inputs.contract.0).into(),
+            value: super::from_revm(inputs.transfer.value).into(),
+            input: inputs.input.to_vec(),
+            output: Default::default(),
+            gas_used: Default::default(),
+            success: false,
+            calls: Default::default(),
+        });
+        (InstructionResult::Continue, Gas::new(0), Bytes::new())
+    }
+
+    fn call_end(
+        &mut self,
+        _: &mut EVMData<'_, DB>,
+        inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+        _: bool,
+    ) -> (InstructionResult, Gas, Bytes) {
+        if let Some(mut call) = self.stack.pop() {
+            call.output = out.to_vec();
+            call.gas_used =
+                eth::U256::from(inputs.gas_limit.saturating_sub(remaining_gas.remaining())).into();
+            call.success = matches!(ret, return_ok!());
+            match self.stack.last_mut() {
+                Some(parent) => parent.calls.push(call),
+                None => self.root = Some(call),
+            }
+        }
+        (ret, remaining_gas, out)
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{domain::eth, infra::blockchain, Ethereum};
 
+pub mod evm;
 pub mod tenderly;
 
 /// Ethereum transaction simulator.
@@ -17,6 +18,13 @@ impl Simulator {
         Self(Inner::Ethereum(eth))
     }
 
+    /// Simulate transactions in an EVM embedded in the driver, loading the
+    /// state lazily from the node. Unlike the other simulators, this one isn't
+    /// rate limited and includes the call trace in simulation errors.
+    pub fn evm(config: evm::Config) -> Self {
+        Self(Inner::Evm(evm::Evm::new(config)))
+    }
+
     /// Simulate the access list needed by a transaction. Return a new
     /// transaction with an updated access list.
     pub async fn access_list(&self, tx: eth::Tx) -> Result<eth::Tx, Error> {
@@ -28,10 +36,28 @@ impl Simulator {
                     .access_list
             }
             Inner::Ethereum(ethereum) => ethereum.create_access_list(tx.clone()).await?,
+            Inner::Evm(evm) => evm.simulate(tx.clone()).await?.access_list,
         };
         Ok(tx.merge_access_list(access_list))
     }
 
+    /// Simulate the access list needed by a transaction and the gas needed by
+    /// the transaction with that access list. Return a new transaction with an
+    /// updated access list.
+    pub async fn access_list_and_gas(&self, tx: eth::Tx) -> Result<(eth::Tx, eth::Gas), Error> {
+        match &self.0 {
+            Inner::Evm(evm) => {
+                let simulation = evm.simulate(tx.clone()).await?;
+                Ok((tx.merge_access_list(simulation.access_list), simulation.gas))
+            }
+            _ => {
+                let tx = self.access_list(tx).await?;
+                let gas = self.gas(tx.clone()).await?;
+                Ok((tx, gas))
+            }
+        }
+    }
+
     /// Simulate the gas needed by a transaction.
     pub async fn gas(&self, tx: eth::Tx) -> Result<eth::Gas, Error> {
         Ok(match &self.0 {
@@ -42,6 +68,7 @@ impl Simulator {
                     .gas
             }
             Inner::Ethereum(ethereum) => ethereum.estimate_gas(tx).await?,
+            Inner::Evm(evm) => evm.simulate(tx).await?.gas,
         })
     }
 }
@@ -50,6 +77,7 @@ impl Simulator {
 enum Inner {
     Tenderly(tenderly::Tenderly),
     Ethereum(Ethereum),
+    Evm(evm::Evm),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -58,4 +86,6 @@ pub enum Error {
     Tenderly(#[from] tenderly::Error),
     #[error("tenderly error: {0:?}")]
     Blockchain(#[from] blockchain::Error),
+    #[error("EVM error: {0:?}")]
+    Evm(#[from] evm::Error),
 }
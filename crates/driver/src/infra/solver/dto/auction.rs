@@ -1,11 +1,12 @@
 use {
     crate::{
-        domain::{competition, eth},
+        domain::{competition, eth, liquidity},
         util::serialize,
     },
+    num::ToPrimitive,
     serde::Serialize,
     serde_with::serde_as,
-    std::collections::HashMap,
+    std::collections::{BTreeMap, HashMap},
 };
 
 impl Auction {
@@ -54,8 +55,98 @@ impl Auction {
                     reward: order.reward,
                 })
                 .collect(),
-            // TODO #899: Implement this when you do liquidity
-            liquidity: vec![],
+            liquidity: auction
+                .liquidity
+                .iter()
+                .filter_map(|liquidity| match &liquidity.kind {
+                    liquidity::Kind::UniswapV2(pool) => {
+                        Some(Liquidity::ConstantProduct(ConstantProductPool {
+                            id: liquidity.id.0.to_string(),
+                            address: liquidity.address.into(),
+                            gas_estimate: liquidity.gas.into(),
+                            tokens: pool
+                                .reserves
+                                .iter()
+                                .map(|reserve| {
+                                    (
+                                        reserve.token.into(),
+                                        ConstantProductReserve {
+                                            balance: reserve.amount,
+                                        },
+                                    )
+                                })
+                                .collect(),
+                            fee: pool.fee.clone(),
+                        }))
+                    }
+                    liquidity::Kind::BalancerV2Weighted(pool) => {
+                        Some(Liquidity::WeightedProduct(WeightedProductPool {
+                            id: liquidity.id.0.to_string(),
+                            address: liquidity.address.into(),
+                            gas_estimate: liquidity.gas.into(),
+                            tokens: pool
+                                .reserves
+                                .iter()
+                                .map(|reserve| {
+                                    (
+                                        reserve.asset.token.into(),
+                                        WeightedProductReserve {
+                                            balance: reserve.asset.amount,
+                                            weight: reserve.weight.clone(),
+                                        },
+                                    )
+                                })
+                                .collect(),
+                            fee: pool.fee.clone(),
+                        }))
+                    }
+                    liquidity::Kind::BalancerV2Stable(pool) => {
+                        match pool.amplification_parameter.to_f64() {
+                            Some(amplification_parameter) => Some(Liquidity::Stable(StablePool {
+                                id: liquidity.id.0.to_string(),
+                                address: liquidity.address.into(),
+                                gas_estimate: liquidity.gas.into(),
+                                tokens: pool
+                                    .reserves
+                                    .iter()
+                                    .map(|reserve| {
+                                        (
+                                            reserve.asset.token.into(),
+                                            StableReserve {
+                                                balance: reserve.asset.amount,
+                                                scaling_factor: reserve.scaling_factor,
+                                            },
+                                        )
+                                    })
+                                    .collect(),
+                                amplification_parameter,
+                                fee: pool.fee.clone(),
+                            })),
+                            None => {
+                                tracing::warn!(
+                                    id = ?liquidity.id,
+                                    address = ?liquidity.address,
+                                    "skipping stable pool, amplification parameter overflows f64"
+                                );
+                                None
+                            }
+                        }
+                    }
+                    liquidity::Kind::UniswapV3(pool) => Some(Liquidity::ConcentratedLiquidity(
+                        ConcentratedLiquidityPool {
+                            id: liquidity.id.0.to_string(),
+                            address: liquidity.address.into(),
+                            gas_estimate: liquidity.gas.into(),
+                            tokens: pool.tokens.iter().copied().map(Into::into).collect(),
+                            sqrt_price: pool.sqrt_price,
+                            liquidity: pool.liquidity.into(),
+                            tick: pool.tick,
+                            liquidity_net: pool.liquidity_net.clone(),
+                            fee: pool.fee.clone(),
+                        },
+                    )),
+                })
+                .collect(),
             effective_gas_price: auction.gas_price.into(),
             deadline: deadline.into(),
         }
@@ -215,8 +306,8 @@ struct ConcentratedLiquidityPool {
     #[serde_as(as = "serialize::U256")]
     liquidity: eth::U256,
     tick: i32,
-    #[serde_as(as = "HashMap<serde_with::DisplayFromStr, serialize::U256>")]
-    liquidity_net: HashMap<i32, eth::U256>,
+    #[serde_as(as = "BTreeMap<serde_with::DisplayFromStr, serde_with::DisplayFromStr>")]
+    liquidity_net: BTreeMap<i32, i128>,
     #[serde_as(as = "serde_with::DisplayFromStr")]
     fee: bigdecimal::BigDecimal,
 }
@@ -7,6 +7,7 @@ use {
             },
             eth,
         },
+        infra::auth,
         util,
     },
     thiserror::Error,
@@ -50,6 +51,8 @@ pub struct Config {
     pub slippage: Slippage,
     /// The address of this solver.
     pub address: eth::Address,
+    /// How the driver authenticates to the solver, if at all.
+    pub auth: Option<auth::Auth>,
 }
 
 impl Solver {
@@ -60,12 +63,25 @@ impl Solver {
             "application/json".parse().unwrap(),
         );
         headers.insert(reqwest::header::ACCEPT, "application/json".parse().unwrap());
-        // TODO(#907) Also add an auth header
+        let mut client = reqwest::ClientBuilder::new();
+        match &config.auth {
+            Some(auth::Auth::Bearer(token)) => {
+                let mut value: reqwest::header::HeaderValue =
+                    format!("Bearer {token}").parse().unwrap();
+                value.set_sensitive(true);
+                headers.insert(reqwest::header::AUTHORIZATION, value);
+            }
+            Some(auth::Auth::Tls { certificate, key }) => {
+                client = client.identity(
+                    reqwest::Identity::from_pkcs8_pem(certificate, key)
+                        .expect("invalid TLS client certificate"),
+                );
+            }
+            // The request body is signed when sending the request.
+            Some(auth::Auth::Hmac(_)) | None => (),
+        }
         Self {
-            client: reqwest::ClientBuilder::new()
-                .default_headers(headers)
-                .build()
-                .unwrap(),
+            client: client.default_headers(headers).build().unwrap(),
             config,
         }
     }
@@ -87,11 +103,18 @@ impl Solver {
         let body =
             serde_json::to_string(&dto::Auction::from_domain(auction, solver_deadline)).unwrap();
         tracing::trace!(%self.config.endpoint, %body, "sending request to solver");
-        let req = self
+        let mut req = self
             .client
             .post(self.config.endpoint.clone())
-            .body(body)
             .timeout(solver_deadline.into());
+        if let Some(auth::Auth::Hmac(key)) = &self.config.auth {
+            let timestamp = auth::timestamp();
+            req = req.header(auth::TIMESTAMP_HEADER, timestamp).header(
+                auth::SIGNATURE_HEADER,
+                auth::sign(key, timestamp, body.as_bytes()),
+            );
+        }
+        let req = req.body(body);
         let res = util::http::send(SOLVER_RESPONSE_MAX_BYTES, req).await;
         tracing::trace!(%self.config.endpoint, ?res, "got response from solver");
         let res: dto::Solution = serde_json::from_str(&res?)?;
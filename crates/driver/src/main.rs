@@ -13,6 +13,7 @@ use {
     infra::{
         blockchain::Ethereum,
         config,
+        liquidity,
         simulator::{self, Simulator},
         solver::{self, Solver},
         Api,
@@ -39,8 +40,14 @@ async fn run() {
     let serve = Api {
         solvers: solvers(&args).await,
         simulator: simulator(&args, &eth),
+        liquidity: liquidity(&args, &eth).await,
         eth,
         addr: args.bind_addr,
+        aggregate: args.aggregate_solve,
+        auth: infra::auth::Verifier {
+            bearer_token: args.api_bearer_token.clone(),
+            hmac_key: args.api_hmac_key.clone().map(String::into_bytes),
+        },
     }
     .serve(async {
         let _ = shutdown_receiver.await;
@@ -61,6 +68,9 @@ async fn run() {
 }
 
 fn simulator(args: &cli::Args, eth: &Ethereum) -> Simulator {
+    if args.tenderly.is_specified() && args.evm_simulation {
+        panic!("the tenderly args and --evm-simulation are mutually exclusive")
+    }
     if args.tenderly.is_specified() {
         Simulator::tenderly(simulator::tenderly::Config {
             url: args.tenderly.tenderly_url.clone(),
@@ -71,11 +81,34 @@ fn simulator(args: &cli::Args, eth: &Ethereum) -> Simulator {
             save: args.tenderly.tenderly_save,
             save_if_fails: args.tenderly.tenderly_save_if_fails,
         })
+    } else if args.evm_simulation {
+        Simulator::evm(simulator::evm::Config {
+            ethrpc: args.ethrpc.clone(),
+            chain_id: eth.chain_id(),
+        })
     } else {
         Simulator::ethereum(eth.to_owned())
     }
 }
 
+async fn liquidity(args: &cli::Args, eth: &Ethereum) -> liquidity::Fetcher {
+    liquidity::Fetcher::new(
+        liquidity::Config {
+            sources: args.liquidity.liquidity_sources.clone(),
+            base_tokens: args
+                .liquidity
+                .base_tokens
+                .iter()
+                .copied()
+                .map(Into::into)
+                .collect(),
+        },
+        eth,
+    )
+    .await
+    .expect("initialize liquidity fetcher")
+}
+
 async fn ethereum(args: &cli::Args) -> Ethereum {
     Ethereum::ethrpc(&args.ethrpc)
         .await
@@ -9,6 +9,7 @@ use shared::ethrpc::Web3;
 
 pub struct Contracts {
     pub balancer_vault: BalancerV2Vault,
+    pub gp_authenticator: GPv2AllowListAuthentication,
     pub gp_settlement: GPv2Settlement,
     pub uniswap_factory: UniswapV2Factory,
     pub uniswap_router: UniswapV2Router02,
@@ -100,6 +101,7 @@ pub async fn deploy(web3: &Web3) -> Result<Contracts> {
 
     Ok(Contracts {
         balancer_vault,
+        gp_authenticator: gp_authentication,
         gp_settlement,
         uniswap_factory,
         uniswap_router,
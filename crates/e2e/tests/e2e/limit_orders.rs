@@ -256,6 +256,7 @@ async fn single_limit_order_test(web3: Web3) {
         None,
         0,
         Arc::new(MockCodeFetching::new()),
+        solver::driver::DriverConfig::default(),
     );
     driver.single_run().await.unwrap();
 
@@ -501,6 +502,7 @@ async fn two_limit_orders_test(web3: Web3) {
         None,
         0,
         Arc::new(MockCodeFetching::new()),
+        solver::driver::DriverConfig::default(),
     );
     driver.single_run().await.unwrap();
 
@@ -755,6 +757,7 @@ async fn mixed_limit_and_market_orders_test(web3: Web3) {
         None,
         0,
         Arc::new(MockCodeFetching::new()),
+        solver::driver::DriverConfig::default(),
     );
     driver.single_run().await.unwrap();
 
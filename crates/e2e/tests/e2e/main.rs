@@ -12,8 +12,10 @@ mod services;
 mod eth_flow;
 mod eth_integration;
 mod limit_orders;
+mod multiple_settlements;
 mod onchain_settlement;
 mod order_cancellation;
+mod post_interactions;
 mod refunder;
 mod settlement_without_onchain_liquidity;
 mod smart_contract_orders;
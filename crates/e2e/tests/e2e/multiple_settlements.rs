@@ -0,0 +1,308 @@
+use crate::{
+    deploy::Contracts,
+    onchain_components::{
+        deploy_token_with_weth_uniswap_pool, to_wei, uniswap_pair_provider, WethPoolConfig,
+    },
+    services::{
+        create_order_converter, create_orderbook_api, wait_for_solvable_orders, OrderbookServices,
+        API_HOST,
+    },
+    tx,
+};
+use contracts::{ERC20Mintable, IUniswapLikeRouter};
+use ethcontract::prelude::{Account, Address, PrivateKey, U256};
+use hex_literal::hex;
+use model::{
+    order::{OrderBuilder, OrderKind, OrderUid},
+    signature::EcdsaSigningScheme,
+};
+use reqwest::Client;
+use secp256k1::SecretKey;
+use shared::{
+    code_fetching::MockCodeFetching, ethrpc::Web3, http_client::HttpClientFactory,
+    sources::uniswap_v2::pool_fetching::PoolFetcher,
+};
+use solver::{
+    driver::DriverConfig,
+    liquidity::uniswap_v2::UniswapLikeLiquidity,
+    liquidity_collector::LiquidityCollector,
+    metrics::NoopMetrics,
+    settlement_access_list::{create_priority_estimator, AccessListEstimatorType},
+    settlement_submission::{
+        submitter::{public_mempool_api::PublicMempoolApi, Strategy},
+        GlobalTxPool, SolutionSubmitter, StrategyArgs,
+    },
+};
+use std::{sync::Arc, time::Duration};
+use web3::signing::SecretKeyRef;
+
+const TRADER_A_PK: [u8; 32] =
+    hex!("0000000000000000000000000000000000000000000000000000000000000001");
+const TRADER_B_PK: [u8; 32] =
+    hex!("0000000000000000000000000000000000000000000000000000000000000002");
+const TRADER_C_PK: [u8; 32] =
+    hex!("0000000000000000000000000000000000000000000000000000000000000003");
+const TRADER_D_PK: [u8; 32] =
+    hex!("0000000000000000000000000000000000000000000000000000000000000004");
+
+const ORDER_PLACEMENT_ENDPOINT: &str = "/api/v1/orders/";
+
+#[tokio::test]
+#[ignore]
+async fn local_node_multiple_settlements() {
+    crate::local_node::test(multiple_settlements).await;
+}
+
+async fn multiple_settlements(web3: Web3) {
+    shared::tracing::initialize_for_tests("warn,orderbook=debug,solver=debug,autopilot=debug");
+    shared::exit_process_on_panic::set_panic_hook();
+    let contracts = crate::deploy::deploy(&web3).await.expect("deploy");
+
+    let accounts: Vec<Address> = web3.eth().accounts().await.expect("get accounts failed");
+    let solver_a = Account::Local(accounts[0], None);
+    let solver_b = Account::Local(accounts[1], None);
+    tx!(
+        solver_a,
+        contracts.gp_authenticator.add_solver(solver_b.address())
+    );
+
+    // Create & mint tokens to trade, pools for fee connections
+    let mut tokens = Vec::new();
+    for _ in 0..4 {
+        let token = deploy_token_with_weth_uniswap_pool(
+            &web3,
+            &contracts,
+            WethPoolConfig {
+                token_amount: to_wei(100_000),
+                weth_amount: to_wei(100_000),
+            },
+        )
+        .await;
+        token.mint(solver_a.address(), to_wei(1_000)).await;
+        tokens.push(token);
+    }
+
+    // The naive solver only settles token pairs with a direct pool, even if the orders match
+    // perfectly without it.
+    for (token_0, token_1) in [(&tokens[0], &tokens[1]), (&tokens[2], &tokens[3])] {
+        create_pool(&contracts, &solver_a, &token_0.contract, &token_1.contract).await;
+    }
+
+    let OrderbookServices {
+        block_stream,
+        base_tokens,
+        ..
+    } = OrderbookServices::new(&web3, &contracts, false).await;
+
+    let http_factory = HttpClientFactory::default();
+    let client = http_factory.create();
+
+    // Two pairs of orders which match each other perfectly, so that neither settlement needs to
+    // interact with the pools and both can be submitted in the same block.
+    let orders = [
+        (TRADER_A_PK, &tokens[0], &tokens[1]),
+        (TRADER_B_PK, &tokens[1], &tokens[0]),
+        (TRADER_C_PK, &tokens[2], &tokens[3]),
+        (TRADER_D_PK, &tokens[3], &tokens[2]),
+    ];
+    let mut uids = Vec::new();
+    for (trader_pk, sell_token, buy_token) in orders {
+        let trader = Account::Offline(PrivateKey::from_raw(trader_pk).unwrap(), None);
+        sell_token.mint(trader.address(), to_wei(101)).await;
+        tx!(
+            trader,
+            sell_token
+                .contract
+                .approve(contracts.allowance, to_wei(101))
+        );
+        uids.push(
+            place_order(
+                &client,
+                &contracts,
+                trader_pk,
+                sell_token.contract.address(),
+                buy_token.contract.address(),
+            )
+            .await,
+        );
+    }
+    wait_for_solvable_orders(&client, 4).await.unwrap();
+
+    // Both solvers propose both settlements, so whichever wins, the other solver's settlement of
+    // the other token pair is submitted as well.
+    let uniswap_pair_provider = uniswap_pair_provider(&contracts);
+    let uniswap_liquidity = UniswapLikeLiquidity::new(
+        IUniswapLikeRouter::at(&web3, contracts.uniswap_router.address()),
+        contracts.gp_settlement.clone(),
+        web3.clone(),
+        Arc::new(PoolFetcher::uniswap(uniswap_pair_provider, web3.clone())),
+    );
+    let solvers = vec![
+        solver::solver::naive_solver(solver_a.clone()),
+        solver::solver::naive_solver(solver_b.clone()),
+    ];
+    let liquidity_collector = LiquidityCollector {
+        liquidity_sources: vec![Box::new(uniswap_liquidity)],
+        base_tokens,
+    };
+    let network_id = web3.net().version().await.unwrap();
+    let submitted_transactions = GlobalTxPool::default();
+    let mut driver = solver::driver::Driver::new(
+        contracts.gp_settlement.clone(),
+        liquidity_collector,
+        solvers,
+        Arc::new(web3.clone()),
+        Duration::from_secs(30),
+        contracts.weth.address(),
+        Duration::from_secs(0),
+        Arc::new(NoopMetrics::default()),
+        web3.clone(),
+        network_id.clone(),
+        Duration::from_secs(30),
+        block_stream,
+        SolutionSubmitter {
+            web3: web3.clone(),
+            contract: contracts.gp_settlement.clone(),
+            gas_price_estimator: Arc::new(web3.clone()),
+            target_confirm_time: Duration::from_secs(1),
+            gas_price_cap: f64::MAX,
+            max_confirm_time: Duration::from_secs(120),
+            retry_interval: Duration::from_secs(5),
+            transaction_strategies: vec![
+                solver::settlement_submission::TransactionStrategy::PublicMempool(StrategyArgs {
+                    submit_api: Box::new(PublicMempoolApi::new(vec![web3.clone()], false)),
+                    max_additional_tip: 0.,
+                    additional_tip_percentage_of_max_fee: 0.,
+                    sub_tx_pool: submitted_transactions.add_sub_pool(Strategy::PublicMempool),
+                }),
+            ],
+            access_list_estimator: Arc::new(
+                create_priority_estimator(
+                    &web3,
+                    &[AccessListEstimatorType::Web3],
+                    None,
+                    network_id,
+                )
+                .unwrap(),
+            ),
+            code_fetcher: Arc::new(MockCodeFetching::new()),
+        },
+        create_orderbook_api(),
+        create_order_converter(&web3, contracts.weth.address()),
+        15000000u128,
+        1.0,
+        None,
+        None.into(),
+        None,
+        0,
+        Arc::new(MockCodeFetching::new()),
+        DriverConfig {
+            max_settlements_per_auction: 2,
+            ..Default::default()
+        },
+    );
+    let nonce_a = web3
+        .eth()
+        .transaction_count(solver_a.address(), None)
+        .await
+        .unwrap();
+    let nonce_b = web3
+        .eth()
+        .transaction_count(solver_b.address(), None)
+        .await
+        .unwrap();
+
+    driver.single_run().await.unwrap();
+
+    // Each solver account submitted one settlement.
+    for (solver, nonce) in [(&solver_a, nonce_a), (&solver_b, nonce_b)] {
+        let new_nonce = web3
+            .eth()
+            .transaction_count(solver.address(), None)
+            .await
+            .unwrap();
+        assert_eq!(new_nonce, nonce + 1);
+    }
+
+    // Every trader received the bought token.
+    for (trader_pk, _, buy_token) in orders {
+        let trader = Account::Offline(PrivateKey::from_raw(trader_pk).unwrap(), None);
+        let balance = buy_token
+            .contract
+            .balance_of(trader.address())
+            .call()
+            .await
+            .expect("Couldn't fetch balance");
+        assert!(balance >= to_wei(80));
+    }
+
+    // The orders of both settlements are in flight.
+    for uid in &uids {
+        assert!(driver.in_flight_orders().contains(uid));
+    }
+}
+
+async fn create_pool(
+    contracts: &Contracts,
+    solver_account: &Account,
+    token_0: &ERC20Mintable,
+    token_1: &ERC20Mintable,
+) {
+    tx!(
+        solver_account,
+        contracts
+            .uniswap_factory
+            .create_pair(token_0.address(), token_1.address())
+    );
+    for token in [token_0, token_1] {
+        tx!(
+            solver_account,
+            token.approve(contracts.uniswap_router.address(), to_wei(1_000))
+        );
+    }
+    tx!(
+        solver_account,
+        contracts.uniswap_router.add_liquidity(
+            token_0.address(),
+            token_1.address(),
+            to_wei(1_000),
+            to_wei(1_000),
+            0_u64.into(),
+            0_u64.into(),
+            solver_account.address(),
+            U256::max_value(),
+        )
+    );
+}
+
+async fn place_order(
+    client: &Client,
+    contracts: &Contracts,
+    trader_pk: [u8; 32],
+    sell_token: Address,
+    buy_token: Address,
+) -> OrderUid {
+    let order = OrderBuilder::default()
+        .with_sell_token(sell_token)
+        .with_sell_amount(to_wei(100))
+        .with_fee_amount(to_wei(1))
+        .with_buy_token(buy_token)
+        .with_buy_amount(to_wei(80))
+        .with_valid_to(model::time::now_in_epoch_seconds() + 300)
+        .with_kind(OrderKind::Sell)
+        .sign_with(
+            EcdsaSigningScheme::Eip712,
+            &contracts.domain_separator,
+            SecretKeyRef::from(&SecretKey::from_slice(&trader_pk).unwrap()),
+        )
+        .build()
+        .into_order_creation();
+    let placement = client
+        .post(&format!("{}{}", API_HOST, ORDER_PLACEMENT_ENDPOINT))
+        .json(&order)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(placement.status(), 201);
+    placement.json().await.unwrap()
+}
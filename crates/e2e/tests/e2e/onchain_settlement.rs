@@ -242,6 +242,7 @@ async fn onchain_settlement(web3: Web3) {
         None,
         0,
         Arc::new(MockCodeFetching::new()),
+        solver::driver::DriverConfig::default(),
     );
     driver.single_run().await.unwrap();
 
@@ -0,0 +1,185 @@
+use crate::{
+    onchain_components::{
+        deploy_mintable_token, deploy_token_with_weth_uniswap_pool, to_wei, WethPoolConfig,
+    },
+    services::{
+        create_orderbook_api, setup_naive_solver_uniswapv2_driver, wait_for_solvable_orders,
+        OrderbookServices, API_HOST,
+    },
+    tx,
+};
+use ethcontract::prelude::{Account, Address, PrivateKey, U256};
+use maplit::hashset;
+use model::{
+    interaction::InteractionData,
+    order::{Interactions, Order, OrderBuilder, OrderKind, OrderUid},
+    signature::EcdsaSigningScheme,
+};
+use secp256k1::SecretKey;
+use shared::{ethrpc::Web3, http_client::HttpClientFactory, maintenance::Maintaining};
+use web3::signing::SecretKeyRef;
+
+const TRADER_PK: [u8; 32] = [1; 32];
+
+const ORDER_PLACEMENT_ENDPOINT: &str = "/api/v1/orders/";
+
+#[tokio::test]
+#[ignore]
+async fn local_node_post_interactions() {
+    crate::local_node::test(post_interactions).await;
+}
+
+async fn post_interactions(web3: Web3) {
+    shared::tracing::initialize_for_tests("warn,orderbook=debug,solver=debug,autopilot=debug");
+    shared::exit_process_on_panic::set_panic_hook();
+    let contracts = crate::deploy::deploy(&web3).await.expect("deploy");
+
+    let accounts: Vec<Address> = web3.eth().accounts().await.expect("get accounts failed");
+    let solver_account = Account::Local(accounts[0], None);
+    let trader = Account::Offline(PrivateKey::from_raw(TRADER_PK).unwrap(), None);
+
+    // Create & mint tokens to trade, pools for fee connections
+    let token = deploy_token_with_weth_uniswap_pool(
+        &web3,
+        &contracts,
+        WethPoolConfig {
+            token_amount: to_wei(100_000),
+            weth_amount: to_wei(100_000),
+        },
+    )
+    .await;
+    token.mint(trader.address(), to_wei(51)).await;
+    let token = token.contract;
+
+    // Approve GPv2 for trading
+    tx!(trader, token.approve(contracts.allowance, to_wei(51)));
+
+    // A reward token which the settlement contract is allowed to mint, so that a hook can pay
+    // out rewards for trading. It doesn't hold any funds of the settlement contract, unlike the
+    // traded tokens which must never be hook targets.
+    let reward = deploy_mintable_token(&web3).await;
+    tx!(
+        solver_account,
+        reward.add_minter(contracts.gp_settlement.address())
+    );
+
+    // Only the reward token is allowed as a post-interaction target.
+    let OrderbookServices {
+        maintenance,
+        block_stream,
+        solvable_orders_cache,
+        base_tokens,
+        ..
+    } = OrderbookServices::new_with_post_interaction_targets(
+        &web3,
+        &contracts,
+        false,
+        hashset!(reward.address()),
+    )
+    .await;
+
+    let http_factory = HttpClientFactory::default();
+    let client = http_factory.create();
+
+    // The hook mints a reward to the trader, which is easy to observe after
+    // the settlement.
+    let hook = InteractionData {
+        target: reward.address(),
+        value: U256::zero(),
+        call_data: reward.mint(trader.address(), 42.into()).tx.data.unwrap().0,
+    };
+    let order = |interactions: Interactions| {
+        let mut order = OrderBuilder::default()
+            .with_kind(OrderKind::Sell)
+            .with_sell_token(token.address())
+            .with_sell_amount(to_wei(50))
+            .with_fee_amount(to_wei(1))
+            .with_buy_token(contracts.weth.address())
+            .with_buy_amount(to_wei(49))
+            .with_valid_to(model::time::now_in_epoch_seconds() + 300)
+            .sign_with(
+                EcdsaSigningScheme::Eip712,
+                &contracts.domain_separator,
+                SecretKeyRef::from(&SecretKey::from_slice(&TRADER_PK).unwrap()),
+            )
+            .build()
+            .into_order_creation();
+        order.interactions = interactions;
+        order
+    };
+
+    // Hooks calling contracts that are not allowed, like the traded token, are rejected.
+    let placement = client
+        .post(&format!("{}{}", API_HOST, ORDER_PLACEMENT_ENDPOINT))
+        .json(&order(Interactions {
+            pre: vec![],
+            post: vec![InteractionData {
+                target: token.address(),
+                ..hook.clone()
+            }],
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(placement.status(), 400);
+
+    let placement = client
+        .post(&format!("{}{}", API_HOST, ORDER_PLACEMENT_ENDPOINT))
+        .json(&order(Interactions {
+            pre: vec![],
+            post: vec![hook.clone()],
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(placement.status(), 201);
+    let order_uid = placement.json::<OrderUid>().await.unwrap();
+
+    // The hook is stored with the order.
+    let stored = client
+        .get(&format!(
+            "{}{}{}",
+            API_HOST, ORDER_PLACEMENT_ENDPOINT, order_uid
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json::<Order>()
+        .await
+        .unwrap();
+    assert_eq!(stored.interactions.post, vec![hook]);
+
+    wait_for_solvable_orders(&client, 1).await.unwrap();
+
+    // Drive solution
+    let mut driver = setup_naive_solver_uniswapv2_driver(
+        &web3,
+        &contracts,
+        base_tokens,
+        block_stream,
+        solver_account,
+    )
+    .await;
+    driver.single_run().await.unwrap();
+
+    // Check matching and that the hook was executed by the settlement contract
+    let balance = token
+        .balance_of(trader.address())
+        .call()
+        .await
+        .expect("Couldn't fetch token balance");
+    assert_eq!(balance, to_wei(0));
+    let reward_balance = reward
+        .balance_of(trader.address())
+        .call()
+        .await
+        .expect("Couldn't fetch reward balance");
+    assert_eq!(reward_balance, 42.into());
+
+    // Drive orderbook in order to check that all orders were settled
+    maintenance.run_maintenance().await.unwrap();
+    solvable_orders_cache.update(0).await.unwrap();
+
+    let auction = create_orderbook_api().get_auction().await.unwrap();
+    assert!(auction.auction.orders.is_empty());
+}
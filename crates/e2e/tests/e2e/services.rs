@@ -17,7 +17,7 @@ use contracts::{IUniswapLikeRouter, WETH9};
 use database::quotes::QuoteId;
 use ethcontract::{Account, H160, U256};
 use model::{quote::QuoteSigningScheme, DomainSeparator};
-use orderbook::{database::Postgres, orderbook::Orderbook};
+use orderbook::{app_data, database::Postgres, orderbook::Orderbook};
 use reqwest::{Client, StatusCode};
 use shared::{
     account_balances::Web3BalanceFetcher,
@@ -90,6 +90,21 @@ pub struct OrderbookServices {
 
 impl OrderbookServices {
     pub async fn new(web3: &Web3, contracts: &Contracts, enable_limit_orders: bool) -> Self {
+        Self::new_with_post_interaction_targets(
+            web3,
+            contracts,
+            enable_limit_orders,
+            Default::default(),
+        )
+        .await
+    }
+
+    pub async fn new_with_post_interaction_targets(
+        web3: &Web3,
+        contracts: &Contracts,
+        enable_limit_orders: bool,
+        post_interaction_targets: HashSet<H160>,
+    ) -> Self {
         let api_db = Arc::new(Postgres::new("postgresql://").unwrap());
         let autopilot_db = autopilot::database::Postgres::new("postgresql://")
             .await
@@ -211,7 +226,8 @@ impl OrderbookServices {
                 1,
                 Arc::new(code_fetcher),
             )
-            .with_limit_orders(enable_limit_orders),
+            .with_limit_orders(enable_limit_orders)
+            .with_post_interaction_targets(post_interaction_targets),
         );
         let refund_event_handler: Arc<dyn Maintaining> =
             Arc::new(autopilot::event_updater::EventUpdater::new(
@@ -260,6 +276,12 @@ impl OrderbookServices {
             api_db.clone(),
             None,
             native_price_estimator,
+            Default::default(),
+            web3.clone(),
+            Arc::new(app_data::Registry::new(
+                api_db.as_ref().clone(),
+                shared::app_data::Validator::new(8192),
+            )),
         );
 
         Self {
@@ -339,6 +361,7 @@ pub async fn setup_naive_solver_uniswapv2_driver(
         None,
         0,
         code_fetcher,
+        solver::driver::DriverConfig::default(),
     )
 }
 
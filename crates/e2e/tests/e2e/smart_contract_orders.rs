@@ -262,6 +262,7 @@ async fn smart_contract_orders(web3: Web3) {
         None,
         0,
         Arc::new(MockCodeFetching::new()),
+        solver::driver::DriverConfig::default(),
     );
     driver.single_run().await.unwrap();
 
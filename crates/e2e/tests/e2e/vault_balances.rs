@@ -3,14 +3,14 @@ use crate::{
         deploy_token_with_weth_uniswap_pool, to_wei, uniswap_pair_provider, WethPoolConfig,
     },
     services::{
-        create_order_converter, create_orderbook_api, wait_for_solvable_orders, OrderbookServices,
-        API_HOST,
+        create_order_converter, create_orderbook_api, setup_naive_solver_uniswapv2_driver,
+        wait_for_solvable_orders, OrderbookServices, API_HOST,
     },
 };
 use contracts::IUniswapLikeRouter;
 use ethcontract::prelude::{Account, Address, PrivateKey, U256};
 use model::{
-    order::{OrderBuilder, OrderKind, SellTokenSource},
+    order::{BuyTokenDestination, OrderBuilder, OrderKind, SellTokenSource},
     signature::EcdsaSigningScheme,
 };
 use secp256k1::SecretKey;
@@ -41,6 +41,12 @@ async fn local_node_vault_balances() {
     crate::local_node::test(vault_balances).await;
 }
 
+#[tokio::test]
+#[ignore]
+async fn local_node_vault_internal_buy_balances() {
+    crate::local_node::test(vault_internal_buy_balances).await;
+}
+
 async fn vault_balances(web3: Web3) {
     shared::tracing::initialize_for_tests("warn,orderbook=debug,solver=debug,autopilot=debug");
     shared::exit_process_on_panic::set_panic_hook();
@@ -175,6 +181,7 @@ async fn vault_balances(web3: Web3) {
         None,
         0,
         Arc::new(MockCodeFetching::new()),
+        solver::driver::DriverConfig::default(),
     );
     driver.single_run().await.unwrap();
 
@@ -194,3 +201,101 @@ async fn vault_balances(web3: Web3) {
         .expect("Couldn't fetch native token balance");
     assert_eq!(balance, U256::from(8_972_194_924_949_384_291_u128));
 }
+
+async fn vault_internal_buy_balances(web3: Web3) {
+    shared::tracing::initialize_for_tests("warn,orderbook=debug,solver=debug,autopilot=debug");
+    shared::exit_process_on_panic::set_panic_hook();
+    let contracts = crate::deploy::deploy(&web3).await.expect("deploy");
+
+    let accounts: Vec<Address> = web3.eth().accounts().await.expect("get accounts failed");
+    let solver_account = Account::Local(accounts[0], None);
+    let trader = Account::Offline(PrivateKey::from_raw(TRADER).unwrap(), None);
+
+    // Create & mint tokens to trade, pools for fee connections
+    let token = deploy_token_with_weth_uniswap_pool(
+        &web3,
+        &contracts,
+        WethPoolConfig {
+            token_amount: to_wei(100_000),
+            weth_amount: to_wei(100_000),
+        },
+    )
+    .await;
+    token.mint(trader.address(), to_wei(10)).await;
+    let token = token.contract;
+
+    // Approve GPv2 for trading
+    tx!(trader, token.approve(contracts.allowance, to_wei(10)));
+
+    let OrderbookServices {
+        block_stream,
+        solvable_orders_cache: _solvable_orders_cache,
+        base_tokens,
+        ..
+    } = OrderbookServices::new(&web3, &contracts, false).await;
+
+    let http_factory = HttpClientFactory::default();
+    let client = http_factory.create();
+
+    // Place Orders
+    let order = OrderBuilder::default()
+        .with_kind(OrderKind::Sell)
+        .with_sell_token(token.address())
+        .with_sell_amount(to_wei(9))
+        .with_fee_amount(to_wei(1))
+        .with_buy_token(contracts.weth.address())
+        .with_buy_amount(to_wei(8))
+        .with_buy_token_balance(BuyTokenDestination::Internal)
+        .with_valid_to(model::time::now_in_epoch_seconds() + 300)
+        .sign_with(
+            EcdsaSigningScheme::Eip712,
+            &contracts.domain_separator,
+            SecretKeyRef::from(&SecretKey::from_slice(&TRADER).unwrap()),
+        )
+        .build()
+        .into_order_creation();
+    let placement = client
+        .post(&format!("{}{}", API_HOST, ORDER_PLACEMENT_ENDPOINT))
+        .json(&order)
+        .send()
+        .await;
+    assert_eq!(placement.unwrap().status(), 201);
+
+    wait_for_solvable_orders(&client, 1).await.unwrap();
+
+    // Drive solution
+    let mut driver = setup_naive_solver_uniswapv2_driver(
+        &web3,
+        &contracts,
+        base_tokens,
+        block_stream,
+        solver_account,
+    )
+    .await;
+    driver.single_run().await.unwrap();
+
+    // Check matching
+    let balance = token
+        .balance_of(trader.address())
+        .call()
+        .await
+        .expect("Couldn't fetch token balance");
+    assert_eq!(balance, U256::zero());
+
+    // The bought tokens are credited to the trader's Vault internal balance.
+    let balance = contracts
+        .weth
+        .balance_of(trader.address())
+        .call()
+        .await
+        .expect("Couldn't fetch native token balance");
+    assert_eq!(balance, U256::zero());
+
+    let balances = contracts
+        .balancer_vault
+        .get_internal_balance(trader.address(), vec![contracts.weth.address()])
+        .call()
+        .await
+        .expect("Couldn't fetch internal balance");
+    assert_eq!(balances, vec![U256::from(8_972_194_924_949_384_291_u128)]);
+}
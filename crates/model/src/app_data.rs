@@ -0,0 +1,74 @@
+//! Contains the full app data documents that orders commit to with their `AppId`.
+
+use primitive_types::H160;
+use serde::{Deserialize, Serialize};
+
+/// Request and response body of the app data api. The document is passed as a string because its
+/// hash depends on the exact serialization.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppDataObject {
+    pub full_app_data: String,
+}
+
+/// The parts of an app data document that the backend understands. Unknown fields are ignored so
+/// that clients can include additional metadata.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppDataDocument {
+    /// Version of the schema the document follows in `major.minor.patch` format.
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+    #[serde(default)]
+    pub metadata: Metadata,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Metadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub referrer: Option<Referrer>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Referrer {
+    pub address: H160,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn deserializes_known_and_ignores_unknown_fields() {
+        let document = json!({
+            "version": "0.5.0",
+            "appCode": "CoW Swap",
+            "metadata": {
+                "referrer": {
+                    "version": "0.1.0",
+                    "address": "0x0101010101010101010101010101010101010101",
+                },
+                "somethingElse": {},
+            },
+        });
+        assert_eq!(
+            serde_json::from_value::<AppDataDocument>(document).unwrap(),
+            AppDataDocument {
+                version: "0.5.0".to_string(),
+                app_code: Some("CoW Swap".to_string()),
+                environment: None,
+                metadata: Metadata {
+                    referrer: Some(Referrer {
+                        address: H160([1; 20]),
+                    }),
+                },
+            }
+        );
+    }
+}
@@ -31,8 +31,10 @@ pub const BUY_ETH_ADDRESS: H160 = H160([0xee; 20]);
 
 #[derive(Eq, PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Interactions {
+    #[serde(default)]
     pub pre: Vec<InteractionData>,
-    // later we can add here intra/post interactions
+    #[serde(default)]
+    pub post: Vec<InteractionData>,
 }
 
 /// An order that is returned when querying the orderbook.
@@ -83,7 +85,7 @@ impl Order {
             },
             signature: order.signature.clone(),
             data: order.data,
-            interactions: Interactions::default(),
+            interactions: order.interactions.clone(),
         })
     }
 
@@ -333,6 +335,11 @@ pub struct OrderCreation {
     #[serde(flatten)]
     pub signature: Signature,
     pub quote_id: Option<QuoteId>,
+    /// Interactions to execute together with the order. Only post-interactions
+    /// (hooks) can be specified and they are not covered by the order
+    /// signature.
+    #[serde(default)]
+    pub interactions: Interactions,
 }
 
 impl OrderCreation {
@@ -359,6 +366,7 @@ impl Default for OrderCreation {
             from: None,
             signature: Signature::Eip712(EcdsaSignature::non_zero()),
             quote_id: None,
+            interactions: Default::default(),
         }
     }
 }
@@ -370,6 +378,7 @@ impl From<Order> for OrderCreation {
             from: Some(order.metadata.owner),
             signature: order.signature,
             quote_id: None,
+            interactions: order.interactions,
         }
     }
 }
@@ -890,7 +899,8 @@ mod tests {
             "buyTokenBalance": "internal",
             "isLiquidityOrder": false,
             "interactions": {
-                    "pre": []
+                    "pre": [],
+                    "post": []
             }
         });
         let signing_scheme = EcdsaSigningScheme::Eip712;
@@ -991,6 +1001,14 @@ mod tests {
                 from,
                 signature,
                 quote_id: Some(42),
+                interactions: Interactions {
+                    pre: vec![],
+                    post: vec![InteractionData {
+                        target: H160([0x55; 20]),
+                        value: 0.into(),
+                        call_data: vec![1, 2],
+                    }],
+                },
             };
             let order_json = json!({
                 "sellToken": "0x1111111111111111111111111111111111111111",
@@ -1009,6 +1027,14 @@ mod tests {
                 "signingScheme": signing_scheme,
                 "signature": signature_bytes,
                 "from": from,
+                "interactions": {
+                    "pre": [],
+                    "post": [{
+                        "target": "0x5555555555555555555555555555555555555555",
+                        "value": "0x0",
+                        "callData": [1, 2],
+                    }],
+                },
             });
 
             assert_eq!(json!(order), order_json);
@@ -0,0 +1,95 @@
+//! Contains the order lifecycle events that are pushed to subscribers of the orderbook api.
+
+use crate::{auction::AuctionId, order::OrderUid};
+use primitive_types::H160;
+use serde::{Deserialize, Serialize};
+
+#[derive(Eq, PartialEq, Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderEvent {
+    pub uid: OrderUid,
+    /// The user that placed the order. For onchain orders this is the sender of the placement
+    /// transaction.
+    pub owner: H160,
+    #[serde(flatten)]
+    pub kind: OrderEventKind,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum OrderEventKind {
+    Created,
+    #[serde(rename_all = "camelCase")]
+    PresignatureConfirmed {
+        block_number: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    IncludedInAuction {
+        auction_id: AuctionId,
+    },
+    /// The order was (partially) executed.
+    #[serde(rename_all = "camelCase")]
+    Traded {
+        block_number: u64,
+    },
+    /// The order was cancelled either through the API or onchain.
+    Cancelled,
+    Expired,
+}
+
+impl OrderEvent {
+    /// Whether the event matches the filter. `None` values match any event.
+    pub fn matches(&self, owner: Option<H160>, uid: Option<OrderUid>) -> bool {
+        owner.map_or(true, |owner| owner == self.owner) && uid.map_or(true, |uid| uid == self.uid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn serialization() {
+        let event = OrderEvent {
+            uid: OrderUid([0x11; 56]),
+            owner: H160([0x22; 20]),
+            kind: OrderEventKind::IncludedInAuction { auction_id: 42 },
+        };
+        let value = json!({
+            "uid": format!("0x{}", "11".repeat(56)),
+            "owner": format!("0x{}", "22".repeat(20)),
+            "type": "includedInAuction",
+            "auctionId": 42,
+        });
+        assert_eq!(serde_json::to_value(&event).unwrap(), value);
+        assert_eq!(serde_json::from_value::<OrderEvent>(value).unwrap(), event);
+
+        let event = OrderEvent {
+            kind: OrderEventKind::Expired,
+            ..event
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({
+                "uid": format!("0x{}", "11".repeat(56)),
+                "owner": format!("0x{}", "22".repeat(20)),
+                "type": "expired",
+            })
+        );
+    }
+
+    #[test]
+    fn matches_filter() {
+        let event = OrderEvent {
+            uid: OrderUid([1; 56]),
+            owner: H160([2; 20]),
+            kind: OrderEventKind::Created,
+        };
+        assert!(event.matches(None, None));
+        assert!(event.matches(Some(H160([2; 20])), None));
+        assert!(event.matches(None, Some(OrderUid([1; 56]))));
+        assert!(!event.matches(Some(H160([3; 20])), None));
+        assert!(!event.matches(Some(H160([2; 20])), Some(OrderUid([3; 56]))));
+    }
+}
@@ -0,0 +1,97 @@
+//! Contains the history of an order as returned by the orderbook api to debug why an order did or
+//! did not get executed.
+
+use crate::{auction::AuctionId, u256_decimal};
+use chrono::{DateTime, Utc};
+use primitive_types::{H256, U256};
+use serde::{Deserialize, Serialize};
+
+#[derive(Eq, PartialEq, Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TimelineEntry {
+    #[serde(rename_all = "camelCase")]
+    Created { timestamp: DateTime<Utc> },
+    /// The order was part of all auctions in the range. The last auction is `None` if the order
+    /// was still part of the most recent auction.
+    #[serde(rename_all = "camelCase")]
+    IncludedInAuctions {
+        first_auction_id: AuctionId,
+        last_auction_id: Option<AuctionId>,
+    },
+    /// The order was filtered out of all auctions in the range for the given reason.
+    #[serde(rename_all = "camelCase")]
+    FilteredFromAuctions {
+        first_auction_id: AuctionId,
+        last_auction_id: Option<AuctionId>,
+        reason: String,
+    },
+    /// A solver proposed a solution executing the order in the solver competition of an auction.
+    #[serde(rename_all = "camelCase")]
+    Solution {
+        auction_id: AuctionId,
+        solver: String,
+        #[serde(with = "u256_decimal")]
+        executed_amount: U256,
+    },
+    #[serde(rename_all = "camelCase")]
+    Traded {
+        block_number: u64,
+        log_index: u64,
+        tx_hash: Option<H256>,
+        /// The auction that was settled by the transaction if known.
+        auction_id: Option<AuctionId>,
+    },
+    /// The order was cancelled. The timestamp is `None` for onchain cancellations.
+    #[serde(rename_all = "camelCase")]
+    Cancelled { timestamp: Option<DateTime<Utc>> },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn serialization() {
+        let entries = vec![
+            TimelineEntry::IncludedInAuctions {
+                first_auction_id: 1,
+                last_auction_id: None,
+            },
+            TimelineEntry::FilteredFromAuctions {
+                first_auction_id: 2,
+                last_auction_id: Some(3),
+                reason: "insufficient_balance".to_string(),
+            },
+            TimelineEntry::Solution {
+                auction_id: 4,
+                solver: "solver".to_string(),
+                executed_amount: 5.into(),
+            },
+        ];
+        let value = json!([
+            {
+                "type": "includedInAuctions",
+                "firstAuctionId": 1,
+                "lastAuctionId": null,
+            },
+            {
+                "type": "filteredFromAuctions",
+                "firstAuctionId": 2,
+                "lastAuctionId": 3,
+                "reason": "insufficient_balance",
+            },
+            {
+                "type": "solution",
+                "auctionId": 4,
+                "solver": "solver",
+                "executedAmount": "5",
+            },
+        ]);
+        assert_eq!(serde_json::to_value(&entries).unwrap(), value);
+        assert_eq!(
+            serde_json::from_value::<Vec<TimelineEntry>>(value).unwrap(),
+            entries
+        );
+    }
+}
@@ -92,6 +92,18 @@ pub struct Objective {
     pub fees: f64,
     pub cost: f64,
     pub gas: u64,
+    /// The score of the settlement according to the ranking objective the
+    /// driver was configured with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<Score>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Score {
+    pub objective: String,
+    pub total: f64,
+    pub breakdown: BTreeMap<String, f64>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
@@ -189,6 +201,7 @@ mod tests {
                         fees: 5.,
                         cost: 6.,
                         gas: 7,
+                        score: None,
                     },
                     clearing_prices: btreemap! {
                         H160([0x22; 20]) => 8.into(),
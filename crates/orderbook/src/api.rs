@@ -1,11 +1,14 @@
 mod cancel_order;
 mod cancel_orders;
+mod get_app_data;
 mod get_auction;
 mod get_fee_and_quote;
 mod get_fee_info;
 mod get_markets;
 mod get_native_price;
 mod get_order_by_uid;
+mod get_order_events;
+mod get_order_timeline;
 mod get_orders_by_tx;
 mod get_solvable_orders;
 mod get_solvable_orders_v2;
@@ -15,21 +18,24 @@ mod get_user_orders;
 mod post_order;
 mod post_quote;
 mod post_solver_competition;
+mod put_app_data;
 mod replace_order;
 mod version;
 
 use crate::{
-    database::trades::TradeRetrieving, orderbook::Orderbook,
-    solver_competition::SolverCompetitionStoring,
+    app_data, database::trades::TradeRetrieving, order_events::OrderEventBroadcaster,
+    orderbook::Orderbook, solver_competition::SolverCompetitionStoring,
 };
 use shared::{
-    api::{error, finalize_router, internal_error, ApiReply},
+    api::{box_route, error, finalize_router, internal_error, ApiReply},
+    ethrpc::Web3,
     order_quoting::QuoteHandler,
     price_estimation::native::NativePriceEstimating,
 };
 use std::sync::Arc;
 use warp::{Filter, Rejection, Reply};
 
+#[allow(clippy::too_many_arguments)]
 pub fn handle_all_routes(
     database: Arc<dyn TradeRetrieving>,
     orderbook: Arc<Orderbook>,
@@ -37,6 +43,9 @@ pub fn handle_all_routes(
     solver_competition: Arc<dyn SolverCompetitionStoring>,
     solver_competition_auth: Option<String>,
     native_price_estimator: Arc<dyn NativePriceEstimating>,
+    order_events: OrderEventBroadcaster,
+    web3: Web3,
+    app_data: Arc<app_data::Registry>,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
     // Note that we add a string with endpoint's name to all responses.
     // This string will be used later to report metrics.
@@ -45,74 +54,96 @@ pub fn handle_all_routes(
     let routes = vec![
         (
             "v1/create_order",
-            post_order::post_order(orderbook.clone()).boxed(),
+            box_route(post_order::post_order(orderbook.clone())),
         ),
         (
             "v1/fee_info",
-            get_fee_info::get_fee_info(quotes.clone()).boxed(),
+            box_route(get_fee_info::get_fee_info(quotes.clone())),
         ),
         (
             "v1/get_order",
-            get_order_by_uid::get_order_by_uid(orderbook.clone()).boxed(),
+            box_route(get_order_by_uid::get_order_by_uid(orderbook.clone())),
+        ),
+        (
+            "v1/get_order_timeline",
+            box_route(get_order_timeline::get_order_timeline(orderbook.clone())),
         ),
         (
             "v1/get_solvable_orders",
-            get_solvable_orders::get_solvable_orders(orderbook.clone()).boxed(),
+            box_route(get_solvable_orders::get_solvable_orders(orderbook.clone())),
+        ),
+        (
+            "v1/get_trades",
+            box_route(get_trades::get_trades(database, web3)),
         ),
-        ("v1/get_trades", get_trades::get_trades(database).boxed()),
         (
             "v1/cancel_order",
-            cancel_order::cancel_order(orderbook.clone()).boxed(),
+            box_route(cancel_order::cancel_order(orderbook.clone())),
         ),
         (
             "v1/cancel_orders",
-            cancel_orders::filter(orderbook.clone()).boxed(),
+            box_route(cancel_orders::filter(orderbook.clone())),
         ),
         (
             "v1/replace_order",
-            replace_order::filter(orderbook.clone()).boxed(),
+            box_route(replace_order::filter(orderbook.clone())),
         ),
         (
             "v1/get_amount_estimate",
-            get_markets::get_amount_estimate(quotes.clone()).boxed(),
+            box_route(get_markets::get_amount_estimate(quotes.clone())),
         ),
         (
             "v1/get_fee_and_quote_sell",
-            get_fee_and_quote::get_fee_and_quote_sell(quotes.clone()).boxed(),
+            box_route(get_fee_and_quote::get_fee_and_quote_sell(quotes.clone())),
         ),
         (
             "v1/get_fee_and_quote_buy",
-            get_fee_and_quote::get_fee_and_quote_buy(quotes.clone()).boxed(),
+            box_route(get_fee_and_quote::get_fee_and_quote_buy(quotes.clone())),
         ),
         (
             "v1/get_user_orders",
-            get_user_orders::get_user_orders(orderbook.clone()).boxed(),
+            box_route(get_user_orders::get_user_orders(orderbook.clone())),
         ),
         (
             "v1/get_orders_by_tx",
-            get_orders_by_tx::get_orders_by_tx(orderbook.clone()).boxed(),
+            box_route(get_orders_by_tx::get_orders_by_tx(orderbook.clone())),
         ),
-        ("v1/post_quote", post_quote::post_quote(quotes).boxed()),
+        ("v1/post_quote", box_route(post_quote::post_quote(quotes))),
         (
             "v1/auction",
-            get_auction::get_auction(orderbook.clone()).boxed(),
+            box_route(get_auction::get_auction(orderbook.clone())),
         ),
         (
             "v1/solver_competition",
-            get_solver_competition::get(solver_competition.clone()).boxed(),
+            box_route(get_solver_competition::get(solver_competition.clone())),
         ),
         (
             "v1/solver_competition",
-            post_solver_competition::post(solver_competition, solver_competition_auth).boxed(),
+            box_route(post_solver_competition::post(
+                solver_competition,
+                solver_competition_auth,
+            )),
         ),
-        ("v1/version", version::version().boxed()),
+        ("v1/version", box_route(version::version())),
         (
             "v1/get_native_price",
-            get_native_price::get_native_price(native_price_estimator).boxed(),
+            box_route(get_native_price::get_native_price(native_price_estimator)),
+        ),
+        (
+            "v1/put_app_data",
+            box_route(put_app_data::put_app_data(app_data.clone())),
+        ),
+        (
+            "v1/get_app_data",
+            box_route(get_app_data::get_app_data(app_data)),
         ),
         (
             "v2/get_solvable_orders",
-            get_solvable_orders_v2::get_solvable_orders(orderbook).boxed(),
+            box_route(get_solvable_orders_v2::get_solvable_orders(orderbook)),
+        ),
+        (
+            "v1/order_events",
+            box_route(get_order_events::get_order_events(order_events)),
         ),
     ];
 
@@ -0,0 +1,70 @@
+use crate::app_data::Registry;
+use anyhow::Result;
+use model::{app_data::AppDataObject, app_id::AppId};
+use shared::api::IntoWarpReply;
+use std::{convert::Infallible, sync::Arc};
+use warp::{hyper::StatusCode, reply, Filter, Rejection};
+
+fn get_app_data_request() -> impl Filter<Extract = (AppId,), Error = Rejection> + Clone {
+    warp::path!("v1" / "app_data" / AppId).and(warp::get())
+}
+
+fn get_app_data_response(result: Result<Option<String>>) -> super::ApiReply {
+    match result {
+        Ok(Some(full_app_data)) => reply::with_status(
+            reply::json(&AppDataObject { full_app_data }),
+            StatusCode::OK,
+        ),
+        Ok(None) => reply::with_status(
+            super::error("NotFound", "App data was not found"),
+            StatusCode::NOT_FOUND,
+        ),
+        Err(err) => err.into_warp_reply(),
+    }
+}
+
+pub fn get_app_data(
+    registry: Arc<Registry>,
+) -> impl Filter<Extract = (super::ApiReply,), Error = Rejection> + Clone {
+    get_app_data_request().and_then(move |hash: AppId| {
+        let registry = registry.clone();
+        async move {
+            let result = registry.fetch(&hash).await;
+            Result::<_, Infallible>::Ok(get_app_data_response(result))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::api::response_body;
+    use warp::{test::request, Reply};
+
+    #[tokio::test]
+    async fn get_app_data_request_ok() {
+        let hash = AppId([1; 32]);
+        let request = request()
+            .path(&format!("/v1/app_data/{:?}", hash))
+            .method("GET");
+        let filter = get_app_data_request();
+        let result = request.filter(&filter).await.unwrap();
+        assert_eq!(result, hash);
+    }
+
+    #[tokio::test]
+    async fn get_app_data_response_ok() {
+        let full_app_data = r#"{"version":"0.5.0"}"#.to_string();
+        let response = get_app_data_response(Ok(Some(full_app_data.clone()))).into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_body(response).await;
+        let object: AppDataObject = serde_json::from_slice(&body).unwrap();
+        assert_eq!(object.full_app_data, full_app_data);
+    }
+
+    #[tokio::test]
+    async fn get_app_data_response_non_existent() {
+        let response = get_app_data_response(Ok(None)).into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,115 @@
+use crate::order_events::OrderEventBroadcaster;
+use futures::{stream, Stream};
+use model::{order::OrderUid, order_event::OrderEvent};
+use primitive_types::H160;
+use serde::Deserialize;
+use std::convert::Infallible;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use warp::{sse, Filter, Rejection, Reply};
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct Query {
+    owner: Option<H160>,
+    uid: Option<OrderUid>,
+}
+
+fn get_order_events_request() -> impl Filter<Extract = (Query,), Error = Rejection> + Clone {
+    warp::path!("v1" / "order_events")
+        .and(warp::get())
+        .and(warp::query::<Query>())
+}
+
+/// Streams order lifecycle updates as server-sent events. Each event is a JSON serialized
+/// `OrderEvent`. Subscribers only receive events of orders matching the optional `owner` and
+/// `uid` query parameters.
+pub fn get_order_events(
+    broadcaster: OrderEventBroadcaster,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    get_order_events_request().map(move |query: Query| {
+        let events = matching_events(broadcaster.subscribe(), query);
+        sse::reply(sse::keep_alive().stream(events))
+    })
+}
+
+fn matching_events(
+    receiver: Receiver<OrderEvent>,
+    query: Query,
+) -> impl Stream<Item = Result<sse::Event, Infallible>> {
+    stream::unfold(receiver, move |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) if event.matches(query.owner, query.uid) => {
+                    let event = sse::Event::default()
+                        .json_data(&event)
+                        .expect("order events serialize to json");
+                    return Some((Ok(event), receiver));
+                }
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!(skipped, "order event subscriber lagged behind");
+                    continue;
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use model::order_event::OrderEventKind;
+    use tokio::sync::broadcast;
+    use warp::test::request;
+
+    #[tokio::test]
+    async fn get_order_events_request_ok() {
+        let filter = get_order_events_request();
+        let query = request()
+            .path("/v1/order_events")
+            .method("GET")
+            .filter(&filter)
+            .await
+            .unwrap();
+        assert_eq!(query, Query::default());
+
+        let owner = H160([1; 20]);
+        let uid = OrderUid([2; 56]);
+        let query = request()
+            .path(&format!("/v1/order_events?owner=0x{:x}&uid={}", owner, uid))
+            .method("GET")
+            .filter(&filter)
+            .await
+            .unwrap();
+        assert_eq!(
+            query,
+            Query {
+                owner: Some(owner),
+                uid: Some(uid),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn only_streams_matching_events() {
+        let (sender, receiver) = broadcast::channel(10);
+        let event = |owner: u8| OrderEvent {
+            uid: OrderUid([owner; 56]),
+            owner: H160([owner; 20]),
+            kind: OrderEventKind::Created,
+        };
+        sender.send(event(1)).unwrap();
+        sender.send(event(2)).unwrap();
+        sender.send(event(1)).unwrap();
+        drop(sender);
+
+        let query = Query {
+            owner: Some(H160([1; 20])),
+            uid: None,
+        };
+        let events = matching_events(receiver, query).collect::<Vec<_>>().await;
+        assert_eq!(events.len(), 2);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::orderbook::Orderbook;
+use anyhow::Result;
+use model::{order::OrderUid, order_timeline::TimelineEntry};
+use shared::api::IntoWarpReply;
+use std::{convert::Infallible, sync::Arc};
+use warp::{hyper::StatusCode, reply, Filter, Rejection};
+
+fn get_order_timeline_request() -> impl Filter<Extract = (OrderUid,), Error = Rejection> + Clone {
+    warp::path!("v1" / "orders" / OrderUid / "timeline").and(warp::get())
+}
+
+fn get_order_timeline_response(result: Result<Option<Vec<TimelineEntry>>>) -> super::ApiReply {
+    match result {
+        Ok(Some(timeline)) => reply::with_status(reply::json(&timeline), StatusCode::OK),
+        Ok(None) => reply::with_status(
+            super::error("NotFound", "Order was not found"),
+            StatusCode::NOT_FOUND,
+        ),
+        Err(err) => err.into_warp_reply(),
+    }
+}
+
+pub fn get_order_timeline(
+    orderbook: Arc<Orderbook>,
+) -> impl Filter<Extract = (super::ApiReply,), Error = Rejection> + Clone {
+    get_order_timeline_request().and_then(move |uid| {
+        let orderbook = orderbook.clone();
+        async move {
+            let result = orderbook.get_order_timeline(&uid).await;
+            Result::<_, Infallible>::Ok(get_order_timeline_response(result))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::api::response_body;
+    use warp::{test::request, Reply};
+
+    #[tokio::test]
+    async fn get_order_timeline_request_ok() {
+        let uid = OrderUid([1; 56]);
+        let request = request()
+            .path(&format!("/v1/orders/{}/timeline", uid))
+            .method("GET");
+        let filter = get_order_timeline_request();
+        let result = request.filter(&filter).await.unwrap();
+        assert_eq!(result, uid);
+    }
+
+    #[tokio::test]
+    async fn get_order_timeline_response_ok() {
+        let timeline = vec![TimelineEntry::Cancelled { timestamp: None }];
+        let response = get_order_timeline_response(Ok(Some(timeline.clone()))).into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_body(response).await;
+        let response_timeline: Vec<TimelineEntry> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(response_timeline, timeline);
+    }
+
+    #[tokio::test]
+    async fn get_order_timeline_response_non_existent() {
+        let response = get_order_timeline_response(Ok(None)).into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -1,17 +1,44 @@
 use crate::database::trades::{TradeFilter, TradeRetrieving};
 use anyhow::{Context, Result};
-use model::order::OrderUid;
+use model::{order::OrderUid, trade::Trade};
 use primitive_types::H160;
 use serde::Deserialize;
-use shared::api::{convert_json_response, error, ApiReply};
+use shared::{
+    api::{convert_json_response, error, ApiReply},
+    current_block::BlockTimestamps,
+    ethrpc::Web3,
+    event_handling::EventIndex,
+};
 use std::{convert::Infallible, sync::Arc};
 use warp::{hyper::StatusCode, Filter, Rejection};
 
+/// The most trades that can be requested at once.
+const MAX_LIMIT: u64 = 1000;
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct Query {
     pub order_uid: Option<OrderUid>,
     pub owner: Option<H160>,
+    pub sell_token: Option<H160>,
+    pub buy_token: Option<H160>,
+    pub from_block: Option<u64>,
+    pub to_block: Option<u64>,
+    /// Unix timestamp in seconds.
+    pub from_timestamp: Option<u32>,
+    /// Unix timestamp in seconds.
+    pub to_timestamp: Option<u32>,
+    pub after_block_number: Option<u64>,
+    pub after_log_index: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+/// A validated query whose timestamps still need to be converted to blocks.
+#[derive(Debug, Default, Eq, PartialEq)]
+struct TradeQuery {
+    filter: TradeFilter,
+    from_timestamp: Option<u32>,
+    to_timestamp: Option<u32>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -24,36 +51,99 @@ impl Query {
         TradeFilter {
             order_uid: self.order_uid,
             owner: self.owner,
+            sell_token: self.sell_token,
+            buy_token: self.buy_token,
+            from_block: self.from_block,
+            to_block: self.to_block,
+            after: self
+                .after_block_number
+                .zip(self.after_log_index)
+                .map(|(block_number, log_index)| EventIndex::new(block_number, log_index)),
+            limit: self.limit,
         }
     }
 
-    fn validate(&self) -> Result<TradeFilter, TradeFilterError> {
-        match (self.order_uid.as_ref(), self.owner.as_ref()) {
-            (Some(_), None) | (None, Some(_)) => Ok(self.trade_filter()),
-            _ => Err(TradeFilterError::InvalidFilter(
-                "Must specify exactly one of owner and order_uid.".to_owned(),
-            )),
+    fn validate(&self) -> Result<TradeQuery, TradeFilterError> {
+        let invalid = |msg: &str| Err(TradeFilterError::InvalidFilter(msg.to_owned()));
+        match (self.order_uid.as_ref(), self.owner.as_ref(), self.limit) {
+            (Some(_), Some(_), _) => {
+                return invalid("Must specify at most one of owner and orderUid.");
+            }
+            // Without an owner or order the result could contain all trades.
+            (None, None, None) => {
+                return invalid("Must specify one of owner and orderUid or a limit.");
+            }
+            _ => (),
+        }
+        if matches!(self.limit, Some(limit) if limit == 0 || limit > MAX_LIMIT) {
+            return invalid(&format!("limit must be between 1 and {MAX_LIMIT}."));
         }
+        if self.after_block_number.is_some() != self.after_log_index.is_some() {
+            return invalid("Must specify both or none of afterBlockNumber and afterLogIndex.");
+        }
+        Ok(TradeQuery {
+            filter: self.trade_filter(),
+            from_timestamp: self.from_timestamp,
+            to_timestamp: self.to_timestamp,
+        })
     }
 }
 
 fn get_trades_request(
-) -> impl Filter<Extract = (Result<TradeFilter, TradeFilterError>,), Error = Rejection> + Clone {
+) -> impl Filter<Extract = (Result<TradeQuery, TradeFilterError>,), Error = Rejection> + Clone {
     warp::path!("v1" / "trades")
         .and(warp::get())
         .and(warp::query::<Query>())
         .map(|query: Query| query.validate())
 }
 
+/// Converts the timestamp range of the query to blocks and fetches the matching trades.
+async fn trades(
+    database: &dyn TradeRetrieving,
+    block_timestamps: &BlockTimestamps,
+    query: TradeQuery,
+) -> Result<Vec<Trade>> {
+    let mut filter = query.filter;
+    if let Some(timestamp) = query.from_timestamp {
+        match block_timestamps.first_block_at_or_after(timestamp).await? {
+            Some(block) => filter.from_block = Some(filter.from_block.unwrap_or(0).max(block)),
+            // There are no blocks this recent yet.
+            None => return Ok(Vec::new()),
+        }
+    }
+    if let Some(timestamp) = query.to_timestamp {
+        // Otherwise all blocks are at or before the timestamp.
+        if let Some(block) = block_timestamps
+            .first_block_at_or_after(timestamp.saturating_add(1))
+            .await?
+        {
+            match block.checked_sub(1) {
+                Some(block) => {
+                    filter.to_block = Some(filter.to_block.unwrap_or(u64::MAX).min(block))
+                }
+                None => return Ok(Vec::new()),
+            }
+        }
+    }
+    database.trades(&filter).await
+}
+
 pub fn get_trades(
     db: Arc<dyn TradeRetrieving>,
+    web3: Web3,
 ) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    // Shared by all requests so that the block timestamps fetched for one request speed up the
+    // following ones.
+    let block_timestamps = Arc::new(BlockTimestamps::new(web3));
     get_trades_request().and_then(move |request_result| {
         let database = db.clone();
+        let block_timestamps = block_timestamps.clone();
         async move {
             match request_result {
-                Ok(trade_filter) => {
-                    let result = database.trades(&trade_filter).await.context("get_trades");
+                Ok(query) => {
+                    let result = trades(database.as_ref(), &block_timestamps, query)
+                        .await
+                        .context("get_trades");
                     Result::<_, Infallible>::Ok(convert_json_response(result))
                 }
                 Err(TradeFilterError::InvalidFilter(msg)) => {
@@ -85,8 +175,8 @@ mod tests {
             .await
             .unwrap()
             .unwrap();
-        assert_eq!(result.owner, Some(owner));
-        assert_eq!(result.order_uid, None);
+        assert_eq!(result.filter.owner, Some(owner));
+        assert_eq!(result.filter.order_uid, None);
 
         let uid = OrderUid([1u8; 56]);
         let order_uid_path = format!("/v1/trades?orderUid={:}", uid);
@@ -94,8 +184,32 @@ mod tests {
             .await
             .unwrap()
             .unwrap();
-        assert_eq!(result.owner, None);
-        assert_eq!(result.order_uid, Some(uid));
+        assert_eq!(result.filter.owner, None);
+        assert_eq!(result.filter.order_uid, Some(uid));
+
+        let token = H160([2; 20]);
+        let path = format!(
+            "/v1/trades?sellToken=0x{:x}&fromBlock=1&toTimestamp=2&afterBlockNumber=3&afterLogIndex=4&limit=5",
+            token
+        );
+        let result = trade_filter(request().path(path.as_str()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            TradeQuery {
+                filter: TradeFilter {
+                    sell_token: Some(token),
+                    from_block: Some(1),
+                    after: Some(EventIndex::new(3, 4)),
+                    limit: Some(5),
+                    ..Default::default()
+                },
+                to_timestamp: Some(2),
+                ..Default::default()
+            }
+        );
     }
 
     #[tokio::test]
@@ -115,5 +229,14 @@ mod tests {
         let path = "/v1/trades";
         let result = trade_filter(request().path(path)).await.unwrap();
         assert!(result.is_err());
+
+        for path in [
+            "/v1/trades?limit=0",
+            "/v1/trades?limit=1001",
+            "/v1/trades?limit=1&afterBlockNumber=1",
+        ] {
+            let result = trade_filter(request().path(path)).await.unwrap();
+            assert!(result.is_err());
+        }
     }
 }
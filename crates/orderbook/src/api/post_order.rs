@@ -6,7 +6,10 @@ use model::{
 };
 use shared::{
     api::{error, extract_payload, internal_error, ApiReply, IntoWarpReply},
-    order_validation::{OrderValidToError, PartialValidationError, ValidationError},
+    order_validation::{
+        InteractionValidationError, OrderValidToError, PartialValidationError, ValidationError,
+        MAX_POST_INTERACTIONS,
+    },
 };
 use std::{convert::Infallible, sync::Arc};
 use warp::{hyper::StatusCode, reply::with_status, Filter, Rejection};
@@ -18,6 +21,21 @@ pub fn create_order_request() -> impl Filter<Extract = (OrderCreation,), Error =
         .and(extract_payload())
 }
 
+fn interaction_error_description(err: InteractionValidationError) -> String {
+    match err {
+        InteractionValidationError::PreInteractionsNotSupported => {
+            "Pre-interactions are not supported".to_string()
+        }
+        InteractionValidationError::TooManyPostInteractions => {
+            format!("At most {MAX_POST_INTERACTIONS} post-interactions are supported")
+        }
+        InteractionValidationError::UnsupportedTarget(target) => {
+            format!("Post-interaction target {target:?} is not supported")
+        }
+        InteractionValidationError::NonZeroValue => "Post-interactions can't send ETH".to_string(),
+    }
+}
+
 pub struct PartialValidationErrorWrapper(pub PartialValidationError);
 impl IntoWarpReply for PartialValidationErrorWrapper {
     fn into_warp_reply(self) -> ApiReply {
@@ -174,6 +192,10 @@ impl IntoWarpReply for ValidationErrorWrapper {
                 error("TooManyLimitOrders", "Too many limit orders"),
                 StatusCode::BAD_REQUEST,
             ),
+            ValidationError::InvalidInteractions(err) => with_status(
+                error("InvalidInteractions", interaction_error_description(err)),
+                StatusCode::BAD_REQUEST,
+            ),
             ValidationError::Other(err) => with_status(
                 internal_error(err.context("order_validation")),
                 StatusCode::INTERNAL_SERVER_ERROR,
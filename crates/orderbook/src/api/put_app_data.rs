@@ -0,0 +1,97 @@
+use crate::app_data::{RegisterError, Registered, Registry};
+use model::{app_data::AppDataObject, app_id::AppId};
+use shared::api::{extract_payload, IntoWarpReply};
+use std::{convert::Infallible, sync::Arc};
+use warp::{hyper::StatusCode, reply, Filter, Rejection};
+
+fn put_app_data_request() -> impl Filter<Extract = (AppId, AppDataObject), Error = Rejection> + Clone
+{
+    warp::path!("v1" / "app_data" / AppId)
+        .and(warp::put())
+        .and(extract_payload())
+}
+
+impl IntoWarpReply for RegisterError {
+    fn into_warp_reply(self) -> super::ApiReply {
+        match self {
+            Self::Invalid(err) => reply::with_status(
+                super::error("AppDataInvalid", err.to_string()),
+                StatusCode::BAD_REQUEST,
+            ),
+            err @ Self::HashMismatch { .. } => reply::with_status(
+                super::error("AppDataHashMismatch", err.to_string()),
+                StatusCode::BAD_REQUEST,
+            ),
+            Self::Other(err) => err.into_warp_reply(),
+        }
+    }
+}
+
+fn put_app_data_response(result: Result<(AppId, Registered), RegisterError>) -> super::ApiReply {
+    match result {
+        Ok((hash, Registered::New)) => reply::with_status(reply::json(&hash), StatusCode::CREATED),
+        Ok((hash, Registered::AlreadyExisted)) => {
+            reply::with_status(reply::json(&hash), StatusCode::OK)
+        }
+        Err(err) => err.into_warp_reply(),
+    }
+}
+
+pub fn put_app_data(
+    registry: Arc<Registry>,
+) -> impl Filter<Extract = (super::ApiReply,), Error = Rejection> + Clone {
+    put_app_data_request().and_then(move |hash, object: AppDataObject| {
+        let registry = registry.clone();
+        async move {
+            let result = registry
+                .register(hash, object.full_app_data.as_bytes())
+                .await
+                .map(|registered| (hash, registered));
+            Result::<_, Infallible>::Ok(put_app_data_response(result))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::app_data::ValidationError;
+    use warp::{test::request, Reply};
+
+    #[tokio::test]
+    async fn put_app_data_request_ok() {
+        let hash = AppId([1; 32]);
+        let object = AppDataObject {
+            full_app_data: r#"{"version":"0.5.0"}"#.to_string(),
+        };
+        let request = request()
+            .path(&format!("/v1/app_data/{:?}", hash))
+            .method("PUT")
+            .json(&object);
+        let filter = put_app_data_request();
+        let result = request.filter(&filter).await.unwrap();
+        assert_eq!(result, (hash, object));
+    }
+
+    #[tokio::test]
+    async fn put_app_data_response_status() {
+        let hash = AppId([1; 32]);
+        let status = |result| put_app_data_response(result).into_response().status();
+        assert_eq!(status(Ok((hash, Registered::New))), StatusCode::CREATED);
+        assert_eq!(
+            status(Ok((hash, Registered::AlreadyExisted))),
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(Err(RegisterError::Invalid(ValidationError::TooLarge(1)))),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            status(Err(RegisterError::HashMismatch {
+                expected: hash,
+                computed: AppId([2; 32]),
+            })),
+            StatusCode::BAD_REQUEST
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! Stores the full app data documents that orders commit to with their app data hash.
+
+use crate::database::Postgres;
+use anyhow::{Context, Result};
+use model::app_id::AppId;
+use shared::app_data::{ValidationError, Validator};
+
+pub struct Registry {
+    database: Postgres,
+    validator: Validator,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Registered {
+    New,
+    /// The same app data was registered before.
+    AlreadyExisted,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RegisterError {
+    #[error("invalid app data: {0}")]
+    Invalid(#[from] ValidationError),
+    #[error("app data hashes to {computed:?} instead of {expected:?}")]
+    HashMismatch { expected: AppId, computed: AppId },
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl Registry {
+    pub fn new(database: Postgres, validator: Validator) -> Self {
+        Self {
+            database,
+            validator,
+        }
+    }
+
+    /// Validates and stores the full app data for the expected hash.
+    pub async fn register(
+        &self,
+        expected: AppId,
+        full_app_data: &[u8],
+    ) -> Result<Registered, RegisterError> {
+        let validated = self.validator.validate(full_app_data)?;
+        if validated.hash != expected {
+            return Err(RegisterError::HashMismatch {
+                expected,
+                computed: validated.hash,
+            });
+        }
+        let inserted = self
+            .database
+            .insert_app_data(&validated.hash, &validated.full_app_data)
+            .await?;
+        Ok(if inserted {
+            Registered::New
+        } else {
+            Registered::AlreadyExisted
+        })
+    }
+
+    pub async fn fetch(&self, hash: &AppId) -> Result<Option<String>> {
+        let full_app_data = self.database.get_app_data(hash).await?;
+        full_app_data
+            .map(String::from_utf8)
+            .transpose()
+            .context("stored app data is not utf8")
+    }
+}
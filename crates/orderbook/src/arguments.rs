@@ -134,6 +134,11 @@ pub struct Arguments {
     #[clap(long, env, default_value = "false")]
     pub enable_limit_orders: bool,
 
+    /// Enable partially fillable market and limit orders. Partially fillable liquidity orders are
+    /// always accepted.
+    #[clap(long, env, default_value = "false")]
+    pub enable_partially_fillable_orders: bool,
+
     /// Max number of limit orders per user.
     #[clap(long, env, default_value = "10")]
     pub max_limit_orders_per_user: u64,
@@ -141,6 +146,27 @@ pub struct Arguments {
     /// Enable buy ETH orders paying to smart contract wallets.
     #[clap(long, env, default_value = "false")]
     pub enable_eth_smart_contract_payments: bool,
+
+    /// Contracts that orders may call in their post-interactions (hooks). Hooks
+    /// are executed by the settlement contract and are not part of the signed
+    /// order, so only add contracts that can't be used to take funds from the
+    /// settlement contract. Orders with hooks are rejected if this is empty.
+    #[clap(long, env, use_value_delimiter = true)]
+    pub post_interaction_targets: Vec<H160>,
+
+    /// How often in seconds the database is checked for order status changes that get pushed to
+    /// subscribers of the order events API.
+    #[clap(
+        long,
+        env,
+        default_value = "1",
+        value_parser = shared::arguments::duration_from_seconds,
+    )]
+    pub order_events_poll_interval: Duration,
+
+    /// The maximum size in bytes of full app data documents that can be registered.
+    #[clap(long, env, default_value = "8192")]
+    pub app_data_size_limit: usize,
 }
 
 impl std::fmt::Display for Arguments {
@@ -200,11 +226,27 @@ impl std::fmt::Display for Arguments {
             self.fast_price_estimation_results_required
         )?;
         writeln!(f, "enable_limit_orders: {}", self.enable_limit_orders)?;
+        writeln!(
+            f,
+            "enable_partially_fillable_orders: {}",
+            self.enable_partially_fillable_orders
+        )?;
         writeln!(
             f,
             "max_limit_orders_per_user: {}",
             self.max_limit_orders_per_user
         )?;
+        writeln!(
+            f,
+            "post_interaction_targets: {:?}",
+            self.post_interaction_targets
+        )?;
+        writeln!(
+            f,
+            "order_events_poll_interval: {:?}",
+            self.order_events_poll_interval
+        )?;
+        writeln!(f, "app_data_size_limit: {}", self.app_data_size_limit)?;
 
         Ok(())
     }
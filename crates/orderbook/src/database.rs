@@ -1,4 +1,7 @@
+pub mod app_data;
 pub mod auctions;
+pub mod order_events;
+pub mod order_timeline;
 pub mod orders;
 pub mod quotes;
 pub mod solver_competition;
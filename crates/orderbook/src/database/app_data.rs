@@ -0,0 +1,31 @@
+use super::Postgres;
+use anyhow::{Context, Result};
+use database::byte_array::ByteArray;
+use model::app_id::AppId;
+
+impl Postgres {
+    /// Returns whether the app data was newly inserted.
+    pub async fn insert_app_data(&self, hash: &AppId, full_app_data: &[u8]) -> Result<bool> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["insert_app_data"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        database::app_data::insert(&mut ex, &ByteArray(hash.0), full_app_data)
+            .await
+            .context("insert_app_data")
+    }
+
+    pub async fn get_app_data(&self, hash: &AppId) -> Result<Option<Vec<u8>>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["get_app_data"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        database::app_data::fetch(&mut ex, &ByteArray(hash.0))
+            .await
+            .context("get_app_data")
+    }
+}
@@ -0,0 +1,235 @@
+use super::Postgres;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use database::order_events::{OnchainOrderEvent, TimestampedOrderEvent};
+use model::{
+    order::OrderUid,
+    order_event::{OrderEvent, OrderEventKind},
+};
+use primitive_types::H160;
+
+/// Where to continue querying order events from.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct OrderEventCursor {
+    /// Orders created through the API with a later timestamp are returned.
+    pub created: DateTime<Utc>,
+    /// Orders cancelled through the API with a later timestamp are returned.
+    pub cancelled: DateTime<Utc>,
+    /// Orders placed onchain in a later block are returned. They are indexed separately from the
+    /// other onchain events and so have their own cursor.
+    pub placed_onchain: i64,
+    /// Other onchain events in a later block are returned.
+    pub block_number: i64,
+    /// Orders with a later `valid_to` (in unix seconds) are checked for expiry.
+    pub valid_to: i64,
+}
+
+impl OrderEventCursor {
+    /// Moves the cursor past the returned events so that the next query only returns newer
+    /// ones. Expired orders were checked up to `now` (in unix seconds).
+    pub fn advance(&mut self, events: &OrderEvents, now: i64) {
+        for (event, timestamp) in &events.timestamped {
+            let cursor = match event.kind {
+                OrderEventKind::Cancelled => &mut self.cancelled,
+                _ => &mut self.created,
+            };
+            *cursor = (*cursor).max(*timestamp);
+        }
+        for (_, block_number) in &events.placed_onchain {
+            self.placed_onchain = self.placed_onchain.max(*block_number);
+        }
+        for (_, block_number) in &events.onchain {
+            self.block_number = self.block_number.max(*block_number);
+        }
+        self.valid_to = now;
+    }
+}
+
+/// Order events together with the timestamp or block they happened at.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OrderEvents {
+    pub timestamped: Vec<(OrderEvent, DateTime<Utc>)>,
+    pub placed_onchain: Vec<(OrderEvent, i64)>,
+    pub onchain: Vec<(OrderEvent, i64)>,
+    pub expired: Vec<OrderEvent>,
+}
+
+impl Postgres {
+    /// The most recent block for which onchain order events have been indexed.
+    pub async fn last_event_block(&self) -> Result<i64> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["last_event_block"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        Ok(database::events::last_block(&mut ex).await?)
+    }
+
+    /// The most recent block for which onchain placed orders have been indexed.
+    pub async fn last_onchain_order_block(&self) -> Result<i64> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["last_onchain_order_block"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        Ok(database::onchain_broadcasted_orders::last_block(&mut ex).await?)
+    }
+
+    /// Order events that happened after the cursor. Expired orders are only checked up to
+    /// `now` (in unix seconds).
+    pub async fn order_events(&self, cursor: &OrderEventCursor, now: i64) -> Result<OrderEvents> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["order_events"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        let created = database::order_events::created_after(&mut ex, cursor.created)
+            .await
+            .context("created_after")?;
+        let cancelled = database::order_events::cancelled_after(&mut ex, cursor.cancelled)
+            .await
+            .context("cancelled_after")?;
+        let placed_onchain =
+            database::order_events::placed_onchain_after_block(&mut ex, cursor.placed_onchain)
+                .await
+                .context("placed_onchain_after_block")?;
+        let presigned = database::order_events::presigned_after_block(&mut ex, cursor.block_number)
+            .await
+            .context("presigned_after_block")?;
+        let traded = database::order_events::traded_after_block(&mut ex, cursor.block_number)
+            .await
+            .context("traded_after_block")?;
+        let invalidated =
+            database::order_events::invalidated_after_block(&mut ex, cursor.block_number)
+                .await
+                .context("invalidated_after_block")?;
+        let expired = database::order_events::expired_between(&mut ex, cursor.valid_to, now)
+            .await
+            .context("expired_between")?;
+
+        let timestamped = |kind: fn() -> OrderEventKind| {
+            move |row: TimestampedOrderEvent| {
+                let event = order_event(row.order_uid, row.owner, kind());
+                (event, row.timestamp)
+            }
+        };
+        let onchain = |kind: fn(u64) -> OrderEventKind| {
+            move |row: OnchainOrderEvent| {
+                let block_number = row.block_number.try_into().unwrap_or_default();
+                let event = order_event(row.order_uid, row.owner, kind(block_number));
+                (event, row.block_number)
+            }
+        };
+        Ok(OrderEvents {
+            timestamped: created
+                .into_iter()
+                .map(timestamped(|| OrderEventKind::Created))
+                .chain(
+                    cancelled
+                        .into_iter()
+                        .map(timestamped(|| OrderEventKind::Cancelled)),
+                )
+                .collect(),
+            placed_onchain: placed_onchain
+                .into_iter()
+                .map(onchain(|_| OrderEventKind::Created))
+                .collect(),
+            onchain: presigned
+                .into_iter()
+                .map(onchain(|block_number| {
+                    OrderEventKind::PresignatureConfirmed { block_number }
+                }))
+                .chain(
+                    traded
+                        .into_iter()
+                        .map(onchain(|block_number| OrderEventKind::Traded {
+                            block_number,
+                        })),
+                )
+                .chain(
+                    invalidated
+                        .into_iter()
+                        .map(onchain(|_| OrderEventKind::Cancelled)),
+                )
+                .collect(),
+            expired: expired
+                .into_iter()
+                .map(|row| order_event(row.order_uid, row.owner, OrderEventKind::Expired))
+                .collect(),
+        })
+    }
+}
+
+fn order_event(
+    uid: database::OrderUid,
+    owner: database::Address,
+    kind: OrderEventKind,
+) -> OrderEvent {
+    OrderEvent {
+        uid: OrderUid(uid.0),
+        owner: H160(owner.0),
+        kind,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    #[test]
+    fn cursor_advances_past_returned_events() {
+        let time =
+            |seconds| DateTime::<Utc>::from_utc(NaiveDateTime::from_timestamp(seconds, 0), Utc);
+        let event = |kind| OrderEvent {
+            uid: OrderUid([1; 56]),
+            owner: H160([2; 20]),
+            kind,
+        };
+
+        let mut cursor = OrderEventCursor {
+            created: time(10),
+            cancelled: time(10),
+            placed_onchain: 4,
+            block_number: 5,
+            valid_to: 10,
+        };
+        cursor.advance(
+            &OrderEvents {
+                timestamped: vec![
+                    (event(OrderEventKind::Created), time(12)),
+                    (event(OrderEventKind::Created), time(11)),
+                    (event(OrderEventKind::Cancelled), time(13)),
+                ],
+                placed_onchain: vec![(event(OrderEventKind::Created), 6)],
+                onchain: vec![
+                    (event(OrderEventKind::Traded { block_number: 7 }), 7),
+                    (event(OrderEventKind::Cancelled), 3),
+                ],
+                expired: vec![event(OrderEventKind::Expired)],
+            },
+            15,
+        );
+        assert_eq!(
+            cursor,
+            OrderEventCursor {
+                created: time(12),
+                cancelled: time(13),
+                placed_onchain: 6,
+                block_number: 7,
+                valid_to: 15,
+            }
+        );
+
+        // Without events only the expiry check moves forward.
+        cursor.advance(&OrderEvents::default(), 20);
+        assert_eq!(cursor.created, time(12));
+        assert_eq!(cursor.cancelled, time(13));
+        assert_eq!(cursor.placed_onchain, 6);
+        assert_eq!(cursor.block_number, 7);
+        assert_eq!(cursor.valid_to, 20);
+    }
+}
@@ -0,0 +1,254 @@
+use super::Postgres;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use database::{
+    byte_array::ByteArray, order_auction_states::OrderAuctionState, trades::OrderTradeRow,
+};
+use model::{
+    auction::AuctionId, order::OrderUid, order_timeline::TimelineEntry,
+    solver_competition::SolverCompetitionDB,
+};
+use primitive_types::H256;
+
+/// Everything the database knows about the history of an order.
+#[derive(Debug, Default)]
+struct OrderHistory {
+    creation_timestamp: DateTime<Utc>,
+    /// `Some` if the order was cancelled. The inner timestamp is `None` for onchain cancellations.
+    cancellation: Option<Option<DateTime<Utc>>>,
+    auction_states: Vec<OrderAuctionState>,
+    competitions: Vec<(AuctionId, SolverCompetitionDB)>,
+    trades: Vec<OrderTradeRow>,
+}
+
+impl Postgres {
+    /// The chronological history of an order or `None` if the order doesn't exist.
+    pub async fn order_timeline(&self, uid: &OrderUid) -> Result<Option<Vec<TimelineEntry>>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["order_timeline"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        let uid = ByteArray(uid.0);
+        let order = match database::orders::single_full_order(&mut ex, &uid)
+            .await
+            .context("single_full_order")?
+        {
+            Some(order) => order,
+            None => return Ok(None),
+        };
+        let cancellation_timestamp = database::orders::read_order(&mut ex, &uid)
+            .await
+            .context("read_order")?
+            .and_then(|order| order.cancellation_timestamp);
+        let auction_states = database::order_auction_states::load(&mut ex, &uid)
+            .await
+            .context("order_auction_states")?;
+        // Solutions can only execute orders that were part of an auction.
+        let competitions = match auction_states.first() {
+            Some(state) => {
+                database::solver_competition::load_by_order(&mut ex, &uid, state.auction_id)
+                    .await
+                    .context("solver_competition")?
+                    .into_iter()
+                    .map(|row| {
+                        let competition = serde_json::from_value(row.json)
+                            .context("deserialize SolverCompetitionDB")?;
+                        Ok((row.id, competition))
+                    })
+                    .collect::<Result<_>>()?
+            }
+            None => Default::default(),
+        };
+        let trades = database::trades::order_trades(&mut ex, &uid)
+            .await
+            .context("order_trades")?;
+
+        let history = OrderHistory {
+            creation_timestamp: order.creation_timestamp,
+            cancellation: (order.invalidated || cancellation_timestamp.is_some())
+                .then_some(cancellation_timestamp),
+            auction_states,
+            competitions,
+            trades,
+        };
+        Ok(Some(timeline(&OrderUid(uid.0), history)))
+    }
+}
+
+fn timeline(uid: &OrderUid, history: OrderHistory) -> Vec<TimelineEntry> {
+    let mut entries = vec![TimelineEntry::Created {
+        timestamp: history.creation_timestamp,
+    }];
+
+    // Each state lasts until the auction before the next state change.
+    let ends = history
+        .auction_states
+        .iter()
+        .skip(1)
+        .map(|state| Some(state.auction_id - 1))
+        .chain(std::iter::once(None));
+    for (state, last_auction_id) in history.auction_states.iter().zip(ends) {
+        entries.push(match &state.filter_reason {
+            None => TimelineEntry::IncludedInAuctions {
+                first_auction_id: state.auction_id,
+                last_auction_id,
+            },
+            Some(reason) => TimelineEntry::FilteredFromAuctions {
+                first_auction_id: state.auction_id,
+                last_auction_id,
+                reason: reason.clone(),
+            },
+        });
+    }
+
+    for (auction_id, competition) in history.competitions {
+        for solution in competition.solutions {
+            if let Some(order) = solution.orders.iter().find(|order| order.id == *uid) {
+                entries.push(TimelineEntry::Solution {
+                    auction_id,
+                    solver: solution.solver.clone(),
+                    executed_amount: order.executed_amount,
+                });
+            }
+        }
+    }
+
+    entries.extend(
+        history
+            .trades
+            .into_iter()
+            .map(|trade| TimelineEntry::Traded {
+                block_number: trade.block_number.try_into().unwrap_or_default(),
+                log_index: trade.log_index.try_into().unwrap_or_default(),
+                tx_hash: trade.tx_hash.map(|hash| H256(hash.0)),
+                auction_id: trade.auction_id,
+            }),
+    );
+
+    if let Some(timestamp) = history.cancellation {
+        entries.push(TimelineEntry::Cancelled { timestamp });
+    }
+
+    // The sort is stable so entries of the same kind keep their relative order.
+    entries.sort_by_key(sort_key);
+    entries
+}
+
+/// Orders entries by auction where possible. Trades that can't be linked to an auction come after
+/// all auctions and the creation and cancellation always come first and last respectively.
+fn sort_key(entry: &TimelineEntry) -> (u8, i64, u8) {
+    match entry {
+        TimelineEntry::Created { .. } => (0, 0, 0),
+        TimelineEntry::IncludedInAuctions {
+            first_auction_id, ..
+        }
+        | TimelineEntry::FilteredFromAuctions {
+            first_auction_id, ..
+        } => (1, *first_auction_id, 0),
+        TimelineEntry::Solution { auction_id, .. } => (1, *auction_id, 1),
+        TimelineEntry::Traded {
+            auction_id: Some(auction_id),
+            ..
+        } => (1, *auction_id, 2),
+        TimelineEntry::Traded {
+            auction_id: None, ..
+        } => (2, 0, 0),
+        TimelineEntry::Cancelled { .. } => (3, 0, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use model::solver_competition::{Order, SolverSettlement};
+
+    #[test]
+    fn assembles_timeline_in_auction_order() {
+        let uid = OrderUid([1; 56]);
+        let state = |auction_id, filter_reason: Option<&str>| OrderAuctionState {
+            order_uid: ByteArray(uid.0),
+            auction_id,
+            filter_reason: filter_reason.map(str::to_owned),
+        };
+        let solution = |solver: &str, order: OrderUid| SolverSettlement {
+            solver: solver.to_string(),
+            orders: vec![Order {
+                id: order,
+                executed_amount: 5.into(),
+            }],
+            ..Default::default()
+        };
+        let history = OrderHistory {
+            creation_timestamp: Default::default(),
+            cancellation: Some(None),
+            auction_states: vec![
+                state(1, None),
+                state(3, Some("insufficient_balance")),
+                state(5, None),
+            ],
+            competitions: vec![(
+                2,
+                SolverCompetitionDB {
+                    solutions: vec![solution("a", uid), solution("b", OrderUid([2; 56]))],
+                    ..Default::default()
+                },
+            )],
+            trades: vec![
+                OrderTradeRow {
+                    block_number: 11,
+                    log_index: 0,
+                    tx_hash: None,
+                    auction_id: None,
+                },
+                OrderTradeRow {
+                    block_number: 10,
+                    log_index: 1,
+                    tx_hash: Some(ByteArray([3; 32])),
+                    auction_id: Some(2),
+                },
+            ],
+        };
+
+        assert_eq!(
+            timeline(&uid, history),
+            vec![
+                TimelineEntry::Created {
+                    timestamp: Default::default(),
+                },
+                TimelineEntry::IncludedInAuctions {
+                    first_auction_id: 1,
+                    last_auction_id: Some(2),
+                },
+                TimelineEntry::Solution {
+                    auction_id: 2,
+                    solver: "a".to_string(),
+                    executed_amount: 5.into(),
+                },
+                TimelineEntry::Traded {
+                    block_number: 10,
+                    log_index: 1,
+                    tx_hash: Some(H256([3; 32])),
+                    auction_id: Some(2),
+                },
+                TimelineEntry::FilteredFromAuctions {
+                    first_auction_id: 3,
+                    last_auction_id: Some(4),
+                    reason: "insufficient_balance".to_string(),
+                },
+                TimelineEntry::IncludedInAuctions {
+                    first_auction_id: 5,
+                    last_auction_id: None,
+                },
+                TimelineEntry::Traded {
+                    block_number: 11,
+                    log_index: 0,
+                    tx_hash: None,
+                    auction_id: None,
+                },
+                TimelineEntry::Cancelled { timestamp: None },
+            ]
+        );
+    }
+}
@@ -22,10 +22,10 @@ use number_conversions::{big_decimal_to_big_uint, big_decimal_to_u256, u256_to_b
 use primitive_types::H160;
 use shared::{
     db_order_conversions::{
-        buy_token_destination_from, buy_token_destination_into, extract_pre_interactions,
-        onchain_order_placement_error_from, order_class_from, order_class_into, order_kind_from,
-        order_kind_into, sell_token_source_from, sell_token_source_into, signing_scheme_from,
-        signing_scheme_into,
+        buy_token_destination_from, buy_token_destination_into, extract_post_interactions,
+        extract_pre_interactions, onchain_order_placement_error_from, order_class_from,
+        order_class_into, order_kind_from, order_kind_into, sell_token_source_from,
+        sell_token_source_into, signing_scheme_from, signing_scheme_into,
     },
     order_quoting::Quote,
     order_validation::LimitOrderCounting,
@@ -75,7 +75,7 @@ impl From<sqlx::Error> for InsertionError {
 }
 
 async fn insert_order(order: &Order, ex: &mut PgConnection) -> Result<(), InsertionError> {
-    let order = database::orders::Order {
+    let db_order = database::orders::Order {
         uid: ByteArray(order.metadata.uid.0),
         owner: ByteArray(order.metadata.owner.0),
         creation_timestamp: order.metadata.creation_date,
@@ -111,7 +111,7 @@ async fn insert_order(order: &Order, ex: &mut PgConnection) -> Result<(), Insert
             _ => None,
         },
     };
-    database::orders::insert_order(ex, &order)
+    database::orders::insert_order(ex, &db_order)
         .await
         .map_err(|err| {
             if database::orders::is_duplicate_record_error(&err) {
@@ -119,7 +119,23 @@ async fn insert_order(order: &Order, ex: &mut PgConnection) -> Result<(), Insert
             } else {
                 InsertionError::DbError(err)
             }
-        })
+        })?;
+    for (index, interaction) in order.interactions.post.iter().enumerate() {
+        let interaction = database::orders::Interaction {
+            target: ByteArray(interaction.target.0),
+            value: u256_to_big_decimal(&interaction.value),
+            data: interaction.call_data.clone(),
+        };
+        database::orders::insert_or_overwrite_interaction(
+            ex,
+            index as i64,
+            &interaction,
+            database::orders::ExecutionTime::Post,
+            &db_order.uid,
+        )
+        .await?;
+    }
+    Ok(())
 }
 
 async fn insert_quote(
@@ -339,6 +355,7 @@ fn calculate_status(order: &FullOrder) -> OrderStatus {
 fn full_order_into_model_order(order: FullOrder) -> Result<Order> {
     let status = calculate_status(&order);
     let pre_interactions = extract_pre_interactions(&order)?;
+    let post_interactions = extract_post_interactions(&order)?;
     let ethflow_data = if let Some((refund_tx, user_valid_to)) = order.ethflow_data {
         Some(EthflowData {
             user_valid_to,
@@ -405,6 +422,7 @@ fn full_order_into_model_order(order: FullOrder) -> Result<Order> {
         signature,
         interactions: Interactions {
             pre: pre_interactions,
+            post: post_interactions,
         },
     })
 }
@@ -438,6 +456,7 @@ mod tests {
         },
     };
     use model::{
+        interaction::InteractionData,
         order::{Order, OrderData, OrderMetadata, OrderStatus, OrderUid},
         signature::{Signature, SigningScheme},
     };
@@ -474,6 +493,7 @@ mod tests {
             buy_token_balance: DbBuyTokenDestination::Internal,
             presignature_pending: false,
             pre_interactions: Vec::new(),
+            post_interactions: Vec::new(),
             ethflow_data: None,
             onchain_user: None,
             onchain_placement_error: None,
@@ -768,6 +788,43 @@ mod tests {
         assert_eq!(old_order_cancellation, None);
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_insert_order_with_post_interactions() {
+        let db = Postgres::new("postgresql://").unwrap();
+        database::clear_DANGER(&db.pool).await.unwrap();
+        let order = Order {
+            data: OrderData {
+                valid_to: u32::MAX,
+                ..Default::default()
+            },
+            metadata: OrderMetadata {
+                uid: OrderUid([1; 56]),
+                ..Default::default()
+            },
+            interactions: Interactions {
+                pre: vec![],
+                post: vec![
+                    InteractionData {
+                        target: H160([1; 20]),
+                        value: 0.into(),
+                        call_data: vec![1, 2],
+                    },
+                    InteractionData {
+                        target: H160([2; 20]),
+                        value: 0.into(),
+                        call_data: vec![3],
+                    },
+                ],
+            },
+            ..Default::default()
+        };
+        db.insert_order(&order, None).await.unwrap();
+
+        let stored = db.single_order(&order.metadata.uid).await.unwrap().unwrap();
+        assert_eq!(stored.interactions, order.interactions);
+    }
+
     #[tokio::test]
     #[ignore]
     async fn postgres_presignature_status() {
@@ -6,6 +6,7 @@ use futures::{stream::TryStreamExt, StreamExt};
 use model::{order::OrderUid, trade::Trade};
 use number_conversions::big_decimal_to_big_uint;
 use primitive_types::H256;
+use shared::event_handling::EventIndex;
 use std::convert::TryInto;
 
 #[async_trait::async_trait]
@@ -18,6 +19,16 @@ pub trait TradeRetrieving: Send + Sync {
 pub struct TradeFilter {
     pub owner: Option<H160>,
     pub order_uid: Option<OrderUid>,
+    pub sell_token: Option<H160>,
+    pub buy_token: Option<H160>,
+    /// Inclusive.
+    pub from_block: Option<u64>,
+    /// Inclusive.
+    pub to_block: Option<u64>,
+    /// Only trades after this event are returned.
+    pub after: Option<EventIndex>,
+    /// Maximum number of trades to return.
+    pub limit: Option<u64>,
 }
 
 #[async_trait::async_trait]
@@ -28,21 +39,39 @@ impl TradeRetrieving for Postgres {
             .with_label_values(&["trades"])
             .start_timer();
 
+        let owner = filter.owner.map(|owner| ByteArray(owner.0));
+        let order_uid = filter.order_uid.map(|uid| ByteArray(uid.0));
+        let sell_token = filter.sell_token.map(|token| ByteArray(token.0));
+        let buy_token = filter.buy_token.map(|token| ByteArray(token.0));
+        let filter = database::trades::TradesFilter {
+            owner: owner.as_ref(),
+            order_uid: order_uid.as_ref(),
+            sell_token: sell_token.as_ref(),
+            buy_token: buy_token.as_ref(),
+            from_block: filter.from_block.map(i64_saturating),
+            to_block: filter.to_block.map(i64_saturating),
+            after: filter.after.map(|index| database::events::EventIndex {
+                block_number: i64_saturating(index.block_number),
+                log_index: i64_saturating(index.log_index),
+            }),
+            limit: filter.limit.map(i64_saturating),
+        };
+
         let mut ex = self.pool.acquire().await?;
-        database::trades::trades(
-            &mut ex,
-            filter.owner.map(|owner| ByteArray(owner.0)).as_ref(),
-            filter.order_uid.map(|uid| ByteArray(uid.0)).as_ref(),
-        )
-        .map(|result| match result {
-            Ok(row) => trade_from(row),
-            Err(err) => Err(anyhow::Error::from(err)),
-        })
-        .try_collect()
-        .await
+        database::trades::trades(&mut ex, &filter)
+            .map(|result| match result {
+                Ok(row) => trade_from(row),
+                Err(err) => Err(anyhow::Error::from(err)),
+            })
+            .try_collect()
+            .await
     }
 }
 
+fn i64_saturating(value: u64) -> i64 {
+    value.try_into().unwrap_or(i64::MAX)
+}
+
 fn trade_from(row: TradesQueryRow) -> Result<Trade> {
     let block_number = row
         .block_number
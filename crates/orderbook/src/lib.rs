@@ -1,15 +1,22 @@
 pub mod api;
+pub mod app_data;
 pub mod arguments;
 pub mod database;
+pub mod order_events;
 pub mod orderbook;
 pub mod solver_competition;
 
-use crate::{database::trades::TradeRetrieving, orderbook::Orderbook};
+use crate::{
+    app_data, database::trades::TradeRetrieving, order_events::OrderEventBroadcaster,
+    orderbook::Orderbook,
+};
 use anyhow::{anyhow, Context as _, Result};
 use contracts::GPv2Settlement;
 use futures::Future;
 use model::DomainSeparator;
-use shared::{order_quoting::QuoteHandler, price_estimation::native::NativePriceEstimating};
+use shared::{
+    ethrpc::Web3, order_quoting::QuoteHandler, price_estimation::native::NativePriceEstimating,
+};
 use solver_competition::SolverCompetitionStoring;
 use std::{net::SocketAddr, sync::Arc};
 use tokio::{task, task::JoinHandle};
@@ -25,6 +32,9 @@ pub fn serve_api(
     solver_competition: Arc<dyn SolverCompetitionStoring>,
     solver_competition_auth: Option<String>,
     native_price_estimator: Arc<dyn NativePriceEstimating>,
+    order_events: OrderEventBroadcaster,
+    web3: Web3,
+    app_data: Arc<app_data::Registry>,
 ) -> JoinHandle<()> {
     let filter = api::handle_all_routes(
         database,
@@ -33,6 +43,9 @@ pub fn serve_api(
         solver_competition,
         solver_competition_auth,
         native_price_estimator,
+        order_events,
+        web3,
+        app_data,
     )
     .boxed();
     tracing::info!(%address, "serving order book");
@@ -6,7 +6,11 @@ use contracts::{
 use ethcontract::errors::DeployError;
 use model::{order::BUY_ETH_ADDRESS, DomainSeparator};
 use orderbook::{
-    database::Postgres, orderbook::Orderbook, serve_api, verify_deployed_contract_constants,
+    app_data,
+    database::Postgres,
+    order_events::{OrderEventBroadcaster, OrderEventPoller},
+    orderbook::Orderbook,
+    serve_api, verify_deployed_contract_constants,
 };
 use shared::{
     account_balances::Web3BalanceFetcher,
@@ -428,7 +432,9 @@ async fn main() -> ! {
             Arc::new(CachedCodeFetcher::new(Arc::new(web3.clone()))),
         )
         .with_limit_orders(args.enable_limit_orders)
-        .with_eth_smart_contract_payments(args.enable_eth_smart_contract_payments),
+        .with_partially_fillable_orders(args.enable_partially_fillable_orders)
+        .with_eth_smart_contract_payments(args.enable_eth_smart_contract_payments)
+        .with_post_interaction_targets(args.post_interaction_targets.iter().copied().collect()),
     );
     let orderbook = Arc::new(Orderbook::new(
         domain_separator,
@@ -448,6 +454,17 @@ async fn main() -> ! {
     check_database_connection(orderbook.as_ref()).await;
     let quotes =
         Arc::new(QuoteHandler::new(order_validator, optimal_quoter).with_fast_quoter(fast_quoter));
+    let order_events = OrderEventBroadcaster::default();
+    let order_event_poller = OrderEventPoller::new(postgres.clone(), order_events.clone())
+        .await
+        .expect("failed to initialize order event poller");
+    task::spawn(order_event_poller.run_forever(args.order_events_poll_interval));
+
+    let app_data = Arc::new(app_data::Registry::new(
+        postgres.clone(),
+        shared::app_data::Validator::new(args.app_data_size_limit),
+    ));
+
     let (shutdown_sender, shutdown_receiver) = tokio::sync::oneshot::channel();
     let serve_api = serve_api(
         database.clone(),
@@ -460,6 +477,9 @@ async fn main() -> ! {
         database.clone(),
         args.shared.solver_competition_auth,
         native_price_estimator,
+        order_events,
+        web3.clone(),
+        app_data,
     );
 
     let service_maintainer = ServiceMaintenance::new(maintainers);
@@ -0,0 +1,139 @@
+//! Pushes order lifecycle updates to API subscribers.
+//!
+//! The orderbook doesn't observe most changes to orders itself. They are made by the autopilot
+//! when indexing events or building auctions. So we periodically poll the database for changes
+//! after the last seen timestamps and blocks and broadcast them to everyone subscribed.
+
+use crate::database::{order_events::OrderEventCursor, Postgres};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use model::{
+    auction::AuctionId,
+    order::OrderUid,
+    order_event::{OrderEvent, OrderEventKind},
+};
+use std::{collections::HashSet, time::Duration};
+use tokio::sync::broadcast;
+
+/// How many events can be buffered for a subscriber before it misses events.
+const CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Clone)]
+pub struct OrderEventBroadcaster {
+    sender: broadcast::Sender<OrderEvent>,
+}
+
+impl Default for OrderEventBroadcaster {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl OrderEventBroadcaster {
+    pub fn subscribe(&self) -> broadcast::Receiver<OrderEvent> {
+        self.sender.subscribe()
+    }
+
+    fn send(&self, event: OrderEvent) {
+        // Errors only if there are no subscribers in which case dropping the event is fine.
+        let _ = self.sender.send(event);
+    }
+}
+
+pub struct OrderEventPoller {
+    database: Postgres,
+    broadcaster: OrderEventBroadcaster,
+    cursor: OrderEventCursor,
+    auction: Option<(AuctionId, HashSet<OrderUid>)>,
+}
+
+impl OrderEventPoller {
+    /// Creates a poller that only reports events happening from now on.
+    pub async fn new(database: Postgres, broadcaster: OrderEventBroadcaster) -> Result<Self> {
+        let now = Utc::now();
+        let cursor = OrderEventCursor {
+            created: now,
+            cancelled: now,
+            placed_onchain: database.last_onchain_order_block().await?,
+            block_number: database.last_event_block().await?,
+            valid_to: now.timestamp(),
+        };
+        let auction = database
+            .most_recent_auction()
+            .await?
+            .map(|auction| (auction.id, auction_orders(&auction.auction)));
+        Ok(Self {
+            database,
+            broadcaster,
+            cursor,
+            auction,
+        })
+    }
+
+    pub async fn run_forever(mut self, poll_interval: Duration) -> ! {
+        loop {
+            if let Err(err) = self.update().await {
+                tracing::warn!(?err, "failed to poll order events");
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    async fn update(&mut self) -> Result<()> {
+        let now = Utc::now().timestamp();
+        let events = self
+            .database
+            .order_events(&self.cursor, now)
+            .await
+            .context("order_events")?;
+        self.cursor.advance(&events, now);
+
+        for (event, _) in events.timestamped {
+            self.broadcaster.send(event);
+        }
+        for (event, _) in events.placed_onchain.into_iter().chain(events.onchain) {
+            self.broadcaster.send(event);
+        }
+        for event in events.expired {
+            self.broadcaster.send(event);
+        }
+
+        self.update_auction().await
+    }
+
+    /// Reports orders that were not part of the previous auction.
+    async fn update_auction(&mut self) -> Result<()> {
+        let auction = match self.database.most_recent_auction().await? {
+            Some(auction) => auction,
+            None => return Ok(()),
+        };
+        if matches!(&self.auction, Some((id, _)) if *id == auction.id) {
+            return Ok(());
+        }
+
+        let previous = self.auction.take().map(|(_, orders)| orders);
+        for order in &auction.auction.orders {
+            if matches!(&previous, Some(orders) if orders.contains(&order.metadata.uid)) {
+                continue;
+            }
+            self.broadcaster.send(OrderEvent {
+                uid: order.metadata.uid,
+                owner: order.metadata.onchain_user.unwrap_or(order.metadata.owner),
+                kind: OrderEventKind::IncludedInAuction {
+                    auction_id: auction.id,
+                },
+            });
+        }
+        self.auction = Some((auction.id, auction_orders(&auction.auction)));
+        Ok(())
+    }
+}
+
+fn auction_orders(auction: &model::auction::Auction) -> HashSet<OrderUid> {
+    auction
+        .orders
+        .iter()
+        .map(|order| order.metadata.uid)
+        .collect()
+}
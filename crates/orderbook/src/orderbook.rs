@@ -8,6 +8,7 @@ use model::{
         Order, OrderCancellation, OrderClass, OrderCreation, OrderStatus, OrderUid,
         SignedOrderCancellations,
     },
+    order_timeline::TimelineEntry,
     quote::QuoteId,
     DomainSeparator,
 };
@@ -294,6 +295,10 @@ impl Orderbook {
         self.database.single_order(uid).await
     }
 
+    pub async fn get_order_timeline(&self, uid: &OrderUid) -> Result<Option<Vec<TimelineEntry>>> {
+        self.database.order_timeline(uid).await
+    }
+
     pub async fn get_orders_for_tx(&self, hash: &H256) -> Result<Vec<Order>> {
         self.database.orders_for_tx(hash).await
     }
@@ -13,7 +13,7 @@ use std::{
 use warp::{
     filters::BoxedFilter,
     hyper::StatusCode,
-    reply::{json, with_status, Json, WithStatus},
+    reply::{json, with_status, Json, Response, WithStatus},
     Filter, Rejection, Reply,
 };
 
@@ -187,13 +187,23 @@ pub fn extract_payload_with_max_size<T: DeserializeOwned + Send>(
     warp::body::content_length_limit(max_size).and(warp::body::json())
 }
 
+/// Boxes a route so that routes with different replies, like JSON responses and event streams,
+/// can be passed to [`finalize_router`] together.
+pub fn box_route<F, R>(route: F) -> BoxedFilter<(Response,)>
+where
+    F: Filter<Extract = (R,), Error = Rejection> + Clone + Send + Sync + 'static,
+    R: Reply,
+{
+    route.map(Reply::into_response).boxed()
+}
+
 /// Sets up basic metrics, cors and proper log tracing for all routes.
 ///
 /// # Panics
 ///
 /// This method panics if `routes` is empty.
 pub fn finalize_router(
-    routes: Vec<(&'static str, BoxedFilter<(ApiReply,)>)>,
+    routes: Vec<(&'static str, BoxedFilter<(Response,)>)>,
     log_prefix: &'static str,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
     let metrics = ApiMetrics::instance(global_metrics::get_metric_storage_registry()).unwrap();
@@ -205,7 +215,7 @@ pub fn finalize_router(
     let router = routes
         .into_iter()
         .fold(
-            Option::<BoxedFilter<(&'static str, ApiReply)>>::None,
+            Option::<BoxedFilter<(&'static str, Response)>>::None,
             |router, (method, route)| {
                 let route = route.map(move |result| (method, result)).untuple_one();
                 let next = match router {
@@ -221,8 +231,7 @@ pub fn finalize_router(
         warp::any()
             .map(Instant::now)
             .and(router)
-            .map(|timer, method, reply: ApiReply| {
-                let response = reply.into_response();
+            .map(|timer, method, response: Response| {
                 metrics.on_request_completed(method, response.status(), timer);
                 response
             });
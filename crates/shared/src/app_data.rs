@@ -0,0 +1,100 @@
+//! Validation of full app data documents before they get stored.
+
+use model::{app_data::AppDataDocument, app_id::AppId};
+use web3::signing::keccak256;
+
+/// The newest version of the app data schema that we understand. Documents with the same major
+/// version that are at most this new are accepted.
+pub const SCHEMA_VERSION: (u64, u64, u64) = (0, 5, 0);
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValidatedAppData {
+    pub hash: AppId,
+    pub document: AppDataDocument,
+    /// The exact bytes that were hashed.
+    pub full_app_data: Vec<u8>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ValidationError {
+    #[error("app data is larger than {0} bytes")]
+    TooLarge(usize),
+    #[error("app data is not a valid document: {0}")]
+    Invalid(#[from] serde_json::Error),
+    #[error("unsupported app data version {0:?}")]
+    UnsupportedVersion(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct Validator {
+    size_limit: usize,
+}
+
+impl Validator {
+    pub fn new(size_limit: usize) -> Self {
+        Self { size_limit }
+    }
+
+    pub fn validate(&self, full_app_data: &[u8]) -> Result<ValidatedAppData, ValidationError> {
+        if full_app_data.len() > self.size_limit {
+            return Err(ValidationError::TooLarge(self.size_limit));
+        }
+        let document: AppDataDocument = serde_json::from_slice(full_app_data)?;
+        if !is_supported_version(&document.version) {
+            return Err(ValidationError::UnsupportedVersion(document.version));
+        }
+        Ok(ValidatedAppData {
+            hash: AppId(keccak256(full_app_data)),
+            document,
+            full_app_data: full_app_data.to_vec(),
+        })
+    }
+}
+
+fn is_supported_version(version: &str) -> bool {
+    let mut parts = version.split('.').map(str::parse::<u64>);
+    let version = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) => (major, minor, patch),
+        _ => return false,
+    };
+    version.0 == SCHEMA_VERSION.0 && version <= SCHEMA_VERSION
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_documents() {
+        let validator = Validator::new(100);
+
+        let document = br#"{"version":"0.4.0","appCode":"test"}"#;
+        let validated = validator.validate(document).unwrap();
+        assert_eq!(validated.hash, AppId(keccak256(document)));
+        assert_eq!(validated.document.app_code.as_deref(), Some("test"));
+
+        assert!(matches!(
+            validator.validate(&[b' '; 101]),
+            Err(ValidationError::TooLarge(100))
+        ));
+        assert!(matches!(
+            validator.validate(br#"{"appCode":"test"}"#),
+            Err(ValidationError::Invalid(_))
+        ));
+        assert!(matches!(
+            validator.validate(br#"{"version":"0.6.0"}"#),
+            Err(ValidationError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    fn supported_versions() {
+        assert!(is_supported_version("0.1.0"));
+        assert!(is_supported_version("0.5.0"));
+        assert!(!is_supported_version("0.5.1"));
+        assert!(!is_supported_version("1.0.0"));
+        assert!(!is_supported_version("0.1"));
+        assert!(!is_supported_version("0.1.0.0"));
+        assert!(!is_supported_version("a.b.c"));
+    }
+}
@@ -5,7 +5,12 @@ pub use self::arguments::Arguments;
 use crate::ethrpc::Web3;
 use anyhow::{anyhow, ensure, Context as _, Result};
 use primitive_types::H256;
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 use tokio::sync::watch;
 use tokio_stream::wrappers::WatchStream;
 use web3::{
@@ -194,6 +199,117 @@ pub async fn timestamp_of_current_block_in_seconds(web3: &Web3) -> Result<u32> {
     timestamp_of_block_in_seconds(web3, BlockNumber::Latest).await
 }
 
+/// How many of the most recent blocks can still be reorged. Their timestamps aren't cached.
+const REORG_SAFE_DEPTH: u64 = 64;
+
+/// The most block timestamps kept by [`BlockTimestamps`]. The cache is cleared when it grows
+/// larger.
+const MAX_CACHED_BLOCK_TIMESTAMPS: usize = 100_000;
+
+/// Maps timestamps to blocks by binary searching over the block timestamps.
+///
+/// Block timestamps strictly increase, so every block visited by a search bounds the later
+/// searches for other timestamps. The timestamps of the visited blocks are kept, which means
+/// that later searches start from a narrow range of blocks and only need a few node requests.
+pub struct BlockTimestamps {
+    web3: Web3,
+    /// Block numbers by timestamp.
+    known: Mutex<BTreeMap<u32, u64>>,
+}
+
+impl BlockTimestamps {
+    pub fn new(web3: Web3) -> Self {
+        Self {
+            web3,
+            known: Default::default(),
+        }
+    }
+
+    /// Finds the first block with a timestamp at or after the specified unix timestamp. Returns
+    /// `None` if the current block is older than that.
+    pub async fn first_block_at_or_after(&self, timestamp: u32) -> Result<Option<u64>> {
+        let current = self
+            .web3
+            .eth()
+            .block(BlockNumber::Latest.into())
+            .await
+            .context("failed to get latest block")?
+            .context("no latest block")?;
+        if current.timestamp.as_u32() < timestamp {
+            return Ok(None);
+        }
+        let current = current.number.context("block missing number")?.as_u64();
+        let block = self
+            .search(current, timestamp, |number| {
+                timestamp_of_block_in_seconds(&self.web3, U64::from(number).into())
+            })
+            .await?;
+        Ok(Some(block))
+    }
+
+    /// Returns the first block in `0..=last` with a timestamp at or after the specified one. The
+    /// timestamp of `last` must be at or after it.
+    async fn search<F, Fut>(&self, last: u64, timestamp: u32, mut fetch: F) -> Result<u64>
+    where
+        F: FnMut(u64) -> Fut,
+        Fut: Future<Output = Result<u32>>,
+    {
+        let cacheable = last.saturating_sub(REORG_SAFE_DEPTH);
+        let (low, high) = {
+            let known = self.known.lock().unwrap();
+            let low = known
+                .range(..timestamp)
+                .next_back()
+                .map(|(_, block)| block + 1)
+                .unwrap_or(0);
+            let high = known
+                .range(timestamp..)
+                .next()
+                .map(|(_, block)| (*block).min(last))
+                .unwrap_or(last);
+            if low <= high {
+                (low, high)
+            } else {
+                (0, last)
+            }
+        };
+        first_block_where(low, high, |number| {
+            let block_timestamp = fetch(number);
+            async move {
+                let block_timestamp = block_timestamp.await?;
+                if number <= cacheable {
+                    let mut known = self.known.lock().unwrap();
+                    if known.len() >= MAX_CACHED_BLOCK_TIMESTAMPS {
+                        known.clear();
+                    }
+                    known.insert(block_timestamp, number);
+                }
+                Ok(block_timestamp >= timestamp)
+            }
+        })
+        .await
+    }
+}
+
+/// Returns the first block in `low..=high` for which the predicate holds. The predicate must
+/// hold for `high` and for all blocks after the first one for which it holds.
+async fn first_block_where<F, Fut>(low: u64, high: u64, mut predicate: F) -> Result<u64>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<bool>>,
+{
+    let (mut low, mut high) = (low, high);
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if predicate(middle).await? {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+    Ok(high)
+}
+
 pub async fn block_number_to_block_number_hash(
     web3: &Web3,
     block_number: BlockNumber,
@@ -243,6 +359,52 @@ mod tests {
     use crate::ethrpc::{create_env_test_transport, create_test_transport};
     use futures::StreamExt;
     use num::Saturating;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn finds_first_block_where_predicate_holds() {
+        let first = |low, high, threshold| {
+            first_block_where(low, high, move |number| async move {
+                Ok(number * 12 >= threshold)
+            })
+        };
+        assert_eq!(first(0, 100, 600).await.unwrap(), 50);
+        assert_eq!(first(0, 100, 601).await.unwrap(), 51);
+        assert_eq!(first(0, 100, 0).await.unwrap(), 0);
+        assert_eq!(first(0, 100, 1200).await.unwrap(), 100);
+        assert_eq!(first(0, 0, 0).await.unwrap(), 0);
+        assert_eq!(first(40, 60, 600).await.unwrap(), 50);
+        assert_eq!(first(50, 50, 600).await.unwrap(), 50);
+    }
+
+    #[tokio::test]
+    async fn block_timestamps_reuse_visited_blocks() {
+        let timestamps =
+            &BlockTimestamps::new(Web3::new(create_test_transport("http://localhost")));
+        let fetches = &AtomicUsize::new(0);
+        let search = |last, timestamp| {
+            timestamps.search(last, timestamp, move |number| {
+                fetches.fetch_add(1, Ordering::SeqCst);
+                async move { Ok((number * 12) as u32) }
+            })
+        };
+        let fetched = || fetches.swap(0, Ordering::SeqCst);
+
+        assert_eq!(search(1_000_000, 6_000_000).await.unwrap(), 500_000);
+        assert_eq!(fetched(), 19);
+
+        // The same and nearby timestamps are found from the cached block timestamps.
+        assert_eq!(search(1_000_000, 6_000_000).await.unwrap(), 500_000);
+        assert_eq!(fetched(), 0);
+        assert_eq!(search(1_000_000, 6_000_120).await.unwrap(), 500_010);
+        assert_eq!(fetched(), 3);
+
+        // The timestamps of recent blocks aren't cached because they could be reorged.
+        assert_eq!(search(1_000_000, 11_999_988).await.unwrap(), 999_999);
+        fetched();
+        assert_eq!(search(1_000_000, 11_999_988).await.unwrap(), 999_999);
+        assert!(fetched() > 0);
+    }
 
     #[tokio::test]
     #[ignore]
@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use bigdecimal::BigDecimal;
 use database::{
     onchain_broadcasted_orders::OnchainOrderPlacementError as DbOnchainOrderPlacementError,
     orders::{
@@ -6,6 +7,7 @@ use database::{
         OrderClass as DbOrderClass, OrderKind as DbOrderKind, SellTokenSource as DbSellTokenSource,
         SigningScheme as DbSigningScheme,
     },
+    Address,
 };
 use ethcontract::{H160, H256};
 use model::{
@@ -23,6 +25,7 @@ use number_conversions::{big_decimal_to_big_uint, big_decimal_to_u256};
 pub fn full_order_into_model_order(order: database::orders::FullOrder) -> Result<Order> {
     let status = OrderStatus::Open;
     let pre_interactions = extract_pre_interactions(&order)?;
+    let post_interactions = extract_post_interactions(&order)?;
     let ethflow_data = if let Some((refund_tx, user_valid_to)) = order.ethflow_data {
         Some(EthflowData {
             user_valid_to,
@@ -89,21 +92,32 @@ pub fn full_order_into_model_order(order: database::orders::FullOrder) -> Result
         signature,
         interactions: Interactions {
             pre: pre_interactions,
+            post: post_interactions,
         },
     })
 }
 
 pub fn extract_pre_interactions(order: &FullOrderDb) -> Result<Vec<InteractionData>> {
-    let mut pre_interactions = Vec::new();
-    for i in 0..order.pre_interactions.len() {
-        pre_interactions.push(InteractionData {
-            target: H160(order.pre_interactions[i].0 .0),
-            value: big_decimal_to_u256(&order.pre_interactions[i].1)
-                .context("pre interaction value is not U256")?,
-            call_data: order.pre_interactions[i].2.to_vec(),
-        });
-    }
-    Ok(pre_interactions)
+    extract_interactions(&order.pre_interactions).context("pre interaction")
+}
+
+pub fn extract_post_interactions(order: &FullOrderDb) -> Result<Vec<InteractionData>> {
+    extract_interactions(&order.post_interactions).context("post interaction")
+}
+
+fn extract_interactions(
+    interactions: &[(Address, BigDecimal, Vec<u8>)],
+) -> Result<Vec<InteractionData>> {
+    interactions
+        .iter()
+        .map(|(target, value, data)| {
+            Ok(InteractionData {
+                target: H160(target.0),
+                value: big_decimal_to_u256(value).context("interaction value is not U256")?,
+                call_data: data.clone(),
+            })
+        })
+        .collect()
 }
 
 pub fn order_kind_into(kind: OrderKind) -> DbOrderKind {
@@ -482,7 +482,7 @@ where
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct EventIndex {
     pub block_number: u64,
     pub log_index: u64,
@@ -334,6 +334,10 @@ pub enum SolverRejectionReason {
 
     /// The solution didn't pass simulation. Includes all data needed to re-create simulation locally
     SimulationFailure(TransactionWithError),
+
+    /// The solution didn't pass simulation because of the listed orders. A
+    /// settlement without them passed simulation and was ranked instead.
+    FailingOrders(Vec<OrderUid>),
 }
 
 #[derive(Debug, Serialize)]
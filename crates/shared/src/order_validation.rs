@@ -16,8 +16,8 @@ use database::{onchain_broadcasted_orders::OnchainOrderPlacementError, quotes::Q
 use ethcontract::{H160, U256};
 use model::{
     order::{
-        BuyTokenDestination, LimitOrderClass, Order, OrderClass, OrderCreation, OrderData,
-        OrderKind, SellTokenSource, BUY_ETH_ADDRESS,
+        BuyTokenDestination, Interactions, LimitOrderClass, Order, OrderClass, OrderCreation,
+        OrderData, OrderKind, SellTokenSource, BUY_ETH_ADDRESS,
     },
     quote::{OrderQuoteSide, QuoteSigningScheme, SellAmount},
     signature::{hashed_eip712_message, Signature, SigningScheme, VerificationError},
@@ -39,6 +39,7 @@ pub trait OrderValidating: Send + Sync {
     ///     - the sell token is not the native asset,
     ///     - the sender is not a banned user,
     ///     - the order validity is appropriate,
+    ///     - partially fillable orders are enabled for the order's class,
     ///     - buy_token is not the same as sell_token,
     ///     - buy and sell token destination and source are supported.
     ///     - buy & sell tokens passed "bad token" detection,
@@ -49,7 +50,8 @@ pub trait OrderValidating: Send + Sync {
     ///     - buy & sell amounts are non-zero,
     ///     - order's signature recovers correctly
     ///     - fee is sufficient,
-    ///     - user has sufficient (transferable) funds to execute the order.
+    ///     - user has sufficient (transferable) funds to execute the order,
+    ///     - the order only specifies allowed post-interactions.
     ///
     /// Furthermore, full order validation also calls partial_validate to ensure that
     /// other aspects of the order are not malformed.
@@ -106,9 +108,31 @@ pub enum ValidationError {
     ZeroAmount,
     IncompatibleSigningScheme,
     TooManyLimitOrders,
+    InvalidInteractions(InteractionValidationError),
     Other(anyhow::Error),
 }
 
+#[derive(Debug)]
+pub enum InteractionValidationError {
+    /// Pre-interactions can't be specified when placing an order.
+    PreInteractionsNotSupported,
+    /// The order specifies more than [`MAX_POST_INTERACTIONS`] post-interactions.
+    TooManyPostInteractions,
+    /// The post-interaction target is not allowed.
+    UnsupportedTarget(H160),
+    /// Post-interactions can't send ETH from the settlement contract.
+    NonZeroValue,
+}
+
+impl From<InteractionValidationError> for ValidationError {
+    fn from(err: InteractionValidationError) -> Self {
+        Self::InvalidInteractions(err)
+    }
+}
+
+/// The maximum number of post-interactions a single order can specify.
+pub const MAX_POST_INTERACTIONS: usize = 4;
+
 pub fn onchain_order_placement_error_from(error: ValidationError) -> OnchainOrderPlacementError {
     match error {
         ValidationError::QuoteNotFound => OnchainOrderPlacementError::QuoteNotFound,
@@ -193,8 +217,13 @@ pub struct OrderValidator {
     balance_fetcher: Arc<dyn BalanceFetching>,
     signature_validator: Arc<dyn SignatureValidating>,
     enable_limit_orders: bool,
+    enable_partially_fillable_orders: bool,
     limit_order_counter: Arc<dyn LimitOrderCounting>,
     max_limit_orders_per_user: u64,
+    /// Contracts that orders are allowed to call in their post-interactions.
+    /// Post-interactions are executed by the settlement contract, so this
+    /// must only contain contracts that can't be used to take the buffers.
+    post_interaction_targets: HashSet<H160>,
     pub code_fetcher: Arc<dyn CodeFetching>,
     pub enable_eth_smart_contract_payments: bool,
 }
@@ -275,8 +304,10 @@ impl OrderValidator {
             balance_fetcher,
             signature_validator,
             enable_limit_orders: false,
+            enable_partially_fillable_orders: false,
             limit_order_counter,
             max_limit_orders_per_user,
+            post_interaction_targets: Default::default(),
             code_fetcher,
             enable_eth_smart_contract_payments: false,
         }
@@ -287,11 +318,44 @@ impl OrderValidator {
         self
     }
 
+    pub fn with_partially_fillable_orders(mut self, enable: bool) -> Self {
+        self.enable_partially_fillable_orders = enable;
+        self
+    }
+
     pub fn with_eth_smart_contract_payments(mut self, enable: bool) -> Self {
         self.enable_eth_smart_contract_payments = enable;
         self
     }
 
+    pub fn with_post_interaction_targets(mut self, targets: HashSet<H160>) -> Self {
+        self.post_interaction_targets = targets;
+        self
+    }
+
+    fn validate_interactions(
+        &self,
+        interactions: &Interactions,
+    ) -> Result<(), InteractionValidationError> {
+        if !interactions.pre.is_empty() {
+            return Err(InteractionValidationError::PreInteractionsNotSupported);
+        }
+        if interactions.post.len() > MAX_POST_INTERACTIONS {
+            return Err(InteractionValidationError::TooManyPostInteractions);
+        }
+        for interaction in &interactions.post {
+            if !self.post_interaction_targets.contains(&interaction.target) {
+                return Err(InteractionValidationError::UnsupportedTarget(
+                    interaction.target,
+                ));
+            }
+            if !interaction.value.is_zero() {
+                return Err(InteractionValidationError::NonZeroValue);
+            }
+        }
+        Ok(())
+    }
+
     async fn check_max_limit_orders(
         &self,
         owner: H160,
@@ -318,11 +382,17 @@ impl OrderValidating for OrderValidator {
             return Err(PartialValidationError::Forbidden);
         }
 
-        if order.partially_fillable && order.class != OrderClass::Liquidity {
+        if order.partially_fillable
+            && order.class != OrderClass::Liquidity
+            && !self.enable_partially_fillable_orders
+        {
             return Err(PartialValidationError::UnsupportedOrderType);
         }
 
-        if order.buy_token_balance != BuyTokenDestination::Erc20 {
+        // The settlement contract can't deposit native ETH into Vault internal balances.
+        if order.buy_token_balance == BuyTokenDestination::Internal
+            && order.buy_token == BUY_ETH_ADDRESS
+        {
             return Err(PartialValidationError::UnsupportedBuyTokenDestination(
                 order.buy_token_balance,
             ));
@@ -413,6 +483,8 @@ impl OrderValidating for OrderValidator {
             return Err(ValidationError::ZeroAmount);
         }
 
+        self.validate_interactions(&order.interactions)?;
+
         let class = if self.liquidity_order_owners.contains(&owner) {
             OrderClass::Liquidity
         } else if self.enable_limit_orders && order.data.fee_amount.is_zero() {
@@ -475,7 +547,7 @@ impl OrderValidating for OrderValidator {
             .unwrap_or_default();
 
         let min_balance =
-            minimum_balance(&order.data).ok_or(ValidationError::SellAmountOverflow)?;
+            minimum_balance(&order.data, &class).ok_or(ValidationError::SellAmountOverflow)?;
 
         // Fast path to check if transfer is possible with a single node query.
         // If not, run extra queries for additional information.
@@ -655,12 +727,15 @@ fn has_same_buy_and_sell_token(order: &PreOrderData, native_token: &WETH9) -> bo
 /// Min balance user must have in sell token for order to be accepted.
 ///
 /// None when addition overflows.
-fn minimum_balance(order: &OrderData) -> Option<U256> {
-    // TODO: Note that we are pessimistic here for partially fillable orders,
-    // since they don't need the full balance in order for the order to be
-    // tradable. However, since they are currently only used for PMMs for
-    // matching against user orders, it makes sense for the full sell token
-    // amount balance to be required.
+fn minimum_balance(order: &OrderData, class: &OrderClass) -> Option<U256> {
+    // Partially fillable user orders don't need the full balance in order for
+    // the order to be tradable, they can be executed with whatever the owner
+    // has available at the time of settlement. We still require some balance
+    // so that orders for tokens the user doesn't have at all are rejected.
+    // Liquidity orders keep requiring their full balance.
+    if order.partially_fillable && matches!(class, OrderClass::Market | OrderClass::Limit(_)) {
+        return Some(1.into());
+    }
     order.sell_amount.checked_add(order.fee_amount)
 }
 
@@ -786,8 +861,8 @@ mod tests {
     use maplit::hashset;
     use mockall::predicate::{always, eq};
     use model::{
-        app_id::AppId, order::OrderBuilder, quote::default_verification_gas_limit,
-        signature::EcdsaSigningScheme,
+        app_id::AppId, interaction::InteractionData, order::OrderBuilder,
+        quote::default_verification_gas_limit, signature::EcdsaSigningScheme,
     };
     use secp256k1::ONE_KEY;
 
@@ -798,13 +873,31 @@ mod tests {
             fee_amount: U256::from(1),
             ..Default::default()
         };
-        assert_eq!(minimum_balance(&order), None);
+        assert_eq!(minimum_balance(&order, &OrderClass::Market), None);
         let order = OrderData {
             sell_amount: U256::from(1),
             fee_amount: U256::from(1),
             ..Default::default()
         };
-        assert_eq!(minimum_balance(&order), Some(U256::from(2)));
+        assert_eq!(
+            minimum_balance(&order, &OrderClass::Market),
+            Some(U256::from(2))
+        );
+        let order = OrderData {
+            sell_amount: U256::MAX,
+            fee_amount: U256::from(1),
+            partially_fillable: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            minimum_balance(&order, &OrderClass::Market),
+            Some(U256::from(1))
+        );
+        assert_eq!(
+            minimum_balance(&order, &OrderClass::Limit(Default::default())),
+            Some(U256::from(1))
+        );
+        assert_eq!(minimum_balance(&order, &OrderClass::Liquidity), None);
     }
 
     #[test]
@@ -904,6 +997,7 @@ mod tests {
         assert!(matches!(
             validator
                 .partial_validate(PreOrderData {
+                    buy_token: BUY_ETH_ADDRESS,
                     buy_token_balance: BuyTokenDestination::Internal,
                     ..Default::default()
                 })
@@ -1039,6 +1133,13 @@ mod tests {
         };
 
         assert!(validator.partial_validate(order()).await.is_ok());
+        assert!(validator
+            .partial_validate(PreOrderData {
+                buy_token_balance: BuyTokenDestination::Internal,
+                ..order()
+            })
+            .await
+            .is_ok());
         assert!(validator
             .partial_validate(PreOrderData {
                 valid_to: u32::MAX,
@@ -1070,6 +1171,60 @@ mod tests {
             .is_ok());
     }
 
+    #[tokio::test]
+    async fn pre_validate_partially_fillable_user_orders() {
+        let validity_configuration = OrderValidPeriodConfiguration {
+            min: Duration::from_secs(1),
+            max_market: Duration::from_secs(100),
+            max_limit: Duration::from_secs(200),
+        };
+        let mut bad_token_detector = MockBadTokenDetecting::new();
+        bad_token_detector
+            .expect_detect()
+            .returning(|_| Ok(TokenQuality::Good));
+        let mut limit_order_counter = MockLimitOrderCounting::new();
+        limit_order_counter.expect_count().returning(|_| Ok(0u64));
+        let validator = OrderValidator::new(
+            dummy_contract!(WETH9, [0xef; 20]),
+            hashset!(),
+            hashset!(),
+            validity_configuration,
+            SignatureConfiguration::off_chain(),
+            Arc::new(bad_token_detector),
+            Arc::new(MockOrderQuoting::new()),
+            Arc::new(MockBalanceFetching::new()),
+            Arc::new(MockSignatureValidating::new()),
+            Arc::new(limit_order_counter),
+            0,
+            Arc::new(MockCodeFetching::new()),
+        );
+        let order = |class| PreOrderData {
+            valid_to: time::now_in_epoch_seconds()
+                + validity_configuration.min.as_secs() as u32
+                + 2,
+            sell_token: H160::from_low_u64_be(1),
+            buy_token: H160::from_low_u64_be(2),
+            partially_fillable: true,
+            class,
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            validator.partial_validate(order(OrderClass::Market)).await,
+            Err(PartialValidationError::UnsupportedOrderType)
+        ));
+
+        let validator = validator.with_partially_fillable_orders(true);
+        assert!(validator
+            .partial_validate(order(OrderClass::Market))
+            .await
+            .is_ok());
+        assert!(validator
+            .partial_validate(order(OrderClass::Limit(Default::default())))
+            .await
+            .is_ok());
+    }
+
     #[tokio::test]
     async fn post_validate_ok() {
         let mut order_quoter = MockOrderQuoting::new();
@@ -1307,6 +1462,75 @@ mod tests {
         assert!(matches!(result, Err(ValidationError::ZeroAmount)));
     }
 
+    #[test]
+    fn validates_post_interactions() {
+        let target = H160([0x42; 20]);
+        let validator = OrderValidator::new(
+            dummy_contract!(WETH9, [0xef; 20]),
+            hashset!(),
+            hashset!(),
+            OrderValidPeriodConfiguration::any(),
+            SignatureConfiguration::all(),
+            Arc::new(MockBadTokenDetecting::new()),
+            Arc::new(MockOrderQuoting::new()),
+            Arc::new(MockBalanceFetching::new()),
+            Arc::new(MockSignatureValidating::new()),
+            Arc::new(MockLimitOrderCounting::new()),
+            0,
+            Arc::new(MockCodeFetching::new()),
+        )
+        .with_post_interaction_targets(hashset!(target));
+        let hook = InteractionData {
+            target,
+            value: 0.into(),
+            call_data: vec![1, 2, 3],
+        };
+
+        assert!(validator
+            .validate_interactions(&Interactions::default())
+            .is_ok());
+        assert!(validator
+            .validate_interactions(&Interactions {
+                pre: vec![],
+                post: vec![hook.clone(); MAX_POST_INTERACTIONS],
+            })
+            .is_ok());
+        assert!(matches!(
+            validator.validate_interactions(&Interactions {
+                pre: vec![hook.clone()],
+                post: vec![],
+            }),
+            Err(InteractionValidationError::PreInteractionsNotSupported)
+        ));
+        assert!(matches!(
+            validator.validate_interactions(&Interactions {
+                pre: vec![],
+                post: vec![hook.clone(); MAX_POST_INTERACTIONS + 1],
+            }),
+            Err(InteractionValidationError::TooManyPostInteractions)
+        ));
+        assert!(matches!(
+            validator.validate_interactions(&Interactions {
+                pre: vec![],
+                post: vec![InteractionData {
+                    target: H160([0x43; 20]),
+                    ..hook.clone()
+                }],
+            }),
+            Err(InteractionValidationError::UnsupportedTarget(t)) if t == H160([0x43; 20])
+        ));
+        assert!(matches!(
+            validator.validate_interactions(&Interactions {
+                pre: vec![],
+                post: vec![InteractionData {
+                    value: 1.into(),
+                    ..hook
+                }],
+            }),
+            Err(InteractionValidationError::NonZeroValue)
+        ));
+    }
+
     #[tokio::test]
     async fn post_zero_fee_limit_orders_disabled() {
         let mut order_quoter = MockOrderQuoting::new();
@@ -53,6 +53,31 @@ impl Remaining {
         }
     }
 
+    /// Like [`Remaining::from_order`] but additionally scales partially fillable orders down so
+    /// that their remaining sell and fee amounts are covered by the order's available balance.
+    pub fn from_order_with_available_balance(order: &Order) -> Result<Self> {
+        let remaining = Self::from_order(order)?;
+        let balance = match order.metadata.available_balance {
+            Some(balance) if order.data.partially_fillable => balance,
+            _ => return Ok(remaining),
+        };
+        let needed = remaining
+            .remaining(order.data.sell_amount)?
+            .checked_add(remaining.remaining(order.data.fee_amount)?)
+            .context("remaining sell and fee amount overflow")?;
+        if balance >= needed {
+            return Ok(remaining);
+        }
+        Ok(Self {
+            numerator: balance,
+            denominator: order
+                .data
+                .sell_amount
+                .checked_add(order.data.fee_amount)
+                .context("sell and fee amount overflow")?,
+        })
+    }
+
     /// Returns Err if the contract would error due to intermediate overflow.
     pub fn remaining(&self, total: U256) -> Result<U256> {
         total
@@ -159,6 +184,56 @@ mod tests {
         assert_eq!(remaining.remaining(200.into()).unwrap(), 20.into());
     }
 
+    #[test]
+    fn scales_partially_fillable_orders_to_available_balance() {
+        let order = Order {
+            data: OrderData {
+                sell_amount: 100.into(),
+                buy_amount: 50.into(),
+                fee_amount: 100.into(),
+                kind: OrderKind::Sell,
+                partially_fillable: true,
+                ..Default::default()
+            },
+            metadata: OrderMetadata {
+                executed_sell_amount_before_fees: 20.into(),
+                available_balance: Some(40.into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let remaining = Remaining::from_order_with_available_balance(&order).unwrap();
+        assert_eq!(remaining.remaining(100.into()).unwrap(), 20.into());
+        assert_eq!(remaining.remaining(50.into()).unwrap(), 10.into());
+
+        // Enough balance for the remaining amounts.
+        let order = Order {
+            metadata: OrderMetadata {
+                available_balance: Some(1000.into()),
+                ..order.metadata
+            },
+            ..order
+        };
+        let remaining = Remaining::from_order_with_available_balance(&order).unwrap();
+        assert_eq!(remaining.remaining(100.into()).unwrap(), 80.into());
+
+        // Fill-or-kill orders are never scaled.
+        let order = Order {
+            data: OrderData {
+                partially_fillable: false,
+                ..order.data
+            },
+            metadata: OrderMetadata {
+                executed_sell_amount_before_fees: 0.into(),
+                available_balance: Some(1.into()),
+                ..order.metadata
+            },
+            ..order
+        };
+        let remaining = Remaining::from_order_with_available_balance(&order).unwrap();
+        assert_eq!(remaining.remaining(100.into()).unwrap(), 100.into());
+    }
+
     #[test]
     fn remaining_amount_errors() {
         // Partially fillable order overflow when computing fill ratio.
@@ -15,11 +15,12 @@ use std::{
 pub fn report_matched_but_not_settled(
     metrics: &dyn SolverMetrics,
     (_, winning_solution): &(Arc<dyn Solver>, RatedSettlement),
+    additional_settlements: &[(Arc<dyn Solver>, RatedSettlement)],
     alternative_settlements: &[(Arc<dyn Solver>, RatedSettlement)],
 ) {
-    let submitted_orders: HashSet<_> = winning_solution
-        .settlement
-        .user_trades()
+    let submitted_orders: HashSet<_> = std::iter::once(winning_solution)
+        .chain(additional_settlements.iter().map(|(_, solution)| solution))
+        .flat_map(|solution| solution.settlement.user_trades())
         .map(|trade| trade.order.metadata.uid)
         .collect();
     let other_matched_orders: HashSet<_> = alternative_settlements
@@ -2,8 +2,11 @@ use crate::{
     liquidity::slippage,
     s3_instance_upload_arguments::S3UploadArguments,
     settlement_access_list::AccessListEstimatorType,
+    settlement_ranker::objective::RankingObjectiveArg,
+    settlement_submission::submitter::builder_api::BuilderEndpoint,
     solver::{single_order_solver, ExternalSolverArg, SolverAccountArg, SolverType},
 };
+use ethcontract::PrivateKey;
 use primitive_types::H160;
 use reqwest::Url;
 use shared::{
@@ -201,6 +204,20 @@ pub struct Arguments {
     )]
     pub flashbots_api_url: Vec<Url>,
 
+    /// The block builders used by the `Builder` transaction strategy. Each
+    /// builder is specified as `name|method|url`, where the method is either
+    /// `eth_sendBundle` or `eth_sendPrivateTransaction`. The settlement is sent
+    /// to all of the builders at once.
+    #[clap(long, env, use_value_delimiter = true)]
+    pub builder_endpoints: Vec<BuilderEndpoint>,
+
+    /// Private key used to sign the requests to the builders in the
+    /// `X-Flashbots-Signature` header. Relays which authenticate their callers,
+    /// like `https://relay.flashbots.net`, reject unsigned bundles. The key
+    /// only identifies the sender and doesn't need to hold any funds.
+    #[clap(long, env)]
+    pub builder_signing_key: Option<PrivateKey>,
+
     /// Maximum additional tip in gwei that we are willing to give to eden above regular gas price estimation
     #[clap(
         long,
@@ -229,6 +246,15 @@ pub struct Arguments {
     )]
     pub max_additional_flashbot_tip: f64,
 
+    /// Maximum additional tip in gwei that we are willing to give to block builders above regular gas price estimation
+    #[clap(
+        long,
+        env,
+        default_value = "3",
+        value_parser = shared::arguments::wei_from_gwei
+    )]
+    pub max_additional_builder_tip: f64,
+
     /// Amount of time to wait before retrying to submit the tx to the ethereum network
     #[clap(
         long,
@@ -268,6 +294,14 @@ pub struct Arguments {
     #[clap(long, env, default_value = "20")]
     pub max_settlements_per_solver: usize,
 
+    /// The maximum number of settlements the driver submits per auction. Settlements in addition
+    /// to the winning one are only submitted if they settle different orders and tokens, come from
+    /// a different solver account and at most one of them executes interactions. Each of them is
+    /// submitted as a separate transaction targeting the same block. Only the executions of the
+    /// orders in the winning settlement are stored with the solver competition.
+    #[clap(long, env, default_value = "1")]
+    pub max_settlements_per_auction: usize,
+
     /// Factor how much of the WETH buffer should be unwrapped if ETH buffer is not big enough to
     /// settle ETH buy orders.
     /// Unwrapping a bigger amount will cause fewer unwraps to happen and thereby reduce the cost
@@ -301,6 +335,43 @@ pub struct Arguments {
     #[clap(long, env, default_value = "0")]
     pub solution_comparison_decimal_cutoff: u16,
 
+    /// When a settlement fails to simulate, try to find the user orders causing the revert by
+    /// bisecting its trades and let the solver solve the auction again without them. The solver
+    /// gets the same time limit for this as for the original auction.
+    #[clap(long, env)]
+    pub bisect_failing_settlements: bool,
+
+    /// The objective by which the driver ranks the settlements of all solvers.
+    #[clap(
+        long,
+        env,
+        default_value = "SurplusFeesCosts",
+        value_enum,
+        ignore_case = true
+    )]
+    pub ranking_objective: RankingObjectiveArg,
+
+    /// The probability with which the `RevertRiskAdjusted` ranking objective
+    /// assumes that settlements interacting with onchain liquidity revert.
+    /// Must be between 0 and 1.
+    #[clap(
+        long,
+        env,
+        default_value = "0.05",
+        value_parser = shared::arguments::parse_percentage_factor
+    )]
+    pub ranking_revert_probability: f64,
+
+    /// The value in wei of one unit of solver reward, used by the `Cip`
+    /// ranking objective to include rewards in the score of a settlement.
+    #[clap(
+        long,
+        env,
+        default_value = "0",
+        value_parser = shared::arguments::parse_unbounded_factor
+    )]
+    pub ranking_reward_native_value: f64,
+
     #[clap(flatten)]
     pub s3_upload: S3UploadArguments,
 }
@@ -360,6 +431,15 @@ impl std::fmt::Display for Arguments {
         )?;
         writeln!(f, "eden_api_url: {}", self.eden_api_url)?;
         display_list(f, "flashbots_api_url", &self.flashbots_api_url)?;
+        writeln!(
+            f,
+            "builder_endpoints: {:?}",
+            self.builder_endpoints
+                .iter()
+                .map(|endpoint| &endpoint.name)
+                .collect::<Vec<_>>()
+        )?;
+        display_secret_option(f, "builder_signing_key", &self.builder_signing_key)?;
         writeln!(
             f,
             "max_additional_eden_tip: {}",
@@ -375,6 +455,11 @@ impl std::fmt::Display for Arguments {
             "max_additional_flashbots_tip: {}",
             self.max_additional_flashbot_tip
         )?;
+        writeln!(
+            f,
+            "max_additional_builder_tip: {}",
+            self.max_additional_builder_tip
+        )?;
         writeln!(
             f,
             "submission_retry_interval_seconds: {:?}",
@@ -405,6 +490,11 @@ impl std::fmt::Display for Arguments {
             "max_settlements_per_solver: {}",
             self.max_settlements_per_solver
         )?;
+        writeln!(
+            f,
+            "max_settlements_per_auction: {}",
+            self.max_settlements_per_auction
+        )?;
         writeln!(f, "weth_unwrap_factor: {}", self.weth_unwrap_factor)?;
         writeln!(f, "simulation_gas_limit: {}", self.simulation_gas_limit)?;
         display_option(
@@ -417,6 +507,22 @@ impl std::fmt::Display for Arguments {
             "token_list_restriction_for_price_checks: {:?}",
             self.token_list_restriction_for_price_checks
         )?;
+        writeln!(
+            f,
+            "bisect_failing_settlements: {}",
+            self.bisect_failing_settlements
+        )?;
+        writeln!(f, "ranking_objective: {:?}", self.ranking_objective)?;
+        writeln!(
+            f,
+            "ranking_revert_probability: {}",
+            self.ranking_revert_probability
+        )?;
+        writeln!(
+            f,
+            "ranking_reward_native_value: {}",
+            self.ranking_reward_native_value
+        )?;
         writeln!(f, "{}", self.s3_upload)?;
         Ok(())
     }
@@ -428,6 +534,7 @@ pub enum TransactionStrategyArg {
     PublicMempool,
     Eden,
     Flashbots,
+    Builder,
     Gelato,
     DryRun,
 }
@@ -0,0 +1,12 @@
+use clap::Parser;
+
+#[tokio::main]
+async fn main() {
+    let args = solver::replay::arguments::Arguments::parse();
+    shared::tracing::initialize(
+        args.logging.log_filter.as_str(),
+        args.logging.log_stderr_threshold,
+    );
+    tracing::info!("replaying auction with validated arguments:\n{}", args);
+    solver::replay::main(args).await;
+}
@@ -2,14 +2,19 @@ pub mod solver_settlements;
 
 use crate::{
     auction_preprocessing,
+    driver::solver_settlements::{self, RatedSettlement},
     driver_logger::DriverLogger,
     in_flight_orders::InFlightOrders,
+    interactions::allowances::{AllowanceManager, AllowanceManaging, ApprovalRequest},
     liquidity::order_converter::OrderConverter,
     liquidity_collector::{LiquidityCollecting, LiquidityCollector},
     metrics::SolverMetrics,
     orderbook::OrderBookApi,
     settlement::{external_prices::ExternalPrices, PriceCheckTokens, Settlement},
-    settlement_ranker::SettlementRanker,
+    settlement_ranker::{
+        objective::{RankingObjective, SurplusFeesCosts},
+        RankedSolverSettlement, SettlementRanker, SolverResult,
+    },
     settlement_rater::SettlementRater,
     settlement_simulation::{self, MAX_BASE_GAS_FEE_INCREASE},
     settlement_submission::{SolutionSubmitter, SubmissionError},
@@ -22,7 +27,7 @@ use futures::future::join_all;
 use gas_estimation::GasPriceEstimating;
 use model::{
     auction::{AuctionId, AuctionWithId},
-    order::{LimitOrderClass, OrderClass, OrderUid},
+    order::OrderUid,
     solver_competition::{
         self, CompetitionAuction, Execution, Objective, SolverCompetitionDB, SolverSettlement,
     },
@@ -46,6 +51,29 @@ use std::{
 use tracing::Instrument as _;
 use web3::types::TransactionReceipt;
 
+/// Optional behaviour of the [`Driver`]. The defaults match a driver without
+/// any of these options enabled.
+#[derive(Clone)]
+pub struct DriverConfig {
+    /// Try to recover settlements that fail to simulate by letting the solver
+    /// solve the auction again without the orders causing the revert.
+    pub bisect_failing_settlements: bool,
+    /// How the settlements of the solvers are ranked against each other.
+    pub ranking_objective: Arc<dyn RankingObjective>,
+    /// The most settlements submitted per auction.
+    pub max_settlements_per_auction: usize,
+}
+
+impl Default for DriverConfig {
+    fn default() -> Self {
+        Self {
+            bisect_failing_settlements: false,
+            ranking_objective: Arc::new(SurplusFeesCosts),
+            max_settlements_per_auction: 1,
+        }
+    }
+}
+
 pub struct Driver {
     liquidity_collector: LiquidityCollector,
     solvers: Solvers,
@@ -65,6 +93,9 @@ pub struct Driver {
     logger: DriverLogger,
     web3: Web3,
     last_attempted_settlement: Option<AuctionId>,
+    settlement_contract: GPv2Settlement,
+    allowance_manager: Arc<dyn AllowanceManaging>,
+    max_settlements_per_auction: usize,
 }
 impl Driver {
     #[allow(clippy::too_many_arguments)]
@@ -91,12 +122,14 @@ impl Driver {
         tenderly: Option<Arc<dyn TenderlyApi>>,
         solution_comparison_decimal_cutoff: u16,
         code_fetcher: Arc<dyn CodeFetching>,
+        config: DriverConfig,
     ) -> Self {
         let settlement_rater = Arc::new(SettlementRater {
             access_list_estimator: solution_submitter.access_list_estimator.clone(),
             settlement_contract: settlement_contract.clone(),
             web3: web3.clone(),
             code_fetcher,
+            bisect_failing_settlements: config.bisect_failing_settlements,
         });
 
         let settlement_ranker = SettlementRanker {
@@ -106,6 +139,7 @@ impl Driver {
             min_order_age,
             settlement_rater,
             decimal_cutoff: solution_comparison_decimal_cutoff,
+            objective: config.ranking_objective,
         };
 
         let logger = DriverLogger {
@@ -113,9 +147,13 @@ impl Driver {
             web3: web3.clone(),
             tenderly,
             network_id,
-            settlement_contract,
+            settlement_contract: settlement_contract.clone(),
             simulation_gas_limit,
         };
+        let allowance_manager = Arc::new(AllowanceManager::new(
+            web3.clone(),
+            settlement_contract.address(),
+        ));
 
         Self {
             liquidity_collector,
@@ -137,6 +175,9 @@ impl Driver {
             logger,
             web3,
             last_attempted_settlement: None,
+            settlement_contract,
+            allowance_manager,
+            max_settlements_per_auction: config.max_settlements_per_auction,
         }
     }
 
@@ -152,37 +193,75 @@ impl Driver {
     }
 
     // Returns solver name and result.
-    async fn run_solvers(
-        &self,
-        auction: Auction,
-    ) -> Vec<(Arc<dyn Solver>, Result<Vec<Settlement>, SolverRunError>)> {
-        join_all(self.solvers.iter().map(|solver| {
-            let auction = auction.clone();
-            let metrics = &self.metrics;
-            async move {
-                let start_time = Instant::now();
-                let result =
-                    match tokio::time::timeout_at(auction.deadline.into(), solver.solve(auction))
-                        .await
-                    {
-                        Ok(inner) => {
-                            inner.map_err(|err| SolverRunError::Solving(format!("{:?}", err)))
-                        }
-                        Err(_timeout) => Err(SolverRunError::Timeout),
-                    };
-                let response = match &result {
-                    Err(SolverRunError::Timeout) => "timeout",
-                    Err(_) => "error",
-                    Ok(solutions) if solutions.is_empty() => "none",
-                    Ok(_) => "solution",
-                };
-                metrics.settlement_computed(solver.name(), response, start_time);
-                (solver.clone(), result)
-            }
-        }))
+    async fn run_solvers(&self, auction: Auction) -> Vec<SolverResult> {
+        join_all(
+            self.solvers
+                .iter()
+                .map(|solver| self.run_solver(solver.clone(), auction.clone())),
+        )
         .await
     }
 
+    async fn run_solver(&self, solver: Arc<dyn Solver>, auction: Auction) -> SolverResult {
+        let start_time = Instant::now();
+        let result =
+            match tokio::time::timeout_at(auction.deadline.into(), solver.solve(auction)).await {
+                Ok(Ok(settlements)) => Ok(self.with_vault_approvals(settlements).await),
+                Ok(Err(err)) => Err(SolverRunError::Solving(format!("{:?}", err))),
+                Err(_timeout) => Err(SolverRunError::Timeout),
+            };
+        let response = match &result {
+            Err(SolverRunError::Timeout) => "timeout",
+            Err(_) => "error",
+            Ok(solutions) if solutions.is_empty() => "none",
+            Ok(_) => "solution",
+        };
+        self.metrics
+            .settlement_computed(solver.name(), response, start_time);
+        (solver, result)
+    }
+
+    /// Adds the approvals needed to credit buy tokens to Vault internal balances. Settlements for
+    /// which the approvals can't be determined are discarded.
+    async fn with_vault_approvals(&self, settlements: Vec<Settlement>) -> Vec<Settlement> {
+        let mut result = Vec::with_capacity(settlements.len());
+        for mut settlement in settlements {
+            match self.add_vault_approvals(&mut settlement).await {
+                Ok(()) => result.push(settlement),
+                Err(err) => tracing::warn!(?err, "failed to add vault approvals"),
+            }
+        }
+        result
+    }
+
+    /// The Vault pulls tokens deposited into internal balances from the settlement contract so it
+    /// needs an allowance for them.
+    async fn add_vault_approvals(&self, settlement: &mut Settlement) -> Result<()> {
+        let amounts = settlement.encoder.internal_buy_token_amounts();
+        if amounts.is_empty() {
+            return Ok(());
+        }
+
+        let vault = self
+            .settlement_contract
+            .vault()
+            .call()
+            .await
+            .context("vault")?;
+        let requests = amounts
+            .into_iter()
+            .map(|(token, amount)| ApprovalRequest {
+                token,
+                spender: vault,
+                amount,
+            })
+            .collect::<Vec<_>>();
+        for approval in self.allowance_manager.get_approvals(&requests).await? {
+            settlement.encoder.append_to_execution_plan(approval);
+        }
+        Ok(())
+    }
+
     pub async fn single_run(&mut self) -> Result<()> {
         let auction = self
             .api
@@ -311,10 +390,26 @@ impl Driver {
         };
 
         tracing::debug!(deadline =? auction.deadline, "solving auction");
-        let run_solver_results = self.run_solvers(auction).await;
+        let run_solver_results = self.run_solvers(auction.clone()).await;
+        let driver = &*self;
+        let resolve = |solver: Arc<dyn Solver>, failing_orders: HashSet<OrderUid>| {
+            let mut auction = auction.clone();
+            auction.orders.retain(
+                |order| !matches!(order.id.order_uid(), Some(uid) if failing_orders.contains(&uid)),
+            );
+            auction.deadline = Instant::now() + driver.solver_time_limit;
+            driver.run_solver(solver, auction)
+        };
         let (mut rated_settlements, errors) = self
             .settlement_ranker
-            .rank_legal_settlements(run_solver_results, &external_prices, gas_price, auction_id)
+            .rank_legal_settlements(
+                run_solver_results,
+                &external_prices,
+                gas_price,
+                auction_id,
+                &rewards,
+                resolve,
+            )
             .await?;
 
         // We don't know the exact block because simulation can happen over multiple blocks but
@@ -332,7 +427,7 @@ impl Driver {
             auction: competition_auction,
             solutions: rated_settlements
                 .iter()
-                .map(|(solver, rated_settlement, _)| SolverSettlement {
+                .map(|(solver, rated_settlement, _, score)| SolverSettlement {
                     solver: solver.name().to_string(),
                     objective: Objective {
                         total: rated_settlement
@@ -347,6 +442,7 @@ impl Driver {
                         cost: rated_settlement.gas_estimate.to_f64_lossy()
                             * rated_settlement.gas_price.to_f64().unwrap_or(f64::NAN),
                         gas: rated_settlement.gas_estimate.low_u64(),
+                        score: Some(score.to_model(self.settlement_ranker.objective.name())),
                     },
                     clearing_prices: rated_settlement
                         .settlement
@@ -378,7 +474,7 @@ impl Driver {
         };
 
         let mut settlement_transaction_attempted = false;
-        if let Some((winning_solver, winning_settlement, _)) = rated_settlements.pop() {
+        if let Some((winning_solver, winning_settlement, _, _)) = rated_settlements.pop() {
             tracing::info!(
                 "winning settlement id {} by solver {}: {:?}",
                 winning_settlement.id,
@@ -386,16 +482,29 @@ impl Driver {
                 winning_settlement
             );
 
+            let additional_settlements = self.take_additional_settlements(
+                (&winning_solver, &winning_settlement),
+                &mut rated_settlements,
+            );
+            for (solver, settlement) in &additional_settlements {
+                tracing::info!(
+                    "additional settlement id {} by solver {}: {:?}",
+                    settlement.id,
+                    solver.name(),
+                    settlement
+                );
+            }
+
+            // The competition only records the transaction of the winning settlement, so the
+            // executions of the orders in additional settlements can't be attributed to the
+            // transaction settling them and aren't stored.
             let executions: Vec<(OrderUid, Execution)> = winning_settlement
                 .settlement
                 .user_trades()
                 .map(|trade| {
                     let uid = &trade.order.metadata.uid;
                     let reward = rewards.get(uid).copied().unwrap_or(0.);
-                    let surplus_fee = match trade.order.metadata.class {
-                        OrderClass::Limit(LimitOrderClass { surplus_fee, .. }) => surplus_fee,
-                        _ => None,
-                    };
+                    let surplus_fee = trade.executed_surplus_fee();
                     // Log in case something goes wrong with storing the rewards in the database.
                     tracing::debug!(%uid, %reward, "winning solution reward");
                     let execution = Execution {
@@ -437,34 +546,51 @@ impl Driver {
                 .complete_runloop_until_transaction(start.elapsed());
             tracing::debug!(?address, ?nonce, "submitting settlement");
             settlement_transaction_attempted = true;
-            let hash = match submit_settlement(
-                &self.solution_submitter,
-                &self.logger,
-                account.clone(),
-                nonce,
-                winning_solver.name(),
-                winning_settlement.settlement.clone(),
-                winning_settlement.gas_estimate,
-                Some(winning_settlement.id as u64),
+            // The additional settlements are independent of the winning one, so they are
+            // submitted concurrently from their own accounts and can land in the same block.
+            let (result, additional_results) = futures::future::join(
+                submit_settlement(
+                    &self.solution_submitter,
+                    &self.logger,
+                    account.clone(),
+                    nonce,
+                    winning_solver.name(),
+                    winning_settlement.settlement.clone(),
+                    winning_settlement.gas_estimate,
+                    Some(winning_settlement.id as u64),
+                ),
+                join_all(additional_settlements.iter().map(|(solver, settlement)| {
+                    self.submit_additional_settlement(solver, settlement)
+                })),
             )
-            .await
-            {
-                Ok(receipt) => {
-                    self.update_in_flight_orders(&receipt, &winning_settlement.settlement);
-                    Some(receipt.transaction_hash)
+            .await;
+
+            for (result, settlement) in std::iter::once((result, &winning_settlement)).chain(
+                additional_results.into_iter().zip(
+                    additional_settlements
+                        .iter()
+                        .map(|(_, settlement)| settlement),
+                ),
+            ) {
+                let hash = match result {
+                    Ok(receipt) => {
+                        self.update_in_flight_orders(&receipt, &settlement.settlement);
+                        Some(receipt.transaction_hash)
+                    }
+                    Err(SubmissionError::Revert(hash)) => Some(hash),
+                    _ => None,
+                };
+                if let Some(hash) = hash {
+                    tracing::debug!(settlement_id = settlement.id, ?hash, "settled transaction");
                 }
-                Err(SubmissionError::Revert(hash)) => Some(hash),
-                _ => None,
-            };
-            if let Some(hash) = hash {
-                tracing::debug!(?hash, "settled transaction");
             }
 
             self.logger.report_on_batch(
                 &(winning_solver, winning_settlement),
+                &additional_settlements,
                 rated_settlements
                     .into_iter()
-                    .map(|(solver, settlement, _)| (solver, settlement))
+                    .map(|(solver, settlement, _, _)| (solver, settlement))
                     .collect(),
             );
         }
@@ -477,7 +603,72 @@ impl Driver {
         Ok(settlement_transaction_attempted)
     }
 
-    /// Marks all orders in the winning settlement as "in flight".
+    /// Removes the settlements which are submitted in the same block as the winning settlement
+    /// from the ranked settlements. See [`solver_settlements::independent_settlements`].
+    fn take_additional_settlements(
+        &self,
+        (winning_solver, winning_settlement): (&Arc<dyn Solver>, &RatedSettlement),
+        rated_settlements: &mut Vec<RankedSolverSettlement>,
+    ) -> Vec<(Arc<dyn Solver>, RatedSettlement)> {
+        // Ranked settlements are sorted in ascending order so the best one comes last.
+        let candidates: Vec<_> = std::iter::once((
+            winning_solver.account().address(),
+            &winning_settlement.settlement,
+        ))
+        .chain(
+            rated_settlements
+                .iter()
+                .rev()
+                .map(|(solver, settlement, _, _)| {
+                    (solver.account().address(), &settlement.settlement)
+                }),
+        )
+        .collect();
+        let selected = solver_settlements::independent_settlements(
+            &candidates,
+            self.max_settlements_per_auction,
+        );
+
+        // Candidate `i > 0` is at index `len - i` of the ranked settlements. Indices are
+        // descending, so removing them one by one doesn't shift the remaining ones.
+        let len = rated_settlements.len();
+        selected
+            .into_iter()
+            .skip(1)
+            .map(|i| {
+                let (solver, settlement, _, _) = rated_settlements.remove(len - i);
+                (solver, settlement)
+            })
+            .collect()
+    }
+
+    async fn submit_additional_settlement(
+        &self,
+        solver: &Arc<dyn Solver>,
+        settlement: &RatedSettlement,
+    ) -> Result<TransactionReceipt, SubmissionError> {
+        let account = solver.account();
+        let nonce = self
+            .web3
+            .eth()
+            .transaction_count(account.address(), None)
+            .await
+            .context("transaction_count")?;
+        tracing::debug!(address = ?account.address(), ?nonce, "submitting additional settlement");
+        submit_settlement(
+            &self.solution_submitter,
+            &self.logger,
+            account.clone(),
+            nonce,
+            solver.name(),
+            settlement.settlement.clone(),
+            settlement.gas_estimate,
+            Some(settlement.id as u64),
+        )
+        .await
+    }
+
+    /// Marks all orders in a submitted settlement as "in flight".
     fn update_in_flight_orders(&mut self, receipt: &TransactionReceipt, settlement: &Settlement) {
         let block = match receipt.block_number {
             Some(block) => block.as_u64(),
@@ -489,6 +680,11 @@ impl Driver {
         self.in_flight_orders.mark_settled_orders(block, settlement);
     }
 
+    /// The orders settled by submitted settlements which the api hasn't seen yet.
+    pub fn in_flight_orders(&self) -> &InFlightOrders {
+        &self.in_flight_orders
+    }
+
     fn next_run_id(&mut self) -> u64 {
         let id = self.run_id;
         self.run_id += 1;
@@ -1,5 +1,8 @@
-use crate::{settlement::Settlement, solver::Solver};
-use ethcontract::U256;
+use crate::{
+    settlement::{Revertable, Settlement},
+    solver::Solver,
+};
+use ethcontract::{H160, U256};
 use model::auction::AuctionId;
 use num::BigRational;
 use shared::{
@@ -126,6 +129,68 @@ pub fn retain_mature_settlements(
         .collect()
 }
 
+/// Selects settlements which can be submitted as separate transactions in the
+/// same block without affecting each other, so that every one of them is
+/// either executed in full or not at all. `settlements` are pairs of the
+/// submitting account and the settlement, ordered from best to worst. The
+/// first settlement is always selected and at most `max` are selected in
+/// total. Returns the indices of the selected settlements.
+///
+/// A settlement is only selected if, compared to all previously selected ones,
+/// - it settles none of the same orders and trades none of the same tokens,
+/// - it is submitted from a different account, so that the transactions don't
+///   have to be ordered by nonce,
+/// - not both of them execute interactions, be it with onchain liquidity,
+///   internalized against the settlement contract's buffers or order hooks,
+///   because the effect of one interaction on another can't be known without
+///   simulating them in sequence.
+///
+/// Without interactions, a settlement only reads and writes the state of its
+/// own orders, of their owners' balances and allowances of its tokens and of
+/// the settlement contract's balances of its tokens. Selected settlements
+/// share none of this state, so each of them behaves the same no matter which
+/// of the others are executed before it in the block. This is why the selected
+/// settlements don't need to be simulated on top of each other.
+pub fn independent_settlements(settlements: &[(H160, &Settlement)], max: usize) -> Vec<usize> {
+    let mut selected = Vec::new();
+    let mut accounts = HashSet::new();
+    let mut orders = HashSet::new();
+    let mut tokens = HashSet::new();
+    let mut has_interactions = false;
+
+    for (index, (account, settlement)) in settlements.iter().enumerate() {
+        if selected.len() >= max {
+            break;
+        }
+        let interacts = matches!(settlement.revertable(), Revertable::HighRisk)
+            || settlement.encoder.contains_internalized_interactions()
+            || settlement.trades().any(|trade| {
+                let interactions = &trade.order.interactions;
+                !interactions.pre.is_empty() || !interactions.post.is_empty()
+            });
+        let independent = !accounts.contains(account)
+            && !(has_interactions && interacts)
+            && settlement
+                .trades()
+                .all(|trade| !orders.contains(&trade.order.metadata.uid))
+            && settlement
+                .clearing_prices()
+                .keys()
+                .all(|token| !tokens.contains(token));
+        if !independent {
+            continue;
+        }
+
+        selected.push(index);
+        accounts.insert(*account);
+        orders.extend(settlement.trades().map(|trade| trade.order.metadata.uid));
+        tokens.extend(settlement.clearing_prices().keys().copied());
+        has_interactions |= interacts;
+    }
+
+    selected
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -473,4 +538,60 @@ mod tests {
         ]);
         assert!(has_user_order(&settlement));
     }
+
+    #[test]
+    fn selects_independent_settlements() {
+        let settlement = |uid: u8, sell_token: u64, buy_token: u64| {
+            let mut trade = trade(Default::default(), uid, OrderClass::Market);
+            trade.order.data.sell_token = H160::from_low_u64_be(sell_token);
+            trade.order.data.buy_token = H160::from_low_u64_be(buy_token);
+            Settlement::with_default_prices(vec![trade])
+        };
+        let with_interaction = |mut settlement: Settlement| {
+            settlement
+                .encoder
+                .append_to_execution_plan(crate::settlement::NoopInteraction);
+            settlement
+        };
+        let with_internalized_interaction = |mut settlement: Settlement| {
+            settlement
+                .encoder
+                .append_to_execution_plan_internalizable(crate::settlement::NoopInteraction, true);
+            settlement
+        };
+        let with_hook = |uid: u8, sell_token: u64, buy_token: u64| {
+            let mut trade = trade(Default::default(), uid, OrderClass::Market);
+            trade.order.data.sell_token = H160::from_low_u64_be(sell_token);
+            trade.order.data.buy_token = H160::from_low_u64_be(buy_token);
+            trade.order.interactions.post = vec![Default::default()];
+            Settlement::with_default_prices(vec![trade])
+        };
+        let account = H160::from_low_u64_be;
+
+        let best = with_interaction(settlement(1, 1, 2));
+        let same_order = settlement(1, 3, 4);
+        let same_token = settlement(2, 2, 3);
+        let same_account = settlement(3, 3, 4);
+        let also_interacting = with_interaction(settlement(4, 5, 6));
+        let internalizing = with_internalized_interaction(settlement(7, 11, 12));
+        let hooked = with_hook(8, 13, 14);
+        let independent = settlement(5, 7, 8);
+        let too_many = settlement(6, 9, 10);
+
+        let settlements = [
+            (account(1), &best),
+            (account(2), &same_order),
+            (account(2), &same_token),
+            (account(1), &same_account),
+            (account(2), &also_interacting),
+            (account(2), &internalizing),
+            (account(2), &hooked),
+            (account(2), &independent),
+            (account(3), &too_many),
+        ];
+        assert_eq!(independent_settlements(&settlements, 2), vec![0, 7]);
+        assert_eq!(independent_settlements(&settlements, 3), vec![0, 7, 8]);
+        assert_eq!(independent_settlements(&settlements, 1), vec![0]);
+        assert!(independent_settlements(&[], 2).is_empty());
+    }
 }
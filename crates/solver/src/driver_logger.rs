@@ -3,6 +3,7 @@ use crate::{
     driver::solver_settlements::RatedSettlement,
     metrics::{SolverMetrics, SolverSimulationOutcome},
     settlement::Settlement,
+    settlement_ranker::RankedSolverSettlement,
     settlement_simulation::{
         simulate_and_error_with_tenderly_link, simulate_before_after_access_list,
     },
@@ -19,7 +20,7 @@ use primitive_types::H256;
 use shared::{ethrpc::Web3, tenderly_api::TenderlyApi};
 use std::sync::Arc;
 use tracing::{Instrument as _, Span};
-use web3::types::{AccessList, TransactionReceipt};
+use web3::types::TransactionReceipt;
 
 pub struct DriverLogger {
     pub metrics: Arc<dyn SolverMetrics>,
@@ -173,6 +174,7 @@ impl DriverLogger {
                             solver, settlement, ..
                         },
                     error: error_at_latest_block,
+                    ..
                 },
                 result,
             ) in errors.iter().zip(simulations)
@@ -207,22 +209,23 @@ impl DriverLogger {
     }
 
     pub fn print_settlements(
-        rated_settlements: &[(Arc<dyn Solver>, RatedSettlement, Option<AccessList>)],
+        rated_settlements: &[RankedSolverSettlement],
         fee_objective_scaling_factor: &BigRational,
     ) {
         let mut text = String::new();
-        for (solver, settlement, access_list) in rated_settlements {
+        for (solver, settlement, access_list, score) in rated_settlements {
             use std::fmt::Write;
             write!(
                 text,
                 "\nid={} solver={} \
-             objective={:.2e} surplus={:.2e} \
+             objective={:.2e} score={:.2e} surplus={:.2e} \
              gas_estimate={:.2e} gas_price={:.2e} \
              unscaled_unsubsidized_fee={:.2e} unscaled_subsidized_fee={:.2e} \
              access_list_addreses={}",
                 settlement.id,
                 solver.name(),
                 settlement.objective_value().to_f64().unwrap_or(f64::NAN),
+                score.total.to_f64().unwrap_or(f64::NAN),
                 settlement.surplus.to_f64().unwrap_or(f64::NAN),
                 settlement.gas_estimate.to_f64_lossy(),
                 settlement.gas_price.to_f64().unwrap_or(f64::NAN),
@@ -243,11 +246,13 @@ impl DriverLogger {
     /// Record metrics on the matched orders from a single batch. Specifically we report on
     /// the number of orders that were;
     ///  - surplus in winning settlement vs unrealized surplus from other feasible solutions.
-    ///  - matched but not settled in this runloop (effectively queued for the next one)
+    ///  - matched but not settled by any of the submitted settlements in this runloop (effectively
+    ///    queued for the next one)
     /// Should help us to identify how much we can save by parallelizing execution.
     pub fn report_on_batch(
         &self,
         submitted: &(Arc<dyn Solver>, RatedSettlement),
+        additional_settlements: &[(Arc<dyn Solver>, RatedSettlement)],
         other_settlements: Vec<(Arc<dyn Solver>, RatedSettlement)>,
     ) {
         // Report surplus
@@ -257,7 +262,12 @@ impl DriverLogger {
             &other_settlements,
         );
         // Report matched but not settled
-        analytics::report_matched_but_not_settled(&*self.metrics, submitted, &other_settlements);
+        analytics::report_matched_but_not_settled(
+            &*self.metrics,
+            submitted,
+            additional_settlements,
+            &other_settlements,
+        );
     }
 }
 
@@ -108,6 +108,15 @@ impl InFlightOrders {
                 self.in_flight_trades.insert(uid, most_recent_data);
             });
     }
+
+    /// Returns whether the order is settled by a submitted settlement which the api hasn't seen
+    /// yet.
+    pub fn contains(&self, uid: &OrderUid) -> bool {
+        self.in_flight
+            .values()
+            .flatten()
+            .any(|in_flight| in_flight == uid)
+    }
 }
 
 #[cfg(test)]
@@ -186,6 +195,8 @@ mod tests {
 
         let mut inflight = InFlightOrders::default();
         inflight.mark_settled_orders(1, &settlement);
+        assert!(inflight.contains(&OrderUid::from_integer(1)));
+        assert!(!inflight.contains(&OrderUid::from_integer(0)));
         let mut order0 = fill_or_kill.clone();
         order0.metadata.uid = OrderUid::from_integer(0);
         let mut auction = Auction {
@@ -10,6 +10,7 @@ pub mod liquidity;
 pub mod liquidity_collector;
 pub mod metrics;
 pub mod orderbook;
+pub mod replay;
 pub mod s3_instance_upload;
 pub mod s3_instance_upload_arguments;
 pub mod settlement;
@@ -121,7 +121,6 @@ pub struct SettlementHandler {
     allowances: Arc<Allowances>,
 }
 
-#[cfg(test)]
 impl SettlementHandler {
     pub fn new(
         pool_id: H256,
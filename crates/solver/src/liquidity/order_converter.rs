@@ -33,15 +33,15 @@ impl OrderConverter {
             order.data.buy_token
         };
 
-        let remaining = shared::remaining_amounts::Remaining::from_order(&order)?;
+        // Partially fillable orders are capped to the balance that is available
+        // to them, so solvers never try to execute more than can be transferred.
+        let remaining =
+            shared::remaining_amounts::Remaining::from_order_with_available_balance(&order)?;
 
         // The reported fee amount that is used for objective computation is the
         // order's full full amount scaled by a constant factor.
         let scaled_fee_amount = U256::from_f64_lossy(
-            remaining
-                .remaining(order.metadata.full_fee_amount)?
-                .to_f64_lossy()
-                * self.fee_objective_scaling_factor,
+            order.metadata.full_fee_amount.to_f64_lossy() * self.fee_objective_scaling_factor,
         );
         let is_mature = order.metadata.creation_date
             + chrono::Duration::from_std(self.min_order_age).unwrap()
@@ -70,10 +70,12 @@ impl OrderConverter {
             kind: order.data.kind,
             partially_fillable: order.data.partially_fillable,
             unscaled_subsidized_fee: remaining.remaining(fee_amount)?,
-            scaled_unsubsidized_fee: scaled_fee_amount,
+            scaled_unsubsidized_fee: remaining.remaining(scaled_fee_amount)?,
             settlement_handling: Arc::new(OrderSettlementHandler {
                 order,
                 native_token,
+                // The settlement prorates the fee by the executed amount, so it
+                // needs the fee for the full order.
                 scaled_unsubsidized_fee_amount: scaled_fee_amount,
             }),
             exchange: Exchange::GnosisProtocol,
@@ -408,6 +410,34 @@ pub mod tests {
         assert_eq!(order.scaled_unsubsidized_fee, 30.into());
     }
 
+    #[test]
+    fn caps_partially_fillable_orders_to_available_balance() {
+        let converter = OrderConverter::test(H160::default());
+        let order = converter
+            .normalize_limit_order(Order {
+                data: OrderData {
+                    sell_amount: 100.into(),
+                    buy_amount: 200.into(),
+                    fee_amount: 100.into(),
+                    kind: OrderKind::Sell,
+                    partially_fillable: true,
+                    ..Default::default()
+                },
+                metadata: OrderMetadata {
+                    available_balance: Some(50.into()),
+                    full_fee_amount: 100.into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(order.sell_amount, 25.into());
+        assert_eq!(order.buy_amount, 50.into());
+        assert_eq!(order.unscaled_subsidized_fee, 25.into());
+        assert_eq!(order.scaled_unsubsidized_fee, 25.into());
+    }
+
     #[test]
     fn limit_orders_get_adjusted_for_surplus_fee() {
         let converter = OrderConverter::test(Default::default());
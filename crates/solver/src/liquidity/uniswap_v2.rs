@@ -33,7 +33,6 @@ pub struct Inner {
     allowances: Mutex<Allowances>,
 }
 
-#[cfg(test)]
 impl Inner {
     pub fn new(
         router: IUniswapLikeRouter,
@@ -41,6 +41,29 @@ pub struct UniswapV3SettlementHandler {
     fee: Option<u32>,
 }
 
+impl Inner {
+    pub fn new(
+        router: UniswapV3SwapRouter,
+        gpv2_settlement: GPv2Settlement,
+        allowances: Mutex<Allowances>,
+    ) -> Self {
+        Inner {
+            router,
+            gpv2_settlement,
+            allowances,
+        }
+    }
+}
+
+impl UniswapV3SettlementHandler {
+    pub fn new(inner: Arc<Inner>, fee: Ratio<u32>) -> Result<Self> {
+        Ok(Self {
+            inner,
+            fee: Some(ratio_to_u32(fee)?),
+        })
+    }
+}
+
 /// Highly corelated to Uniswap V3 only.
 /// Converts:
 /// 1% fee to 10000
@@ -26,7 +26,7 @@ use shared::{
 };
 use solver::{
     arguments::TransactionStrategyArg,
-    driver::Driver,
+    driver::{Driver, DriverConfig},
     liquidity::{
         balancer_v2::BalancerV2Liquidity, order_converter::OrderConverter,
         uniswap_v2::UniswapLikeLiquidity, uniswap_v3::UniswapV3Liquidity, zeroex::ZeroExLiquidity,
@@ -36,11 +36,12 @@ use solver::{
     orderbook::OrderBookApi,
     s3_instance_upload::S3InstanceUploader,
     settlement_post_processing::PostProcessingPipeline,
+    settlement_ranker::objective,
     settlement_submission::{
         gelato::GelatoSubmitter,
         submitter::{
-            eden_api::EdenApi, flashbots_api::FlashbotsApi, public_mempool_api::PublicMempoolApi,
-            Strategy,
+            builder_api::BuilderApi, eden_api::EdenApi, flashbots_api::FlashbotsApi,
+            public_mempool_api::PublicMempoolApi, Strategy,
         },
         GlobalTxPool, SolutionSubmitter, StrategyArgs, TransactionStrategy,
     },
@@ -386,6 +387,23 @@ async fn main() -> ! {
                     }))
                 }
             }
+            TransactionStrategyArg::Builder => {
+                assert!(
+                    !args.builder_endpoints.is_empty(),
+                    "missing builder endpoints"
+                );
+                transaction_strategies.push(TransactionStrategy::Builder(StrategyArgs {
+                    submit_api: Box::new(BuilderApi::new(
+                        http_factory.create(),
+                        web3.clone(),
+                        args.builder_endpoints.clone(),
+                        args.builder_signing_key.clone(),
+                    )),
+                    max_additional_tip: args.max_additional_builder_tip,
+                    additional_tip_percentage_of_max_fee: args.additional_tip_percentage,
+                    sub_tx_pool: submitted_transactions.add_sub_pool(Strategy::Builder),
+                }))
+            }
             TransactionStrategyArg::PublicMempool => {
                 assert!(
                     !submission_nodes.is_empty(),
@@ -475,6 +493,15 @@ async fn main() -> ! {
         tenderly_api,
         args.solution_comparison_decimal_cutoff,
         code_fetcher,
+        DriverConfig {
+            bisect_failing_settlements: args.bisect_failing_settlements,
+            ranking_objective: objective::create(
+                args.ranking_objective,
+                args.ranking_revert_probability,
+                args.ranking_reward_native_value,
+            ),
+            max_settlements_per_auction: args.max_settlements_per_auction,
+        },
     );
 
     let maintainer = ServiceMaintenance::new(maintainers);
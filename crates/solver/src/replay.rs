@@ -0,0 +1,698 @@
+//! Replays recorded auctions against solvers.
+//!
+//! This makes it possible to reproduce solver regressions offline: the
+//! recorded orders, prices and liquidity are fed to the solvers as they were,
+//! and no request is sent to a node unless simulation is requested with
+//! `--node-url`.
+//!
+//! Only the naive and baseline solvers can be replayed, and only the constant
+//! product AMMs of a recorded instance are converted to liquidity. Other AMMs
+//! are skipped with a warning. With `--fetch-liquidity`, the UniswapV2 pools
+//! for the traded tokens are also fetched from the node.
+//!
+//! The resulting settlements are logged together with the rest of the tool's
+//! output, ranked by their objective value.
+
+pub mod arguments;
+
+use self::arguments::Arguments;
+use crate::{
+    driver::solver_settlements::RatedSettlement,
+    interactions::allowances::Allowances,
+    liquidity::{
+        order_converter::OrderConverter,
+        slippage::SlippageCalculator,
+        uniswap_v2::{self, UniswapLikeLiquidity},
+        ConstantProductOrder, LimitOrder, Liquidity,
+    },
+    liquidity_collector::LiquidityCollecting,
+    settlement::{external_prices::ExternalPrices, Settlement},
+    settlement_access_list::{create_priority_estimator, AccessListEstimatorType},
+    settlement_rater::{SettlementRater, SettlementRating},
+    solver::{
+        baseline_solver::BaselineSolver, naive_solver::NaiveSolver, Auction, Solver, SolverType,
+    },
+};
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use contracts::{GPv2Settlement, IUniswapLikeRouter, UniswapV2Router02, WETH9};
+use ethcontract::Account;
+use gas_estimation::GasPrice1559;
+use itertools::Itertools;
+use model::{
+    auction::{AuctionId, AuctionWithId},
+    order::{Order, OrderClass, OrderData, OrderKind, OrderMetadata, OrderUid},
+    ratio_as_decimal,
+    u256_decimal::{self, DecimalU256},
+    TokenPair,
+};
+use num::{rational::Ratio, BigRational, FromPrimitive, ToPrimitive, Zero};
+use primitive_types::{H160, U256};
+use serde::Deserialize;
+use serde_with::serde_as;
+use shared::{
+    baseline_solver::BaseTokens, code_fetching::CachedCodeFetcher, dummy_contract, ethrpc::Web3,
+    http_client::HttpClientFactory, http_solver::model::TokenAmount, recent_block_cache::Block,
+};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// A recorded auction.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Instance {
+    /// An auction as returned by the orderbook. It doesn't contain any
+    /// liquidity.
+    Auction(Box<AuctionWithId>),
+    /// A solver instance as uploaded to S3. Only constant product pools are
+    /// replayed, other kinds of liquidity are ignored.
+    Model(Box<BatchAuctionModel>),
+}
+
+/// The parts of a serialized `BatchAuctionModel` needed to replay it.
+#[derive(Debug, Deserialize)]
+pub struct BatchAuctionModel {
+    tokens: BTreeMap<H160, TokenInfoModel>,
+    orders: BTreeMap<usize, OrderModel>,
+    amms: BTreeMap<H160, AmmModel>,
+    metadata: Option<MetadataModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenInfoModel {
+    external_price: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrderModel {
+    id: Option<OrderUid>,
+    sell_token: H160,
+    buy_token: H160,
+    #[serde(with = "u256_decimal")]
+    sell_amount: U256,
+    #[serde(with = "u256_decimal")]
+    buy_amount: U256,
+    allow_partial_fill: bool,
+    is_sell_order: bool,
+    fee: TokenAmount,
+    is_liquidity_order: bool,
+    is_mature: bool,
+    reward: f64,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind")]
+enum AmmModel {
+    ConstantProduct {
+        #[serde_as(as = "BTreeMap<_, DecimalU256>")]
+        reserves: BTreeMap<H160, U256>,
+        #[serde(with = "ratio_as_decimal")]
+        fee: BigRational,
+    },
+    #[serde(other)]
+    Unsupported,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetadataModel {
+    auction_id: Option<AuctionId>,
+    gas_price: Option<f64>,
+    native_token: Option<H160>,
+}
+
+/// The contracts used to encode the settlements of the replayed auction.
+struct Contracts {
+    settlement: GPv2Settlement,
+    uniswap_router: IUniswapLikeRouter,
+}
+
+pub async fn main(args: Arguments) {
+    if let Err(err) = run(args).await {
+        tracing::error!(?err, "failed to replay auction");
+        std::process::exit(1);
+    }
+}
+
+async fn run(args: Arguments) -> Result<()> {
+    let instance: Instance = serde_json::from_slice(
+        &std::fs::read(&args.instance).context("failed to read recorded auction")?,
+    )
+    .context("failed to parse recorded auction")?;
+
+    let web3 = args.node_url.as_ref().map(|url| {
+        shared::ethrpc::web3(
+            &args.ethrpc,
+            &HttpClientFactory::new(&args.http_client),
+            url,
+            "replay",
+        )
+    });
+    let settlement = match &web3 {
+        Some(web3) => GPv2Settlement::deployed(web3)
+            .await
+            .context("settlement contract")?,
+        None => dummy_contract!(GPv2Settlement, H160::zero()),
+    };
+    let contracts = Contracts {
+        settlement,
+        uniswap_router: dummy_contract!(IUniswapLikeRouter, args.uniswap_router),
+    };
+
+    let native_token = args.native_token.or_else(|| instance.native_token());
+    let native_token = native_token.context("native token is not known, set --native-token")?;
+    let gas_price = args
+        .gas_price
+        .or_else(|| instance.gas_price())
+        .unwrap_or_default();
+    let order_converter = OrderConverter {
+        native_token: dummy_contract!(WETH9, native_token),
+        fee_objective_scaling_factor: args.fee_objective_scaling_factor,
+        min_order_age: Duration::ZERO,
+    };
+    let mut auction = instance.into_auction(
+        &order_converter,
+        &contracts,
+        gas_price,
+        Instant::now() + args.solver_time_limit,
+    )?;
+
+    let base_tokens = Arc::new(BaseTokens::new(native_token, &args.base_tokens));
+    if args.fetch_liquidity {
+        let web3 = web3
+            .as_ref()
+            .context("fetching liquidity requires --node-url")?;
+        let liquidity = fetch_liquidity(web3, contracts.settlement.clone(), &base_tokens, &auction)
+            .await
+            .context("failed to fetch liquidity")?;
+        // Pools which were recorded with the auction are kept as they were.
+        let recorded = auction
+            .liquidity
+            .iter()
+            .filter_map(Liquidity::address)
+            .collect::<HashSet<_>>();
+        auction.liquidity.extend(liquidity.into_iter().filter(
+            |liquidity| match liquidity.address() {
+                Some(address) => !recorded.contains(&address),
+                None => true,
+            },
+        ));
+    }
+    tracing::info!(
+        orders = auction.orders.len(),
+        liquidity = auction.liquidity.len(),
+        "replaying auction {}",
+        auction.id,
+    );
+
+    let account = Account::Local(args.solver_account, None);
+    let solvers = args
+        .solvers
+        .iter()
+        .map(|solver| create_solver(*solver, account.clone(), base_tokens.clone()))
+        .collect::<Result<Vec<_>>>()?;
+
+    let results = futures::future::join_all(solvers.iter().map(|solver| {
+        let auction = auction.clone();
+        async move { (solver.clone(), solver.solve(auction).await) }
+    }))
+    .await;
+    let mut settlements = Vec::new();
+    for (solver, result) in results {
+        match result {
+            Ok(result) => {
+                tracing::info!(
+                    solver = %solver.name(),
+                    settlements = result.len(),
+                    "solver found settlements"
+                );
+                settlements.extend(result.into_iter().map(|s| (solver.clone(), s)));
+            }
+            Err(err) => tracing::warn!(solver = %solver.name(), ?err, "solver failed"),
+        }
+    }
+
+    let mut rated = match web3 {
+        Some(web3) => {
+            let network_id = web3.net().version().await?;
+            let rater = SettlementRater {
+                access_list_estimator: Arc::new(create_priority_estimator(
+                    &web3,
+                    &[AccessListEstimatorType::Web3],
+                    None,
+                    network_id,
+                )?),
+                code_fetcher: Arc::new(CachedCodeFetcher::new(Arc::new(web3.clone()))),
+                settlement_contract: contracts.settlement,
+                web3,
+                bisect_failing_settlements: false,
+            };
+            let gas_price = GasPrice1559 {
+                base_fee_per_gas: gas_price,
+                max_fee_per_gas: gas_price,
+                max_priority_fee_per_gas: 0.,
+            };
+            let (rated, errors) = rater
+                .rate_settlements(settlements, &auction.external_prices, gas_price)
+                .await?;
+            for error in errors {
+                tracing::warn!(
+                    solver = %error.simulation.solver.name(),
+                    err = %error.error,
+                    "settlement failed to simulate"
+                );
+            }
+            rated
+                .into_iter()
+                .map(|(solver, settlement, _)| (solver, settlement))
+                .collect()
+        }
+        None => {
+            tracing::info!(
+                "settlements are ranked without gas costs because they weren't simulated"
+            );
+            settlements
+                .into_iter()
+                .enumerate()
+                .map(|(id, (solver, settlement))| {
+                    let rated = rate_without_simulation(
+                        id,
+                        settlement,
+                        &auction.external_prices,
+                        gas_price,
+                    );
+                    (solver, rated)
+                })
+                .collect::<Vec<_>>()
+        }
+    };
+
+    rated.sort_by_key(|(_, settlement)| std::cmp::Reverse(settlement.objective_value()));
+    for (rank, (solver, settlement)) in rated.iter().enumerate() {
+        tracing::info!(
+            "{}. {}: objective value {:.0}, surplus {:.0}, fees {:.0}, gas {}, trades {}",
+            rank + 1,
+            solver.name(),
+            settlement.objective_value().to_f64().unwrap_or(f64::NAN),
+            settlement.surplus.to_f64().unwrap_or(f64::NAN),
+            settlement
+                .scaled_unsubsidized_fee
+                .to_f64()
+                .unwrap_or(f64::NAN),
+            settlement.gas_estimate,
+            settlement.settlement.trades().count(),
+        );
+    }
+    Ok(())
+}
+
+/// Fetches the UniswapV2 pools which are relevant for the orders of the
+/// auction, at the block the auction was recorded at if it is known.
+async fn fetch_liquidity(
+    web3: &Web3,
+    settlement: GPv2Settlement,
+    base_tokens: &BaseTokens,
+    auction: &Auction,
+) -> Result<Vec<Liquidity>> {
+    let (_, pool_fetcher) = shared::sources::uniswap_v2::get_liquidity_source(web3)
+        .await
+        .context("UniswapV2 pool fetcher")?;
+    let router = UniswapV2Router02::deployed(web3)
+        .await
+        .context("UniswapV2 router")?;
+    let liquidity = UniswapLikeLiquidity::new(
+        IUniswapLikeRouter::at(web3, router.address()),
+        settlement,
+        web3.clone(),
+        pool_fetcher,
+    );
+    let pairs = base_tokens.relevant_pairs(
+        auction
+            .orders
+            .iter()
+            .filter_map(|order| TokenPair::new(order.sell_token, order.buy_token)),
+    );
+    let block = match auction.liquidity_fetch_block {
+        0 => Block::Recent,
+        block => Block::Number(block),
+    };
+    liquidity.get_liquidity(pairs, block).await
+}
+
+fn create_solver(
+    solver_type: SolverType,
+    account: Account,
+    base_tokens: Arc<BaseTokens>,
+) -> Result<Arc<dyn Solver>> {
+    let slippage_calculator = SlippageCalculator::default();
+    match solver_type {
+        SolverType::Naive => Ok(Arc::new(NaiveSolver::new(account, slippage_calculator))),
+        SolverType::Baseline => Ok(Arc::new(BaselineSolver::new(
+            account,
+            base_tokens,
+            slippage_calculator,
+        ))),
+        _ => bail!(
+            "{solver_type:?} solver can't be replayed, only the Naive and Baseline solvers are \
+             supported"
+        ),
+    }
+}
+
+fn rate_without_simulation(
+    id: usize,
+    settlement: Settlement,
+    prices: &ExternalPrices,
+    gas_price: f64,
+) -> RatedSettlement {
+    RatedSettlement {
+        id,
+        surplus: settlement.total_surplus(prices),
+        unscaled_subsidized_fee: settlement.total_unscaled_subsidized_fees(prices),
+        scaled_unsubsidized_fee: settlement.total_scaled_unsubsidized_fees(prices),
+        settlement,
+        gas_estimate: U256::zero(),
+        gas_price: BigRational::from_f64(gas_price).unwrap_or_else(BigRational::zero),
+    }
+}
+
+impl Instance {
+    fn native_token(&self) -> Option<H160> {
+        match self {
+            Instance::Auction(_) => None,
+            Instance::Model(model) => model.metadata.as_ref()?.native_token,
+        }
+    }
+
+    fn gas_price(&self) -> Option<f64> {
+        match self {
+            Instance::Auction(_) => None,
+            Instance::Model(model) => model.metadata.as_ref()?.gas_price,
+        }
+    }
+
+    fn into_auction(
+        self,
+        order_converter: &OrderConverter,
+        contracts: &Contracts,
+        gas_price: f64,
+        deadline: Instant,
+    ) -> Result<Auction> {
+        let native_token = order_converter.native_token.address();
+        match self {
+            Instance::Auction(auction) => {
+                let AuctionWithId { id, auction } = *auction;
+                let orders = auction
+                    .orders
+                    .into_iter()
+                    .map(|order| {
+                        let reward = auction
+                            .rewards
+                            .get(&order.metadata.uid)
+                            .copied()
+                            .unwrap_or(0.);
+                        let mut order = order_converter.normalize_limit_order(order)?;
+                        order.reward = reward;
+                        Ok(order)
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(Auction {
+                    id,
+                    orders,
+                    liquidity_fetch_block: auction.block,
+                    gas_price,
+                    deadline,
+                    external_prices: ExternalPrices::try_from_auction_prices(
+                        native_token,
+                        auction.prices,
+                    )?,
+                    ..Default::default()
+                })
+            }
+            Instance::Model(model) => {
+                let orders = model
+                    .orders
+                    .into_iter()
+                    .map(|(index, order)| order.into_limit_order(index, order_converter))
+                    .collect::<Result<_>>()?;
+                let amms = model.amms.len();
+                let liquidity = model
+                    .amms
+                    .into_iter()
+                    .filter_map(|(address, amm)| amm.into_liquidity(address, contracts))
+                    .collect::<Result<Vec<_>>>()?;
+                if liquidity.len() < amms {
+                    tracing::warn!(
+                        skipped = amms - liquidity.len(),
+                        "only constant product AMMs are replayed, the other AMMs of the instance \
+                         were skipped"
+                    );
+                }
+                let prices = model
+                    .tokens
+                    .into_iter()
+                    .filter_map(|(token, info)| {
+                        Some((token, BigRational::from_f64(info.external_price?)?))
+                    })
+                    .collect::<HashMap<_, _>>();
+                Ok(Auction {
+                    id: model
+                        .metadata
+                        .and_then(|metadata| metadata.auction_id)
+                        .unwrap_or_default(),
+                    orders,
+                    liquidity,
+                    gas_price,
+                    deadline,
+                    external_prices: ExternalPrices::new(native_token, prices)?,
+                    ..Default::default()
+                })
+            }
+        }
+    }
+}
+
+impl OrderModel {
+    fn into_limit_order(
+        self,
+        index: usize,
+        order_converter: &OrderConverter,
+    ) -> Result<LimitOrder> {
+        // Orders are only identified by their index in the instance if the
+        // solver didn't include their UID.
+        let uid = match self.id {
+            Some(uid) => uid,
+            None => OrderUid::from_integer(index.try_into()?),
+        };
+        let order = Order {
+            data: OrderData {
+                sell_token: self.sell_token,
+                buy_token: self.buy_token,
+                sell_amount: self.sell_amount,
+                buy_amount: self.buy_amount,
+                valid_to: u32::MAX,
+                fee_amount: self.fee.amount,
+                kind: if self.is_sell_order {
+                    OrderKind::Sell
+                } else {
+                    OrderKind::Buy
+                },
+                partially_fillable: self.allow_partial_fill,
+                ..Default::default()
+            },
+            metadata: OrderMetadata {
+                uid,
+                class: if self.is_liquidity_order {
+                    OrderClass::Liquidity
+                } else {
+                    OrderClass::Market
+                },
+                full_fee_amount: self.fee.amount,
+                is_liquidity_order: self.is_liquidity_order,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut order = order_converter.normalize_limit_order(order)?;
+        order.is_mature = self.is_mature;
+        order.reward = self.reward;
+        Ok(order)
+    }
+}
+
+impl AmmModel {
+    fn into_liquidity(self, address: H160, contracts: &Contracts) -> Option<Result<Liquidity>> {
+        match self {
+            AmmModel::ConstantProduct { reserves, fee } => Some(
+                constant_product(address, reserves, fee, contracts).map(Liquidity::ConstantProduct),
+            ),
+            AmmModel::Unsupported => {
+                tracing::warn!(?address, "skipping AMM which isn't a constant product pool");
+                None
+            }
+        }
+    }
+}
+
+fn constant_product(
+    address: H160,
+    reserves: BTreeMap<H160, U256>,
+    fee: BigRational,
+    contracts: &Contracts,
+) -> Result<ConstantProductOrder> {
+    ensure!(
+        reserves.len() == 2,
+        "constant product pool without two tokens"
+    );
+    // The reserves are sorted by token address, like the tokens of a pair.
+    let ((token0, reserve0), (token1, reserve1)) = reserves.into_iter().next_tuple().unwrap();
+    let to_u128 = |reserve: U256| -> Result<u128> {
+        reserve
+            .try_into()
+            .map_err(|_| anyhow!("reserve overflows u128"))
+    };
+    let to_u32 = |value: &num::BigInt| value.to_u32().context("fee overflows u32");
+    Ok(ConstantProductOrder {
+        address,
+        tokens: TokenPair::new(token0, token1).context("pool with identical tokens")?,
+        reserves: (to_u128(reserve0)?, to_u128(reserve1)?),
+        fee: Ratio::new(to_u32(fee.numer())?, to_u32(fee.denom())?),
+        settlement_handling: Arc::new(uniswap_v2::Inner::new(
+            contracts.uniswap_router.clone(),
+            contracts.settlement.clone(),
+            Mutex::new(Allowances::empty(contracts.uniswap_router.address())),
+        )),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn converts_batch_auction_model() {
+        let native_token = H160([0xee; 20]);
+        let token = H160([0x01; 20]);
+        let instance: Instance = serde_json::from_value(json!({
+            "tokens": {
+                "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee": {
+                    "decimals": 18,
+                    "alias": "WETH",
+                    "external_price": 1.0,
+                    "normalize_priority": 1,
+                    "internal_buffer": "0",
+                    "accepted_for_internalization": true,
+                },
+                "0x0101010101010101010101010101010101010101": {
+                    "decimals": 18,
+                    "alias": null,
+                    "external_price": 0.5,
+                    "normalize_priority": 0,
+                    "internal_buffer": "0",
+                    "accepted_for_internalization": false,
+                },
+            },
+            "orders": {
+                "0": {
+                    "sell_token": "0x0101010101010101010101010101010101010101",
+                    "buy_token": "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee",
+                    "sell_amount": "2000",
+                    "buy_amount": "900",
+                    "allow_partial_fill": false,
+                    "is_sell_order": true,
+                    "fee": {
+                        "amount": "10",
+                        "token": "0x0101010101010101010101010101010101010101",
+                    },
+                    "cost": {
+                        "amount": "1",
+                        "token": "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee",
+                    },
+                    "is_liquidity_order": false,
+                    "is_mature": true,
+                    "mandatory": false,
+                    "has_atomic_execution": false,
+                    "reward": 0.5,
+                },
+            },
+            "amms": {
+                "0x0202020202020202020202020202020202020202": {
+                    "kind": "ConstantProduct",
+                    "reserves": {
+                        "0x0101010101010101010101010101010101010101": "20000",
+                        "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee": "10000",
+                    },
+                    "fee": "0.003",
+                    "cost": {
+                        "amount": "1",
+                        "token": "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee",
+                    },
+                    "mandatory": false,
+                    "address": "0x0202020202020202020202020202020202020202",
+                },
+                "0x0303030303030303030303030303030303030303": {
+                    "kind": "Stable",
+                    "reserves": {},
+                    "scaling_rates": {},
+                    "amplification_parameter": "1",
+                    "fee": "0.003",
+                    "cost": {
+                        "amount": "1",
+                        "token": "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee",
+                    },
+                    "mandatory": false,
+                    "address": "0x0303030303030303030303030303030303030303",
+                },
+            },
+            "metadata": {
+                "environment": null,
+                "auction_id": 42,
+                "run_id": 1,
+                "gas_price": 100.0,
+                "native_token": "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee",
+            },
+        }))
+        .unwrap();
+        assert_eq!(instance.native_token(), Some(native_token));
+        assert_eq!(instance.gas_price(), Some(100.));
+
+        let contracts = Contracts {
+            settlement: dummy_contract!(GPv2Settlement, H160::zero()),
+            uniswap_router: dummy_contract!(IUniswapLikeRouter, H160::zero()),
+        };
+        let auction = instance
+            .into_auction(
+                &OrderConverter::test(native_token),
+                &contracts,
+                100.,
+                Instant::now(),
+            )
+            .unwrap();
+
+        assert_eq!(auction.id, 42);
+        assert_eq!(auction.orders.len(), 1);
+        let order = &auction.orders[0];
+        assert_eq!(order.id.order_uid(), Some(OrderUid::from_integer(0)));
+        assert_eq!(order.sell_token, token);
+        assert_eq!(order.kind, OrderKind::Sell);
+        assert_eq!(order.reward, 0.5);
+        assert!(!order.is_liquidity_order());
+
+        assert_eq!(auction.liquidity.len(), 1);
+        match &auction.liquidity[0] {
+            Liquidity::ConstantProduct(pool) => {
+                assert_eq!(pool.tokens, TokenPair::new(token, native_token).unwrap());
+                assert_eq!(pool.reserves, (20000, 10000));
+                assert_eq!(pool.fee, Ratio::new(3, 1000));
+            }
+            liquidity => panic!("unexpected liquidity {liquidity:?}"),
+        }
+        assert_eq!(
+            auction.external_prices.price(&token),
+            Some(&BigRational::new(1.into(), 2.into()))
+        );
+    }
+}
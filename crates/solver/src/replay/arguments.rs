@@ -0,0 +1,133 @@
+use crate::solver::SolverType;
+use primitive_types::H160;
+use reqwest::Url;
+use shared::{
+    arguments::{display_list, display_option, duration_from_seconds},
+    ethrpc, http_client, logging_args_with_default_filter,
+};
+use std::{path::PathBuf, time::Duration};
+use tracing::level_filters::LevelFilter;
+
+logging_args_with_default_filter!(LoggingArguments, "warn,solver=debug,shared=debug");
+
+/// Replays a recorded auction against the naive and baseline solvers.
+///
+/// Only the constant product AMMs of a recorded instance are replayed, other
+/// AMMs (e.g. stable, weighted or concentrated liquidity pools) are skipped
+/// with a warning. Auctions recorded by the orderbook don't contain any
+/// liquidity, use `--fetch-liquidity` to fetch UniswapV2 pools for them.
+#[derive(clap::Parser)]
+pub struct Arguments {
+    #[clap(flatten)]
+    pub http_client: http_client::Arguments,
+
+    #[clap(flatten)]
+    pub ethrpc: ethrpc::Arguments,
+
+    #[clap(flatten)]
+    pub logging: LoggingArguments,
+
+    /// Path to a recorded auction. This can either be an `AuctionWithId` as
+    /// returned by the orderbook or a `BatchAuctionModel` as uploaded to S3 by
+    /// the solver. Only the latter contains liquidity, and only its constant
+    /// product AMMs are replayed.
+    pub instance: PathBuf,
+
+    /// The solvers to run against the recorded auction. Only the Naive and
+    /// Baseline solvers can be replayed, the other solvers depend on a node or
+    /// an external API.
+    #[clap(
+        long,
+        env,
+        default_value = "Naive,Baseline",
+        use_value_delimiter = true
+    )]
+    pub solvers: Vec<SolverType>,
+
+    /// The native token of the chain the auction was recorded on. Required for
+    /// auctions that don't specify it in their metadata.
+    #[clap(long, env)]
+    pub native_token: Option<H160>,
+
+    /// The base tokens used by the baseline solver for multi-hop routes.
+    #[clap(long, env, use_value_delimiter = true)]
+    pub base_tokens: Vec<H160>,
+
+    /// The router used to encode interactions with constant product pools.
+    /// Only relevant when simulating settlements.
+    #[clap(
+        long,
+        env,
+        default_value = "0x0000000000000000000000000000000000000000"
+    )]
+    pub uniswap_router: H160,
+
+    /// The gas price in wei. Defaults to the gas price in the metadata of the
+    /// recorded auction, or 0 if there is none.
+    #[clap(long, env)]
+    pub gas_price: Option<f64>,
+
+    /// The factor by which the fees of orders are scaled in the objective
+    /// value.
+    #[clap(long, env, default_value = "1")]
+    pub fee_objective_scaling_factor: f64,
+
+    /// The time the solvers have to compute their settlements.
+    #[clap(
+        long,
+        env,
+        default_value = "30",
+        value_parser = duration_from_seconds,
+    )]
+    pub solver_time_limit: Duration,
+
+    /// The node used to simulate the settlements. If it is not set, no
+    /// request is sent to a node and settlements are ranked without gas costs.
+    #[clap(long, env)]
+    pub node_url: Option<Url>,
+
+    /// Fetch the UniswapV2 pools for the traded tokens and the base tokens from
+    /// the node, at the block the auction was recorded at if it is known. The
+    /// pools are added to the liquidity of the recorded auction.
+    #[clap(long, env, requires = "node_url")]
+    pub fetch_liquidity: bool,
+
+    /// The account which is used as the sender when simulating settlements.
+    #[clap(
+        long,
+        env,
+        default_value = "0x0000000000000000000000000000000000000000"
+    )]
+    pub solver_account: H160,
+}
+
+impl std::fmt::Display for Arguments {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.http_client)?;
+        write!(f, "{}", self.ethrpc)?;
+        writeln!(f, "instance: {}", self.instance.display())?;
+        writeln!(f, "solvers: {:?}", self.solvers)?;
+        display_option(
+            f,
+            "native_token",
+            &self.native_token.map(|t| format!("{t:?}")),
+        )?;
+        display_list(
+            f,
+            "base_tokens",
+            self.base_tokens.iter().map(|t| format!("{t:?}")),
+        )?;
+        writeln!(f, "uniswap_router: {:?}", self.uniswap_router)?;
+        display_option(f, "gas_price", &self.gas_price)?;
+        writeln!(
+            f,
+            "fee_objective_scaling_factor: {}",
+            self.fee_objective_scaling_factor
+        )?;
+        writeln!(f, "solver_time_limit: {:?}", self.solver_time_limit)?;
+        display_option(f, "node_url", &self.node_url)?;
+        writeln!(f, "fetch_liquidity: {}", self.fetch_liquidity)?;
+        writeln!(f, "solver_account: {:?}", self.solver_account)?;
+        Ok(())
+    }
+}
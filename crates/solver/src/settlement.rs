@@ -9,7 +9,7 @@ use crate::{
 };
 use anyhow::{ensure, Result};
 use itertools::Itertools;
-use model::order::{Order, OrderKind};
+use model::order::{Order, OrderClass, OrderKind, OrderUid};
 use num::{rational::Ratio, BigInt, BigRational, One, Signed, Zero};
 use primitive_types::{H160, U256};
 use shared::{
@@ -147,6 +147,14 @@ impl Trade {
         self.compute_fee_execution(self.order.data.fee_amount)
     }
 
+    /// Returns the executed `surplus_fee` of a limit order (prorated of executed amount).
+    pub fn executed_surplus_fee(&self) -> Option<U256> {
+        match &self.order.metadata.class {
+            OrderClass::Limit(limit) => self.compute_fee_execution(limit.surplus_fee?),
+            _ => None,
+        }
+    }
+
     fn compute_fee_execution(&self, fee_amount: U256) -> Option<U256> {
         match self.order.data.kind {
             model::order::OrderKind::Buy => fee_amount
@@ -269,6 +277,15 @@ impl Settlement {
         }
     }
 
+    /// Returns a copy of the settlement without the user trades of the
+    /// specified orders.
+    pub fn without_user_trades(&self, uids: &HashSet<OrderUid>) -> Self {
+        Self {
+            encoder: self.encoder.without_user_trades(uids),
+            submitter: self.submitter.clone(),
+        }
+    }
+
     #[cfg(test)]
     pub fn with_trades(clearing_prices: HashMap<H160, U256>, trades: Vec<Trade>) -> Self {
         let encoder = SettlementEncoder::with_trades(clearing_prices, trades);
@@ -537,7 +554,7 @@ pub mod tests {
     use super::*;
     use crate::{liquidity::SettlementHandling, settlement::external_prices::externalprices};
     use maplit::hashmap;
-    use model::order::{LimitOrderClass, OrderClass, OrderData, OrderKind, OrderMetadata};
+    use model::order::{LimitOrderClass, OrderData, OrderKind, OrderMetadata};
     use num::FromPrimitive;
     use shared::addr;
 
@@ -4,7 +4,7 @@ use anyhow::{bail, ensure, Context as _, Result};
 use itertools::{Either, Itertools};
 use model::{
     interaction::InteractionData,
-    order::{LimitOrderClass, Order, OrderClass, OrderKind},
+    order::{BuyTokenDestination, LimitOrderClass, Order, OrderClass, OrderKind, OrderUid},
 };
 use num::{BigRational, One};
 use number_conversions::big_rational_to_u256;
@@ -43,6 +43,8 @@ pub struct SettlementEncoder {
     // TODO: Can we fix this in a better way?
     execution_plan: Vec<MaybeInternalizableInteraction>,
     pre_interactions: Vec<InteractionData>,
+    /// Post-interactions together with the order they belong to.
+    post_interactions: Vec<(OrderUid, InteractionData)>,
     unwraps: Vec<UnwrapWethInteraction>,
 }
 
@@ -114,6 +116,7 @@ impl SettlementEncoder {
             trades: Vec::new(),
             execution_plan: Vec::new(),
             pre_interactions: Vec::new(),
+            post_interactions: Vec::new(),
             unwraps: Vec::new(),
         }
     }
@@ -147,6 +150,43 @@ impl SettlementEncoder {
                 .map(|(execution, _)| (execution.clone(), true))
                 .collect(),
             pre_interactions: self.pre_interactions.clone(),
+            post_interactions: self.post_interactions.clone(),
+            unwraps: self.unwraps.clone(),
+        }
+    }
+
+    // Returns a copy of self without the user trades of the specified orders
+    // and their pre and post interactions.
+    pub fn without_user_trades(&self, uids: &HashSet<OrderUid>) -> Self {
+        let (removed, trades): (Vec<_>, Vec<_>) = self.trades.iter().cloned().partition(|trade| {
+            trade.data.order.is_user_order() && uids.contains(&trade.data.order.metadata.uid)
+        });
+
+        let mut pre_interactions = self.pre_interactions.clone();
+        for trade in &removed {
+            for interaction in &trade.data.order.interactions.pre {
+                if let Some(i) = pre_interactions
+                    .iter()
+                    .position(|other| other == interaction)
+                {
+                    pre_interactions.remove(i);
+                }
+            }
+        }
+        let removed_uids: HashSet<_> = removed
+            .iter()
+            .map(|trade| trade.data.order.metadata.uid)
+            .collect();
+        let mut post_interactions = self.post_interactions.clone();
+        post_interactions.retain(|(uid, _)| !removed_uids.contains(uid));
+
+        SettlementEncoder {
+            tokens: self.tokens.clone(),
+            clearing_prices: self.clearing_prices.clone(),
+            trades,
+            execution_plan: self.execution_plan.clone(),
+            pre_interactions,
+            post_interactions,
             unwraps: self.unwraps.clone(),
         }
     }
@@ -249,6 +289,7 @@ impl SettlementEncoder {
         executed_amount: U256,
         scaled_unsubsidized_fee: U256,
     ) -> Result<TradeExecution> {
+        let uid = order.metadata.uid;
         let interactions = order.interactions.clone();
         let execution = match &order.metadata.class {
             OrderClass::Market => {
@@ -265,12 +306,8 @@ impl SettlementEncoder {
                 )?
             }
             OrderClass::Limit(limit) => {
-                // Solvers calculate with slightly adjusted amounts compared to this order but because
-                // limit orders are fill-or-kill we can simply use the total original `sell_amount`.
-                let executed_amount = match order.data.kind {
-                    OrderKind::Sell => order.data.sell_amount,
-                    OrderKind::Buy => order.data.buy_amount,
-                };
+                let executed_amount =
+                    executed_amount_for_limit_order(&order, limit, executed_amount)?;
                 let (sell_price, buy_price) = self.custom_price_for_limit_order(&order, limit)?;
 
                 self.add_custom_price_trade(
@@ -283,6 +320,12 @@ impl SettlementEncoder {
             }
         };
         self.pre_interactions.extend(interactions.pre.into_iter());
+        self.post_interactions.extend(
+            interactions
+                .post
+                .into_iter()
+                .map(|interaction| (uid, interaction)),
+        );
         Ok(execution)
     }
 
@@ -601,7 +644,13 @@ impl SettlementEncoder {
                     )
                     .chain(self.unwraps.iter().flat_map(|unwrap| unwrap.encode()))
                     .collect(),
-                Vec::new(),
+                // An order traded more than once executes its post-interactions only once, but
+                // equal post-interactions of different orders are all executed.
+                self.post_interactions
+                    .into_iter()
+                    .unique()
+                    .flat_map(|(_, interaction)| interaction.encode())
+                    .collect(),
             ],
         }
     }
@@ -644,6 +693,8 @@ impl SettlementEncoder {
         self.sort_tokens_and_update_indices();
 
         self.execution_plan.append(&mut other.execution_plan);
+        self.pre_interactions.append(&mut other.pre_interactions);
+        self.post_interactions.append(&mut other.post_interactions);
 
         for unwrap in other.unwraps {
             self.add_unwrap(unwrap);
@@ -691,6 +742,25 @@ impl SettlementEncoder {
             }
         })
     }
+
+    /// Calculates how much of each token this settlement deposits into Vault internal balances
+    /// of order receivers. The Vault pulls these tokens from the settlement contract so it needs
+    /// an allowance for them.
+    pub fn internal_buy_token_amounts(&self) -> HashMap<H160, U256> {
+        let mut amounts = HashMap::<H160, U256>::new();
+        for trade in self.all_trades() {
+            if trade.data.order.data.buy_token_balance != BuyTokenDestination::Internal {
+                continue;
+            }
+            let buy_amount = trade
+                .executed_amounts()
+                .map(|execution| execution.buy_amount)
+                .unwrap_or_else(U256::max_value);
+            let amount = amounts.entry(trade.data.order.data.buy_token).or_default();
+            *amount = amount.saturating_add(buy_amount);
+        }
+        amounts
+    }
 }
 
 impl PricedTrade<'_> {
@@ -710,6 +780,43 @@ impl PricedTrade<'_> {
     }
 }
 
+/// Solvers calculate with slightly adjusted amounts for limit orders (see
+/// `compute_synthetic_order_amounts_for_limit_order()`). This converts the executed amount of
+/// the adjusted order back into the executed amount of the original order signed by the user.
+fn executed_amount_for_limit_order(
+    order: &Order,
+    limit: &LimitOrderClass,
+    executed_amount: U256,
+) -> Result<U256> {
+    match (order.data.partially_fillable, order.data.kind) {
+        // Fill-or-kill orders are always executed completely so we can simply use the total
+        // original amounts.
+        (false, OrderKind::Sell) => Ok(order.data.sell_amount),
+        (false, OrderKind::Buy) => Ok(order.data.buy_amount),
+        // The `buy_amount` is not adjusted for the `surplus_fee`.
+        (true, OrderKind::Buy) => Ok(executed_amount),
+        // Scale the executed amount from the solver's `sell_amount` which was reduced by the
+        // `surplus_fee` up to the original `sell_amount`.
+        (true, OrderKind::Sell) => {
+            let surplus_fee = limit
+                .surplus_fee
+                .context("solvable order without surplus fee")?;
+            let adjusted_sell_amount = order
+                .data
+                .sell_amount
+                .checked_add(order.data.fee_amount)
+                .context("adjusted sell_amount computation failed")?
+                .checked_sub(surplus_fee)
+                .context("adjusted sell_amount computation failed")?;
+            executed_amount
+                .checked_mul(order.data.sell_amount)
+                .context("executed_amount computation failed")?
+                .checked_div(adjusted_sell_amount)
+                .context("executed_amount computation failed")
+        }
+    }
+}
+
 pub fn verify_executed_amount(order: &Order, executed: U256) -> Result<()> {
     let remaining = shared::remaining_amounts::Remaining::from_order(order)?;
     let valid_executed_amount = match (order.data.partially_fillable, order.data.kind) {
@@ -728,8 +835,8 @@ pub mod tests {
     use crate::settlement::NoopInteraction;
     use contracts::WETH9;
     use ethcontract::Bytes;
-    use maplit::hashmap;
-    use model::order::{OrderBuilder, OrderData};
+    use maplit::{hashmap, hashset};
+    use model::order::{Interactions, OrderBuilder, OrderData};
     use shared::{
         dummy_contract,
         interaction::{EncodedInteraction, Interaction},
@@ -1250,6 +1357,33 @@ pub mod tests {
         assert_eq!(updated_buy_token_index, 1.into());
     }
 
+    #[test]
+    fn sums_internal_buy_token_amounts() {
+        let prices = hashmap! { token(1) => 1.into(), token(2) => 2.into(), token(3) => 4.into() };
+        let mut encoder = SettlementEncoder::new(prices);
+        let mut add_trade = |buy_token, buy_amount: u64, buy_token_balance| {
+            let order = OrderBuilder::default()
+                .with_kind(OrderKind::Buy)
+                .with_sell_token(token(3))
+                .with_sell_amount(100.into())
+                .with_buy_token(buy_token)
+                .with_buy_amount(buy_amount.into())
+                .with_buy_token_balance(buy_token_balance)
+                .build();
+            encoder
+                .add_trade(order, buy_amount.into(), 0.into())
+                .unwrap();
+        };
+        add_trade(token(1), 10, BuyTokenDestination::Internal);
+        add_trade(token(1), 5, BuyTokenDestination::Internal);
+        add_trade(token(2), 10, BuyTokenDestination::Erc20);
+
+        assert_eq!(
+            encoder.internal_buy_token_amounts(),
+            hashmap! { token(1) => 15.into() }
+        );
+    }
+
     #[derive(Debug)]
     pub struct TestInteraction;
     impl Interaction for TestInteraction {
@@ -1275,6 +1409,126 @@ pub mod tests {
         assert_eq!(encoded.interactions[1].len(), 2);
     }
 
+    #[test]
+    fn encodes_pre_and_post_interactions_of_orders() {
+        let prices = hashmap! { token(1) => 1.into(), token(2) => 1.into() };
+        let interaction = |byte: u8| InteractionData {
+            target: H160([byte; 20]),
+            value: 0.into(),
+            call_data: vec![byte],
+        };
+        let mut order = OrderBuilder::default()
+            .with_sell_token(token(1))
+            .with_sell_amount(1.into())
+            .with_buy_token(token(2))
+            .with_buy_amount(1.into())
+            .build();
+        order.interactions = Interactions {
+            pre: vec![interaction(1)],
+            post: vec![interaction(2), interaction(3)],
+        };
+
+        let mut encoder = SettlementEncoder::new(prices);
+        encoder.add_trade(order, 1.into(), 0.into()).unwrap();
+        let encoded = encoder.finish(InternalizationStrategy::EncodeAllInteractions);
+
+        assert_eq!(encoded.interactions[0], interaction(1).encode());
+        assert!(encoded.interactions[1].is_empty());
+        assert_eq!(
+            encoded.interactions[2],
+            [interaction(2).encode(), interaction(3).encode()].concat()
+        );
+    }
+
+    #[test]
+    fn deduplicates_post_interactions_per_order() {
+        let prices = hashmap! { token(1) => 1.into(), token(2) => 1.into() };
+        let hook = InteractionData {
+            target: H160([1; 20]),
+            value: 0.into(),
+            call_data: vec![1],
+        };
+        let order = |uid: u8| {
+            let mut order = OrderBuilder::default()
+                .with_sell_token(token(1))
+                .with_sell_amount(2.into())
+                .with_buy_token(token(2))
+                .with_buy_amount(2.into())
+                .with_partially_fillable(true)
+                .build();
+            order.metadata.uid = OrderUid([uid; 56]);
+            order.interactions.post = vec![hook.clone()];
+            order
+        };
+
+        let mut encoder = SettlementEncoder::new(prices);
+        encoder.add_trade(order(1), 1.into(), 0.into()).unwrap();
+        encoder.add_trade(order(1), 1.into(), 0.into()).unwrap();
+        encoder.add_trade(order(2), 1.into(), 0.into()).unwrap();
+        let encoded = encoder.finish(InternalizationStrategy::EncodeAllInteractions);
+
+        assert_eq!(
+            encoded.interactions[2],
+            [hook.encode(), hook.encode()].concat()
+        );
+    }
+
+    #[test]
+    fn removes_user_trades_with_their_interactions() {
+        let prices = hashmap! { token(1) => 1.into(), token(2) => 1.into() };
+        let interaction = |byte: u8| InteractionData {
+            target: H160([byte; 20]),
+            value: 0.into(),
+            call_data: vec![byte],
+        };
+        let order = |uid: u8, class: OrderClass| {
+            let mut order = OrderBuilder::default()
+                .with_sell_token(token(1))
+                .with_sell_amount(1.into())
+                .with_buy_token(token(2))
+                .with_buy_amount(1.into())
+                .with_class(class)
+                .build();
+            order.metadata.uid = OrderUid([uid; 56]);
+            order.interactions = Interactions {
+                pre: vec![interaction(uid)],
+                post: vec![interaction(uid + 10)],
+            };
+            order
+        };
+
+        let mut encoder = SettlementEncoder::new(prices);
+        for (uid, class) in [
+            (1, OrderClass::Market),
+            (2, OrderClass::Market),
+            (3, OrderClass::Liquidity),
+        ] {
+            encoder
+                .add_trade(order(uid, class), 1.into(), 0.into())
+                .unwrap();
+        }
+
+        let uids = hashset! { OrderUid([2; 56]), OrderUid([3; 56]) };
+        let encoder = encoder.without_user_trades(&uids);
+
+        assert_eq!(
+            encoder
+                .all_trades()
+                .map(|trade| trade.data.order.metadata.uid)
+                .collect::<Vec<_>>(),
+            vec![OrderUid([1; 56]), OrderUid([3; 56])]
+        );
+        let encoded = encoder.finish(InternalizationStrategy::EncodeAllInteractions);
+        assert_eq!(
+            encoded.interactions[0],
+            [interaction(1).encode(), interaction(3).encode()].concat()
+        );
+        assert_eq!(
+            encoded.interactions[2],
+            [interaction(11).encode(), interaction(13).encode()].concat()
+        );
+    }
+
     #[test]
     fn computes_custom_price_for_sell_limit_order_correctly() {
         let weth = token(1);
@@ -1329,6 +1583,45 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn scales_executed_amount_for_partially_fillable_sell_limit_order() {
+        let weth = token(1);
+        let usdc = token(2);
+        let prices = hashmap! {
+            weth => U256::exp10(18),
+            usdc => U256::exp10(27)
+        };
+
+        let mut encoder = SettlementEncoder::new(prices);
+        // sell 1.01 WETH for 1_000 USDC with a fee of 0.01 WETH (or 10 USDC)
+        let order = OrderBuilder::default()
+            .with_class(OrderClass::Limit(Default::default()))
+            .with_sell_token(weth)
+            .with_sell_amount(1_010_000_000_000_000_000u128.into()) // 1.01 WETH
+            .with_buy_token(usdc)
+            .with_buy_amount(U256::exp10(9)) // 1_000 USDC
+            .with_surplus_fee(U256::exp10(16)) // 0.01 WETH
+            .with_fee_amount(0.into())
+            .with_kind(OrderKind::Sell)
+            .with_partially_fillable(true)
+            .build();
+
+        // The solver only executed half of the adjusted 1 WETH sell amount.
+        let execution = encoder
+            .add_trade(order, 500_000_000_000_000_000u128.into(), U256::exp10(16))
+            .unwrap();
+        assert_eq!(
+            TradeExecution {
+                sell_token: weth,
+                buy_token: usdc,
+                sell_amount: 505_000_000_000_000_000u128.into(), // 0.505 WETH
+                buy_amount: 500_000_000u128.into(),              // 500 USDC
+                fee_amount: 0.into(),
+            },
+            execution
+        );
+    }
+
     #[test]
     fn computes_custom_price_for_buy_limit_order_correctly() {
         let weth = token(1);
@@ -1,24 +1,36 @@
+pub mod objective;
+
+use self::objective::{RankingObjective, Score};
 use crate::{
     driver::solver_settlements::{self, retain_mature_settlements, RatedSettlement},
     metrics::{SolverMetrics, SolverRunOutcome, SolverSimulationOutcome},
     settlement::{external_prices::ExternalPrices, PriceCheckTokens, Settlement},
-    settlement_rater::{RatedSolverSettlement, SettlementRating},
+    settlement_rater::SettlementRating,
     settlement_simulation::call_data,
     solver::{SimulationWithError, Solver},
 };
 use anyhow::Result;
+use futures::{future::join_all, Future};
 use gas_estimation::GasPrice1559;
 use itertools::enumerate;
-use model::auction::AuctionId;
+use model::{auction::AuctionId, order::OrderUid};
 use num::{rational::Ratio, BigInt, BigRational, CheckedDiv, FromPrimitive};
 use rand::prelude::SliceRandom;
 use shared::http_solver::model::{
     AuctionResult, InternalizationStrategy, SolverRejectionReason, SolverRunError,
     TransactionWithError,
 };
-use std::{cmp::Ordering, sync::Arc, time::Duration};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+use web3::types::AccessList;
 
-type SolverResult = (Arc<dyn Solver>, Result<Vec<Settlement>, SolverRunError>);
+pub type SolverResult = (Arc<dyn Solver>, Result<Vec<Settlement>, SolverRunError>);
+/// A rated settlement together with its score according to the ranking objective.
+pub type RankedSolverSettlement = (Arc<dyn Solver>, RatedSettlement, Option<AccessList>, Score);
 
 // We require from solvers to have a bit more ETH balance then needed
 // at the moment of simulating the transaction, to cover the potential increase
@@ -35,19 +47,27 @@ pub struct SettlementRanker {
     pub max_settlement_price_deviation: Option<Ratio<BigInt>>,
     pub token_list_restriction_for_price_checks: PriceCheckTokens,
     pub decimal_cutoff: u16,
+    pub objective: Arc<dyn RankingObjective>,
 }
 
 impl SettlementRanker {
     /// Discards settlements without user orders and settlements which violate price checks.
     /// Logs info and updates metrics about the out come of this run loop for each solver.
+    /// Solvers are notified about discarded settlements if `notify` is set.
     fn discard_illegal_settlements(
         &self,
         solver: &Arc<dyn Solver>,
         settlements: Result<Vec<Settlement>, SolverRunError>,
         external_prices: &ExternalPrices,
         auction_id: AuctionId,
+        notify: bool,
     ) -> Vec<Settlement> {
         let name = solver.name();
+        let notify_rejection = |reason| {
+            if notify {
+                solver.notify_auction_result(auction_id, AuctionResult::Rejected(reason));
+            }
+        };
         match settlements {
             Ok(settlements) => {
                 let settlements: Vec<_> = settlements.into_iter().filter_map(|settlement| {
@@ -59,7 +79,7 @@ impl SettlementRanker {
                             solver_name = %name,
                             "settlement(s) filtered containing only liquidity orders",
                         );
-                        solver.notify_auction_result(auction_id, AuctionResult::Rejected(SolverRejectionReason::NoUserOrders));
+                        notify_rejection(SolverRejectionReason::NoUserOrders);
                         return None;
                     }
 
@@ -78,7 +98,7 @@ impl SettlementRanker {
                                     "settlement(s) filtered for violating maximum external price deviation",
                                 );
 
-                                solver.notify_auction_result(auction_id, AuctionResult::Rejected(SolverRejectionReason::PriceViolation));
+                                notify_rejection(SolverRejectionReason::PriceViolation);
                                 return None;
                             }
                     }
@@ -100,10 +120,7 @@ impl SettlementRanker {
                 };
                 self.metrics.solver_run(outcome, name);
                 tracing::warn!(solver_name = %name, ?err, "solver error");
-                solver.notify_auction_result(
-                    auction_id,
-                    AuctionResult::Rejected(SolverRejectionReason::RunError(err)),
-                );
+                notify_rejection(SolverRejectionReason::RunError(err));
                 vec![]
             }
         }
@@ -115,11 +132,12 @@ impl SettlementRanker {
         settlements: Vec<SolverResult>,
         prices: &ExternalPrices,
         auction_id: AuctionId,
+        notify: bool,
     ) -> Vec<(Arc<dyn Solver>, Settlement)> {
         let mut solver_settlements = vec![];
         for (solver, settlements) in settlements {
             let settlements =
-                self.discard_illegal_settlements(&solver, settlements, prices, auction_id);
+                self.discard_illegal_settlements(&solver, settlements, prices, auction_id, notify);
             for settlement in settlements {
                 solver_settlements.push((solver.clone(), settlement));
             }
@@ -132,18 +150,29 @@ impl SettlementRanker {
 
     /// Determines legal settlements and ranks them by simulating them.
     /// Settlements get partitioned into simulation errors and a list
-    /// of `RatedSettlement`s sorted by ascending order of objective value.
-    pub async fn rank_legal_settlements(
+    /// of `RatedSettlement`s with their scores, sorted by ascending order of
+    /// their score according to the configured ranking objective.
+    ///
+    /// Solvers whose settlements failed because of specific orders are run
+    /// again with `resolve` on the auction without these orders, and their new
+    /// settlements are ranked alongside the others.
+    pub async fn rank_legal_settlements<F, Fut>(
         &self,
         settlements: Vec<SolverResult>,
         external_prices: &ExternalPrices,
         gas_price: GasPrice1559,
         auction_id: AuctionId,
-    ) -> Result<(Vec<RatedSolverSettlement>, Vec<SimulationWithError>)> {
+        rewards: &BTreeMap<OrderUid, f64>,
+        resolve: F,
+    ) -> Result<(Vec<RankedSolverSettlement>, Vec<SimulationWithError>)>
+    where
+        F: Fn(Arc<dyn Solver>, HashSet<OrderUid>) -> Fut,
+        Fut: Future<Output = SolverResult>,
+    {
         let gas_price = gas_price.bump(SOLVER_BALANCE_MULTIPLIER);
 
         let solver_settlements =
-            self.get_legal_settlements(settlements, external_prices, auction_id);
+            self.get_legal_settlements(settlements, external_prices, auction_id, true);
 
         // log considered settlements. While we already log all found settlements, this additonal
         // statement allows us to figure out which settlements were filtered out and which ones are
@@ -164,53 +193,119 @@ impl SettlementRanker {
             );
         }
 
-        let (mut rated_settlements, errors) = self
+        let (mut rated_settlements, mut errors) = self
             .settlement_rater
             .rate_settlements(solver_settlements, external_prices, gas_price)
             .await?;
 
+        let mut failing_orders = HashMap::<String, (Arc<dyn Solver>, HashSet<OrderUid>)>::new();
+        for error in &errors {
+            if !error.failing_orders.is_empty() {
+                let solver = &error.simulation.solver;
+                failing_orders
+                    .entry(solver.name().to_string())
+                    .or_insert_with(|| (solver.clone(), HashSet::new()))
+                    .1
+                    .extend(error.failing_orders.iter().copied());
+            }
+        }
+        let resolved = join_all(
+            failing_orders
+                .into_values()
+                .map(|(solver, failing_orders)| resolve(solver, failing_orders)),
+        )
+        .await;
+        // Solvers get a single result for the settlement which failed because of
+        // specific orders: they are rejected for these orders, unless they
+        // recovered from them and get ranked instead.
+        let resolved = self.get_legal_settlements(resolved, external_prices, auction_id, false);
+        let mut recovered = HashSet::new();
+        let mut resolve_errors = Vec::new();
+        if !resolved.is_empty() {
+            let (rated, failed) = self
+                .settlement_rater
+                .rate_settlements(resolved, external_prices, gas_price)
+                .await?;
+            recovered.extend(rated.iter().map(|(solver, _, _)| solver.name().to_string()));
+            // Keep the settlement ids unique within the run loop.
+            let offset = rated_settlements
+                .iter()
+                .map(|(_, settlement, _)| settlement.id + 1)
+                .max()
+                .unwrap_or_default();
+            rated_settlements.extend(rated.into_iter().map(
+                |(solver, mut settlement, access_list)| {
+                    settlement.id += offset;
+                    (solver, settlement, access_list)
+                },
+            ));
+            for error in &failed {
+                tracing::debug!(
+                    solver_name = %error.simulation.solver.name(), error = ?error.error,
+                    "settlement without failing orders failed simulation",
+                );
+            }
+            resolve_errors = failed;
+        }
+
+        let mut ranked_settlements: Vec<_> = rated_settlements
+            .into_iter()
+            .map(|(solver, settlement, access_list)| {
+                let score = self.objective.score(&settlement, rewards);
+                tracing::debug!(
+                    solver_name = %solver.name(), settlement_id = settlement.id,
+                    score = ?score.to_model(self.objective.name()),
+                    "scored solution",
+                );
+                (solver, settlement, access_list, score)
+            })
+            .collect();
+
         // Before sorting, make sure to shuffle the settlements. This is to make sure we don't give
-        // preference to any specific solver when there is an objective value tie.
-        rated_settlements.shuffle(&mut rand::thread_rng());
+        // preference to any specific solver when there is a score tie.
+        ranked_settlements.shuffle(&mut rand::thread_rng());
 
-        rated_settlements.sort_by(|a, b| compare_solutions(&a.1, &b.1, self.decimal_cutoff));
+        ranked_settlements
+            .sort_by(|a, b| compare_solutions(&a.3.total, &b.3.total, self.decimal_cutoff));
 
         tracing::info!(
             "{} settlements passed simulation and {} failed",
-            rated_settlements.len(),
+            ranked_settlements.len(),
             errors.len(),
         );
         for error in &errors {
-            error.simulation.solver.notify_auction_result(
-                auction_id,
-                AuctionResult::Rejected(SolverRejectionReason::SimulationFailure(
-                    TransactionWithError {
-                        transaction: error.simulation.transaction.clone(),
-                        error: error.error.to_string(),
-                    },
-                )),
-            );
+            let solver = &error.simulation.solver;
+            let reason = if error.failing_orders.is_empty() {
+                SolverRejectionReason::SimulationFailure(TransactionWithError {
+                    transaction: error.simulation.transaction.clone(),
+                    error: error.error.to_string(),
+                })
+            } else if recovered.contains(solver.name()) {
+                continue;
+            } else {
+                SolverRejectionReason::FailingOrders(error.failing_orders.clone())
+            };
+            solver.notify_auction_result(auction_id, AuctionResult::Rejected(reason));
         }
-        for (i, (solver, _, _)) in enumerate(&rated_settlements) {
-            let rank = rated_settlements.len() - i;
+        errors.extend(resolve_errors);
+        for (i, (solver, _, _, _)) in enumerate(&ranked_settlements) {
+            let rank = ranked_settlements.len() - i;
             solver.notify_auction_result(auction_id, AuctionResult::Ranked(rank));
             self.metrics
                 .settlement_simulation(solver.name(), SolverSimulationOutcome::Success);
         }
 
-        Ok((rated_settlements, errors))
+        Ok((ranked_settlements, errors))
     }
 }
 
-fn compare_solutions(lhs: &RatedSettlement, rhs: &RatedSettlement, decimals: u16) -> Ordering {
+fn compare_solutions(lhs: &BigRational, rhs: &BigRational, decimals: u16) -> Ordering {
     let precision = BigRational::from_i8(10).unwrap().pow(decimals.into());
     let rounded_lhs = lhs
-        .objective_value()
         .checked_div(&precision)
         .expect("precision cannot be 0")
         .floor();
     let rounded_rhs = rhs
-        .objective_value()
         .checked_div(&precision)
         .expect("precision cannot be 0")
         .floor();
@@ -220,29 +315,15 @@ fn compare_solutions(lhs: &RatedSettlement, rhs: &RatedSettlement, decimals: u16
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ethcontract::U256;
-    use num::Zero;
-
-    use crate::driver::solver_settlements::RatedSettlement;
 
-    impl RatedSettlement {
-        fn with_objective(objective_value: f64) -> Self {
-            Self {
-                id: 42,
-                settlement: Default::default(),
-                surplus: BigRational::from_float(objective_value).unwrap(),
-                unscaled_subsidized_fee: Zero::zero(),
-                scaled_unsubsidized_fee: Zero::zero(),
-                gas_estimate: U256::zero(),
-                gas_price: Zero::zero(),
-            }
-        }
+    fn score(value: f64) -> BigRational {
+        BigRational::from_float(value).unwrap()
     }
 
     #[test]
     fn compare_solutions_precise() {
-        let better = RatedSettlement::with_objective(77495164315950.95);
-        let worse = RatedSettlement::with_objective(77278255312878.95);
+        let better = score(77495164315950.95);
+        let worse = score(77278255312878.95);
         assert_eq!(compare_solutions(&better, &worse, 0), Ordering::Greater);
         assert_eq!(compare_solutions(&worse, &better, 0), Ordering::Less);
         assert_eq!(compare_solutions(&better, &better, 0), Ordering::Equal);
@@ -250,8 +331,8 @@ mod tests {
 
     #[test]
     fn compare_solutions_rounded() {
-        let better = RatedSettlement::with_objective(77495164315950.95);
-        let worse = RatedSettlement::with_objective(77278255312878.95);
+        let better = score(77495164315950.95);
+        let worse = score(77278255312878.95);
         assert_eq!(compare_solutions(&better, &worse, 12), Ordering::Equal);
         assert_eq!(compare_solutions(&better, &worse, 11), Ordering::Greater);
     }
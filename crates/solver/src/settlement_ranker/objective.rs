@@ -0,0 +1,255 @@
+use crate::{driver::solver_settlements::RatedSettlement, settlement::Revertable};
+use model::order::OrderUid;
+use num::{BigRational, ToPrimitive, Zero};
+use shared::conversions::U256Ext as _;
+use std::{collections::BTreeMap, sync::Arc};
+
+/// The score of a settlement together with the components it is made of. All
+/// values are in wei.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Score {
+    pub total: BigRational,
+    pub breakdown: Vec<(&'static str, BigRational)>,
+}
+
+impl Score {
+    fn new(breakdown: Vec<(&'static str, BigRational)>) -> Self {
+        Self {
+            total: breakdown.iter().map(|(_, value)| value).sum(),
+            breakdown,
+        }
+    }
+
+    /// Converts the score into the format it is stored in with the solver
+    /// competition.
+    pub fn to_model(&self, objective: &str) -> model::solver_competition::Score {
+        model::solver_competition::Score {
+            objective: objective.to_string(),
+            total: self.total.to_f64().unwrap_or(f64::NAN),
+            breakdown: self
+                .breakdown
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_f64().unwrap_or(f64::NAN)))
+                .collect(),
+        }
+    }
+}
+
+/// The objective by which rated settlements are ranked. The settlement with
+/// the highest score wins.
+pub trait RankingObjective: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Scores a settlement. `rewards` are the CIP-14 solver rewards of the
+    /// orders in the auction.
+    fn score(&self, settlement: &RatedSettlement, rewards: &BTreeMap<OrderUid, f64>) -> Score;
+}
+
+#[derive(Copy, Clone, Debug, clap::ValueEnum)]
+#[clap(rename_all = "verbatim")]
+pub enum RankingObjectiveArg {
+    SurplusFeesCosts,
+    Surplus,
+    RevertRiskAdjusted,
+    Cip,
+}
+
+/// Creates the ranking objective selected on the command line. The parameters
+/// have to be finite, which the argument parsers ensure.
+pub fn create(
+    objective: RankingObjectiveArg,
+    revert_probability: f64,
+    reward_native_value: f64,
+) -> Arc<dyn RankingObjective> {
+    match objective {
+        RankingObjectiveArg::SurplusFeesCosts => Arc::new(SurplusFeesCosts),
+        RankingObjectiveArg::Surplus => Arc::new(Surplus),
+        RankingObjectiveArg::RevertRiskAdjusted => Arc::new(RevertRiskAdjusted {
+            revert_probability: BigRational::from_float(revert_probability)
+                .expect("finite revert probability"),
+        }),
+        RankingObjectiveArg::Cip => Arc::new(Cip {
+            reward_native_value: BigRational::from_float(reward_native_value)
+                .expect("finite reward value"),
+        }),
+    }
+}
+
+fn cost(settlement: &RatedSettlement) -> BigRational {
+    settlement.gas_estimate.to_big_rational() * &settlement.gas_price
+}
+
+/// Surplus plus fees minus the gas cost of the settlement. This is the
+/// objective value of the settlement.
+pub struct SurplusFeesCosts;
+
+impl RankingObjective for SurplusFeesCosts {
+    fn name(&self) -> &'static str {
+        "SurplusFeesCosts"
+    }
+
+    fn score(&self, settlement: &RatedSettlement, _: &BTreeMap<OrderUid, f64>) -> Score {
+        Score::new(vec![
+            ("surplus", settlement.surplus.clone()),
+            ("fees", settlement.scaled_unsubsidized_fee.clone()),
+            ("cost", -cost(settlement)),
+        ])
+    }
+}
+
+/// Only the surplus the settlement generates for users.
+pub struct Surplus;
+
+impl RankingObjective for Surplus {
+    fn name(&self) -> &'static str {
+        "Surplus"
+    }
+
+    fn score(&self, settlement: &RatedSettlement, _: &BTreeMap<OrderUid, f64>) -> Score {
+        Score::new(vec![("surplus", settlement.surplus.clone())])
+    }
+}
+
+/// The expected objective value when settlements interacting with onchain
+/// liquidity revert with a fixed probability. Surplus and fees are only
+/// realized if the settlement succeeds, while the gas cost has to be paid
+/// either way.
+pub struct RevertRiskAdjusted {
+    pub revert_probability: BigRational,
+}
+
+impl RankingObjective for RevertRiskAdjusted {
+    fn name(&self) -> &'static str {
+        "RevertRiskAdjusted"
+    }
+
+    fn score(&self, settlement: &RatedSettlement, _: &BTreeMap<OrderUid, f64>) -> Score {
+        let revert_probability = match settlement.settlement.revertable() {
+            Revertable::NoRisk => BigRational::zero(),
+            Revertable::HighRisk => self.revert_probability.clone(),
+        };
+        let value = &settlement.surplus + &settlement.scaled_unsubsidized_fee;
+        Score::new(vec![
+            ("surplus", settlement.surplus.clone()),
+            ("fees", settlement.scaled_unsubsidized_fee.clone()),
+            ("cost", -cost(settlement)),
+            ("revertRisk", -(value * revert_probability)),
+        ])
+    }
+}
+
+/// The objective value plus the CIP-14 rewards of the settled user orders.
+pub struct Cip {
+    /// The value in wei of a unit of reward.
+    pub reward_native_value: BigRational,
+}
+
+impl RankingObjective for Cip {
+    fn name(&self) -> &'static str {
+        "Cip"
+    }
+
+    fn score(&self, settlement: &RatedSettlement, rewards: &BTreeMap<OrderUid, f64>) -> Score {
+        let rewards = settlement
+            .settlement
+            .user_trades()
+            .filter_map(|trade| rewards.get(&trade.order.metadata.uid))
+            .filter_map(|reward| BigRational::from_float(*reward))
+            .fold(BigRational::zero(), |total, reward| total + reward);
+        Score::new(vec![
+            ("surplus", settlement.surplus.clone()),
+            ("fees", settlement.scaled_unsubsidized_fee.clone()),
+            ("cost", -cost(settlement)),
+            ("reward", rewards * &self.reward_native_value),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settlement::{Settlement, Trade};
+    use maplit::{btreemap, hashmap};
+    use model::order::{Order, OrderData, OrderMetadata};
+    use primitive_types::{H160, U256};
+
+    fn rated(settlement: Settlement) -> RatedSettlement {
+        let r = |value: i64| BigRational::from_integer(value.into());
+        RatedSettlement {
+            id: 0,
+            settlement,
+            surplus: r(100),
+            unscaled_subsidized_fee: r(0),
+            scaled_unsubsidized_fee: r(20),
+            gas_estimate: U256::from(10),
+            gas_price: r(3),
+        }
+    }
+
+    fn totals(settlement: &RatedSettlement, rewards: &BTreeMap<OrderUid, f64>) -> Vec<i64> {
+        [
+            create(RankingObjectiveArg::SurplusFeesCosts, 0.5, 2.),
+            create(RankingObjectiveArg::Surplus, 0.5, 2.),
+            create(RankingObjectiveArg::RevertRiskAdjusted, 0.5, 2.),
+            create(RankingObjectiveArg::Cip, 0.5, 2.),
+        ]
+        .iter()
+        .map(|objective| {
+            let score = objective.score(settlement, rewards);
+            assert_eq!(
+                score.total,
+                score.breakdown.iter().map(|(_, value)| value).sum()
+            );
+            score.total.to_integer().to_i64().unwrap()
+        })
+        .collect()
+    }
+
+    #[test]
+    fn scores_settlements() {
+        let token = H160::from_low_u64_be(1);
+        let order = Order {
+            data: OrderData {
+                sell_token: token,
+                buy_token: token,
+                sell_amount: 1.into(),
+                buy_amount: 1.into(),
+                ..Default::default()
+            },
+            metadata: OrderMetadata {
+                uid: OrderUid([1; 56]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let settlement = Settlement::with_trades(
+            hashmap! { token => 1.into() },
+            vec![Trade {
+                order,
+                executed_amount: 1.into(),
+                ..Default::default()
+            }],
+        );
+        let rewards = btreemap! {
+            OrderUid([1; 56]) => 4.,
+            OrderUid([2; 56]) => 8.,
+        };
+
+        // The settlement doesn't interact with onchain liquidity, so it is not
+        // at risk of reverting.
+        assert_eq!(totals(&rated(settlement), &rewards), vec![90, 100, 90, 98]);
+    }
+
+    #[test]
+    fn adjusts_for_revert_risk() {
+        let mut settlement = Settlement::default();
+        settlement
+            .encoder
+            .append_to_execution_plan(crate::settlement::NoopInteraction);
+
+        assert_eq!(
+            totals(&rated(settlement), &Default::default()),
+            vec![90, 100, 30, 90]
+        );
+    }
+}
@@ -8,19 +8,32 @@ use crate::{
 use anyhow::{Context, Result};
 use contracts::GPv2Settlement;
 use ethcontract::errors::ExecutionError;
-use futures::future::join_all;
+use futures::{future::join_all, Future};
 use gas_estimation::GasPrice1559;
 use itertools::{Either, Itertools};
+use model::order::OrderUid;
 use num::BigRational;
 use primitive_types::U256;
 use shared::{
     code_fetching::CodeFetching,
+    ethcontract_error::EthcontractErrorType,
     ethrpc::Web3,
     http_solver::model::{InternalizationStrategy, SimulatedTransaction},
 };
-use std::{borrow::Borrow, sync::Arc};
+use std::{
+    borrow::Borrow,
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
 use web3::types::AccessList;
 
+/// The maximum number of simulations spent on bisecting the failing
+/// settlements of a single auction.
+const MAX_BISECTION_SIMULATIONS_PER_AUCTION: usize = 20;
+
 type SolverSettlement = (Arc<dyn Solver>, Settlement);
 pub type RatedSolverSettlement = (Arc<dyn Solver>, RatedSettlement, Option<AccessList>);
 
@@ -57,6 +70,9 @@ pub struct SettlementRater {
     pub code_fetcher: Arc<dyn CodeFetching>,
     pub settlement_contract: GPv2Settlement,
     pub web3: Web3,
+    /// Find the user orders causing settlements to fail to simulate by
+    /// bisecting their trades.
+    pub bisect_failing_settlements: bool,
 }
 
 impl SettlementRater {
@@ -92,6 +108,115 @@ impl SettlementRater {
         )
         .await
     }
+
+    /// Returns whether the settlement reverts in the simulation. Errors
+    /// communicating with the node are returned instead of counting as a
+    /// revert, so that they don't get blamed on an order.
+    async fn simulation_fails(
+        &self,
+        solver: &Arc<dyn Solver>,
+        settlement: Settlement,
+        gas_price: GasPrice1559,
+        budget: &AtomicUsize,
+    ) -> Result<bool> {
+        budget
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| {
+                left.checked_sub(1)
+            })
+            .ok()
+            .context("bisection simulation budget exhausted")?;
+        let simulation = self
+            .simulate_settlements(
+                vec![(solver.clone(), settlement)],
+                gas_price,
+                InternalizationStrategy::EncodeAllInteractions,
+            )
+            .await?
+            .pop()
+            .context("missing bisection simulation")?;
+        match simulation.gas_estimate {
+            Ok(_) => Ok(false),
+            Err(err) if EthcontractErrorType::classify(&err) == EthcontractErrorType::Node => {
+                Err(err).context("node error during bisection simulation")
+            }
+            Err(_) => Ok(true),
+        }
+    }
+
+    /// Finds the user trades causing a settlement to revert by bisecting them
+    /// one offending order at a time. Returns the offending orders once the
+    /// settlement without their trades passes simulation.
+    ///
+    /// The interactions of the settlement are kept while bisecting because
+    /// they can't be attributed to specific trades, so the remaining trades
+    /// might get their interactions paid for by the settlement contract's
+    /// buffers. This is fine for finding the offending orders, but it means
+    /// that the settlement without their trades can't be submitted. The solver
+    /// has to solve the auction again without them instead.
+    ///
+    /// Every simulation takes one unit of the `budget` shared by all
+    /// settlements of the auction, bisection fails once it is exhausted.
+    async fn bisect_failing_orders(
+        &self,
+        solver: &Arc<dyn Solver>,
+        original: &Settlement,
+        gas_price: GasPrice1559,
+        budget: &AtomicUsize,
+    ) -> Result<Option<Vec<OrderUid>>> {
+        let mut settlement = original.clone();
+        let mut failing_orders = Vec::new();
+        loop {
+            let uids = settlement
+                .user_trades()
+                .map(|trade| trade.order.metadata.uid)
+                .collect::<Vec<_>>();
+            if uids.len() <= 1 {
+                return Ok(None);
+            }
+
+            let len = shortest_failing_prefix(uids.len(), |len| {
+                let settlement =
+                    settlement.without_user_trades(&uids[len..].iter().copied().collect());
+                self.simulation_fails(solver, settlement, gas_price, budget)
+            })
+            .await?;
+            // The settlement fails even without any user trades, so the revert
+            // isn't caused by a specific order.
+            if len == 0 {
+                return Ok(None);
+            }
+
+            let uid = uids[len - 1];
+            failing_orders.push(uid);
+            settlement = settlement.without_user_trades(&HashSet::from([uid]));
+            if !self
+                .simulation_fails(solver, settlement.clone(), gas_price, budget)
+                .await?
+            {
+                return Ok(Some(failing_orders));
+            }
+        }
+    }
+}
+
+/// Returns the length of the shortest prefix of trades for which the
+/// settlement still fails, meaning that the last trade of that prefix causes
+/// the revert. `fails` is expected to be true for the full list of trades.
+async fn shortest_failing_prefix<F, Fut>(len: usize, mut fails: F) -> Result<usize>
+where
+    F: FnMut(usize) -> Fut,
+    Fut: Future<Output = Result<bool>>,
+{
+    let (mut low, mut high) = (0, len);
+    while low < high {
+        let mid = (low + high) / 2;
+        if fails(mid).await? {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    Ok(high)
 }
 
 #[async_trait::async_trait]
@@ -164,15 +289,49 @@ impl SettlementRating for SettlementRater {
             .await?;
 
         // split simulations into succeeded and failed groups, then do the rating only for succeeded settlements
-        let (settlements, simulations_failed): (Vec<_>, Vec<_>) = simulations
-            .into_iter()
-            .partition_map(|simulation| match simulation.gas_estimate {
-                Ok(_) => Either::Left((
-                    simulation.simulation.solver,
-                    simulation.simulation.settlement,
-                )),
-                Err(_) => Either::Right(simulation),
-            });
+        let (settlements, mut simulations_failed): (Vec<_>, Vec<_>) =
+            simulations.into_iter().partition_map(
+                |SimulationWithResult {
+                     simulation,
+                     gas_estimate,
+                 }| match gas_estimate {
+                    Ok(_) => Either::Left((simulation.solver, simulation.settlement)),
+                    Err(error) => Either::Right(SimulationWithError {
+                        simulation,
+                        error,
+                        failing_orders: Vec::new(),
+                    }),
+                },
+            );
+
+        // one bad order shouldn't sink a whole batch, so find the orders causing the revert
+        // to let the solver solve the auction again without them
+        if self.bisect_failing_settlements {
+            let budget = AtomicUsize::new(MAX_BISECTION_SIMULATIONS_PER_AUCTION);
+            let bisected = join_all(simulations_failed.iter().map(|failure| {
+                self.bisect_failing_orders(
+                    &failure.simulation.solver,
+                    &failure.simulation.settlement,
+                    gas_price,
+                    &budget,
+                )
+            }))
+            .await;
+            for (failure, bisected) in simulations_failed.iter_mut().zip(bisected) {
+                match bisected {
+                    Ok(Some(failing_orders)) => {
+                        tracing::debug!(
+                            solver = %failure.simulation.solver.name(),
+                            ?failing_orders,
+                            "found orders causing settlement to fail"
+                        );
+                        failure.failing_orders = failing_orders;
+                    }
+                    Ok(None) => (),
+                    Err(err) => tracing::warn!(?err, "failed to bisect failing settlement"),
+                }
+            }
+        }
 
         // since rating is done with internalizations, repeat the simulations for previously succeeded simulations
         let mut simulations = self
@@ -201,27 +360,59 @@ impl SettlementRating for SettlementRater {
             }
         };
 
-        simulations.extend(simulations_failed);
-        Ok((simulations.into_iter().enumerate()).partition_map(
-            |(
-                i,
-                SimulationWithResult {
-                    simulation,
-                    gas_estimate,
-                },
-            )| {
-                match gas_estimate {
-                    Ok(gas_estimate) => Either::Left((
-                        simulation.solver,
-                        rate_settlement(i, simulation.settlement, gas_estimate),
-                        simulation.transaction.access_list,
-                    )),
-                    Err(err) => Either::Right(SimulationWithError {
+        let (rated, mut errors): (Vec<_>, Vec<_>) = (simulations.into_iter().enumerate())
+            .partition_map(
+                |(
+                    i,
+                    SimulationWithResult {
                         simulation,
-                        error: err,
-                    }),
-                }
-            },
-        ))
+                        gas_estimate,
+                    },
+                )| {
+                    match gas_estimate {
+                        Ok(gas_estimate) => Either::Left((
+                            simulation.solver,
+                            rate_settlement(i, simulation.settlement, gas_estimate),
+                            simulation.transaction.access_list,
+                        )),
+                        Err(err) => Either::Right(SimulationWithError {
+                            simulation,
+                            error: err,
+                            failing_orders: Vec::new(),
+                        }),
+                    }
+                },
+            );
+        errors.extend(simulations_failed);
+        Ok((rated, errors))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future;
+
+    #[tokio::test]
+    async fn finds_shortest_failing_prefix() {
+        // Trades at these indices cause the settlement to revert.
+        let prefix = |failing: &'static [usize], len| {
+            shortest_failing_prefix(len, move |len| {
+                future::ready(Ok(failing.iter().any(|i| *i < len)))
+            })
+        };
+
+        assert_eq!(prefix(&[0], 1).await.unwrap(), 1);
+        assert_eq!(prefix(&[3], 8).await.unwrap(), 4);
+        assert_eq!(prefix(&[7], 8).await.unwrap(), 8);
+        assert_eq!(prefix(&[2, 5], 7).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn shortest_failing_prefix_is_empty_when_not_caused_by_trades() {
+        let prefix = shortest_failing_prefix(5, |_| future::ready(Ok(true)))
+            .await
+            .unwrap();
+        assert_eq!(prefix, 0);
     }
 }
@@ -124,6 +124,8 @@ pub enum TransactionStrategy {
     Eden(StrategyArgs),
     Flashbots(StrategyArgs),
     PublicMempool(StrategyArgs),
+    /// Sends the settlement to a configurable set of block builders at once.
+    Builder(StrategyArgs),
     Gelato(Arc<GelatoSubmitter>),
     DryRun,
 }
@@ -134,6 +136,7 @@ impl TransactionStrategy {
             TransactionStrategy::Eden(args) => Some(args),
             TransactionStrategy::Flashbots(args) => Some(args),
             TransactionStrategy::PublicMempool(args) => Some(args),
+            TransactionStrategy::Builder(args) => Some(args),
             TransactionStrategy::Gelato(_) | TransactionStrategy::DryRun => None,
         }
     }
@@ -247,7 +250,9 @@ impl SolutionSubmitter {
         index: usize,
     ) -> Result<TransactionReceipt, SubmissionError> {
         match strategy {
-            TransactionStrategy::Eden(_) | TransactionStrategy::Flashbots(_) => {
+            TransactionStrategy::Eden(_)
+            | TransactionStrategy::Flashbots(_)
+            | TransactionStrategy::Builder(_) => {
                 if !matches!(account, Account::Offline(..)) {
                     return Err(SubmissionError::from(anyhow!(
                         "Submission to private network requires offline account for signing"
@@ -472,6 +477,9 @@ mod tests {
         let strategy = TransactionStrategy::PublicMempool(StrategyArgs::default());
         assert!(strategy.strategy_args().is_some());
 
+        let strategy = TransactionStrategy::Builder(StrategyArgs::default());
+        assert!(strategy.strategy_args().is_some());
+
         let strategy = TransactionStrategy::DryRun;
         assert!(strategy.strategy_args().is_none());
     }
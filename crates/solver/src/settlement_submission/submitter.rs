@@ -13,6 +13,7 @@
 // from outside) so it is only at that point that we need to check the hashes individually to the
 // find the one that got mined (if any).
 
+pub mod builder_api;
 mod common;
 pub mod eden_api;
 pub mod flashbots_api;
@@ -73,6 +74,7 @@ pub enum Strategy {
     Eden,
     Flashbots,
     PublicMempool,
+    Builder,
 }
 
 impl fmt::Display for Strategy {
@@ -101,7 +103,7 @@ pub struct TransactionHandle {
 #[cfg_attr(test, mockall::automock)]
 #[async_trait::async_trait]
 pub trait TransactionSubmitting: Send + Sync {
-    /// Submits transation to the specific network (public mempool, eden, flashbots...).
+    /// Submits transation to the specific network (public mempool, eden, flashbots, builders...).
     /// Returns transaction handle
     async fn submit_transaction(
         &self,
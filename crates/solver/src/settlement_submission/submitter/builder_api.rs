@@ -0,0 +1,447 @@
+//! Generic submission to block builders and private relays.
+//!
+//! Builders are configured with the RPC method they support, either
+//! `eth_sendBundle` or `eth_sendPrivateTransaction`, so that new relays can be
+//! added through configuration. The same signed settlement is sent to all of
+//! them at once.
+//!
+//! Cancellations use the matching cancellation method of each builder,
+//! `eth_cancelBundle` or `eth_cancelPrivateTransaction`, instead of racing a
+//! noop transaction against the settlement.
+//!
+//! Relays which authenticate their callers, like
+//! `https://relay.flashbots.net`, require the requests to be signed with the
+//! `X-Flashbots-Signature` header. The requests are signed if a signing key is
+//! configured.
+
+use crate::settlement::{Revertable, Settlement};
+
+use super::{
+    super::submitter::{TransactionHandle, TransactionSubmitting},
+    AdditionalTip, Strategy, SubmissionLoopStatus,
+};
+use anyhow::{anyhow, bail, Context, Result};
+use ethcontract::{
+    transaction::{Transaction, TransactionBuilder},
+    PrivateKey, H256, U256,
+};
+use futures::FutureExt;
+use reqwest::{header, Client, Url};
+use serde_json::{json, Value};
+use shared::ethrpc::{Web3, Web3Transport};
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+use web3::{
+    signing::{self, Key, SecretKeyRef},
+    types::{Bytes, U64},
+};
+
+/// The number of blocks, starting with the next one, for which a bundle is
+/// submitted. Bundles are only valid for the block they target, so without
+/// this they would expire after a single block unless the gas price
+/// increases enough for the submission loop to send a replacement.
+const BUNDLE_TARGET_BLOCKS: u64 = 10;
+
+/// The header with the signature of the request body expected by Flashbots
+/// style relays.
+const FLASHBOTS_SIGNATURE_HEADER: &str = "X-Flashbots-Signature";
+
+/// The RPC method a builder accepts transactions with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuilderMethod {
+    /// `eth_sendBundle`, with a bundle containing only the settlement.
+    /// Cancelled with `eth_cancelBundle`.
+    SendBundle,
+    /// `eth_sendPrivateTransaction`. Cancelled with
+    /// `eth_cancelPrivateTransaction`.
+    SendPrivateTransaction,
+}
+
+impl FromStr for BuilderMethod {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "eth_sendBundle" => Ok(Self::SendBundle),
+            "eth_sendPrivateTransaction" => Ok(Self::SendPrivateTransaction),
+            _ => bail!("unsupported builder method {s}"),
+        }
+    }
+}
+
+/// A builder endpoint, parsed from `name|method|url`.
+#[derive(Clone, Debug)]
+pub struct BuilderEndpoint {
+    pub name: String,
+    pub method: BuilderMethod,
+    pub url: Url,
+}
+
+impl FromStr for BuilderEndpoint {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.split('|');
+        let name = parts.next().context("missing name")?;
+        let method = parts.next().context("missing method")?;
+        let url = parts.next().context("missing url")?;
+        Ok(Self {
+            name: name.to_string(),
+            method: method.parse().context("parse method")?,
+            url: url.parse().context("parse url")?,
+        })
+    }
+}
+
+/// What was submitted to a builder for a nonce, so that it can be cancelled.
+#[derive(Debug, Default)]
+struct Pending {
+    nonce: U256,
+    /// The hashes of the private transactions.
+    transactions: Vec<H256>,
+    /// The replacement UUIDs of the bundles, by target block. Resubmitting a
+    /// bundle for the same block replaces the previous one.
+    bundles: HashMap<U64, String>,
+}
+
+#[derive(Clone)]
+struct Builder {
+    name: String,
+    method: BuilderMethod,
+    url: Url,
+    client: Client,
+    signing_key: Option<Arc<PrivateKey>>,
+    pending: Arc<Mutex<Pending>>,
+}
+
+impl Builder {
+    async fn send(
+        &self,
+        raw_transaction: &Bytes,
+        tx_hash: H256,
+        nonce: U256,
+        block: U64,
+    ) -> Result<()> {
+        match self.method {
+            BuilderMethod::SendPrivateTransaction => {
+                self.pending(nonce).transactions.push(tx_hash);
+                self.request(
+                    "eth_sendPrivateTransaction",
+                    json!({ "tx": raw_transaction }),
+                )
+                .await?;
+            }
+            BuilderMethod::SendBundle => {
+                let bundles = {
+                    let mut pending = self.pending(nonce);
+                    pending.bundles.retain(|target, _| *target > block);
+                    (1..=BUNDLE_TARGET_BLOCKS)
+                        .map(|offset| {
+                            let target = block + offset;
+                            let uuid = pending
+                                .bundles
+                                .entry(target)
+                                .or_insert_with(replacement_uuid)
+                                .clone();
+                            (target, uuid)
+                        })
+                        .collect::<Vec<_>>()
+                };
+                futures::future::try_join_all(bundles.into_iter().map(|(target, uuid)| {
+                    self.request(
+                        "eth_sendBundle",
+                        json!({
+                            "txs": [raw_transaction],
+                            "blockNumber": target,
+                            "replacementUuid": uuid,
+                        }),
+                    )
+                }))
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Cancel everything which is still pending with the builder for the
+    /// nonce.
+    async fn cancel(&self, nonce: U256) -> Result<()> {
+        let pending = std::mem::take(&mut *self.pending(nonce));
+        match self.method {
+            BuilderMethod::SendPrivateTransaction => {
+                futures::future::try_join_all(pending.transactions.into_iter().map(|tx_hash| {
+                    self.request("eth_cancelPrivateTransaction", json!({ "txHash": tx_hash }))
+                }))
+                .await?;
+            }
+            BuilderMethod::SendBundle => {
+                futures::future::try_join_all(pending.bundles.into_values().map(|uuid| {
+                    self.request("eth_cancelBundle", json!({ "replacementUuid": uuid }))
+                }))
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The submissions pending for the nonce. Submissions for other nonces
+    /// were either mined or replaced, so they are forgotten.
+    fn pending(&self, nonce: U256) -> std::sync::MutexGuard<'_, Pending> {
+        let mut pending = self.pending.lock().unwrap();
+        if pending.nonce != nonce {
+            *pending = Pending {
+                nonce,
+                ..Default::default()
+            };
+        }
+        pending
+    }
+
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let body = serde_json::to_vec(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": [params],
+        }))?;
+        let mut request = self
+            .client
+            .post(self.url.clone())
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(key) = &self.signing_key {
+            request = request.header(FLASHBOTS_SIGNATURE_HEADER, flashbots_signature(key, &body));
+        }
+        let response: Value = request
+            .body(body)
+            .send()
+            .await
+            .with_context(|| format!("{method} request"))?
+            .error_for_status()
+            .with_context(|| format!("{method} status"))?
+            .json()
+            .await
+            .with_context(|| format!("{method} response"))?;
+        if let Some(error) = response.get("error") {
+            bail!("{method} failed: {error}");
+        }
+        Ok(response.get("result").cloned().unwrap_or_default())
+    }
+}
+
+/// The `X-Flashbots-Signature` header value for a request body: the address of
+/// the signing key and its EIP-191 signature of the hex encoded Keccak-256
+/// hash of the body.
+fn flashbots_signature(key: &PrivateKey, body: &[u8]) -> String {
+    let key = SecretKeyRef::new(key);
+    let message = format!("0x{}", hex::encode(signing::keccak256(body)));
+    // Unwrap because the only error is for invalid messages which we don't create.
+    let signature = key
+        .sign(&signing::hash_message(message.as_bytes()).0, None)
+        .unwrap();
+    let mut bytes = [0u8; 65];
+    bytes[..32].copy_from_slice(signature.r.as_bytes());
+    bytes[32..64].copy_from_slice(signature.s.as_bytes());
+    bytes[64] = signature.v as u8;
+    format!("{:?}:0x{}", key.address(), hex::encode(bytes))
+}
+
+/// A random version 4 UUID, used to replace and cancel bundles.
+fn replacement_uuid() -> String {
+    let mut bytes: [u8; 16] = rand::random();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+#[derive(Clone)]
+pub struct BuilderApi {
+    web3: Web3,
+    builders: Vec<Builder>,
+}
+
+impl BuilderApi {
+    /// Creates a submitter sending transactions to all of the builder
+    /// endpoints. The node is used to look up the blocks targeted by bundles.
+    /// If a signing key is specified, the builder requests are signed with it
+    /// in the `X-Flashbots-Signature` header.
+    pub fn new(
+        client: Client,
+        web3: Web3,
+        endpoints: Vec<BuilderEndpoint>,
+        signing_key: Option<PrivateKey>,
+    ) -> Self {
+        let signing_key = signing_key.map(Arc::new);
+        let builders = endpoints
+            .into_iter()
+            .map(|endpoint| Builder {
+                name: endpoint.name,
+                method: endpoint.method,
+                url: endpoint.url,
+                client: client.clone(),
+                signing_key: signing_key.clone(),
+                pending: Default::default(),
+            })
+            .collect();
+        Self { web3, builders }
+    }
+
+    /// Reports the results of a request sent to all of the builders. The
+    /// request only needs to succeed with one of the builders.
+    fn report(&self, action: &str, results: Vec<Result<()>>) -> Result<()> {
+        let mut errors = vec![];
+        for (builder, result) in self.builders.iter().zip(results) {
+            let label = format!("builder_{}", builder.name);
+            super::track_submission_success(&label, result.is_ok());
+            if let Err(err) = result {
+                tracing::warn!(?err, %label, "builder {action} failed");
+                errors.push(format!("{label} failed to {action}: {err}"));
+            }
+        }
+        if errors.len() == self.builders.len() {
+            return Err(anyhow!(errors.join("\n")).context("all builders failed"));
+        }
+        Ok(())
+    }
+}
+
+fn build(tx: TransactionBuilder<Web3Transport>) -> Result<(Bytes, H256, U256)> {
+    let nonce = tx.nonce.context("transaction without nonce")?;
+    match tx.build().now_or_never().unwrap().unwrap() {
+        Transaction::Request(_) => unreachable!("verified offline account was used"),
+        Transaction::Raw { bytes, hash } => Ok((bytes, hash, nonce)),
+    }
+}
+
+#[async_trait::async_trait]
+impl TransactionSubmitting for BuilderApi {
+    async fn submit_transaction(
+        &self,
+        tx: TransactionBuilder<Web3Transport>,
+    ) -> Result<TransactionHandle> {
+        let (raw_transaction, tx_hash, nonce) = build(tx)?;
+        let block = self
+            .web3
+            .eth()
+            .block_number()
+            .await
+            .context("block_number")?;
+
+        let results = futures::future::join_all(
+            self.builders
+                .iter()
+                .map(|builder| builder.send(&raw_transaction, tx_hash, nonce, block)),
+        )
+        .await;
+        // Inclusion is tracked through the nonce of the account, so it doesn't
+        // matter which builder ends up including the transaction.
+        self.report("submit", results)?;
+
+        Ok(TransactionHandle {
+            tx_hash,
+            handle: tx_hash,
+        })
+    }
+
+    // The builders are asked to drop the pending settlement instead of being
+    // sent the noop transaction, which would compete with the settlement
+    // rather than replace it. The noop transaction only provides the nonce.
+    async fn cancel_transaction(
+        &self,
+        tx: TransactionBuilder<Web3Transport>,
+    ) -> Result<TransactionHandle> {
+        let (_, tx_hash, nonce) = build(tx)?;
+        let results =
+            futures::future::join_all(self.builders.iter().map(|builder| builder.cancel(nonce)))
+                .await;
+        self.report("cancel", results)?;
+
+        Ok(TransactionHandle {
+            tx_hash,
+            handle: tx_hash,
+        })
+    }
+
+    fn submission_status(&self, settlement: &Settlement, network_id: &str) -> SubmissionLoopStatus {
+        if shared::gas_price_estimation::is_mainnet(network_id) {
+            if let Revertable::NoRisk = settlement.revertable() {
+                return SubmissionLoopStatus::Enabled(AdditionalTip::Off);
+            }
+        }
+
+        SubmissionLoopStatus::Enabled(AdditionalTip::On)
+    }
+
+    fn name(&self) -> Strategy {
+        Strategy::Builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_builder_endpoints() {
+        let endpoint: BuilderEndpoint = "flashbots|eth_sendBundle|https://relay.flashbots.net"
+            .parse()
+            .unwrap();
+        assert_eq!(endpoint.name, "flashbots");
+        assert_eq!(endpoint.method, BuilderMethod::SendBundle);
+        assert_eq!(endpoint.url.as_str(), "https://relay.flashbots.net/");
+
+        let endpoint: BuilderEndpoint =
+            "protect|eth_sendPrivateTransaction|https://rpc.flashbots.net"
+                .parse()
+                .unwrap();
+        assert_eq!(endpoint.method, BuilderMethod::SendPrivateTransaction);
+
+        assert!(
+            "flashbots|eth_sendRawTransaction|https://relay.flashbots.net"
+                .parse::<BuilderEndpoint>()
+                .is_err()
+        );
+        assert!("flashbots|eth_sendBundle"
+            .parse::<BuilderEndpoint>()
+            .is_err());
+    }
+
+    #[test]
+    fn signs_requests_for_flashbots() {
+        let key = PrivateKey::from_raw([1; 32]).unwrap();
+        let body = br#"{"jsonrpc":"2.0","id":1,"method":"eth_cancelBundle","params":[]}"#;
+        let header = flashbots_signature(&key, body);
+        let (address, signature) = header.split_once(':').unwrap();
+        assert_eq!(address, format!("{:?}", key.public_address()));
+
+        let signature = hex::decode(signature.strip_prefix("0x").unwrap()).unwrap();
+        assert_eq!(signature.len(), 65);
+        let message = format!("0x{}", hex::encode(signing::keccak256(body)));
+        let recovered = signing::recover(
+            &signing::hash_message(message.as_bytes()).0,
+            &signature[..64],
+            signature[64] as i32 - 27,
+        )
+        .unwrap();
+        assert_eq!(recovered, key.public_address());
+    }
+
+    #[test]
+    fn generates_v4_uuids() {
+        let uuid = replacement_uuid();
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "4");
+        assert!(matches!(&uuid[19..20], "8" | "9" | "a" | "b"));
+        assert_ne!(uuid, replacement_uuid());
+    }
+}
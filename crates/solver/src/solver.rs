@@ -30,7 +30,7 @@ use crate::{
 use anyhow::{anyhow, Context, Result};
 use contracts::{BalancerV2Vault, GPv2Settlement};
 use ethcontract::{errors::ExecutionError, Account, PrivateKey, H160, U256};
-use model::{auction::AuctionId, DomainSeparator};
+use model::{auction::AuctionId, order::OrderUid, DomainSeparator};
 use num::BigRational;
 use reqwest::Url;
 use shared::{
@@ -56,9 +56,9 @@ use std::{
 use web3::types::AccessList;
 
 pub mod balancer_sor_solver;
-mod baseline_solver;
+pub mod baseline_solver;
 pub mod http_solver;
-mod naive_solver;
+pub mod naive_solver;
 mod oneinch_solver;
 pub mod optimizing_solver;
 mod paraswap_solver;
@@ -173,6 +173,9 @@ pub struct Simulation {
 pub struct SimulationWithError {
     pub simulation: Simulation,
     pub error: ExecutionError,
+    /// The orders causing the revert, if a settlement without them passed
+    /// simulation.
+    pub failing_orders: Vec<OrderUid>,
 }
 
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, clap::ValueEnum)]
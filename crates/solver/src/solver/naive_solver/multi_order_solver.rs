@@ -38,12 +38,18 @@ impl TokenContext {
     }
 }
 
+/// How many times a partially fillable order gets halved before it is removed from the solution
+/// entirely.
+const MAX_PARTIAL_FILL_REDUCTIONS: usize = 4;
+
 pub fn solve(
     slippage: &SlippageContext,
     orders: impl IntoIterator<Item = LimitOrder>,
     pool: &ConstantProductOrder,
 ) -> Option<Settlement> {
     let mut orders: Vec<LimitOrder> = orders.into_iter().collect();
+    // Number of times each order (by index) has already been reduced.
+    let mut reductions = vec![0; orders.len()];
     while !orders.is_empty() {
         let (context_a, context_b) = split_into_contexts(&orders, pool);
         if let Some(valid_solution) =
@@ -65,16 +71,44 @@ pub fn solve(
                     (lhs.1.buy_amount * rhs.1.sell_amount)
                         .cmp(&(lhs.1.sell_amount * rhs.1.buy_amount))
                 });
-            match order_to_remove {
-                Some((index, _)) => orders.swap_remove(index),
+            let index = match order_to_remove {
+                Some((index, _)) => index,
                 None => break,
             };
+            // Partially fillable orders are scaled down instead of being removed right away so
+            // that as much of them as possible gets filled.
+            let reducible =
+                orders[index].partially_fillable && reductions[index] < MAX_PARTIAL_FILL_REDUCTIONS;
+            if reducible && halve_order(&mut orders[index]) {
+                reductions[index] += 1;
+            } else {
+                orders.swap_remove(index);
+                reductions.swap_remove(index);
+            }
         }
     }
 
     None
 }
 
+/// Halves the amounts of a partially fillable order. The buy amount is rounded up so that the
+/// limit price never gets worse for the trader. Returns false if the order would no longer have
+/// any amount left to execute.
+fn halve_order(order: &mut LimitOrder) -> bool {
+    let (sell_amount, buy_amount) = (
+        order.sell_amount / 2,
+        (order.buy_amount / 2) + (order.buy_amount % 2),
+    );
+    if sell_amount.is_zero() || buy_amount.is_zero() {
+        return false;
+    }
+    order.sell_amount = sell_amount;
+    order.buy_amount = buy_amount;
+    order.unscaled_subsidized_fee /= 2;
+    order.scaled_unsubsidized_fee /= 2;
+    true
+}
+
 ///
 /// Computes a settlement using orders of a single pair and the direct AMM between those tokens.get(.
 /// Panics if orders are not already filtered for a specific token pair, or the reserve information
@@ -683,6 +717,38 @@ mod tests {
         assert!(is_valid_solution(&result));
     }
 
+    #[test]
+    fn halves_partially_fillable_orders() {
+        let mut order = LimitOrder {
+            sell_amount: 10.into(),
+            buy_amount: 5.into(),
+            unscaled_subsidized_fee: 2.into(),
+            scaled_unsubsidized_fee: 4.into(),
+            partially_fillable: true,
+            ..Default::default()
+        };
+
+        assert!(halve_order(&mut order));
+        assert_eq!(order.sell_amount, 5.into());
+        assert_eq!(order.buy_amount, 3.into());
+        assert_eq!(order.unscaled_subsidized_fee, 1.into());
+        assert_eq!(order.scaled_unsubsidized_fee, 2.into());
+
+        // The buy amount is rounded up so the limit price only gets better.
+        assert!(halve_order(&mut order));
+        assert_eq!(order.sell_amount, 2.into());
+        assert_eq!(order.buy_amount, 2.into());
+
+        assert!(halve_order(&mut order));
+        assert_eq!(order.sell_amount, 1.into());
+        assert_eq!(order.buy_amount, 1.into());
+
+        // Nothing would be left to sell.
+        assert!(!halve_order(&mut order));
+        assert_eq!(order.sell_amount, 1.into());
+        assert_eq!(order.buy_amount, 1.into());
+    }
+
     #[test]
     fn returns_empty_solution_if_orders_have_no_overlap() {
         let token_a = Address::from_low_u64_be(0);
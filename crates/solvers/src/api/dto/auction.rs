@@ -1,15 +1,214 @@
-use crate::util::serialize;
+use crate::{
+    domain::{auction, eth, liquidity, order},
+    util::serialize,
+};
 use bigdecimal::BigDecimal;
 use ethereum_types::{H160, U256};
 use serde::Deserialize;
 use serde_with::{serde_as, DisplayFromStr};
 use std::collections::HashMap;
 
+impl Auction {
+    /// Converts a data transfer object into its domain object representation.
+    pub fn to_domain(&self) -> Result<auction::Auction, Error> {
+        Ok(auction::Auction {
+            id: self.id.clone().map(auction::Id),
+            tokens: self
+                .tokens
+                .iter()
+                .map(|(address, token)| {
+                    (
+                        eth::TokenAddress(*address),
+                        auction::Token {
+                            decimals: token.decimals,
+                            symbol: token.symbol.clone(),
+                            reference_price: token.reference_price,
+                            available_balance: token.available_balance,
+                            trusted: token.trusted,
+                        },
+                    )
+                })
+                .collect(),
+            orders: self
+                .orders
+                .iter()
+                .map(|order| order::Order {
+                    uid: order::Uid(order.uid),
+                    sell: eth::Asset {
+                        token: eth::TokenAddress(order.sell_token),
+                        amount: order.sell_amount,
+                    },
+                    buy: eth::Asset {
+                        token: eth::TokenAddress(order.buy_token),
+                        amount: order.buy_amount,
+                    },
+                    fee: order::Fee(order.fee_amount),
+                    side: match order.kind {
+                        Kind::Sell => order::Side::Sell,
+                        Kind::Buy => order::Side::Buy,
+                    },
+                    class: match order.class {
+                        Class::Market => order::Class::Market,
+                        Class::Limit => order::Class::Limit,
+                        Class::Liquidity => order::Class::Liquidity,
+                    },
+                    partially_fillable: order.partially_fillable,
+                })
+                .collect(),
+            liquidity: self
+                .liquidity
+                .iter()
+                .filter_map(|liquidity| match liquidity {
+                    Liquidity::ConstantProduct(pool) => Some(pool.to_domain()),
+                    Liquidity::WeightedProduct(pool) => Some(Ok(pool.to_domain())),
+                    Liquidity::Stable(pool) => Some(Ok(pool.to_domain())),
+                    Liquidity::ConcentratedLiquidity(pool) => Some(pool.to_domain()),
+                    // Foreign limit orders are not supported by any of the
+                    // solver engines yet.
+                    Liquidity::LimitOrder(_) => None,
+                })
+                .collect::<Result<_, _>>()?,
+            gas_price: eth::GasPrice(self.effective_gas_price),
+            deadline: auction::Deadline(self.deadline),
+        })
+    }
+}
+
+impl ConstantProductPool {
+    fn to_domain(&self) -> Result<liquidity::Liquidity, Error> {
+        let reserves = match self.tokens.iter().collect::<Vec<_>>()[..] {
+            [(a, a_reserve), (b, b_reserve)] => [
+                eth::Asset {
+                    token: eth::TokenAddress(*a),
+                    amount: a_reserve.balance,
+                },
+                eth::Asset {
+                    token: eth::TokenAddress(*b),
+                    amount: b_reserve.balance,
+                },
+            ],
+            _ => {
+                return Err(Error(format!(
+                    "constant product pool {} doesn't have exactly two tokens",
+                    self.id
+                )))
+            }
+        };
+        Ok(liquidity::Liquidity {
+            id: liquidity::Id(self.id),
+            address: self.address,
+            gas: eth::Gas(self.gas_estimate),
+            state: liquidity::State::ConstantProduct(liquidity::constant_product::Pool {
+                reserves,
+                fee: liquidity::Fee(self.fee.clone()),
+            }),
+        })
+    }
+}
+
+impl WeightedProductPool {
+    fn to_domain(&self) -> liquidity::Liquidity {
+        liquidity::Liquidity {
+            id: liquidity::Id(self.id),
+            address: self.address,
+            gas: eth::Gas(self.gas_estimate),
+            state: liquidity::State::WeightedProduct(liquidity::weighted_product::Pool {
+                reserves: self
+                    .tokens
+                    .iter()
+                    .map(|(token, reserve)| liquidity::weighted_product::Reserve {
+                        asset: eth::Asset {
+                            token: eth::TokenAddress(*token),
+                            amount: reserve.balance,
+                        },
+                        weight: reserve.weight.clone(),
+                    })
+                    .collect(),
+                fee: liquidity::Fee(self.fee.clone()),
+            }),
+        }
+    }
+}
+
+impl StablePool {
+    fn to_domain(&self) -> liquidity::Liquidity {
+        liquidity::Liquidity {
+            id: liquidity::Id(self.id),
+            address: self.address,
+            gas: eth::Gas(self.gas_estimate),
+            state: liquidity::State::Stable(liquidity::stable::Pool {
+                reserves: self
+                    .tokens
+                    .iter()
+                    .map(|(token, reserve)| liquidity::stable::Reserve {
+                        asset: eth::Asset {
+                            token: eth::TokenAddress(*token),
+                            amount: reserve.balance,
+                        },
+                        scaling_factor: reserve.scaling_factor,
+                    })
+                    .collect(),
+                amplification_parameter: self.amplification_parameter.clone(),
+                fee: liquidity::Fee(self.fee.clone()),
+            }),
+        }
+    }
+}
+
+impl ConcentratedLiquidityPool {
+    fn to_domain(&self) -> Result<liquidity::Liquidity, Error> {
+        let tokens = match self.tokens[..] {
+            [a, b] => [eth::TokenAddress(a), eth::TokenAddress(b)],
+            _ => {
+                return Err(Error(format!(
+                    "concentrated liquidity pool {} doesn't have exactly two tokens",
+                    self.id
+                )))
+            }
+        };
+        let liquidity = self.liquidity.try_into().map_err(|_| {
+            Error(format!(
+                "concentrated liquidity pool {} liquidity overflows u128",
+                self.id
+            ))
+        })?;
+        Ok(liquidity::Liquidity {
+            id: liquidity::Id(self.id),
+            address: self.address,
+            gas: eth::Gas(self.gas_estimate),
+            state: liquidity::State::Concentrated(liquidity::concentrated::Pool {
+                tokens,
+                sqrt_price: self.sqrt_price,
+                liquidity,
+                tick: self.tick,
+                liquidity_net: self
+                    .liquidity_net
+                    .iter()
+                    .map(|(tick, net)| (*tick, *net))
+                    .collect(),
+                fee: liquidity::Fee(self.fee.clone()),
+            }),
+        })
+    }
+}
+
+/// The auction is invalid and can't be converted into a domain object.
+#[derive(Debug)]
+pub struct Error(pub String);
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[serde_as]
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Auction {
-    id: Option<i64>,
+    id: Option<String>,
     tokens: HashMap<H160, Token>,
     orders: Vec<Order>,
     liquidity: Vec<Liquidity>,
@@ -80,9 +279,11 @@ enum Liquidity {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ConstantProductPool {
-    id: String,
+    #[serde_as(as = "DisplayFromStr")]
+    id: usize,
     address: H160,
-    gas_estimate: u64,
+    #[serde_as(as = "serialize::U256")]
+    gas_estimate: U256,
     tokens: HashMap<H160, ConstantProductReserve>,
     fee: BigDecimal,
 }
@@ -98,9 +299,11 @@ struct ConstantProductReserve {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct WeightedProductPool {
-    id: String,
+    #[serde_as(as = "DisplayFromStr")]
+    id: usize,
     address: H160,
-    gas_estimate: u64,
+    #[serde_as(as = "serialize::U256")]
+    gas_estimate: U256,
     tokens: HashMap<H160, WeightedProductReserve>,
     fee: BigDecimal,
 }
@@ -117,9 +320,11 @@ struct WeightedProductReserve {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct StablePool {
-    id: String,
+    #[serde_as(as = "DisplayFromStr")]
+    id: usize,
     address: H160,
-    gas_estimate: u64,
+    #[serde_as(as = "serialize::U256")]
+    gas_estimate: U256,
     tokens: HashMap<H160, StableReserve>,
     amplification_parameter: BigDecimal,
     fee: BigDecimal,
@@ -139,17 +344,19 @@ struct StableReserve {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ConcentratedLiquidityPool {
-    id: String,
+    #[serde_as(as = "DisplayFromStr")]
+    id: usize,
     address: H160,
-    gas_estimate: u64,
+    #[serde_as(as = "serialize::U256")]
+    gas_estimate: U256,
     tokens: Vec<H160>,
     #[serde_as(as = "serialize::U256")]
     sqrt_price: U256,
     #[serde_as(as = "serialize::U256")]
     liquidity: U256,
     tick: i32,
-    #[serde_as(as = "HashMap<DisplayFromStr, serialize::U256>")]
-    liquidity_net: HashMap<i32, U256>,
+    #[serde_as(as = "HashMap<DisplayFromStr, DisplayFromStr>")]
+    liquidity_net: HashMap<i32, i128>,
     fee: BigDecimal,
 }
 
@@ -157,9 +364,11 @@ struct ConcentratedLiquidityPool {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ForeignLimitOrder {
-    id: String,
+    #[serde_as(as = "DisplayFromStr")]
+    id: usize,
     address: H160,
-    gas_estimate: u64,
+    #[serde_as(as = "serialize::U256")]
+    gas_estimate: U256,
     #[serde_as(as = "serialize::Hex")]
     hash: [u8; 32],
     maker_token: H160,
@@ -1,16 +1,78 @@
-use crate::util::serialize;
+use crate::{domain::solution, util::serialize};
 use ethereum_types::{H160, U256};
 use serde::Serialize;
 use serde_with::serde_as;
 use std::collections::HashMap;
 
 impl Solution {
-    /// Returns the trivial solution.
-    pub fn trivial() -> Self {
+    /// Creates a new solution data transfer object from a domain solution.
+    pub fn from_domain(solution: &solution::Solution) -> Self {
         Self {
-            prices: Default::default(),
-            trades: Default::default(),
-            interactions: Default::default(),
+            prices: solution
+                .prices
+                .0
+                .iter()
+                .map(|(token, price)| (token.0, *price))
+                .collect(),
+            trades: solution
+                .trades
+                .iter()
+                .map(|trade| match trade {
+                    solution::Trade::Fulfillment(trade) => Trade::Fulfillment(Fulfillment {
+                        order: trade.order.0,
+                        executed_amount: trade.executed,
+                    }),
+                })
+                .collect(),
+            interactions: solution
+                .interactions
+                .iter()
+                .map(|interaction| match interaction {
+                    solution::Interaction::Liquidity(interaction) => {
+                        Interaction::Liquidity(LiquidityInteraction {
+                            internalize: interaction.internalize,
+                            id: interaction.liquidity.id.0,
+                            input_token: interaction.input.token.0,
+                            output_token: interaction.output.token.0,
+                            input_amount: interaction.input.amount,
+                            output_amount: interaction.output.amount,
+                        })
+                    }
+                    solution::Interaction::Custom(interaction) => {
+                        Interaction::Custom(CustomInteraction {
+                            internalize: interaction.internalize,
+                            target: interaction.target,
+                            value: interaction.value,
+                            call_data: interaction.calldata.clone(),
+                            allowances: interaction
+                                .allowances
+                                .iter()
+                                .map(|allowance| Allowance {
+                                    token: allowance.asset.token.0,
+                                    spender: allowance.spender,
+                                    amount: allowance.asset.amount,
+                                })
+                                .collect(),
+                            inputs: interaction
+                                .inputs
+                                .iter()
+                                .map(|input| Asset {
+                                    token: input.token.0,
+                                    amount: input.amount,
+                                })
+                                .collect(),
+                            outputs: interaction
+                                .outputs
+                                .iter()
+                                .map(|output| Asset {
+                                    token: output.token.0,
+                                    amount: output.amount,
+                                })
+                                .collect(),
+                        })
+                    }
+                })
+                .collect(),
         }
     }
 }
@@ -1,11 +1,13 @@
 //! Serve a solver engine API.
 
-use std::{future::Future, net::SocketAddr};
+use crate::domain::solver::Solver;
+use std::{future::Future, net::SocketAddr, sync::Arc};
 
 pub mod dto;
 
 pub struct Api {
     pub addr: SocketAddr,
+    pub solver: Solver,
 }
 
 impl Api {
@@ -18,7 +20,8 @@ impl Api {
             .layer(
                 tower::ServiceBuilder::new().layer(tower_http::trace::TraceLayer::new_for_http()),
             )
-            .route("/", axum::routing::post(solve));
+            .route("/", axum::routing::post(solve))
+            .with_state(Arc::new(self.solver));
 
         // Start the server.
         axum::Server::bind(&self.addr)
@@ -28,6 +31,13 @@ impl Api {
     }
 }
 
-async fn solve(_auction: axum::extract::Json<dto::Auction>) -> axum::response::Json<dto::Solution> {
-    axum::response::Json(dto::Solution::trivial())
+async fn solve(
+    state: axum::extract::State<Arc<Solver>>,
+    auction: axum::extract::Json<dto::Auction>,
+) -> Result<axum::response::Json<dto::Solution>, (hyper::StatusCode, String)> {
+    let auction = auction
+        .to_domain()
+        .map_err(|err| (hyper::StatusCode::BAD_REQUEST, err.to_string()))?;
+    let solution = state.solve(&auction).await;
+    Ok(axum::response::Json(dto::Solution::from_domain(&solution)))
 }
@@ -0,0 +1,205 @@
+use crate::{
+    boundary::liquidity::{concentrated, constant_product, stable, weighted_product},
+    domain::{eth, liquidity, order, solver::baseline},
+};
+use ethereum_types::{H160, U256};
+use model::TokenPair;
+use shared::{
+    baseline_solver::{estimate_buy_amount, estimate_sell_amount, BaseTokens, BaselineSolvable},
+    sources::{
+        balancer_v2::pool_fetching::{StablePool, WeightedPool},
+        uniswap_v2::pool_fetching::Pool as UniswapV2Pool,
+    },
+};
+use std::collections::{HashMap, HashSet};
+
+/// Routes orders over the auction liquidity using the path finding logic from
+/// the `shared` crate.
+pub struct Solver<'a> {
+    base_tokens: BaseTokens,
+    max_hops: usize,
+    amms: HashMap<TokenPair, Vec<Amm<'a>>>,
+}
+
+impl<'a> Solver<'a> {
+    pub fn new(
+        weth: &eth::WethAddress,
+        base_tokens: &HashSet<eth::TokenAddress>,
+        max_hops: usize,
+        liquidity: &'a [liquidity::Liquidity],
+    ) -> Self {
+        Self {
+            base_tokens: to_boundary_base_tokens(weth, base_tokens),
+            max_hops,
+            amms: to_boundary_amms(liquidity),
+        }
+    }
+
+    /// Finds the best route for the request, or `None` if no route satisfies
+    /// the request's limit price.
+    pub fn route(&self, request: baseline::Request) -> Option<baseline::Route<'a>> {
+        let candidates = self.base_tokens.path_candidates_with_hops(
+            request.sell.token.0,
+            request.buy.token.0,
+            self.max_hops,
+        );
+
+        match request.side {
+            order::Side::Sell => candidates
+                .iter()
+                .filter_map(|path| {
+                    let estimate = estimate_buy_amount(request.sell.amount, path, &self.amms)?;
+                    self.traverse_path(&estimate.path, request.sell.token.0, request.sell.amount)
+                })
+                .filter(|route| route.output().amount >= request.buy.amount)
+                .max_by_key(|route| route.output().amount),
+            order::Side::Buy => candidates
+                .iter()
+                .filter_map(|path| {
+                    let estimate = estimate_sell_amount(request.buy.amount, path, &self.amms)?;
+                    self.traverse_path(&estimate.path, request.sell.token.0, estimate.value)
+                })
+                .filter(|route| {
+                    route.input().amount <= request.sell.amount
+                        && route.output().amount >= request.buy.amount
+                })
+                .min_by_key(|route| route.input().amount),
+        }
+    }
+
+    /// Computes the amounts of each segment by swapping the sell amount
+    /// forward through the path. This ensures that the segments are
+    /// consistent even for AMMs where buy and sell amount estimates are not
+    /// perfectly symmetrical.
+    fn traverse_path(
+        &self,
+        path: &[&Amm<'a>],
+        mut sell_token: H160,
+        mut sell_amount: U256,
+    ) -> Option<baseline::Route<'a>> {
+        let mut segments = Vec::new();
+        for amm in path {
+            let buy_token = amm.tokens.other(&sell_token).expect("Inconsistent path");
+            let buy_amount = amm.get_amount_out(buy_token, (sell_amount, sell_token))?;
+            segments.push(baseline::Segment {
+                liquidity: amm.liquidity,
+                input: eth::Asset {
+                    token: eth::TokenAddress(sell_token),
+                    amount: sell_amount,
+                },
+                output: eth::Asset {
+                    token: eth::TokenAddress(buy_token),
+                    amount: buy_amount,
+                },
+            });
+            sell_token = buy_token;
+            sell_amount = buy_amount;
+        }
+        baseline::Route::new(segments)
+    }
+}
+
+fn to_boundary_base_tokens(
+    weth: &eth::WethAddress,
+    base_tokens: &HashSet<eth::TokenAddress>,
+) -> BaseTokens {
+    let base_tokens = base_tokens.iter().map(|token| token.0).collect::<Vec<_>>();
+    BaseTokens::new(weth.0, &base_tokens)
+}
+
+fn to_boundary_amms(liquidity: &[liquidity::Liquidity]) -> HashMap<TokenPair, Vec<Amm>> {
+    liquidity
+        .iter()
+        .fold(HashMap::new(), |mut amms, liquidity| {
+            let (tokens, pool): (Vec<_>, _) = match &liquidity.state {
+                liquidity::State::ConstantProduct(pool) => (
+                    pool.reserves.iter().map(|asset| asset.token).collect(),
+                    constant_product::to_boundary_pool(liquidity.address, pool)
+                        .map(Pool::ConstantProduct),
+                ),
+                liquidity::State::WeightedProduct(pool) => (
+                    pool.reserves
+                        .iter()
+                        .map(|reserve| reserve.asset.token)
+                        .collect(),
+                    weighted_product::to_boundary_pool(liquidity.address, pool)
+                        .map(Pool::WeightedProduct),
+                ),
+                liquidity::State::Stable(pool) => (
+                    pool.reserves
+                        .iter()
+                        .map(|reserve| reserve.asset.token)
+                        .collect(),
+                    stable::to_boundary_pool(liquidity.address, pool).map(Pool::Stable),
+                ),
+                liquidity::State::Concentrated(pool) => (
+                    pool.tokens.to_vec(),
+                    concentrated::Pool::new(pool).map(Pool::Concentrated),
+                ),
+            };
+            let pool = match pool {
+                Some(pool) => pool,
+                None => return amms,
+            };
+
+            for tokens in token_pairs(&tokens) {
+                amms.entry(tokens).or_default().push(Amm {
+                    tokens,
+                    liquidity,
+                    pool: pool.clone(),
+                });
+            }
+            amms
+        })
+}
+
+/// All pairs of distinct tokens.
+fn token_pairs(tokens: &[eth::TokenAddress]) -> Vec<TokenPair> {
+    tokens
+        .iter()
+        .enumerate()
+        .flat_map(|(i, a)| {
+            tokens[i + 1..]
+                .iter()
+                .filter_map(|b| TokenPair::new(a.0, b.0))
+        })
+        .collect()
+}
+
+struct Amm<'a> {
+    tokens: TokenPair,
+    liquidity: &'a liquidity::Liquidity,
+    pool: Pool<'a>,
+}
+
+#[derive(Clone)]
+enum Pool<'a> {
+    ConstantProduct(UniswapV2Pool),
+    WeightedProduct(WeightedPool),
+    Stable(StablePool),
+    Concentrated(concentrated::Pool<'a>),
+}
+
+impl BaselineSolvable for Amm<'_> {
+    fn get_amount_out(&self, out_token: H160, input: (U256, H160)) -> Option<U256> {
+        match &self.pool {
+            Pool::ConstantProduct(pool) => BaselineSolvable::get_amount_out(pool, out_token, input),
+            Pool::WeightedProduct(pool) => BaselineSolvable::get_amount_out(pool, out_token, input),
+            Pool::Stable(pool) => BaselineSolvable::get_amount_out(pool, out_token, input),
+            Pool::Concentrated(pool) => pool.get_amount_out(out_token, input),
+        }
+    }
+
+    fn get_amount_in(&self, in_token: H160, output: (U256, H160)) -> Option<U256> {
+        match &self.pool {
+            Pool::ConstantProduct(pool) => BaselineSolvable::get_amount_in(pool, in_token, output),
+            Pool::WeightedProduct(pool) => BaselineSolvable::get_amount_in(pool, in_token, output),
+            Pool::Stable(pool) => BaselineSolvable::get_amount_in(pool, in_token, output),
+            Pool::Concentrated(pool) => pool.get_amount_in(in_token, output),
+        }
+    }
+
+    fn gas_cost(&self) -> usize {
+        self.liquidity.gas.0.try_into().unwrap_or(usize::MAX)
+    }
+}
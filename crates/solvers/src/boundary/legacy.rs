@@ -0,0 +1,529 @@
+use crate::domain::{auction, eth, liquidity, order, solution, solver::legacy};
+use anyhow::{Context as _, Result};
+use bigdecimal::BigDecimal;
+use ethereum_types::H160;
+use num::{BigInt, BigRational, ToPrimitive};
+use shared::{
+    http_solver::{
+        gas_model::GasModel,
+        model::{
+            AmmModel, AmmParameters, BatchAuctionModel, ConcentratedPoolParameters,
+            ConstantProductPoolParameters, ExecutionPlanCoordinatesModel, MetadataModel,
+            OrderModel, SettledBatchAuctionModel, StablePoolParameters, TokenAmount,
+            TokenInfoModel, WeightedPoolTokenData, WeightedProductPoolParameters,
+        },
+        DefaultHttpSolverApi, HttpSolverApi, SolverConfig,
+    },
+    sources::uniswap_v3::{
+        graph_api::Token,
+        pool_fetching::{PoolInfo, PoolState, PoolStats},
+    },
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// Solves auctions by forwarding them to an external solver that speaks the
+/// HTTP solver API of the legacy `solver` crate.
+pub struct Legacy {
+    solver: DefaultHttpSolverApi,
+    weth: eth::WethAddress,
+}
+
+impl Legacy {
+    pub fn new(config: legacy::Config) -> Self {
+        Self {
+            solver: DefaultHttpSolverApi {
+                name: config.solver_name,
+                network_name: config.chain_id.to_string(),
+                chain_id: config.chain_id,
+                base: config.base_url,
+                client: reqwest::Client::new(),
+                config: SolverConfig {
+                    api_key: config.api_key,
+                    max_nr_exec_orders: config.max_nr_exec_orders,
+                    use_internal_buffers: config.use_internal_buffers,
+                    objective: None,
+                },
+            },
+            weth: config.weth,
+        }
+    }
+
+    pub async fn solve(&self, auction: &auction::Auction) -> Result<solution::Solution> {
+        let (model, liquidity) = to_boundary_auction(auction, &self.weth);
+        let timeout = (auction.deadline.0 - chrono::Utc::now())
+            .to_std()
+            .context("auction deadline has already passed")?;
+        let solution = self.solver.solve(&model, timeout).await?;
+        to_domain_solution(solution, auction, &liquidity)
+    }
+}
+
+/// Converts an auction into the legacy HTTP solver model. Also returns the
+/// liquidity by address, since that is how the legacy model references it.
+fn to_boundary_auction<'a>(
+    auction: &'a auction::Auction,
+    weth: &eth::WethAddress,
+) -> (BatchAuctionModel, HashMap<H160, &'a liquidity::Liquidity>) {
+    let gas_price = auction.gas_price.0.to_f64_lossy();
+    let gas_model = GasModel {
+        native_token: weth.0,
+        gas_price,
+    };
+
+    let tokens = auction
+        .tokens
+        .iter()
+        .map(|(address, token)| {
+            (
+                address.0,
+                TokenInfoModel {
+                    decimals: token.decimals,
+                    alias: token.symbol.clone(),
+                    // Reference prices are denominated in wei for one unit
+                    // (10^18 atoms) of the token.
+                    external_price: token
+                        .reference_price
+                        .map(|price| price.to_f64_lossy() / 1e18),
+                    normalize_priority: Some(u64::from(address.0 == weth.0)),
+                    internal_buffer: Some(token.available_balance),
+                    accepted_for_internalization: token.trusted,
+                },
+            )
+        })
+        .collect();
+
+    let orders = auction
+        .orders
+        .iter()
+        .enumerate()
+        .map(|(index, order)| {
+            (
+                index,
+                OrderModel {
+                    id: Some(model::order::OrderUid(order.uid.0)),
+                    sell_token: order.sell.token.0,
+                    buy_token: order.buy.token.0,
+                    sell_amount: order.sell.amount,
+                    buy_amount: order.buy.amount,
+                    allow_partial_fill: order.partially_fillable,
+                    is_sell_order: order.side == order::Side::Sell,
+                    fee: TokenAmount {
+                        amount: order.fee.0,
+                        token: order.sell.token.0,
+                    },
+                    cost: gas_model.gp_order_cost(),
+                    is_liquidity_order: order.class == order::Class::Liquidity,
+                    is_mature: true,
+                    mandatory: false,
+                    has_atomic_execution: false,
+                    reward: 0.,
+                },
+            )
+        })
+        .collect();
+
+    let mut amms = BTreeMap::new();
+    let mut liquidity_by_address = HashMap::new();
+    for liquidity in &auction.liquidity {
+        let parameters = match to_boundary_amm_parameters(liquidity, &auction.tokens) {
+            Some(parameters) => parameters,
+            None => {
+                tracing::debug!(id = ?liquidity.id, "unsupported liquidity for legacy solver");
+                continue;
+            }
+        };
+        let fee = match to_big_rational(liquidity_fee(liquidity)) {
+            Some(fee) => fee,
+            None => continue,
+        };
+        if liquidity_by_address.contains_key(&liquidity.address) {
+            tracing::debug!(address = ?liquidity.address, "duplicate liquidity address");
+            continue;
+        }
+        liquidity_by_address.insert(liquidity.address, liquidity);
+        amms.insert(
+            liquidity.address,
+            AmmModel {
+                parameters,
+                fee,
+                cost: gas_model.cost_for_gas(liquidity.gas.0),
+                mandatory: false,
+                address: liquidity.address,
+            },
+        );
+    }
+
+    let model = BatchAuctionModel {
+        tokens,
+        orders,
+        amms,
+        metadata: Some(MetadataModel {
+            environment: None,
+            auction_id: auction.id.as_ref().and_then(|id| id.0.parse().ok()),
+            run_id: None,
+            gas_price: Some(gas_price),
+            native_token: Some(weth.0),
+        }),
+    };
+    (model, liquidity_by_address)
+}
+
+fn liquidity_fee(liquidity: &liquidity::Liquidity) -> &BigDecimal {
+    match &liquidity.state {
+        liquidity::State::ConstantProduct(pool) => &pool.fee.0,
+        liquidity::State::WeightedProduct(pool) => &pool.fee.0,
+        liquidity::State::Stable(pool) => &pool.fee.0,
+        liquidity::State::Concentrated(pool) => &pool.fee.0,
+    }
+}
+
+fn to_boundary_amm_parameters(
+    liquidity: &liquidity::Liquidity,
+    tokens: &HashMap<eth::TokenAddress, auction::Token>,
+) -> Option<AmmParameters> {
+    Some(match &liquidity.state {
+        liquidity::State::ConstantProduct(pool) => {
+            AmmParameters::ConstantProduct(ConstantProductPoolParameters {
+                reserves: pool
+                    .reserves
+                    .iter()
+                    .map(|asset| (asset.token.0, asset.amount))
+                    .collect(),
+            })
+        }
+        liquidity::State::WeightedProduct(pool) => {
+            AmmParameters::WeightedProduct(WeightedProductPoolParameters {
+                reserves: pool
+                    .reserves
+                    .iter()
+                    .map(|reserve| {
+                        Some((
+                            reserve.asset.token.0,
+                            WeightedPoolTokenData {
+                                balance: reserve.asset.amount,
+                                weight: to_big_rational(&reserve.weight)?,
+                            },
+                        ))
+                    })
+                    .collect::<Option<_>>()?,
+            })
+        }
+        liquidity::State::Stable(pool) => AmmParameters::Stable(StablePoolParameters {
+            reserves: pool
+                .reserves
+                .iter()
+                .map(|reserve| (reserve.asset.token.0, reserve.asset.amount))
+                .collect(),
+            scaling_rates: pool
+                .reserves
+                .iter()
+                .map(|reserve| (reserve.asset.token.0, reserve.scaling_factor))
+                .collect(),
+            amplification_parameter: to_big_rational(&pool.amplification_parameter)?,
+        }),
+        liquidity::State::Concentrated(pool) => {
+            let fee = to_big_rational(&pool.fee.0)?;
+            AmmParameters::Concentrated(ConcentratedPoolParameters {
+                pool: PoolInfo {
+                    address: liquidity.address,
+                    tokens: pool
+                        .tokens
+                        .iter()
+                        .map(|token| {
+                            Some(Token {
+                                id: token.0,
+                                decimals: tokens.get(token)?.decimals?,
+                            })
+                        })
+                        .collect::<Option<_>>()?,
+                    state: PoolState {
+                        sqrt_price: pool.sqrt_price,
+                        liquidity: pool.liquidity.into(),
+                        tick: pool.tick.into(),
+                        liquidity_net: pool
+                            .liquidity_net
+                            .iter()
+                            .map(|(tick, liquidity)| {
+                                (BigInt::from(*tick), BigInt::from(*liquidity))
+                            })
+                            .collect(),
+                        fee: num::rational::Ratio::new(
+                            fee.numer().to_u32()?,
+                            fee.denom().to_u32()?,
+                        ),
+                    },
+                    gas_stats: PoolStats {
+                        mean_gas: liquidity.gas.0,
+                    },
+                },
+            })
+        }
+    })
+}
+
+/// Converts a decimal into a rational. Returns `None` for decimals with a huge
+/// exponent.
+fn to_big_rational(value: &BigDecimal) -> Option<BigRational> {
+    let (integer, exponent) = value.as_bigint_and_exponent();
+    let scale = num::pow(BigInt::from(10), exponent.unsigned_abs().try_into().ok()?);
+    Some(if exponent >= 0 {
+        BigRational::new(integer, scale)
+    } else {
+        BigRational::from_integer(integer * scale)
+    })
+}
+
+fn to_domain_solution(
+    mut model: SettledBatchAuctionModel,
+    auction: &auction::Auction,
+    liquidity: &HashMap<H160, &liquidity::Liquidity>,
+) -> Result<solution::Solution> {
+    anyhow::ensure!(
+        model.foreign_liquidity_orders.is_empty(),
+        "foreign liquidity orders are not supported"
+    );
+
+    let trades = model
+        .orders
+        .iter()
+        .filter(|(_, executed)| {
+            !(executed.exec_sell_amount.is_zero() && executed.exec_buy_amount.is_zero())
+        })
+        .map(|(index, executed)| {
+            let order = auction
+                .orders
+                .get(*index)
+                .with_context(|| format!("invalid order index {index}"))?;
+            Ok(solution::Trade::Fulfillment(solution::Fulfillment {
+                order: order.uid,
+                executed: match order.side {
+                    order::Side::Sell => executed.exec_sell_amount,
+                    order::Side::Buy => executed.exec_buy_amount,
+                },
+            }))
+        })
+        .collect::<Result<_>>()?;
+
+    // Interactions are executed in the order of their execution plan
+    // coordinates.
+    let mut interactions = Vec::<(ExecutionPlanCoordinatesModel, _)>::new();
+    for (address, amm) in &model.amms {
+        let liquidity = liquidity
+            .get(address)
+            .with_context(|| format!("invalid AMM {address:?}"))?;
+        for execution in &amm.execution {
+            interactions.push((
+                execution.exec_plan.coordinates.clone(),
+                solution::Interaction::Liquidity(solution::LiquidityInteraction {
+                    liquidity: (*liquidity).clone(),
+                    input: eth::Asset {
+                        token: eth::TokenAddress(execution.buy_token),
+                        amount: execution.exec_buy_amount,
+                    },
+                    output: eth::Asset {
+                        token: eth::TokenAddress(execution.sell_token),
+                        amount: execution.exec_sell_amount,
+                    },
+                    internalize: execution.exec_plan.internal,
+                }),
+            ));
+        }
+    }
+
+    model.add_missing_execution_plans();
+    // The legacy model specifies approvals separately from the interactions
+    // that need them. Since the settlement contract sets all allowances before
+    // executing any interactions, they can all be attached to the first one.
+    let mut approvals = Some(model.approvals);
+    for interaction in &model.interaction_data {
+        let exec_plan = interaction
+            .exec_plan
+            .clone()
+            .expect("missing execution plans were added");
+        interactions.push((
+            exec_plan.coordinates,
+            solution::Interaction::Custom(solution::CustomInteraction {
+                target: interaction.target,
+                value: interaction.value,
+                calldata: interaction.call_data.clone(),
+                inputs: interaction.inputs.iter().map(to_domain_asset).collect(),
+                outputs: interaction.outputs.iter().map(to_domain_asset).collect(),
+                allowances: approvals
+                    .take()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|approval| solution::Allowance {
+                        spender: approval.spender,
+                        asset: eth::Asset {
+                            token: eth::TokenAddress(approval.token),
+                            amount: approval.amount,
+                        },
+                    })
+                    .collect(),
+                internalize: exec_plan.internal,
+            }),
+        ));
+    }
+    interactions.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    Ok(solution::Solution {
+        prices: solution::ClearingPrices(
+            model
+                .prices
+                .into_iter()
+                .map(|(token, price)| (eth::TokenAddress(token), price))
+                .collect(),
+        ),
+        trades,
+        interactions: interactions
+            .into_iter()
+            .map(|(_, interaction)| interaction)
+            .collect(),
+    })
+}
+
+fn to_domain_asset(amount: &TokenAmount) -> eth::Asset {
+    eth::Asset {
+        token: eth::TokenAddress(amount.token),
+        amount: amount.amount,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::http_solver::model::{
+        ApprovalModel, ExecutedAmmModel, ExecutedOrderModel, ExecutionPlan, InteractionData,
+        UpdatedAmmModel,
+    };
+
+    fn token(byte: u8) -> eth::TokenAddress {
+        eth::TokenAddress(H160([byte; 20]))
+    }
+
+    fn exec_plan(position: u32) -> ExecutionPlan {
+        ExecutionPlan {
+            coordinates: ExecutionPlanCoordinatesModel {
+                sequence: 0,
+                position,
+            },
+            internal: false,
+        }
+    }
+
+    #[test]
+    fn converts_solution_in_execution_plan_order() {
+        let liquidity = liquidity::Liquidity {
+            id: liquidity::Id(0),
+            address: H160([0xff; 20]),
+            gas: eth::Gas(100_000.into()),
+            state: liquidity::State::ConstantProduct(liquidity::constant_product::Pool {
+                reserves: [
+                    eth::Asset {
+                        token: token(1),
+                        amount: 1_000.into(),
+                    },
+                    eth::Asset {
+                        token: token(2),
+                        amount: 1_000.into(),
+                    },
+                ],
+                fee: liquidity::Fee("0.003".parse().unwrap()),
+            }),
+        };
+        let order = |uid, side| order::Order {
+            uid: order::Uid([uid; 56]),
+            sell: eth::Asset {
+                token: token(1),
+                amount: 10.into(),
+            },
+            buy: eth::Asset {
+                token: token(2),
+                amount: 9.into(),
+            },
+            fee: Default::default(),
+            side,
+            class: order::Class::Market,
+            partially_fillable: false,
+        };
+        let auction = auction::Auction {
+            id: None,
+            tokens: Default::default(),
+            orders: vec![order(1, order::Side::Sell), order(2, order::Side::Buy)],
+            liquidity: vec![liquidity.clone()],
+            gas_price: eth::GasPrice(1.into()),
+            deadline: auction::Deadline(Default::default()),
+        };
+        let executed = |sell: u64, buy: u64| ExecutedOrderModel {
+            exec_sell_amount: sell.into(),
+            exec_buy_amount: buy.into(),
+            cost: None,
+            fee: None,
+            exec_plan: None,
+        };
+        let model = SettledBatchAuctionModel {
+            orders: HashMap::from([(0, executed(10, 9)), (1, executed(0, 0))]),
+            amms: HashMap::from([(
+                liquidity.address,
+                UpdatedAmmModel {
+                    execution: vec![ExecutedAmmModel {
+                        sell_token: token(2).0,
+                        buy_token: token(1).0,
+                        exec_sell_amount: 9.into(),
+                        exec_buy_amount: 10.into(),
+                        exec_plan: exec_plan(1),
+                    }],
+                    cost: None,
+                },
+            )]),
+            prices: HashMap::from([(token(1).0, 9.into()), (token(2).0, 10.into())]),
+            approvals: vec![ApprovalModel {
+                token: token(1).0,
+                spender: H160([0xee; 20]),
+                amount: 10.into(),
+            }],
+            interaction_data: vec![InteractionData {
+                target: H160([0xee; 20]),
+                exec_plan: Some(exec_plan(0)),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let solution = to_domain_solution(
+            model,
+            &auction,
+            &HashMap::from([(liquidity.address, &liquidity)]),
+        )
+        .unwrap();
+
+        // Orders without any executed amounts are not part of the solution.
+        assert!(matches!(
+            &solution.trades[..],
+            [solution::Trade::Fulfillment(trade)]
+                if trade.order == order::Uid([1; 56]) && trade.executed == 10.into()
+        ));
+        match &solution.interactions[..] {
+            [solution::Interaction::Custom(custom), solution::Interaction::Liquidity(swap)] => {
+                assert_eq!(custom.allowances.len(), 1);
+                assert_eq!(swap.input.token, token(1));
+                assert_eq!(swap.input.amount, 10.into());
+                assert_eq!(swap.output.token, token(2));
+                assert_eq!(swap.output.amount, 9.into());
+            }
+            interactions => panic!("unexpected interactions {interactions:?}"),
+        }
+    }
+
+    #[test]
+    fn converts_decimals_to_rationals() {
+        assert_eq!(
+            to_big_rational(&"0.003".parse().unwrap()).unwrap(),
+            BigRational::new(3.into(), 1000.into())
+        );
+        assert_eq!(
+            to_big_rational(&"1e3".parse().unwrap()).unwrap(),
+            BigRational::from_integer(1000.into())
+        );
+    }
+}
@@ -0,0 +1,510 @@
+//! Swap math for UniswapV3-like concentrated liquidity pools.
+//!
+//! This is a port of the relevant parts of the `UniswapV3Pool`, `SwapMath`,
+//! `SqrtPriceMath` and `TickMath` contracts, so that the computed amounts
+//! match the onchain swaps exactly.
+//!
+//! https://github.com/Uniswap/v3-core/tree/main/contracts
+
+use crate::domain::liquidity;
+use ethereum_types::{H160, U256, U512};
+use num::ToPrimitive;
+
+const MIN_TICK: i32 = -887272;
+const MAX_TICK: i32 = -MIN_TICK;
+
+/// Fees are specified in hundredths of a basis point.
+const FEE_DENOMINATOR: u32 = 1_000_000;
+
+/// A concentrated liquidity pool that can compute swap amounts.
+#[derive(Clone)]
+pub struct Pool<'a> {
+    state: &'a liquidity::concentrated::Pool,
+    fee: u32,
+}
+
+impl<'a> Pool<'a> {
+    /// Returns `None` if the fee isn't a whole number of hundredths of a basis
+    /// point.
+    pub fn new(state: &'a liquidity::concentrated::Pool) -> Option<Self> {
+        let fee = &state.fee.0 * bigdecimal::BigDecimal::from(FEE_DENOMINATOR);
+        if !fee.is_integer() {
+            return None;
+        }
+        let fee = fee.to_u32().filter(|fee| *fee < FEE_DENOMINATOR)?;
+        Some(Self { state, fee })
+    }
+
+    fn swap(&self, zero_for_one: bool, amount: Amount) -> Option<U256> {
+        let price_limit = if zero_for_one {
+            min_sqrt_ratio() + 1
+        } else {
+            max_sqrt_ratio() - 1
+        };
+
+        let mut remaining = amount.value();
+        let mut calculated = U256::zero();
+        let mut sqrt_price = self.state.sqrt_price;
+        let mut tick = self.state.tick;
+        let mut liquidity = self.state.liquidity;
+
+        while !remaining.is_zero() {
+            if sqrt_price == price_limit {
+                // There is not enough liquidity to swap the full amount.
+                return None;
+            }
+
+            let next = if zero_for_one {
+                self.state.liquidity_net.range(..=tick).next_back()
+            } else {
+                self.state.liquidity_net.range(tick + 1..).next()
+            };
+            let tick_next = match next {
+                Some((tick, _)) => (*tick).clamp(MIN_TICK, MAX_TICK),
+                None if zero_for_one => MIN_TICK,
+                None => MAX_TICK,
+            };
+            let sqrt_price_next = sqrt_ratio_at_tick(tick_next)?;
+            let target = if zero_for_one {
+                sqrt_price_next.max(price_limit)
+            } else {
+                sqrt_price_next.min(price_limit)
+            };
+
+            let step = swap_step(
+                sqrt_price,
+                target,
+                liquidity,
+                amount.with_value(remaining),
+                self.fee,
+            )?;
+            sqrt_price = step.sqrt_price;
+            match amount {
+                Amount::ExactIn(_) => {
+                    remaining = remaining.checked_sub(step.amount_in.checked_add(step.fee)?)?;
+                    calculated = calculated.checked_add(step.amount_out)?;
+                }
+                Amount::ExactOut(_) => {
+                    remaining = remaining.checked_sub(step.amount_out)?;
+                    calculated = calculated.checked_add(step.amount_in.checked_add(step.fee)?)?;
+                }
+            }
+
+            if sqrt_price == sqrt_price_next {
+                if let Some((_, net)) = next {
+                    let net = if zero_for_one { -net } else { *net };
+                    liquidity = if net < 0 {
+                        liquidity.checked_sub(net.unsigned_abs())?
+                    } else {
+                        liquidity.checked_add(net.unsigned_abs())?
+                    };
+                }
+                tick = if zero_for_one {
+                    tick_next - 1
+                } else {
+                    tick_next
+                };
+            }
+            // Otherwise the swap ended within the current tick range, so the
+            // loop terminates and the tick is no longer needed.
+        }
+
+        Some(calculated)
+    }
+
+    fn zero_for_one(&self, in_token: H160, out_token: H160) -> Option<bool> {
+        match self.state.tokens.map(|token| token.0) {
+            [token0, token1] if (in_token, out_token) == (token0, token1) => Some(true),
+            [token0, token1] if (in_token, out_token) == (token1, token0) => Some(false),
+            _ => None,
+        }
+    }
+
+    /// The amount of `out_token` received for swapping the input. Returns
+    /// `None` if the pool doesn't have enough liquidity.
+    pub fn get_amount_out(
+        &self,
+        out_token: H160,
+        (in_amount, in_token): (U256, H160),
+    ) -> Option<U256> {
+        let zero_for_one = self.zero_for_one(in_token, out_token)?;
+        self.swap(zero_for_one, Amount::ExactIn(in_amount))
+    }
+
+    /// The amount of `in_token` required for receiving the output. Returns
+    /// `None` if the pool doesn't have enough liquidity.
+    pub fn get_amount_in(
+        &self,
+        in_token: H160,
+        (out_amount, out_token): (U256, H160),
+    ) -> Option<U256> {
+        let zero_for_one = self.zero_for_one(in_token, out_token)?;
+        self.swap(zero_for_one, Amount::ExactOut(out_amount))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Amount {
+    ExactIn(U256),
+    ExactOut(U256),
+}
+
+impl Amount {
+    fn value(self) -> U256 {
+        match self {
+            Self::ExactIn(value) | Self::ExactOut(value) => value,
+        }
+    }
+
+    fn with_value(self, value: U256) -> Self {
+        match self {
+            Self::ExactIn(_) => Self::ExactIn(value),
+            Self::ExactOut(_) => Self::ExactOut(value),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Step {
+    sqrt_price: U256,
+    amount_in: U256,
+    amount_out: U256,
+    fee: U256,
+}
+
+/// Computes the result of swapping within a single tick range. Port of
+/// `SwapMath.computeSwapStep`.
+fn swap_step(
+    sqrt_price: U256,
+    target: U256,
+    liquidity: u128,
+    amount: Amount,
+    fee: u32,
+) -> Option<Step> {
+    let zero_for_one = sqrt_price >= target;
+    let fee_complement = U256::from(FEE_DENOMINATOR - fee);
+
+    let (next, amount_in_to_target, amount_out_to_target) = match amount {
+        Amount::ExactIn(remaining) => {
+            let remaining_less_fee =
+                mul_div(remaining, fee_complement, FEE_DENOMINATOR.into(), false)?;
+            let amount_in = if zero_for_one {
+                amount0_delta(target, sqrt_price, liquidity, true)?
+            } else {
+                amount1_delta(sqrt_price, target, liquidity, true)?
+            };
+            let next = if remaining_less_fee >= amount_in {
+                target
+            } else {
+                next_sqrt_price_from_input(sqrt_price, liquidity, remaining_less_fee, zero_for_one)?
+            };
+            (next, Some(amount_in), None)
+        }
+        Amount::ExactOut(remaining) => {
+            let amount_out = if zero_for_one {
+                amount1_delta(target, sqrt_price, liquidity, false)?
+            } else {
+                amount0_delta(sqrt_price, target, liquidity, false)?
+            };
+            let next = if remaining >= amount_out {
+                target
+            } else {
+                next_sqrt_price_from_output(sqrt_price, liquidity, remaining, zero_for_one)?
+            };
+            (next, None, Some(amount_out))
+        }
+    };
+    let max = next == target;
+
+    let (amount_in, mut amount_out) = if zero_for_one {
+        (
+            match amount_in_to_target {
+                Some(amount_in) if max => amount_in,
+                _ => amount0_delta(next, sqrt_price, liquidity, true)?,
+            },
+            match amount_out_to_target {
+                Some(amount_out) if max => amount_out,
+                _ => amount1_delta(next, sqrt_price, liquidity, false)?,
+            },
+        )
+    } else {
+        (
+            match amount_in_to_target {
+                Some(amount_in) if max => amount_in,
+                _ => amount1_delta(sqrt_price, next, liquidity, true)?,
+            },
+            match amount_out_to_target {
+                Some(amount_out) if max => amount_out,
+                _ => amount0_delta(sqrt_price, next, liquidity, false)?,
+            },
+        )
+    };
+
+    let fee = match amount {
+        Amount::ExactIn(remaining) if !max => remaining.checked_sub(amount_in)?,
+        Amount::ExactIn(_) => mul_div(amount_in, fee.into(), fee_complement, true)?,
+        Amount::ExactOut(remaining) => {
+            amount_out = amount_out.min(remaining);
+            mul_div(amount_in, fee.into(), fee_complement, true)?
+        }
+    };
+
+    Some(Step {
+        sqrt_price: next,
+        amount_in,
+        amount_out,
+        fee,
+    })
+}
+
+/// The amount of token0 between two prices. Port of
+/// `SqrtPriceMath.getAmount0Delta`.
+fn amount0_delta(a: U256, b: U256, liquidity: u128, round_up: bool) -> Option<U256> {
+    let (lower, upper) = if a < b { (a, b) } else { (b, a) };
+    if lower.is_zero() {
+        return None;
+    }
+    let numerator = U256::from(liquidity) << 96;
+    let amount = mul_div(numerator, upper - lower, upper, round_up)?;
+    to_u256(div(amount.into(), lower.into(), round_up))
+}
+
+/// The amount of token1 between two prices. Port of
+/// `SqrtPriceMath.getAmount1Delta`.
+fn amount1_delta(a: U256, b: U256, liquidity: u128, round_up: bool) -> Option<U256> {
+    let (lower, upper) = if a < b { (a, b) } else { (b, a) };
+    mul_div(liquidity.into(), upper - lower, q96(), round_up)
+}
+
+/// Port of `SqrtPriceMath.getNextSqrtPriceFromInput`.
+fn next_sqrt_price_from_input(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount: U256,
+    zero_for_one: bool,
+) -> Option<U256> {
+    if zero_for_one {
+        next_sqrt_price_from_amount0(sqrt_price, liquidity, amount, true)
+    } else {
+        next_sqrt_price_from_amount1(sqrt_price, liquidity, amount, true)
+    }
+}
+
+/// Port of `SqrtPriceMath.getNextSqrtPriceFromOutput`.
+fn next_sqrt_price_from_output(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount: U256,
+    zero_for_one: bool,
+) -> Option<U256> {
+    if zero_for_one {
+        next_sqrt_price_from_amount1(sqrt_price, liquidity, amount, false)
+    } else {
+        next_sqrt_price_from_amount0(sqrt_price, liquidity, amount, false)
+    }
+}
+
+/// Port of `SqrtPriceMath.getNextSqrtPriceFromAmount0RoundingUp`.
+fn next_sqrt_price_from_amount0(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount: U256,
+    add: bool,
+) -> Option<U256> {
+    if amount.is_zero() {
+        return Some(sqrt_price);
+    }
+    let numerator = U512::from(liquidity) << 96;
+    let product = sqrt_price.full_mul(amount);
+    let denominator = if add {
+        numerator.checked_add(product)?
+    } else {
+        numerator.checked_sub(product).filter(|d| !d.is_zero())?
+    };
+    to_u256(div(
+        numerator.checked_mul(U512::from(sqrt_price))?,
+        denominator,
+        true,
+    ))
+}
+
+/// Port of `SqrtPriceMath.getNextSqrtPriceFromAmount1RoundingDown`.
+fn next_sqrt_price_from_amount1(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount: U256,
+    add: bool,
+) -> Option<U256> {
+    if liquidity == 0 {
+        return None;
+    }
+    if add {
+        let quotient = mul_div(amount, q96(), liquidity.into(), false)?;
+        sqrt_price.checked_add(quotient)
+    } else {
+        let quotient = mul_div(amount, q96(), liquidity.into(), true)?;
+        sqrt_price
+            .checked_sub(quotient)
+            .filter(|price| !price.is_zero())
+    }
+}
+
+/// The square root of `1.0001 ^ tick` as a Q64.96 fixed point number. Port
+/// of `TickMath.getSqrtRatioAtTick`.
+fn sqrt_ratio_at_tick(tick: i32) -> Option<U256> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return None;
+    }
+    let abs_tick = tick.unsigned_abs();
+
+    // Each factor is `2^128 / sqrt(1.0001) ^ bit`.
+    const FACTORS: [(u32, u128); 19] = [
+        (0x2, 0xfff97272373d413259a46990580e213a),
+        (0x4, 0xfff2e50f5f656932ef12357cf3c7fdcc),
+        (0x8, 0xffe5caca7e10e4e61c3624eaa0941cd0),
+        (0x10, 0xffcb9843d60f6159c9db58835c926644),
+        (0x20, 0xff973b41fa98c081472e6896dfb254c0),
+        (0x40, 0xff2ea16466c96a3843ec78b326b52861),
+        (0x80, 0xfe5dee046a99a2a811c461f1969c3053),
+        (0x100, 0xfcbe86c7900a88aedcffc83b479aa3a4),
+        (0x200, 0xf987a7253ac413176f2b074cf7815e54),
+        (0x400, 0xf3392b0822b70005940c7a398e4b70f3),
+        (0x800, 0xe7159475a2c29b7443b29c7fa6e889d9),
+        (0x1000, 0xd097f3bdfd2022b8845ad8f792aa5825),
+        (0x2000, 0xa9f746462d870fdf8a65dc1f90e061e5),
+        (0x4000, 0x70d869a156d2a1b890bb3df62baf32f7),
+        (0x8000, 0x31be135f97d08fd981231505542fcfa6),
+        (0x10000, 0x9aa508b5b7a84e1c677de54f3e99bc9),
+        (0x20000, 0x5d6af8dedb81196699c329225ee604),
+        (0x40000, 0x2216e584f5fa1ea926041bedfe98),
+        (0x80000, 0x48a170391f7dc42444e8fa2),
+    ];
+
+    let mut ratio = if abs_tick & 0x1 != 0 {
+        U256::from(0xfffcb933bd6fad37aa2d162d1a594001_u128)
+    } else {
+        U256::one() << 128
+    };
+    for (bit, factor) in FACTORS {
+        if abs_tick & bit != 0 {
+            ratio = (ratio * U256::from(factor)) >> 128;
+        }
+    }
+    if tick > 0 {
+        ratio = U256::MAX / ratio;
+    }
+
+    // Round up when converting from Q128.128 to Q64.96 so that the price is
+    // always at least the price of the tick.
+    let round_up = if ratio.low_u32() == 0 { 0 } else { 1 };
+    Some((ratio >> 32) + round_up)
+}
+
+fn min_sqrt_ratio() -> U256 {
+    U256::from(4295128739_u64)
+}
+
+fn max_sqrt_ratio() -> U256 {
+    U256::from_dec_str("1461446703485210103287273052203988822378723970342").unwrap()
+}
+
+fn q96() -> U256 {
+    U256::one() << 96
+}
+
+/// Computes `a * b / denominator` with full precision.
+fn mul_div(a: U256, b: U256, denominator: U256, round_up: bool) -> Option<U256> {
+    if denominator.is_zero() {
+        return None;
+    }
+    to_u256(div(a.full_mul(b), denominator.into(), round_up))
+}
+
+fn div(numerator: U512, denominator: U512, round_up: bool) -> U512 {
+    let (quotient, remainder) = numerator.div_mod(denominator);
+    if round_up && !remainder.is_zero() {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
+
+fn to_u256(value: U512) -> Option<U256> {
+    value.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::eth;
+    use std::collections::BTreeMap;
+
+    fn pool(liquidity_net: BTreeMap<i32, i128>) -> liquidity::concentrated::Pool {
+        liquidity::concentrated::Pool {
+            tokens: [
+                eth::TokenAddress(H160([1; 20])),
+                eth::TokenAddress(H160([2; 20])),
+            ],
+            sqrt_price: q96(),
+            liquidity: 10_u128.pow(18),
+            tick: 0,
+            liquidity_net,
+            fee: liquidity::Fee("0.003".parse().unwrap()),
+        }
+    }
+
+    #[test]
+    fn sqrt_ratio_at_tick_bounds() {
+        assert_eq!(sqrt_ratio_at_tick(0), Some(q96()));
+        assert_eq!(sqrt_ratio_at_tick(MIN_TICK), Some(min_sqrt_ratio()));
+        assert_eq!(sqrt_ratio_at_tick(MAX_TICK), Some(max_sqrt_ratio()));
+        assert_eq!(sqrt_ratio_at_tick(MIN_TICK - 1), None);
+        assert_eq!(sqrt_ratio_at_tick(MAX_TICK + 1), None);
+    }
+
+    #[test]
+    fn swaps_within_a_tick_range() {
+        let state = pool(BTreeMap::from([
+            (-60, 10_i128.pow(18)),
+            (60, -10_i128.pow(18)),
+        ]));
+        let pool = Pool::new(&state).unwrap();
+        let (token0, token1) = (state.tokens[0].0, state.tokens[1].0);
+
+        let amount_in = U256::exp10(15);
+        let amount_out = pool.get_amount_out(token1, (amount_in, token0)).unwrap();
+        // Roughly the input at a price of 1 minus the 0.3% fee and price
+        // impact.
+        assert!(amount_out < U256::from(997) * U256::exp10(12));
+        assert!(amount_out > U256::from(995) * U256::exp10(12));
+
+        // Buying the same amount requires the original input, up to rounding.
+        let required = pool.get_amount_in(token0, (amount_out, token1)).unwrap();
+        let difference = required.max(amount_in) - required.min(amount_in);
+        assert!(difference <= 2.into());
+    }
+
+    #[test]
+    fn runs_out_of_liquidity_when_crossing_ticks() {
+        let state = pool(BTreeMap::from([
+            (-60, 10_i128.pow(18)),
+            (60, -10_i128.pow(18)),
+        ]));
+        let pool = Pool::new(&state).unwrap();
+        let (token0, token1) = (state.tokens[0].0, state.tokens[1].0);
+
+        // Moving the price past tick 60 leaves no liquidity to swap against.
+        assert_eq!(pool.get_amount_out(token0, (U256::exp10(18), token1)), None);
+        assert_eq!(pool.get_amount_in(token1, (U256::exp10(18), token0)), None);
+    }
+
+    #[test]
+    fn rejects_unknown_tokens() {
+        let state = pool(Default::default());
+        let pool = Pool::new(&state).unwrap();
+        assert_eq!(
+            pool.get_amount_out(H160([3; 20]), (1.into(), state.tokens[0].0)),
+            None
+        );
+    }
+}
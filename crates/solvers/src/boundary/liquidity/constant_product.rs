@@ -0,0 +1,59 @@
+use crate::domain::liquidity;
+use ethereum_types::H160;
+use model::TokenPair;
+use num::{rational::Ratio, ToPrimitive};
+use shared::sources::uniswap_v2::pool_fetching::Pool;
+
+/// Converts a constant product pool into a UniswapV2 pool. Returns `None` if
+/// the pool state can't be represented, for example because of reserves that
+/// don't fit into 128 bits.
+pub fn to_boundary_pool(address: H160, pool: &liquidity::constant_product::Pool) -> Option<Pool> {
+    let [a, b] = pool.reserves;
+    let tokens = TokenPair::new(a.token.0, b.token.0)?;
+    let (a, b) = if tokens.get().0 == a.token.0 {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    Some(Pool {
+        address,
+        tokens,
+        reserves: (a.amount.try_into().ok()?, b.amount.try_into().ok()?),
+        fee: to_fee_ratio(&pool.fee)?,
+    })
+}
+
+fn to_fee_ratio(fee: &liquidity::Fee) -> Option<Ratio<u32>> {
+    let (numer, exponent) = fee.0.as_bigint_and_exponent();
+    let denom = 10_u32.checked_pow(exponent.try_into().ok()?)?;
+    Some(Ratio::new(numer.to_u32()?, denom))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::eth;
+
+    #[test]
+    fn converts_pool_with_ordered_reserves() {
+        let token = |byte| eth::TokenAddress(H160([byte; 20]));
+        let pool = liquidity::constant_product::Pool {
+            reserves: [
+                eth::Asset {
+                    token: token(2),
+                    amount: 200.into(),
+                },
+                eth::Asset {
+                    token: token(1),
+                    amount: 100.into(),
+                },
+            ],
+            fee: liquidity::Fee("0.003".parse().unwrap()),
+        };
+
+        let pool = to_boundary_pool(H160([3; 20]), &pool).unwrap();
+        assert_eq!(pool.tokens.get(), (token(1).0, token(2).0));
+        assert_eq!(pool.reserves, (100, 200));
+        assert_eq!(pool.fee, Ratio::new(3, 1000));
+    }
+}
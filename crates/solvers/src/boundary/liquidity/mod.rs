@@ -0,0 +1,28 @@
+//! Conversions from the domain liquidity into the pool types that implement
+//! the baseline solver swap math.
+
+pub mod concentrated;
+pub mod constant_product;
+pub mod stable;
+pub mod weighted_product;
+
+use bigdecimal::BigDecimal;
+use ethereum_types::U256;
+use shared::sources::balancer_v2::swap::fixed_point::Bfp;
+
+/// Converts a decimal into a Balancer 18 decimal fixed point number. Excess
+/// precision is truncated.
+fn to_fixed_point(value: &BigDecimal) -> Option<Bfp> {
+    let scaled = (value * BigDecimal::from(1_000_000_000_000_000_000_u64)).with_scale(0);
+    to_u256(&scaled).map(Bfp::from_wei)
+}
+
+/// Converts an integer decimal into a `U256`. Returns `None` for negative,
+/// fractional or overflowing values.
+fn to_u256(value: &BigDecimal) -> Option<U256> {
+    if !value.is_integer() {
+        return None;
+    }
+    let (integer, _) = value.with_scale(0).into_bigint_and_exponent();
+    U256::from_dec_str(&integer.to_string()).ok()
+}
@@ -0,0 +1,88 @@
+use super::{to_fixed_point, to_u256};
+use crate::domain::liquidity;
+use bigdecimal::BigDecimal;
+use ethereum_types::{H160, U256};
+use shared::sources::balancer_v2::pool_fetching::{
+    AmplificationParameter, CommonPoolState, StablePool, TokenState,
+};
+
+/// Converts a stable pool into a Balancer stable pool. Returns `None` if the
+/// scaling factors or amplification parameter can't be represented.
+pub fn to_boundary_pool(address: H160, pool: &liquidity::stable::Pool) -> Option<StablePool> {
+    let reserves = pool
+        .reserves
+        .iter()
+        .map(|reserve| {
+            Some((
+                reserve.asset.token.0,
+                TokenState {
+                    balance: reserve.asset.amount,
+                    scaling_exponent: to_scaling_exponent(reserve.scaling_factor)?,
+                },
+            ))
+        })
+        .collect::<Option<_>>()?;
+    Some(StablePool {
+        common: CommonPoolState {
+            // The pool ID is only needed for encoding swaps, not for computing
+            // their amounts.
+            id: Default::default(),
+            address,
+            swap_fee: to_fixed_point(&pool.fee.0)?,
+            paused: false,
+        },
+        reserves,
+        amplification_parameter: to_amplification_parameter(&pool.amplification_parameter)?,
+    })
+}
+
+/// Balancer scaling exponents are `18 - decimals` while the scaling factor is
+/// `10 ** decimals`.
+fn to_scaling_exponent(factor: U256) -> Option<u8> {
+    (0..=18_u8)
+        .find(|decimals| U256::exp10((*decimals).into()) == factor)
+        .map(|decimals| 18 - decimals)
+}
+
+/// Represents the amplification parameter as a factor and precision, such that
+/// `factor / precision` is the parameter, like the pool contracts do.
+fn to_amplification_parameter(value: &BigDecimal) -> Option<AmplificationParameter> {
+    // A negative exponent means that the value is an integer with trailing
+    // zeros, which can't be represented with a precision.
+    let value = match value.as_bigint_and_exponent() {
+        (_, exponent) if exponent < 0 => value.with_scale(0),
+        _ => value.clone(),
+    };
+    let (factor, exponent) = value.into_bigint_and_exponent();
+    let factor = to_u256(&BigDecimal::new(factor, 0))?;
+    let precision = U256::from(10).checked_pow(U256::from(u64::try_from(exponent).ok()?))?;
+    AmplificationParameter::new(factor, precision).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaling_exponent_from_factor() {
+        assert_eq!(to_scaling_exponent(U256::exp10(18)), Some(0));
+        assert_eq!(to_scaling_exponent(U256::exp10(6)), Some(12));
+        assert_eq!(to_scaling_exponent(1.into()), Some(18));
+        assert_eq!(to_scaling_exponent(42.into()), None);
+        assert_eq!(to_scaling_exponent(U256::exp10(19)), None);
+    }
+
+    #[test]
+    fn amplification_parameter_from_decimal() {
+        let parameter = to_amplification_parameter(&"0.57".parse().unwrap()).unwrap();
+        assert_eq!(
+            parameter.as_big_rational(),
+            num::BigRational::new(57.into(), 100.into())
+        );
+        let parameter = to_amplification_parameter(&"200".parse().unwrap()).unwrap();
+        assert_eq!(
+            parameter.as_big_rational(),
+            num::BigRational::new(200.into(), 1.into())
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use super::to_fixed_point;
+use crate::domain::liquidity;
+use ethereum_types::H160;
+use shared::sources::balancer_v2::pool_fetching::{
+    CommonPoolState, TokenState, WeightedPool, WeightedTokenState,
+};
+
+/// Converts a weighted product pool into a Balancer weighted pool. Returns
+/// `None` if the weights or fee don't fit into fixed point numbers.
+pub fn to_boundary_pool(
+    address: H160,
+    pool: &liquidity::weighted_product::Pool,
+) -> Option<WeightedPool> {
+    let reserves = pool
+        .reserves
+        .iter()
+        .map(|reserve| {
+            Some((
+                reserve.asset.token.0,
+                WeightedTokenState {
+                    common: TokenState {
+                        balance: reserve.asset.amount,
+                        // The API doesn't specify token decimals for weighted
+                        // pools. Swap amounts don't depend on the scaling, only
+                        // the rounding of the fixed point math does.
+                        scaling_exponent: 0,
+                    },
+                    weight: to_fixed_point(&reserve.weight)?,
+                },
+            ))
+        })
+        .collect::<Option<_>>()?;
+    Some(WeightedPool {
+        common: CommonPoolState {
+            // The pool ID is only needed for encoding swaps, not for computing
+            // their amounts.
+            id: Default::default(),
+            address,
+            swap_fee: to_fixed_point(&pool.fee.0)?,
+            paused: false,
+        },
+        reserves,
+    })
+}
@@ -0,0 +1,6 @@
+//! Wrappers around code from the `shared` crate that the solver engines reuse
+//! until the relevant logic is ported over to this crate.
+
+pub mod baseline;
+pub mod legacy;
+pub mod liquidity;
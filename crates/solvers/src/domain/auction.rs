@@ -0,0 +1,35 @@
+use crate::domain::{eth, liquidity, order};
+use ethereum_types::U256;
+use std::collections::HashMap;
+
+/// The auction that the solver engine is asked to solve.
+#[derive(Debug, Clone)]
+pub struct Auction {
+    /// `None` for requests that are not part of an auction, for example when
+    /// quoting token prices.
+    pub id: Option<Id>,
+    pub tokens: HashMap<eth::TokenAddress, Token>,
+    pub orders: Vec<order::Order>,
+    pub liquidity: Vec<liquidity::Liquidity>,
+    pub gas_price: eth::GasPrice,
+    pub deadline: Deadline,
+}
+
+/// An opaque auction identifier.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Id(pub String);
+
+/// Information about a token relevant to the auction.
+#[derive(Clone, Debug)]
+pub struct Token {
+    pub decimals: Option<u8>,
+    pub symbol: Option<String>,
+    /// The reference price of the token denominated in the native token.
+    pub reference_price: Option<U256>,
+    pub available_balance: U256,
+    pub trusted: bool,
+}
+
+/// The time by which the solver engine has to respond with a solution.
+#[derive(Clone, Copy, Debug)]
+pub struct Deadline(pub chrono::DateTime<chrono::Utc>);
@@ -0,0 +1,25 @@
+use ethereum_types::{H160, U256};
+
+/// An ERC20 token address.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct TokenAddress(pub H160);
+
+/// The WETH token (or equivalent) for the EVM compatible network.
+#[derive(Clone, Copy, Debug)]
+pub struct WethAddress(pub H160);
+
+/// An asset on the Ethereum blockchain. Represents a particular amount of a
+/// particular token.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Asset {
+    pub token: TokenAddress,
+    pub amount: U256,
+}
+
+/// An amount of gas units.
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Gas(pub U256);
+
+/// A gas price in wei per gas unit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GasPrice(pub U256);
@@ -0,0 +1,18 @@
+use crate::domain::{eth, liquidity};
+use ethereum_types::U256;
+use std::collections::BTreeMap;
+
+/// State for a UniswapV3-like concentrated liquidity pool.
+#[derive(Clone, Debug)]
+pub struct Pool {
+    pub tokens: [eth::TokenAddress; 2],
+    /// The square root of the current price as a Q64.96 fixed point number.
+    pub sqrt_price: U256,
+    /// The liquidity which is currently in range.
+    pub liquidity: u128,
+    pub tick: i32,
+    /// The net liquidity that is added (or removed when negative) when
+    /// crossing each initialized tick from left to right.
+    pub liquidity_net: BTreeMap<i32, i128>,
+    pub fee: liquidity::Fee,
+}
@@ -0,0 +1,8 @@
+use crate::domain::{eth, liquidity};
+
+/// State for a UniswapV2-like constant product liquidity pool.
+#[derive(Clone, Debug)]
+pub struct Pool {
+    pub reserves: [eth::Asset; 2],
+    pub fee: liquidity::Fee,
+}
@@ -0,0 +1,38 @@
+//! Onchain liquidity that solver engines can use to route orders.
+
+pub mod concentrated;
+pub mod constant_product;
+pub mod stable;
+pub mod weighted_product;
+
+use crate::domain::eth;
+use ethereum_types::H160;
+
+/// A source of liquidity which can be used by the solver.
+#[derive(Clone, Debug)]
+pub struct Liquidity {
+    pub id: Id,
+    pub address: H160,
+    /// Estimation of gas needed to use this liquidity onchain.
+    pub gas: eth::Gas,
+    pub state: State,
+}
+
+/// An opaque liquidity identifier. It is used for referencing the liquidity
+/// from interactions in the solution.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Id(pub usize);
+
+/// The liquidity state, specific to the type of liquidity.
+#[derive(Clone, Debug)]
+pub enum State {
+    ConstantProduct(constant_product::Pool),
+    WeightedProduct(weighted_product::Pool),
+    Stable(stable::Pool),
+    Concentrated(concentrated::Pool),
+}
+
+/// A fee for using a piece of liquidity as a fraction of the input amount,
+/// for example `0.003` for a 0.3% fee.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Fee(pub bigdecimal::BigDecimal);
@@ -0,0 +1,19 @@
+use crate::domain::{eth, liquidity};
+use ethereum_types::U256;
+
+/// State for a Balancer-like stable pool.
+#[derive(Clone, Debug)]
+pub struct Pool {
+    pub reserves: Vec<Reserve>,
+    pub amplification_parameter: bigdecimal::BigDecimal,
+    pub fee: liquidity::Fee,
+}
+
+/// A stable pool token reserve.
+#[derive(Clone, Debug)]
+pub struct Reserve {
+    pub asset: eth::Asset,
+    /// The factor used to scale the token balance to 18 decimals, i.e.
+    /// `10 ** decimals`.
+    pub scaling_factor: U256,
+}
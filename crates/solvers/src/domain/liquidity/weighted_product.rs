@@ -0,0 +1,17 @@
+use crate::domain::{eth, liquidity};
+
+/// State for a Balancer-like weighted product pool.
+#[derive(Clone, Debug)]
+pub struct Pool {
+    pub reserves: Vec<Reserve>,
+    pub fee: liquidity::Fee,
+}
+
+/// A weighted product pool token reserve.
+#[derive(Clone, Debug)]
+pub struct Reserve {
+    pub asset: eth::Asset,
+    /// The normalized weight of the token, for example `0.8` for the larger
+    /// token of an 80/20 pool.
+    pub weight: bigdecimal::BigDecimal,
+}
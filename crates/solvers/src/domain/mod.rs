@@ -0,0 +1,9 @@
+//! The domain model of a solver engine.
+
+pub mod auction;
+pub mod eth;
+pub mod liquidity;
+pub mod order;
+pub mod risk;
+pub mod solution;
+pub mod solver;
@@ -0,0 +1,48 @@
+//! The domain object representing a CoW Protocol order.
+
+use crate::domain::eth;
+use ethereum_types::U256;
+
+/// A CoW Protocol order in the auction.
+#[derive(Debug, Clone)]
+pub struct Order {
+    pub uid: Uid,
+    pub sell: eth::Asset,
+    pub buy: eth::Asset,
+    /// The fee that the solver is expected to charge for this order, in the
+    /// order's sell token.
+    pub fee: Fee,
+    pub side: Side,
+    pub class: Class,
+    pub partially_fillable: bool,
+}
+
+/// UID of an order.
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub struct Uid(pub [u8; 56]);
+
+impl std::fmt::Debug for Uid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "0x{}", hex::encode(self.0))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Fee(pub U256);
+
+/// The trading side of an order.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Side {
+    /// An order with a fixed buy amount and maximum sell amount.
+    Buy,
+    /// An order with a fixed sell amount and a minimum buy amount.
+    Sell,
+}
+
+/// The order classification.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Class {
+    Market,
+    Limit,
+    Liquidity,
+}
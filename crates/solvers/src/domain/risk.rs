@@ -0,0 +1,71 @@
+use crate::domain::{auction, eth, solution};
+
+/// Parameters of a logistic model estimating the probability of a solution
+/// being settled successfully onchain, i.e. without reverting. Positive
+/// factors make solutions riskier the more gas they use, the higher the gas
+/// price and the more orders they settle.
+#[derive(Clone, Debug)]
+pub struct Risk {
+    /// Weight of the gas amount, in millions of gas.
+    pub gas_amount_factor: f64,
+    /// Weight of the gas price, in 10 gwei.
+    pub gas_price_factor: f64,
+    /// Weight of the number of orders.
+    pub nmb_orders_factor: f64,
+    pub intercept: f64,
+    /// Solutions with a lower success probability than this are discarded.
+    pub min_success_probability: f64,
+}
+
+impl Risk {
+    pub fn success_probability(
+        &self,
+        gas: eth::Gas,
+        gas_price: eth::GasPrice,
+        nmb_orders: usize,
+    ) -> f64 {
+        let gas_amount = gas.0.to_f64_lossy() / 1_000_000.;
+        let gas_price = gas_price.0.to_f64_lossy() / 10_000_000_000.;
+        let exponent = self.intercept
+            + self.gas_amount_factor * gas_amount
+            + self.gas_price_factor * gas_price
+            + self.nmb_orders_factor * nmb_orders as f64;
+        1. / (1. + exponent.exp())
+    }
+
+    /// Returns whether the solution is likely enough to be settled
+    /// successfully in the context of the auction.
+    pub fn accepts(&self, solution: &solution::Solution, auction: &auction::Auction) -> bool {
+        self.success_probability(solution.gas(), auction.gas_price, solution.trades.len())
+            >= self.min_success_probability
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn success_probability_decreases_with_risk() {
+        let risk = Risk {
+            gas_amount_factor: 2.,
+            gas_price_factor: 1.,
+            nmb_orders_factor: 0.5,
+            intercept: -3.,
+            min_success_probability: 0.,
+        };
+        let probability = |gas: u64, gas_price: u64, nmb_orders| {
+            risk.success_probability(
+                eth::Gas(gas.into()),
+                eth::GasPrice(gas_price.into()),
+                nmb_orders,
+            )
+        };
+
+        let base = probability(100_000, 10_000_000_000, 1);
+        assert!(base > 0. && base < 1.);
+        assert!(probability(1_000_000, 10_000_000_000, 1) < base);
+        assert!(probability(100_000, 100_000_000_000, 1) < base);
+        assert!(probability(100_000, 10_000_000_000, 10) < base);
+    }
+}
@@ -0,0 +1,309 @@
+use crate::domain::{eth, liquidity, order};
+use ethereum_types::{H160, U256};
+use std::collections::{HashMap, HashSet};
+
+/// A solution to an auction.
+#[derive(Clone, Debug, Default)]
+pub struct Solution {
+    pub prices: ClearingPrices,
+    pub trades: Vec<Trade>,
+    pub interactions: Vec<Interaction>,
+}
+
+impl Solution {
+    /// Combines two solutions into one. Solutions trading exactly one common
+    /// token are combined by rescaling their clearing prices so that they
+    /// agree on the price of that token. Returns `None` if the solutions share
+    /// more than one token or use any of the same liquidity, in which case
+    /// their clearing prices and interaction amounts would be inconsistent.
+    pub fn merge(&self, other: &Self) -> Option<Self> {
+        let liquidity = self.liquidity().collect::<HashSet<_>>();
+        if other.liquidity().any(|id| liquidity.contains(&id)) {
+            return None;
+        }
+
+        let common = self
+            .prices
+            .0
+            .keys()
+            .filter(|token| other.prices.0.contains_key(token))
+            .collect::<Vec<_>>();
+        let (scale, other_scale) = match common[..] {
+            [] => (U256::one(), U256::one()),
+            [token] => (other.prices.0[token], self.prices.0[token]),
+            _ => return None,
+        };
+        let prices = self
+            .prices
+            .0
+            .iter()
+            .map(|(token, price)| Some((*token, price.checked_mul(scale)?)))
+            .chain(
+                other
+                    .prices
+                    .0
+                    .iter()
+                    .map(|(token, price)| Some((*token, price.checked_mul(other_scale)?))),
+            )
+            .collect::<Option<_>>()?;
+
+        Some(Self {
+            prices: ClearingPrices(prices),
+            trades: self.trades.iter().chain(&other.trades).cloned().collect(),
+            interactions: self
+                .interactions
+                .iter()
+                .chain(&other.interactions)
+                .cloned()
+                .collect(),
+        })
+    }
+
+    /// The gas used by the liquidity interactions of the solution. The gas of
+    /// custom interactions is unknown.
+    pub fn gas(&self) -> eth::Gas {
+        eth::Gas(
+            self.interactions
+                .iter()
+                .map(|interaction| match interaction {
+                    Interaction::Liquidity(interaction) => interaction.liquidity.gas.0,
+                    Interaction::Custom(_) => U256::zero(),
+                })
+                .fold(U256::zero(), U256::saturating_add),
+        )
+    }
+
+    fn liquidity(&self) -> impl Iterator<Item = liquidity::Id> + '_ {
+        self.interactions
+            .iter()
+            .filter_map(|interaction| match interaction {
+                Interaction::Liquidity(interaction) => Some(interaction.liquidity.id),
+                Interaction::Custom(_) => None,
+            })
+    }
+}
+
+/// A solution for a single order, which is traded against the interactions.
+#[derive(Debug)]
+pub struct Single {
+    pub order: order::Order,
+    /// The amount of the order's sell token that the interactions use.
+    pub input: eth::Asset,
+    /// The amount of the order's buy token that the interactions produce.
+    pub output: eth::Asset,
+    pub interactions: Vec<Interaction>,
+}
+
+impl Single {
+    /// Creates a full solution that executes the entire order. Returns `None`
+    /// if the interactions don't satisfy the order's limit price.
+    pub fn into_solution(self) -> Option<Solution> {
+        let Self {
+            order,
+            input,
+            output,
+            interactions,
+        } = self;
+
+        if (order.sell.token, order.buy.token) != (input.token, output.token) {
+            return None;
+        }
+
+        // The order is traded at the price of the interactions. For buy
+        // orders, any excess output of the interactions stays in the
+        // settlement contract.
+        let (sell, buy, executed) = match order.side {
+            order::Side::Sell => (order.sell.amount, output.amount, order.sell.amount),
+            order::Side::Buy => (input.amount, order.buy.amount, order.buy.amount),
+        };
+        if input.amount > sell || output.amount < buy {
+            return None;
+        }
+        if sell > order.sell.amount || buy < order.buy.amount {
+            return None;
+        }
+
+        Some(Solution {
+            prices: ClearingPrices(HashMap::from([
+                (order.sell.token, buy),
+                (order.buy.token, sell),
+            ])),
+            trades: vec![Trade::Fulfillment(Fulfillment {
+                order: order.uid,
+                executed,
+            })],
+            interactions,
+        })
+    }
+}
+
+/// The uniform clearing prices of the traded tokens.
+#[derive(Clone, Debug, Default)]
+pub struct ClearingPrices(pub HashMap<eth::TokenAddress, U256>);
+
+/// A trade which executes an order as part of the solution.
+#[derive(Clone, Debug)]
+pub enum Trade {
+    Fulfillment(Fulfillment),
+}
+
+/// A trade of an order from the auction.
+#[derive(Clone, Debug)]
+pub struct Fulfillment {
+    pub order: order::Uid,
+    /// The executed amount of the order, in the sell token for sell orders
+    /// and in the buy token for buy orders.
+    pub executed: U256,
+}
+
+/// An interaction that is executed as part of the solution.
+#[derive(Clone, Debug)]
+pub enum Interaction {
+    Liquidity(LiquidityInteraction),
+    Custom(CustomInteraction),
+}
+
+/// An interaction using a piece of liquidity from the auction.
+#[derive(Clone, Debug)]
+pub struct LiquidityInteraction {
+    pub liquidity: liquidity::Liquidity,
+    pub input: eth::Asset,
+    pub output: eth::Asset,
+    /// Whether the interaction can be internalized, i.e. executed using the
+    /// settlement contract's buffers instead of onchain.
+    pub internalize: bool,
+}
+
+/// An arbitrary interaction with a contract, for example with liquidity that
+/// is not part of the auction.
+#[derive(Clone, Debug)]
+pub struct CustomInteraction {
+    pub target: H160,
+    pub value: U256,
+    pub calldata: Vec<u8>,
+    /// The amounts of tokens that the settlement contract sends to the
+    /// interaction.
+    pub inputs: Vec<eth::Asset>,
+    /// The amounts of tokens that the interaction sends back to the
+    /// settlement contract.
+    pub outputs: Vec<eth::Asset>,
+    /// The allowances which the settlement contract needs to set before the
+    /// interaction can be executed.
+    pub allowances: Vec<Allowance>,
+    /// Whether the interaction can be internalized, i.e. executed using the
+    /// settlement contract's buffers instead of onchain.
+    pub internalize: bool,
+}
+
+/// An ERC20 allowance from the settlement contract to a spender.
+#[derive(Clone, Debug)]
+pub struct Allowance {
+    pub spender: H160,
+    pub asset: eth::Asset,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(token: u8, amount: u64) -> eth::Asset {
+        eth::Asset {
+            token: eth::TokenAddress(H160([token; 20])),
+            amount: amount.into(),
+        }
+    }
+
+    fn single(side: order::Side, input: eth::Asset, output: eth::Asset) -> Single {
+        Single {
+            order: order::Order {
+                uid: order::Uid([1; 56]),
+                sell: asset(1, 100),
+                buy: asset(2, 200),
+                fee: Default::default(),
+                side,
+                class: order::Class::Market,
+                partially_fillable: false,
+            },
+            input,
+            output,
+            interactions: Default::default(),
+        }
+    }
+
+    #[test]
+    fn single_sell_order_trades_at_interaction_price() {
+        let solution = single(order::Side::Sell, asset(1, 100), asset(2, 250))
+            .into_solution()
+            .unwrap();
+        assert_eq!(solution.prices.0[&asset(1, 0).token], 250.into());
+        assert_eq!(solution.prices.0[&asset(2, 0).token], 100.into());
+        assert!(matches!(
+            &solution.trades[..],
+            [Trade::Fulfillment(Fulfillment { executed, .. })] if *executed == 100.into()
+        ));
+
+        assert!(single(order::Side::Sell, asset(1, 100), asset(2, 199))
+            .into_solution()
+            .is_none());
+    }
+
+    #[test]
+    fn single_buy_order_trades_at_interaction_price() {
+        let solution = single(order::Side::Buy, asset(1, 80), asset(2, 201))
+            .into_solution()
+            .unwrap();
+        assert_eq!(solution.prices.0[&asset(1, 0).token], 200.into());
+        assert_eq!(solution.prices.0[&asset(2, 0).token], 80.into());
+        assert!(matches!(
+            &solution.trades[..],
+            [Trade::Fulfillment(Fulfillment { executed, .. })] if *executed == 200.into()
+        ));
+
+        assert!(single(order::Side::Buy, asset(1, 101), asset(2, 200))
+            .into_solution()
+            .is_none());
+        assert!(single(order::Side::Buy, asset(1, 80), asset(2, 199))
+            .into_solution()
+            .is_none());
+    }
+
+    #[test]
+    fn merges_solutions_with_disjoint_tokens() {
+        let a = single(order::Side::Sell, asset(1, 100), asset(2, 200))
+            .into_solution()
+            .unwrap();
+        let mut b = a.clone();
+        b.prices = ClearingPrices(HashMap::from([
+            (asset(3, 0).token, 1.into()),
+            (asset(4, 0).token, 1.into()),
+        ]));
+
+        let merged = a.merge(&b).unwrap();
+        assert_eq!(merged.prices.0.len(), 4);
+        assert_eq!(merged.trades.len(), 2);
+
+        assert!(a.merge(&a).is_none());
+    }
+
+    #[test]
+    fn merges_solutions_with_one_common_token() {
+        let a = single(order::Side::Sell, asset(1, 100), asset(2, 200))
+            .into_solution()
+            .unwrap();
+        let mut b = a.clone();
+        b.prices = ClearingPrices(HashMap::from([
+            (asset(2, 0).token, 3.into()),
+            (asset(3, 0).token, 2.into()),
+        ]));
+
+        let merged = a.merge(&b).unwrap();
+        assert_eq!(
+            merged.prices.0,
+            HashMap::from([
+                (asset(1, 0).token, 600.into()),
+                (asset(2, 0).token, 300.into()),
+                (asset(3, 0).token, 200.into()),
+            ])
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! "Baseline" solver implementation.
+//!
+//! The baseline solver finds the best route for each order over the onchain
+//! liquidity in the auction, using paths with a limited number of intermediate
+//! tokens taken from a set of well-known base tokens. Orders are solved individually
+//! without considering coincidences of wants between them.
+
+use crate::{
+    boundary,
+    domain::{auction, eth, liquidity, order, risk::Risk, solution},
+};
+use std::collections::HashSet;
+
+pub struct Baseline {
+    pub weth: eth::WethAddress,
+    /// Set of tokens to additionally consider as intermediary hops when
+    /// path-finding. This allows paths of the kind `TOKEN1 -> WETH -> TOKEN2`
+    /// to be considered.
+    pub base_tokens: HashSet<eth::TokenAddress>,
+    /// The maximum number of intermediate tokens in a route.
+    pub max_hops: usize,
+    /// Solutions for single orders that are too risky are discarded.
+    pub risk: Option<Risk>,
+}
+
+impl Baseline {
+    pub fn solve(&self, auction: &auction::Auction) -> solution::Solution {
+        let boundary_solver = boundary::baseline::Solver::new(
+            &self.weth,
+            &self.base_tokens,
+            self.max_hops,
+            &auction.liquidity,
+        );
+
+        auction
+            .orders
+            .iter()
+            // Liquidity orders are only settled when they are needed for
+            // trading user orders, which the baseline solver can't do.
+            .filter(|order| order.class != order::Class::Liquidity)
+            .filter_map(|order| {
+                let route = boundary_solver.route(Request {
+                    sell: order.sell,
+                    buy: order.buy,
+                    side: order.side,
+                })?;
+                solution::Single {
+                    order: order.clone(),
+                    input: route.input(),
+                    output: route.output(),
+                    interactions: route
+                        .segments()
+                        .iter()
+                        .map(|segment| {
+                            solution::Interaction::Liquidity(solution::LiquidityInteraction {
+                                liquidity: segment.liquidity.clone(),
+                                input: segment.input,
+                                output: segment.output,
+                                internalize: false,
+                            })
+                        })
+                        .collect(),
+                }
+                .into_solution()
+            })
+            .filter(|single| match &self.risk {
+                Some(risk) => risk.accepts(single, auction),
+                None => true,
+            })
+            // Solutions which can't be combined with the previous ones
+            // (because their clearing prices are inconsistent or they use the
+            // same liquidity) are dropped.
+            .fold(solution::Solution::default(), |solution, single| {
+                solution.merge(&single).unwrap_or(solution)
+            })
+    }
+}
+
+/// A request to find a route for trading one token into another.
+pub struct Request {
+    pub sell: eth::Asset,
+    pub buy: eth::Asset,
+    pub side: order::Side,
+}
+
+/// A route of one or more segments, where each segment swaps the output of
+/// the previous one using a piece of liquidity.
+pub struct Route<'a> {
+    segments: Vec<Segment<'a>>,
+}
+
+/// A single swap in a route.
+pub struct Segment<'a> {
+    pub liquidity: &'a liquidity::Liquidity,
+    pub input: eth::Asset,
+    pub output: eth::Asset,
+}
+
+impl<'a> Route<'a> {
+    /// Returns `None` for empty routes.
+    pub fn new(segments: Vec<Segment<'a>>) -> Option<Self> {
+        if segments.is_empty() {
+            return None;
+        }
+        Some(Self { segments })
+    }
+
+    pub fn segments(&self) -> &[Segment<'a>] {
+        &self.segments
+    }
+
+    pub fn input(&self) -> eth::Asset {
+        self.segments[0].input
+    }
+
+    pub fn output(&self) -> eth::Asset {
+        self.segments.last().expect("routes are never empty").output
+    }
+}
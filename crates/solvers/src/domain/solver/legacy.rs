@@ -0,0 +1,45 @@
+//! "Legacy" solver implementation.
+//!
+//! The legacy solver forwards auctions to an external solver which speaks the
+//! HTTP solver API of the `solver` crate, converting between that API's
+//! models and the solver engine API. This allows existing solvers to be used
+//! with the driver without being rewritten.
+
+use crate::{
+    boundary,
+    domain::{auction, eth, solution},
+};
+use url::Url;
+
+pub struct Legacy(boundary::legacy::Legacy);
+
+pub struct Config {
+    pub weth: eth::WethAddress,
+    pub solver_name: String,
+    pub chain_id: u64,
+    /// The base URL of the external solver. Auctions are sent to its `/solve`
+    /// endpoint.
+    pub base_url: Url,
+    /// Optional value for the `X-API-KEY` header.
+    pub api_key: Option<String>,
+    pub max_nr_exec_orders: u32,
+    pub use_internal_buffers: Option<bool>,
+}
+
+impl Legacy {
+    pub fn new(config: Config) -> Self {
+        Self(boundary::legacy::Legacy::new(config))
+    }
+
+    /// Solves the auction with the external solver. Errors are logged and
+    /// result in an empty solution.
+    pub async fn solve(&self, auction: &auction::Auction) -> solution::Solution {
+        match self.0.solve(auction).await {
+            Ok(solution) => solution,
+            Err(err) => {
+                tracing::warn!(?err, "failed to solve auction with legacy solver");
+                Default::default()
+            }
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use crate::domain::{auction, solution};
+
+pub mod baseline;
+pub mod legacy;
+pub mod naive;
+
+pub use self::{baseline::Baseline, legacy::Legacy, naive::Naive};
+
+/// A solver engine.
+pub enum Solver {
+    Baseline(Baseline),
+    Naive(Naive),
+    Legacy(Legacy),
+}
+
+impl Solver {
+    pub async fn solve(&self, auction: &auction::Auction) -> solution::Solution {
+        match self {
+            Solver::Baseline(solver) => solver.solve(auction),
+            Solver::Naive(solver) => solver.solve(auction),
+            Solver::Legacy(solver) => solver.solve(auction).await,
+        }
+    }
+}
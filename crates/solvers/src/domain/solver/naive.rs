@@ -0,0 +1,636 @@
+//! "Naive" solver implementation.
+//!
+//! The naive solver matches the orders of each token pair against each other
+//! and only uses the deepest constant product pool of the pair for balancing
+//! out the remaining excess. All orders of a pair are traded at the effective
+//! price of that pool interaction, or at its spot price if the orders match
+//! perfectly. Solutions for different token pairs are combined when their
+//! clearing prices can be made consistent, i.e. when they share at most one
+//! token. This means that ring trades (e.g. A→B, B→C and C→A) are not
+//! supported; only the solutions for some of the pairs of a ring get included.
+//!
+//! This is a port of the legacy `solver` crate's `multi_order_solver`.
+
+use crate::domain::{auction, eth, liquidity, order, risk::Risk, solution};
+use ethereum_types::U256;
+use num::{BigInt, BigRational, CheckedDiv, Zero};
+use number_conversions::{big_int_to_u256, big_rational_to_u256, u256_to_big_int};
+use std::collections::{BTreeMap, HashMap};
+
+/// How many times a partially fillable order gets halved before it is removed
+/// from the solution entirely.
+const MAX_PARTIAL_FILL_REDUCTIONS: usize = 4;
+
+pub struct Naive {
+    /// Solutions for token pairs that are too risky are discarded.
+    pub risk: Option<Risk>,
+}
+
+impl Naive {
+    pub fn solve(&self, auction: &auction::Auction) -> solution::Solution {
+        let pools = deepest_pools(&auction.liquidity);
+        group_by_pair(&auction.orders)
+            .into_iter()
+            .filter_map(|(pair, orders)| {
+                if orders
+                    .iter()
+                    .all(|order| order.class == order::Class::Liquidity)
+                {
+                    return None;
+                }
+                solve_pair(orders, pools.get(&pair)?)
+            })
+            .filter(|solution| match &self.risk {
+                Some(risk) => risk.accepts(solution, auction),
+                None => true,
+            })
+            .fold(solution::Solution::default(), |solution, pair| {
+                solution.merge(&pair).unwrap_or(solution)
+            })
+    }
+}
+
+/// A token pair with the tokens in a canonical order.
+type Pair = (eth::TokenAddress, eth::TokenAddress);
+
+fn pair(a: eth::TokenAddress, b: eth::TokenAddress) -> Option<Pair> {
+    match a.cmp(&b) {
+        std::cmp::Ordering::Less => Some((a, b)),
+        std::cmp::Ordering::Greater => Some((b, a)),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+/// Groups the orders by token pair. Orders without any amounts can't be
+/// traded and are skipped.
+fn group_by_pair(orders: &[order::Order]) -> BTreeMap<Pair, Vec<order::Order>> {
+    let mut pairs = BTreeMap::<_, Vec<_>>::new();
+    for order in orders {
+        if order.sell.amount.is_zero() || order.buy.amount.is_zero() {
+            continue;
+        }
+        if let Some(pair) = pair(order.sell.token, order.buy.token) {
+            pairs.entry(pair).or_default().push(order.clone());
+        }
+    }
+    pairs
+}
+
+/// A constant product pool usable by the naive solver.
+struct Pool<'a> {
+    liquidity: &'a liquidity::Liquidity,
+    reserves: [eth::Asset; 2],
+    /// The pool fee as the fraction of the input amount.
+    fee: BigRational,
+}
+
+impl Pool<'_> {
+    fn reserve(&self, token: eth::TokenAddress) -> Option<U256> {
+        self.reserves
+            .iter()
+            .find(|reserve| reserve.token == token)
+            .map(|reserve| reserve.amount)
+    }
+
+    /// The input amount required for receiving the output amount, rounded up
+    /// like the onchain computation.
+    fn amount_in(&self, output: eth::Asset) -> Option<U256> {
+        let reserve_out = u256_to_big_int(&self.reserve(output.token)?);
+        let reserve_in = u256_to_big_int(
+            &self
+                .reserves
+                .iter()
+                .find(|reserve| reserve.token != output.token)?
+                .amount,
+        );
+        let output = u256_to_big_int(&output.amount);
+        let numerator = reserve_in * &output * self.fee.denom();
+        let denominator = (reserve_out - output) * (self.fee.denom() - self.fee.numer());
+        if denominator <= BigInt::zero() {
+            return None;
+        }
+        big_int_to_u256(&(numerator / denominator + 1)).ok()
+    }
+}
+
+/// The deepest constant product pool for each token pair.
+fn deepest_pools(liquidity: &[liquidity::Liquidity]) -> HashMap<Pair, Pool> {
+    let mut pools = HashMap::<_, Pool>::new();
+    for liquidity in liquidity {
+        let state = match &liquidity.state {
+            liquidity::State::ConstantProduct(state) => state,
+            _ => continue,
+        };
+        let fee = match to_fee(&state.fee) {
+            Some(fee) => fee,
+            None => continue,
+        };
+        let [a, b] = state.reserves;
+        let pair = match pair(a.token, b.token) {
+            Some(pair) => pair,
+            None => continue,
+        };
+
+        let depth = |pool: &Pool| pool.reserves[0].amount.full_mul(pool.reserves[1].amount);
+        let pool = Pool {
+            liquidity,
+            reserves: state.reserves,
+            fee,
+        };
+        match pools.get(&pair) {
+            Some(deepest) if depth(deepest) >= depth(&pool) => (),
+            _ => {
+                pools.insert(pair, pool);
+            }
+        }
+    }
+    pools
+}
+
+fn to_fee(fee: &liquidity::Fee) -> Option<BigRational> {
+    let (numer, exponent) = fee.0.as_bigint_and_exponent();
+    let denom = num::pow(BigInt::from(10), exponent.try_into().ok()?);
+    let fee = BigRational::new(numer, denom);
+    (fee >= BigRational::zero() && fee < BigRational::from_integer(1.into())).then_some(fee)
+}
+
+/// Computes a solution for orders of a single token pair using the pool of
+/// that pair. Orders are removed (or scaled down if they are partially
+/// fillable) until the remaining ones can all be executed.
+fn solve_pair(mut orders: Vec<order::Order>, pool: &Pool) -> Option<solution::Solution> {
+    // Number of times each order (by index) has already been reduced.
+    let mut reductions = vec![0; orders.len()];
+    while !orders.is_empty() {
+        let (context_a, context_b) = split_into_contexts(&orders, pool);
+        if let Some(solution) = solve_orders(&orders, pool, &context_a, &context_b)
+            .filter(|solution| satisfies_limit_prices(&orders, solution))
+        {
+            return Some(solution);
+        }
+
+        // Remove the order with the worst limit price that is selling the
+        // excess token (to make it less excessive) and try again.
+        let excess_token = if context_a.is_excess_before_fees(&context_b) {
+            context_a.token
+        } else {
+            context_b.token
+        };
+        let index = orders
+            .iter()
+            .enumerate()
+            .filter(|(_, order)| order.sell.token == excess_token)
+            .max_by(|(_, lhs), (_, rhs)| {
+                (lhs.buy.amount.full_mul(rhs.sell.amount))
+                    .cmp(&lhs.sell.amount.full_mul(rhs.buy.amount))
+            })
+            .map(|(index, _)| index)?;
+
+        // Partially fillable orders are scaled down instead of being removed
+        // right away so that as much of them as possible gets filled.
+        let reducible =
+            orders[index].partially_fillable && reductions[index] < MAX_PARTIAL_FILL_REDUCTIONS;
+        if reducible && halve_order(&mut orders[index]) {
+            reductions[index] += 1;
+        } else {
+            orders.swap_remove(index);
+            reductions.swap_remove(index);
+        }
+    }
+
+    None
+}
+
+/// Halves the amounts of a partially fillable order. The buy amount is rounded
+/// up so that the limit price never gets worse for the trader. Returns `false`
+/// if the order would no longer have any amount left to execute.
+fn halve_order(order: &mut order::Order) -> bool {
+    let (sell, buy) = (
+        order.sell.amount / 2,
+        (order.buy.amount / 2) + (order.buy.amount % 2),
+    );
+    if sell.is_zero() || buy.is_zero() {
+        return false;
+    }
+    order.sell.amount = sell;
+    order.buy.amount = buy;
+    order.fee.0 /= 2;
+    true
+}
+
+#[derive(Debug)]
+struct TokenContext {
+    token: eth::TokenAddress,
+    reserve: U256,
+    buy_volume: U256,
+    sell_volume: U256,
+}
+
+impl TokenContext {
+    fn net_volume(&self) -> BigInt {
+        u256_to_big_int(&self.sell_volume) - u256_to_big_int(&self.buy_volume)
+    }
+
+    fn is_excess_after_fees(&self, deficit: &TokenContext, fee: &BigRational) -> bool {
+        fee.denom() * u256_to_big_int(&self.reserve) * deficit.net_volume()
+            < (fee.denom() - fee.numer()) * u256_to_big_int(&deficit.reserve) * self.net_volume()
+    }
+
+    fn is_excess_before_fees(&self, deficit: &TokenContext) -> bool {
+        u256_to_big_int(&self.reserve) * deficit.net_volume()
+            < u256_to_big_int(&deficit.reserve) * self.net_volume()
+    }
+}
+
+fn split_into_contexts(orders: &[order::Order], pool: &Pool) -> (TokenContext, TokenContext) {
+    let context = |token| TokenContext {
+        token,
+        reserve: pool
+            .reserve(token)
+            .expect("orders are grouped by the token pair of the pool"),
+        buy_volume: U256::zero(),
+        sell_volume: U256::zero(),
+    };
+    let (mut a, mut b) = (
+        context(pool.reserves[0].token),
+        context(pool.reserves[1].token),
+    );
+    for order in orders {
+        match order.side {
+            order::Side::Buy => {
+                let context = if order.buy.token == a.token {
+                    &mut a
+                } else {
+                    &mut b
+                };
+                context.buy_volume = context.buy_volume.saturating_add(order.buy.amount);
+            }
+            order::Side::Sell => {
+                let context = if order.sell.token == a.token {
+                    &mut a
+                } else {
+                    &mut b
+                };
+                context.sell_volume = context.sell_volume.saturating_add(order.sell.amount);
+            }
+        }
+    }
+    (a, b)
+}
+
+fn solve_orders(
+    orders: &[order::Order],
+    pool: &Pool,
+    context_a: &TokenContext,
+    context_b: &TokenContext,
+) -> Option<solution::Solution> {
+    if context_a.is_excess_after_fees(context_b, &pool.fee) {
+        solve_with_pool(orders, pool, context_b, context_a)
+    } else if context_b.is_excess_after_fees(context_a, &pool.fee) {
+        solve_with_pool(orders, pool, context_a, context_b)
+    } else {
+        Some(solve_without_pool(orders, context_a, context_b))
+    }
+}
+
+/// Creates a solution at the current spot price of the pool, without using
+/// any of its liquidity.
+fn solve_without_pool(
+    orders: &[order::Order],
+    context_a: &TokenContext,
+    context_b: &TokenContext,
+) -> solution::Solution {
+    solution::Solution {
+        prices: solution::ClearingPrices(HashMap::from([
+            (context_a.token, context_b.reserve),
+            (context_b.token, context_a.reserve),
+        ])),
+        trades: orders.iter().map(fulfillment).collect(),
+        interactions: Default::default(),
+    }
+}
+
+/// Creates a solution using the pool's liquidity to balance excess and
+/// shortage. The clearing price is the effective exchange rate of the pool
+/// interaction.
+fn solve_with_pool(
+    orders: &[order::Order],
+    pool: &Pool,
+    shortage: &TokenContext,
+    excess: &TokenContext,
+) -> Option<solution::Solution> {
+    let pool_out = compute_pool_out(shortage, excess, &pool.fee)?;
+    let pool_in = compute_pool_in(pool_out.clone(), shortage, excess, &pool.fee)?;
+    let pool_out = big_rational_to_u256(&pool_out).ok()?;
+    let pool_in = big_rational_to_u256(&pool_in).ok()?;
+
+    let prices = HashMap::from([(excess.token, pool_out), (shortage.token, pool_in)]);
+
+    // Because the smart contracts round in the favour of the traders, it could
+    // be that we actually require a bit more from the pool in order to pay out
+    // all proceeds. The rounding error is moved to the sell token so that it
+    // either comes out of the fees or existing buffers.
+    let pool_out_with_rounding = orders.iter().try_fold(BigInt::zero(), |total, order| {
+        let (sell, buy) = executed_amounts(order, &prices)?;
+        Some(if order.sell.token == shortage.token {
+            total - u256_to_big_int(&sell)
+        } else {
+            total + u256_to_big_int(&buy)
+        })
+    })?;
+    let pool_out = big_int_to_u256(&pool_out_with_rounding.max(u256_to_big_int(&pool_out))).ok()?;
+    let output = eth::Asset {
+        token: shortage.token,
+        amount: pool_out,
+    };
+    let input = eth::Asset {
+        token: excess.token,
+        amount: pool.amount_in(output)?,
+    };
+
+    Some(solution::Solution {
+        prices: solution::ClearingPrices(prices),
+        trades: orders.iter().map(fulfillment).collect(),
+        interactions: vec![solution::Interaction::Liquidity(
+            solution::LiquidityInteraction {
+                liquidity: pool.liquidity.clone(),
+                input,
+                output,
+                internalize: false,
+            },
+        )],
+    })
+}
+
+/// Given information about the shortage token (the one we need to take from
+/// the pool) and the excess token (the one we give to the pool), this
+/// function computes the exact output amount required from the pool to
+/// perfectly match demand and supply at the effective pool price (the one
+/// used for that in/out swap).
+fn compute_pool_out(
+    shortage: &TokenContext,
+    excess: &TokenContext,
+    fee: &BigRational,
+) -> Option<BigRational> {
+    let fee_complement = fee.denom() - fee.numer();
+    let numerator_minuend =
+        &fee_complement * excess.net_volume() * u256_to_big_int(&shortage.reserve);
+    let numerator_subtrahend =
+        fee.denom() * shortage.net_volume() * u256_to_big_int(&excess.reserve);
+    let denominator =
+        fee.denom() * u256_to_big_int(&excess.reserve) + fee_complement * excess.net_volume();
+    if denominator.is_zero() {
+        return None;
+    }
+    Some(BigRational::new(
+        numerator_minuend - numerator_subtrahend,
+        denominator,
+    ))
+}
+
+/// Given the desired amount to receive and the state of the pool, this
+/// computes the required amount of tokens to be sent to the pool. Unlike the
+/// onchain computation, this doesn't round up since it works with rationals.
+fn compute_pool_in(
+    out: BigRational,
+    shortage: &TokenContext,
+    excess: &TokenContext,
+    fee: &BigRational,
+) -> Option<BigRational> {
+    let numerator = BigRational::from_integer(fee.denom().clone())
+        * out.clone()
+        * u256_to_big_int(&excess.reserve);
+    let denominator = BigRational::from_integer(fee.denom() - fee.numer())
+        * (BigRational::from_integer(u256_to_big_int(&shortage.reserve)) - out);
+    numerator.checked_div(&denominator)
+}
+
+fn fulfillment(order: &order::Order) -> solution::Trade {
+    solution::Trade::Fulfillment(solution::Fulfillment {
+        order: order.uid,
+        executed: match order.side {
+            order::Side::Sell => order.sell.amount,
+            order::Side::Buy => order.buy.amount,
+        },
+    })
+}
+
+/// The sell and buy amounts of fully executing the order at the clearing
+/// prices, rounded in favour of the trader like the settlement contract does.
+fn executed_amounts(
+    order: &order::Order,
+    prices: &HashMap<eth::TokenAddress, U256>,
+) -> Option<(U256, U256)> {
+    let sell_price = *prices.get(&order.sell.token)?;
+    let buy_price = *prices.get(&order.buy.token)?;
+    match order.side {
+        order::Side::Sell => {
+            let sell = order.sell.amount;
+            let buy = sell.checked_mul(sell_price)?;
+            let buy = buy.checked_add(buy_price.checked_sub(1.into())?)? / buy_price;
+            Some((sell, buy))
+        }
+        order::Side::Buy => {
+            let buy = order.buy.amount;
+            let sell = buy.checked_mul(buy_price)?.checked_div(sell_price)?;
+            Some((sell, buy))
+        }
+    }
+}
+
+/// Returns true if for each order the clearing price is not worse than the
+/// limit price, i.e. `buy_price / sell_price >= buy_amount / sell_amount`.
+fn satisfies_limit_prices(orders: &[order::Order], solution: &solution::Solution) -> bool {
+    orders.iter().all(|order| {
+        let (sell_price, buy_price) = match (
+            solution.prices.0.get(&order.sell.token),
+            solution.prices.0.get(&order.buy.token),
+        ) {
+            (Some(sell_price), Some(buy_price)) => (*sell_price, *buy_price),
+            _ => return false,
+        };
+        matches!(
+            (
+                order.sell.amount.checked_mul(sell_price),
+                order.buy.amount.checked_mul(buy_price),
+            ),
+            (Some(sell_volume), Some(buy_volume)) if sell_volume >= buy_volume
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethereum_types::H160;
+
+    fn token(byte: u8) -> eth::TokenAddress {
+        eth::TokenAddress(H160([byte; 20]))
+    }
+
+    fn to_wei(base: u64) -> U256 {
+        U256::from(base) * U256::exp10(18)
+    }
+
+    fn order(id: u8, sell: (u8, u64), buy: (u8, u64), side: order::Side) -> order::Order {
+        order::Order {
+            uid: order::Uid([id; 56]),
+            sell: eth::Asset {
+                token: token(sell.0),
+                amount: to_wei(sell.1),
+            },
+            buy: eth::Asset {
+                token: token(buy.0),
+                amount: to_wei(buy.1),
+            },
+            fee: Default::default(),
+            side,
+            class: order::Class::Market,
+            partially_fillable: false,
+        }
+    }
+
+    fn pool(a: u64, b: u64) -> liquidity::Liquidity {
+        liquidity::Liquidity {
+            id: liquidity::Id(0),
+            address: H160([0xff; 20]),
+            gas: eth::Gas(100_000.into()),
+            state: liquidity::State::ConstantProduct(liquidity::constant_product::Pool {
+                reserves: [
+                    eth::Asset {
+                        token: token(1),
+                        amount: to_wei(a),
+                    },
+                    eth::Asset {
+                        token: token(2),
+                        amount: to_wei(b),
+                    },
+                ],
+                fee: liquidity::Fee("0.003".parse().unwrap()),
+            }),
+        }
+    }
+
+    fn solve(
+        orders: Vec<order::Order>,
+        liquidity: Vec<liquidity::Liquidity>,
+    ) -> solution::Solution {
+        Naive { risk: None }.solve(&auction::Auction {
+            id: None,
+            tokens: Default::default(),
+            orders,
+            liquidity,
+            gas_price: eth::GasPrice(1.into()),
+            deadline: auction::Deadline(Default::default()),
+        })
+    }
+
+    #[test]
+    fn matches_sell_orders_on_both_sides_using_pool_for_excess() {
+        let orders = vec![
+            order(1, (1, 40), (2, 30), order::Side::Sell),
+            order(2, (2, 100), (1, 90), order::Side::Sell),
+        ];
+        let solution = solve(orders.clone(), vec![pool(1000, 1000)]);
+
+        assert_eq!(solution.trades.len(), 2);
+        let interaction = match &solution.interactions[..] {
+            [solution::Interaction::Liquidity(interaction)] => interaction,
+            _ => panic!("expected a single pool interaction"),
+        };
+        // The excess of token 2 gets sold to the pool.
+        assert_eq!(interaction.input.token, token(2));
+        assert_eq!(interaction.output.token, token(1));
+
+        // The sell amounts together with the pool interaction cover what the
+        // orders receive at the clearing prices.
+        let prices = &solution.prices.0;
+        for order in &orders {
+            let (_, buy) = executed_amounts(order, prices).unwrap();
+            assert!(buy >= order.buy.amount);
+        }
+        let (_, bought_1) = executed_amounts(&orders[1], prices).unwrap();
+        assert!(orders[0].sell.amount + interaction.output.amount >= bought_1);
+        let (_, bought_2) = executed_amounts(&orders[0], prices).unwrap();
+        assert!(orders[1].sell.amount - interaction.input.amount >= bought_2);
+    }
+
+    #[test]
+    fn matches_orders_at_spot_price_without_using_pool() {
+        let orders = vec![
+            order(1, (1, 1001), (2, 1000), order::Side::Sell),
+            order(2, (2, 1001), (1, 1000), order::Side::Sell),
+        ];
+        let solution = solve(orders, vec![pool(1_000_001, 1_000_000)]);
+
+        assert!(solution.interactions.is_empty());
+        assert_eq!(
+            solution.prices.0,
+            HashMap::from([(token(1), to_wei(1_000_000)), (token(2), to_wei(1_000_001))])
+        );
+    }
+
+    #[test]
+    fn excludes_orders_with_unsatisfiable_limit_prices() {
+        let orders = vec![
+            order(1, (1, 1), (2, 1000), order::Side::Sell),
+            order(2, (1, 1000), (2, 1000), order::Side::Sell),
+            order(3, (2, 1000), (1, 1000), order::Side::Sell),
+            order(4, (2, 2), (1, 1000), order::Side::Sell),
+        ];
+        let solution = solve(orders, vec![pool(1_000_000, 1_000_000)]);
+
+        let traded = solution
+            .trades
+            .iter()
+            .map(|trade| match trade {
+                solution::Trade::Fulfillment(trade) => trade.order,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(traded.len(), 2);
+        assert!(traded.contains(&order::Uid([2; 56])));
+        assert!(traded.contains(&order::Uid([3; 56])));
+    }
+
+    #[test]
+    fn returns_empty_solution_if_orders_have_no_overlap() {
+        let orders = vec![
+            order(1, (1, 900), (2, 1000), order::Side::Sell),
+            order(2, (2, 900), (1, 1000), order::Side::Sell),
+        ];
+        let solution = solve(orders, vec![pool(1_000_001, 1_000_000)]);
+        assert!(solution.trades.is_empty());
+        assert!(solution.prices.0.is_empty());
+    }
+
+    #[test]
+    fn halves_partially_fillable_orders() {
+        let mut order = order::Order {
+            sell: eth::Asset {
+                token: token(1),
+                amount: 10.into(),
+            },
+            buy: eth::Asset {
+                token: token(2),
+                amount: 5.into(),
+            },
+            fee: order::Fee(2.into()),
+            partially_fillable: true,
+            ..order(1, (1, 0), (2, 0), order::Side::Sell)
+        };
+
+        assert!(halve_order(&mut order));
+        assert_eq!(order.sell.amount, 5.into());
+        assert_eq!(order.buy.amount, 3.into());
+        assert_eq!(order.fee.0, 1.into());
+
+        assert!(halve_order(&mut order));
+        assert!(halve_order(&mut order));
+        assert_eq!(order.sell.amount, 1.into());
+        assert_eq!(order.buy.amount, 1.into());
+
+        // Nothing would be left to sell.
+        assert!(!halve_order(&mut order));
+        assert_eq!(order.sell.amount, 1.into());
+        assert_eq!(order.buy.amount, 1.into());
+    }
+}
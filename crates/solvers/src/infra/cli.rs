@@ -0,0 +1,64 @@
+//! Command line arguments of the solver engine binary.
+
+use std::{net::SocketAddr, path::PathBuf};
+
+#[derive(Debug, clap::Parser)]
+pub struct Args {
+    /// The address to bind the solver engine API to.
+    #[clap(long, env, default_value = "127.0.0.1:7872")]
+    pub addr: SocketAddr,
+
+    /// The log filter, in the `tracing_subscriber::EnvFilter` syntax.
+    #[clap(long, env, default_value = "warn,solvers=debug")]
+    pub log: String,
+
+    /// The solver engine to run.
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+impl std::fmt::Display for Args {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "addr: {}", self.addr)?;
+        writeln!(f, "log: {}", self.log)?;
+        write!(f, "command: {}", self.command)
+    }
+}
+
+/// The solver engine to run. Each engine is configured with its own TOML
+/// file.
+#[derive(Debug, clap::Subcommand)]
+pub enum Command {
+    /// Route each order individually over the auction liquidity.
+    Baseline {
+        /// Path to the engine configuration file. For an example see
+        /// `baseline.example.toml`.
+        #[clap(long, env)]
+        config: PathBuf,
+    },
+    /// Match opposing orders of each token pair against each other.
+    Naive {
+        /// Path to the engine configuration file. For an example see
+        /// `naive.example.toml`.
+        #[clap(long, env)]
+        config: PathBuf,
+    },
+    /// Forward auctions to an external solver using the legacy HTTP solver
+    /// API.
+    Legacy {
+        /// Path to the engine configuration file. For an example see
+        /// `legacy.example.toml`.
+        #[clap(long, env)]
+        config: PathBuf,
+    },
+}
+
+impl std::fmt::Display for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Command::Baseline { config } => write!(f, "baseline --config {config:?}"),
+            Command::Naive { config } => write!(f, "naive --config {config:?}"),
+            Command::Legacy { config } => write!(f, "legacy --config {config:?}"),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{
+    domain::{eth, solver::Baseline},
+    infra::config::RiskConfig,
+};
+use ethereum_types::H160;
+use serde::Deserialize;
+use std::path::Path;
+use tokio::fs;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct Config {
+    /// The address of the WETH contract of the chain.
+    weth: H160,
+    /// Tokens to consider as intermediate hops when routing orders, in
+    /// addition to WETH.
+    #[serde(default)]
+    base_tokens: Vec<H160>,
+    /// The maximum number of intermediate tokens in a route. At most
+    /// [`MAX_HOPS_LIMIT`].
+    #[serde(default = "default_max_hops")]
+    max_hops: usize,
+    risk_parameters: Option<RiskConfig>,
+}
+
+fn default_max_hops() -> usize {
+    2
+}
+
+/// The number of candidate routes grows exponentially with the number of hops,
+/// so larger values would make the solver too slow to find a solution in time.
+const MAX_HOPS_LIMIT: usize = 4;
+
+/// Load the baseline solver configuration from a TOML file. Panics if the
+/// config is invalid or on I/O errors.
+pub async fn load(path: &Path) -> Baseline {
+    let data = fs::read_to_string(path)
+        .await
+        .unwrap_or_else(|err| panic!("I/O error while reading {path:?}: {err}"));
+    let config: Config = toml::de::from_str(&data)
+        .unwrap_or_else(|err| panic!("invalid baseline config {path:?}: {err}"));
+    assert!(
+        config.max_hops <= MAX_HOPS_LIMIT,
+        "invalid baseline config {path:?}: max-hops {} exceeds the limit of {MAX_HOPS_LIMIT}",
+        config.max_hops,
+    );
+    Baseline {
+        weth: eth::WethAddress(config.weth),
+        base_tokens: config
+            .base_tokens
+            .into_iter()
+            .map(eth::TokenAddress)
+            .collect(),
+        max_hops: config.max_hops,
+        risk: config.risk_parameters.map(RiskConfig::into_domain),
+    }
+}
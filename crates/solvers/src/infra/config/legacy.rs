@@ -0,0 +1,48 @@
+use crate::domain::{eth, solver::legacy};
+use ethereum_types::H160;
+use serde::Deserialize;
+use std::path::Path;
+use tokio::fs;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct Config {
+    /// The address of the WETH contract of the chain.
+    weth: H160,
+    /// The name of the external solver, used for logging.
+    solver_name: String,
+    chain_id: u64,
+    /// The base URL of the external solver.
+    endpoint: url::Url,
+    /// Optional value for the `X-API-KEY` header.
+    api_key: Option<String>,
+    /// The maximum number of orders the external solver should execute.
+    #[serde(default = "default_max_nr_exec_orders")]
+    max_nr_exec_orders: u32,
+    /// Whether the external solver may use the settlement contract's internal
+    /// buffers. Uses the solver's default if unset.
+    use_internal_buffers: Option<bool>,
+}
+
+fn default_max_nr_exec_orders() -> u32 {
+    100
+}
+
+/// Load the legacy solver configuration from a TOML file. Panics if the config
+/// is invalid or on I/O errors.
+pub async fn load(path: &Path) -> legacy::Config {
+    let data = fs::read_to_string(path)
+        .await
+        .unwrap_or_else(|err| panic!("I/O error while reading {path:?}: {err}"));
+    let config: Config = toml::de::from_str(&data)
+        .unwrap_or_else(|err| panic!("invalid legacy config {path:?}: {err}"));
+    legacy::Config {
+        weth: eth::WethAddress(config.weth),
+        solver_name: config.solver_name,
+        chain_id: config.chain_id,
+        base_url: config.endpoint,
+        api_key: config.api_key,
+        max_nr_exec_orders: config.max_nr_exec_orders,
+        use_internal_buffers: config.use_internal_buffers,
+    }
+}
@@ -0,0 +1,29 @@
+pub mod baseline;
+pub mod legacy;
+pub mod naive;
+
+use crate::domain::risk::Risk;
+use serde::Deserialize;
+
+/// Risk parameters shared by the engine configuration files.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct RiskConfig {
+    gas_amount_factor: f64,
+    gas_price_factor: f64,
+    nmb_orders_factor: f64,
+    intercept: f64,
+    min_success_probability: f64,
+}
+
+impl RiskConfig {
+    fn into_domain(self) -> Risk {
+        Risk {
+            gas_amount_factor: self.gas_amount_factor,
+            gas_price_factor: self.gas_price_factor,
+            nmb_orders_factor: self.nmb_orders_factor,
+            intercept: self.intercept,
+            min_success_probability: self.min_success_probability,
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{domain::solver::Naive, infra::config::RiskConfig};
+use serde::Deserialize;
+use std::path::Path;
+use tokio::fs;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct Config {
+    risk_parameters: Option<RiskConfig>,
+}
+
+/// Load the naive solver configuration from a TOML file. Panics if the config
+/// is invalid or on I/O errors.
+pub async fn load(path: &Path) -> Naive {
+    let data = fs::read_to_string(path)
+        .await
+        .unwrap_or_else(|err| panic!("I/O error while reading {path:?}: {err}"));
+    let config: Config = toml::de::from_str(&data)
+        .unwrap_or_else(|err| panic!("invalid naive config {path:?}: {err}"));
+    Naive {
+        risk: config.risk_parameters.map(RiskConfig::into_domain),
+    }
+}
@@ -1,10 +1,15 @@
 // TODO remove this once the crate stabilizes a bit.
 #![allow(dead_code)]
 
+use clap::Parser;
+use infra::{cli, config};
 #[cfg(unix)]
 use tokio::signal::unix::{self, SignalKind};
 
 mod api;
+mod boundary;
+mod domain;
+mod infra;
 mod util;
 
 #[tokio::main]
@@ -13,8 +18,27 @@ async fn main() {
 }
 
 async fn run() {
+    let args = cli::Args::parse();
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::new(&args.log))
+        .init();
+    tracing::info!("running solver engine with arguments:\n{}", args);
+
+    let solver = match args.command {
+        cli::Command::Baseline { config } => {
+            domain::solver::Solver::Baseline(config::baseline::load(&config).await)
+        }
+        cli::Command::Naive { config } => {
+            domain::solver::Solver::Naive(config::naive::load(&config).await)
+        }
+        cli::Command::Legacy { config } => domain::solver::Solver::Legacy(
+            domain::solver::Legacy::new(config::legacy::load(&config).await),
+        ),
+    };
+
     api::Api {
-        addr: "127.0.0.1:7872".parse().unwrap(),
+        addr: args.addr,
+        solver,
     }
     .serve(shutdown_signal())
     .await
@@ -0,0 +1,9 @@
+-- Orders can now have interactions that are executed after the trades of a settlement in addition
+-- to the ones executed before. Existing interactions are all pre-interactions.
+
+CREATE TYPE ExecutionTime AS ENUM ('pre', 'post');
+
+ALTER TABLE interactions ADD COLUMN execution ExecutionTime NOT NULL DEFAULT 'pre';
+
+ALTER TABLE interactions DROP CONSTRAINT interactions_pkey;
+ALTER TABLE interactions ADD PRIMARY KEY (order_uid, execution, index);
@@ -0,0 +1,3 @@
+-- The orderbook polls for recently cancelled orders to push order status updates to subscribers.
+-- Without an index this requires scanning the whole orders table.
+CREATE INDEX order_cancellation_timestamp ON orders USING BTREE (cancellation_timestamp);
@@ -0,0 +1,13 @@
+-- Records how orders fared when the autopilot built auctions so that the history of an order can be
+-- reconstructed when debugging why it didn't get filled.
+--
+-- To limit the amount of data only changes are stored. A row means that starting with that auction
+-- the order was included in auctions (if filter_reason is NULL) or filtered out for the given
+-- reason until the next row for the same order.
+CREATE TABLE order_auction_states (
+    order_uid bytea NOT NULL,
+    auction_id bigint NOT NULL,
+    filter_reason text,
+
+    PRIMARY KEY (order_uid, auction_id)
+);
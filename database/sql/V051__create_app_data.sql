@@ -0,0 +1,9 @@
+-- Stores the full app data documents that orders commit to with their app data hash so that the
+-- backend can interpret order metadata.
+--
+-- The document is stored as the exact bytes that were hashed because reserializing the JSON could
+-- change its hash.
+CREATE TABLE app_data (
+    contract_app_data bytea PRIMARY KEY,
+    full_app_data bytea NOT NULL
+);